@@ -5,7 +5,8 @@
 use std::io::{self, Write};
 use thiserror::Error;
 
-const MTF_MAGIC: &[u8; 4] = b"MTF\0";
+/// Magic bytes at the start of every MTF blob.
+pub const MTF_MAGIC: &[u8; 4] = b"MTF\0";
 const MTF_VERSION: u32 = 1;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,6 +35,8 @@ pub enum MTFError {
     InvalidUtf8,
     #[error("String offset {0} out of bounds")]
     InvalidStringOffset(u32),
+    #[error("Value of field `{0}` does not fit in {1} bits")]
+    FieldOverflow(&'static str, u32),
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 }
@@ -45,6 +48,41 @@ pub trait MTFType {
     fn mtf_string_table() -> &'static [u8];
 }
 
+/// Reverse the byte order of a value in place.
+///
+/// Implemented for the primitive types and arrays of them; derive it for structs
+/// with `#[derive(ByteSwap)]` to swap every field. Used to fix up data written on
+/// a target of the other endianness.
+pub trait ByteSwap {
+    fn byte_swap(&mut self);
+}
+
+macro_rules! impl_byte_swap {
+    ($($ty:ty),*) => {
+        $(impl ByteSwap for $ty {
+            fn byte_swap(&mut self) {
+                *self = <$ty>::from_ne_bytes({
+                    let mut bytes = self.to_ne_bytes();
+                    bytes.reverse();
+                    bytes
+                });
+            }
+        })*
+    };
+}
+
+impl_byte_swap!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+
+impl ByteSwap for bool {
+    fn byte_swap(&mut self) {}
+}
+
+impl<T: ByteSwap, const N: usize> ByteSwap for [T; N] {
+    fn byte_swap(&mut self) {
+        self.iter_mut().for_each(ByteSwap::byte_swap);
+    }
+}
+
 /// Write MTF metadata blob: [MAGIC][VERSION][TYPE_COUNT][TYPES][STRING_TABLE_SIZE][STRING_TABLE]
 pub fn write_mtf(types: &[TypeDef], strings: &[u8], mut out: impl Write) -> Result<()> {
     out.write_all(MTF_MAGIC)?;
@@ -171,3 +209,50 @@ pub fn build_string_table(strings: &[&str]) -> (Vec<u8>, std::collections::HashM
     }
     (table, offsets)
 }
+
+fn bit_mask(size_bits: usize) -> u128 {
+    (1u128 << size_bits) - 1
+}
+
+/// Read `size_bits` (1..=64) bits starting at bit `offset_bits` of `bytes`.
+///
+/// Bits are numbered least-significant first within each byte, the layout used
+/// by `#[derive(MTFBits)]`. Panics if the range lies outside `bytes`.
+pub fn read_bits(bytes: &[u8], offset_bits: usize, size_bits: usize) -> u64 {
+    assert!(
+        (1..=64).contains(&size_bits),
+        "bit field width must be 1..=64"
+    );
+    let first = offset_bits / 8;
+    let shift = offset_bits % 8;
+    let mut acc = 0u128;
+    for (i, byte) in bytes[first..first + (shift + size_bits).div_ceil(8)]
+        .iter()
+        .enumerate()
+    {
+        acc |= (*byte as u128) << (i * 8);
+    }
+    ((acc >> shift) & bit_mask(size_bits)) as u64
+}
+
+/// Write the low `size_bits` (1..=64) bits of `value` at bit `offset_bits` of `bytes`.
+///
+/// Higher bits of `value` are ignored and surrounding bits are preserved.
+/// Panics if the range lies outside `bytes`.
+pub fn write_bits(bytes: &mut [u8], offset_bits: usize, size_bits: usize, value: u64) {
+    assert!(
+        (1..=64).contains(&size_bits),
+        "bit field width must be 1..=64"
+    );
+    let first = offset_bits / 8;
+    let shift = offset_bits % 8;
+    let mask = bit_mask(size_bits) << shift;
+    let bits = ((value as u128) << shift) & mask;
+    for (i, byte) in bytes[first..first + (shift + size_bits).div_ceil(8)]
+        .iter_mut()
+        .enumerate()
+    {
+        let m = (mask >> (i * 8)) as u8;
+        *byte = (*byte & !m) | ((bits >> (i * 8)) as u8 & m);
+    }
+}
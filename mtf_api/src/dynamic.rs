@@ -1,14 +1,15 @@
 // mtf_api/src/dynamic.rs
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::path::Path;
 use std::ptr::NonNull;
 
-use bytemuck::{Pod, from_bytes};
-use mtf::{FieldDef, MTFError, Result, TypeDef, read_mtf, read_string};
+use bytemuck::{Pod, try_from_bytes};
+use mtf::{
+    FieldDef, MTF_MAGIC, MTFError, Result, TypeDef, read_bits, read_mtf, read_string, write_bits,
+};
 
 /// A handle to a single field in a struct.
 ///
@@ -92,23 +93,21 @@ impl<'a, T> FieldHandle<'a, T> {
     }
 }
 
-/// Dynamic access to a slice of structs with MTF metadata.
+/// Parsed MTF metadata for one struct type.
 ///
-/// Allows field access by name at runtime, useful for:
-/// - Generic tooling and editors
-/// - Serialization/deserialization
-/// - Dynamic queries
-pub struct DynamicContainer {
-    data: Vec<u8>,
+/// Parse a blob once and share the schema between any number of
+/// [`DynamicContainerRef`]s, so reflecting over borrowed data allocates nothing.
+#[derive(Debug, Clone)]
+pub struct Schema {
     type_def: TypeDef,
     strings: Vec<u8>,
     struct_size: usize,
     field_map: HashMap<String, FieldDef>,
 }
 
-impl DynamicContainer {
-    /// Construct from raw data and a complete MTF blob.
-    pub fn from_raw(data: Vec<u8>, blob: &[u8]) -> Result<Self> {
+impl Schema {
+    /// Parse the first type of a complete MTF blob.
+    pub fn parse(blob: &[u8]) -> Result<Self> {
         let (types, strings) = read_mtf(blob)?;
 
         let type_def = types.into_iter().next().ok_or(MTFError::UnexpectedEof)?;
@@ -123,7 +122,6 @@ impl DynamicContainer {
         }
 
         Ok(Self {
-            data,
             type_def,
             strings: strings.to_vec(),
             struct_size,
@@ -131,70 +129,40 @@ impl DynamicContainer {
         })
     }
 
-    /// Construct directly from a file containing MTF-embedded data.
-    ///
-    /// Expects format: [DATA][METADATA_SIZE: u32][METADATA]
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut file = File::open(path)?;
-        let len = file.metadata()?.len();
-
-        if len < 4 {
-            return Err(MTFError::UnexpectedEof);
-        }
-
-        // Read metadata size from the end
-        file.seek(SeekFrom::End(-4))?;
-        let mut buf = [0u8; 4];
-        file.read_exact(&mut buf)?;
-        let metadata_size = u32::from_le_bytes(buf) as u64;
-
-        if metadata_size + 4 > len {
-            return Err(MTFError::UnexpectedEof);
-        }
-
-        // Calculate where data ends and metadata begins
-        let data_len = len - metadata_size - 4;
-
-        // Read data
-        file.seek(SeekFrom::Start(0))?;
-        let mut data = vec![0u8; data_len as usize];
-        file.read_exact(&mut data)?;
-
-        // Read metadata blob
-        let mut blob = vec![0u8; metadata_size as usize];
-        file.read_exact(&mut blob)?;
-
-        Self::from_raw(data, &blob)
+    /// Get the type name.
+    pub fn type_name(&self) -> Result<&str> {
+        read_string(&self.strings, self.type_def.name_offset)
     }
 
-    /// Returns the number of structs in the container.
-    pub fn len(&self) -> usize {
-        if self.struct_size == 0 {
-            0
-        } else {
-            self.data.len() / self.struct_size
-        }
+    /// Size of one struct in bytes.
+    pub fn struct_size(&self) -> usize {
+        self.struct_size
     }
 
-    /// Returns true if the container is empty.
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    /// Look up a field definition by name.
+    pub fn field(&self, field_name: &str) -> Option<&FieldDef> {
+        self.field_map.get(field_name)
     }
 
-    /// Get the type name.
-    pub fn type_name(&self) -> Result<&str> {
-        read_string(&self.strings, self.type_def.name_offset)
+    /// Iterate over the field names (in no particular order).
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.field_map.keys().map(String::as_str)
     }
 
-    /// List all field names.
-    pub fn field_names(&self) -> Vec<String> {
-        self.field_map.keys().cloned().collect()
+    /// Number of whole structs in `data_len` bytes.
+    fn count(&self, data_len: usize) -> usize {
+        data_len.checked_div(self.struct_size).unwrap_or(0)
     }
 
-    /// Immutable access to a field of a struct at index.
-    pub fn field<T: Pod>(&self, index: usize, field_name: &str) -> Option<&T> {
+    /// Byte range of a `T`-sized field of struct `index` within `data_len` bytes.
+    fn field_range<T: Pod>(
+        &self,
+        data_len: usize,
+        index: usize,
+        field_name: &str,
+    ) -> Option<Range<usize>> {
         // Bounds check
-        if index >= self.len() {
+        if index >= self.count(data_len) {
             return None;
         }
 
@@ -210,62 +178,186 @@ impl DynamicContainer {
         // Check alignment
         let field_offset = (field.offset_bits / 8) as usize;
         if !field_offset.is_multiple_of(std::mem::align_of::<T>()) {
-            //if field_offset % std::mem::align_of::<T>() != 0 {
             return None; // Misaligned
         }
 
         // Calculate struct position
-        let struct_start = index * self.struct_size;
-        let field_start = struct_start + field_offset;
-        let field_end = field_start + field_size;
+        let field_start = index * self.struct_size + field_offset;
+        Some(field_start..field_start + field_size)
+    }
+
+    /// Absolute bit offset and size of a field, if it can be accessed as bits.
+    fn bit_range(&self, data_len: usize, index: usize, field_name: &str) -> Option<(usize, usize)> {
+        if index >= self.count(data_len) {
+            return None;
+        }
+        let field = self.field_map.get(field_name)?;
+        let size = field.size_bits as usize;
+        if size == 0 || size > 64 || field.offset_bits as usize + size > self.struct_size * 8 {
+            return None;
+        }
+        Some((
+            index * self.struct_size * 8 + field.offset_bits as usize,
+            size,
+        ))
+    }
+}
 
-        // Get field slice
-        let field_slice = self.data.get(field_start..field_end)?;
+/// Dynamic access to a slice of structs with MTF metadata.
+///
+/// Allows field access by name at runtime, useful for:
+/// - Generic tooling and editors
+/// - Serialization/deserialization
+/// - Dynamic queries
+pub struct DynamicContainer {
+    data: Vec<u8>,
+    schema: Schema,
+}
 
-        Some(from_bytes(field_slice))
+/// Locate the data length and the metadata blob in MTF-embedded bytes.
+///
+/// The current layout keeps the size in the last 4 bytes, right after the
+/// blob. Failing that, look for the pre-0.2 layout, where the size precedes a
+/// blob that runs to the end.
+fn split_embedded(bytes: &[u8]) -> Result<(usize, Range<usize>)> {
+    let len = bytes.len();
+    if len < 4 {
+        return Err(MTFError::UnexpectedEof);
+    }
+    let size = u32::from_le_bytes(bytes[len - 4..].try_into().unwrap()) as usize;
+    let current = (len - 4).checked_sub(size);
+    if let Some(start) = current
+        && bytes[start..].starts_with(MTF_MAGIC)
+    {
+        return Ok((start, start..len - 4));
     }
+    let legacy = (4..=len - MTF_MAGIC.len()).rev().find(|&start| {
+        bytes[start..].starts_with(MTF_MAGIC)
+            && u32::from_le_bytes(bytes[start - 4..start].try_into().unwrap()) as usize
+                == len - start
+    });
+    match (legacy, current) {
+        (Some(start), _) => Ok((start - 4, start..len)),
+        // Neither layout matches; let the schema parser report the blob
+        (None, Some(start)) => Ok((start, start..len - 4)),
+        (None, None) => Err(MTFError::UnexpectedEof),
+    }
+}
 
-    /// Mutable access to a field of a struct at index.
-    pub fn field_mut<T: Pod>(&mut self, index: usize, field_name: &str) -> FieldHandle<'_, T> {
-        // Bounds check
-        if index >= self.len() {
-            return FieldHandle::none();
-        }
+impl DynamicContainer {
+    /// Construct from raw data and a complete MTF blob.
+    pub fn from_raw(data: Vec<u8>, blob: &[u8]) -> Result<Self> {
+        Ok(Self {
+            data,
+            schema: Schema::parse(blob)?,
+        })
+    }
 
-        // Get field definition
-        let field = match self.field_map.get(field_name) {
-            Some(f) => f,
-            None => return FieldHandle::none(),
-        };
+    /// Construct from raw data and an already parsed schema.
+    pub fn from_schema(data: Vec<u8>, schema: Schema) -> Self {
+        Self { data, schema }
+    }
 
-        // Check size matches
-        let field_size = (field.size_bits as usize).div_ceil(8);
-        if field_size != std::mem::size_of::<T>() {
-            return FieldHandle::none();
-        }
+    /// Construct from an in-memory buffer containing MTF-embedded data.
+    ///
+    /// Same layouts as [`from_file`](Self::from_file).
+    pub fn from_embedded(mut bytes: Vec<u8>) -> Result<Self> {
+        let (data_len, blob) = split_embedded(&bytes)?;
+        let schema = Schema::parse(&bytes[blob])?;
+        bytes.truncate(data_len);
+        Ok(Self::from_schema(bytes, schema))
+    }
 
-        // Check alignment
-        let field_offset = (field.offset_bits / 8) as usize;
-        if !field_offset.is_multiple_of(std::mem::align_of::<T>()) {
-            //if field_offset % std::mem::align_of::<T>() != 0 {
-            return FieldHandle::none(); // Misaligned
-        }
+    /// Construct directly from a file containing MTF-embedded data.
+    ///
+    /// Expects format: [DATA][METADATA][METADATA_SIZE: u32], as written by
+    /// [`write_slice_with_mtf`](crate::write_slice_with_mtf). Files in the
+    /// layout written before 0.2, [DATA][METADATA_SIZE: u32][METADATA], are
+    /// also accepted.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_embedded(std::fs::read(path)?)
+    }
 
-        // Calculate struct position
-        let struct_start = index * self.struct_size;
-        let field_start = struct_start + field_offset;
-        let field_end = field_start + field_size;
+    /// Returns the number of structs in the container.
+    pub fn len(&self) -> usize {
+        self.schema.count(self.data.len())
+    }
+
+    /// Returns true if the container is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the type name.
+    pub fn type_name(&self) -> Result<&str> {
+        self.schema.type_name()
+    }
+
+    /// List all field names.
+    pub fn field_names(&self) -> Vec<String> {
+        self.schema.field_names().map(str::to_string).collect()
+    }
+
+    /// The parsed MTF schema.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Borrow the container as a [`DynamicContainerRef`].
+    pub fn as_dynamic_ref(&self) -> DynamicContainerRef<'_> {
+        DynamicContainerRef::new(&self.data, &self.schema)
+    }
+
+    /// Immutable access to a field of a struct at index.
+    pub fn field<T: Pod>(&self, index: usize, field_name: &str) -> Option<&T> {
+        self.as_dynamic_ref().field(index, field_name)
+    }
+
+    /// Mutable access to a field of a struct at index.
+    pub fn field_mut<T: Pod>(&mut self, index: usize, field_name: &str) -> FieldHandle<'_, T> {
+        let Some(range) = self
+            .schema
+            .field_range::<T>(self.data.len(), index, field_name)
+        else {
+            return FieldHandle::none();
+        };
 
         // Get mutable field slice
-        let field_slice = match self.data.get_mut(field_start..field_end) {
+        let field_slice = match self.data.get_mut(range) {
             Some(s) => s,
             None => return FieldHandle::none(),
         };
 
         let ptr = field_slice.as_mut_ptr() as *mut T;
+        if !ptr.is_aligned() {
+            return FieldHandle::none();
+        }
         unsafe { FieldHandle::from_ptr(ptr) }
     }
 
+    /// Read a field of up to 64 bits as an unsigned integer.
+    ///
+    /// Works at bit granularity, so it also reads fields that do not start
+    /// or end on a byte boundary (e.g. from `#[derive(MTFBits)]`).
+    pub fn field_bits(&self, index: usize, field_name: &str) -> Option<u64> {
+        self.as_dynamic_ref().field_bits(index, field_name)
+    }
+
+    /// Write an unsigned value into a field of up to 64 bits.
+    ///
+    /// Returns false if the struct or field does not exist, or if `value`
+    /// does not fit in the field.
+    pub fn set_field_bits(&mut self, index: usize, field_name: &str, value: u64) -> bool {
+        let Some((offset, size)) = self.schema.bit_range(self.data.len(), index, field_name) else {
+            return false;
+        };
+        if size < 64 && value >> size != 0 {
+            return false;
+        }
+        write_bits(&mut self.data, offset, size, value);
+        true
+    }
+
     /// Get raw byte data.
     pub fn raw(&self) -> &[u8] {
         &self.data
@@ -285,6 +377,91 @@ impl DynamicContainer {
     }
 }
 
+/// Zero-copy dynamic access to structs in borrowed bytes.
+///
+/// Unlike [`DynamicContainer`], neither the data nor the schema is copied, so
+/// reflecting over a PAK asset slice or an mmap region allocates nothing.
+///
+/// ```
+/// use mtf_api::{DynamicContainerRef, MTFType, Schema, MTF};
+///
+/// #[repr(C)]
+/// #[derive(MTF)]
+/// struct Point {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// let schema = Schema::parse(Point::mtf_type_blob()).unwrap();
+/// let bytes = [1u32, 2, 3, 4].map(u32::to_le_bytes).concat();
+/// let points = DynamicContainerRef::new(&bytes, &schema);
+///
+/// assert_eq!(points.len(), 2);
+/// assert_eq!(points.field_bits(1, "y"), Some(4));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicContainerRef<'a> {
+    data: &'a [u8],
+    schema: &'a Schema,
+}
+
+impl<'a> DynamicContainerRef<'a> {
+    /// Wrap `data`, laid out as consecutive structs described by `schema`.
+    pub fn new(data: &'a [u8], schema: &'a Schema) -> Self {
+        Self { data, schema }
+    }
+
+    /// Returns the number of structs in the data.
+    pub fn len(&self) -> usize {
+        self.schema.count(self.data.len())
+    }
+
+    /// Returns true if there are no structs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the type name.
+    pub fn type_name(&self) -> Result<&'a str> {
+        self.schema.type_name()
+    }
+
+    /// The schema describing the data.
+    pub fn schema(&self) -> &'a Schema {
+        self.schema
+    }
+
+    /// Immutable access to a field of a struct at index.
+    ///
+    /// Returns `None` if the field is missing, has a different size than `T`,
+    /// or is not aligned for `T` in memory.
+    pub fn field<T: Pod>(&self, index: usize, field_name: &str) -> Option<&'a T> {
+        let range = self
+            .schema
+            .field_range::<T>(self.data.len(), index, field_name)?;
+        try_from_bytes(self.data.get(range)?).ok()
+    }
+
+    /// Read a field of up to 64 bits as an unsigned integer.
+    ///
+    /// Works at bit granularity and ignores alignment, so it also reads
+    /// bit fields and unaligned data.
+    pub fn field_bits(&self, index: usize, field_name: &str) -> Option<u64> {
+        let (offset, size) = self.schema.bit_range(self.data.len(), index, field_name)?;
+        Some(read_bits(self.data, offset, size))
+    }
+
+    /// Get raw byte data.
+    pub fn raw(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Iterator over struct indices.
+    pub fn iter(&self) -> std::ops::Range<usize> {
+        0..self.len()
+    }
+}
+
 /// Iterator over the container structs (yields indices).
 pub struct DynamicContainerIter<'a> {
     container: &'a DynamicContainer,
@@ -374,4 +551,136 @@ mod tests {
         let y: &u32 = container.field(0, "y").unwrap();
         assert_eq!(*y, 0xDEADBEEF);
     }
+
+    #[test]
+    fn test_from_embedded() {
+        let blob = create_test_blob();
+        let mut bytes = vec![1u8, 0, 0, 0, 2, 0, 0, 0];
+        bytes.extend_from_slice(&blob);
+        bytes.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+
+        let container = DynamicContainer::from_embedded(bytes).unwrap();
+        assert_eq!(container.raw(), &[1, 0, 0, 0, 2, 0, 0, 0]);
+        assert_eq!(container.field::<u32>(0, "y"), Some(&2));
+        assert!(DynamicContainer::from_embedded(vec![9, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_dynamic_ref_borrows_bytes() {
+        let blob = create_test_blob();
+        let schema = Schema::parse(&blob).unwrap();
+        assert_eq!(schema.struct_size(), 8);
+        let mut names: Vec<_> = schema.field_names().collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["x", "y"]);
+
+        // Two structs plus a trailing partial one, which is ignored
+        let words: [u32; 5] = [1, 2, 3, 4, 0];
+        let bytes = &bytemuck::cast_slice::<u32, u8>(&words)[..18];
+        let view = DynamicContainerRef::new(bytes, &schema);
+        assert_eq!(view.len(), 2);
+        assert_eq!(view.type_name().unwrap(), "Test");
+        assert_eq!(view.field::<u32>(1, "x"), Some(&3));
+        assert_eq!(view.field_bits(1, "y"), Some(4));
+        assert_eq!(view.field::<u32>(2, "x"), None);
+        assert_eq!(view.field::<u64>(0, "x"), None);
+        assert_eq!(view.raw().as_ptr(), bytes.as_ptr());
+
+        // Misaligned borrows fall back to bit access
+        let shifted = DynamicContainerRef::new(&bytes[1..17], &schema);
+        assert_eq!(shifted.field::<u32>(0, "x"), None);
+        assert_eq!(shifted.field_bits(0, "x"), Some(0x0200_0000));
+
+        let container = DynamicContainer::from_raw(bytes[..16].to_vec(), &blob).unwrap();
+        let view = container.as_dynamic_ref();
+        assert_eq!(view.field::<u32>(0, "y"), Some(&2));
+        assert_eq!(view.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_bit_fields() {
+        use crate::{MTFBits, MTFType};
+
+        #[derive(Debug, PartialEq, MTFBits)]
+        struct Header {
+            #[mtf(bits = 3)]
+            version: u8,
+            #[mtf(bits = 1)]
+            ack: bool,
+            #[mtf(bits = 12)]
+            length: u16,
+            #[mtf(bits = 5)]
+            channel: u32,
+        }
+
+        assert_eq!(Header::PACKED_BITS, 21);
+        assert_eq!(Header::PACKED_BYTES, 3);
+        let (types, _) = read_mtf(Header::mtf_type_blob()).unwrap();
+        let offsets: Vec<_> = types[0]
+            .fields
+            .iter()
+            .map(|f| (f.offset_bits, f.size_bits))
+            .collect();
+        assert_eq!(offsets, vec![(0, 3), (3, 1), (4, 12), (16, 5)]);
+
+        let header = Header {
+            version: 5,
+            ack: true,
+            length: 1000,
+            channel: 17,
+        };
+        let bytes = header.pack().unwrap();
+
+        // Same layout as writing the fields in order with a BitWriter
+        let mut w = packed_bits::BitWriter::new();
+        w.write(5, 3).unwrap();
+        w.write_bool(true).unwrap();
+        w.write(1000, 12).unwrap();
+        w.write(17, 5).unwrap();
+        assert_eq!(bytes, w.finish());
+        assert_eq!(Header::unpack(&bytes).unwrap(), header);
+
+        let too_big = Header {
+            version: 8,
+            ..header
+        };
+        assert!(matches!(
+            too_big.pack(),
+            Err(MTFError::FieldOverflow("version", 3))
+        ));
+        assert!(Header::unpack(&bytes[..2]).is_err());
+
+        // Two packed records, accessed by name through the dynamic container
+        let mut data = bytes.clone();
+        data.extend(
+            Header {
+                version: 1,
+                ack: false,
+                length: 4095,
+                channel: 31,
+            }
+            .pack()
+            .unwrap(),
+        );
+        let mut container = DynamicContainer::from_raw(data, Header::mtf_type_blob()).unwrap();
+        assert_eq!(container.len(), 2);
+        assert_eq!(container.field_bits(0, "length"), Some(1000));
+        assert_eq!(container.field_bits(1, "length"), Some(4095));
+        assert_eq!(container.field_bits(1, "ack"), Some(0));
+        assert_eq!(container.field_bits(2, "length"), None);
+
+        assert!(container.set_field_bits(1, "channel", 3));
+        assert!(!container.set_field_bits(1, "channel", 32));
+        assert!(!container.set_field_bits(0, "missing", 0));
+        let second = Header::unpack(&container.raw()[3..]).unwrap();
+        assert_eq!(
+            second,
+            Header {
+                version: 1,
+                ack: false,
+                length: 4095,
+                channel: 3
+            }
+        );
+    }
 }
@@ -0,0 +1,113 @@
+// mtf_api/src/layout.rs
+
+//! Layout hashes for guarding on-disk struct layouts.
+//!
+//! A layout hash covers the type name, total size and every field's name,
+//! bit offset and bit size as recorded in the MTF schema. Pin the hash of each
+//! persisted type in a test so that accidental layout changes fail CI instead of
+//! silently corrupting existing files:
+//!
+//! ```ignore
+//! #[test]
+//! fn player_layout_is_stable() {
+//!     mtf_api::assert_layout_stable::<Player>(0x3c1f_9a2b_7d44_e801);
+//! }
+//! ```
+
+use mtf::{MTFError, MTFType, Result, read_mtf, read_string};
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Compute the layout hash of an MTF blob.
+///
+/// Only the first type in the blob is hashed (the one produced by `#[derive(MTF)]`).
+pub fn layout_hash_of_blob(blob: &[u8]) -> Result<u64> {
+    let (types, strings) = read_mtf(blob)?;
+    let type_def = types.first().ok_or(MTFError::UnexpectedEof)?;
+
+    let mut hash = fnv1a(
+        FNV_OFFSET,
+        read_string(strings, type_def.name_offset)?.as_bytes(),
+    );
+    hash = fnv1a(hash, &type_def.size_bits.to_le_bytes());
+    hash = fnv1a(hash, &(type_def.fields.len() as u32).to_le_bytes());
+    for field in &type_def.fields {
+        hash = fnv1a(hash, read_string(strings, field.name_offset)?.as_bytes());
+        hash = fnv1a(hash, &[0]);
+        hash = fnv1a(hash, &field.offset_bits.to_le_bytes());
+        hash = fnv1a(hash, &field.size_bits.to_le_bytes());
+    }
+    Ok(hash)
+}
+
+/// Compute the layout hash of `T` from its MTF schema.
+pub fn layout_hash<T: MTFType>() -> Result<u64> {
+    layout_hash_of_blob(T::mtf_type_blob())
+}
+
+/// Assert that the layout of `T` still hashes to `expected_hash`.
+///
+/// # Panics
+/// Panics with the actual hash if the layout changed, so the new value can be
+/// copied into the test once the change is intentional.
+pub fn assert_layout_stable<T: MTFType>(expected_hash: u64) {
+    let actual = layout_hash::<T>().expect("invalid MTF schema");
+    assert!(
+        actual == expected_hash,
+        "layout of `{}` changed: expected hash {:#018x}, found {:#018x}",
+        std::any::type_name::<T>(),
+        expected_hash,
+        actual
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mtf_derive::MTF;
+
+    #[repr(C)]
+    #[derive(MTF)]
+    #[allow(dead_code)]
+    struct Before {
+        x: f32,
+        y: f32,
+    }
+
+    mod renamed {
+        use mtf_derive::MTF;
+
+        #[repr(C)]
+        #[derive(MTF)]
+        #[allow(dead_code)]
+        pub struct Before {
+            pub x: f32,
+            pub z: f32,
+        }
+    }
+
+    #[test]
+    fn test_layout_hash_detects_changes() {
+        let hash = layout_hash::<Before>().unwrap();
+        assert_eq!(hash, layout_hash::<Before>().unwrap());
+        assert_ne!(hash, layout_hash::<renamed::Before>().unwrap());
+
+        assert_layout_stable::<Before>(hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "layout of")]
+    fn test_assert_layout_stable_panics() {
+        let hash = layout_hash::<Before>().unwrap();
+        assert_layout_stable::<renamed::Before>(hash);
+    }
+}
@@ -4,17 +4,25 @@
 //!
 //! Provides dynamic reflection and type-safe serialization for MTF-annotated types.
 
-pub use mtf::{MTFError, MTFType, Result};
-pub use mtf_derive::MTF;
+pub use mtf::{ByteSwap, MTFError, MTFType, Result};
+pub use mtf_derive::{ByteSwap, MTF, MTFBits};
 
 mod dynamic;
-pub use dynamic::{DynamicContainer, FieldHandle};
+pub use dynamic::{DynamicContainer, DynamicContainerRef, FieldHandle, Schema};
+
+mod layout;
+pub use layout::{assert_layout_stable, layout_hash, layout_hash_of_blob};
 
 use std::io::Write;
 
 /// Write a slice of MTF types with embedded metadata.
 ///
-/// Format: [DATA][METADATA_SIZE: u32][METADATA: complete MTF blob]
+/// Format: [DATA][METADATA: complete MTF blob][METADATA_SIZE: u32], as read by
+/// [`DynamicContainer::from_file`]. The size sits at a fixed position from the
+/// end, so readers locate the metadata without scanning.
+///
+/// Before 0.2 the size came first (`[DATA][METADATA_SIZE][METADATA]`);
+/// `from_file` still reads files in that layout.
 pub fn write_slice_with_mtf<T: MTFType + bytemuck::Pod>(
     mut out: impl Write,
     slice: &[T],
@@ -25,13 +33,11 @@ pub fn write_slice_with_mtf<T: MTFType + bytemuck::Pod>(
 
     // Get the complete MTF blob (includes magic, version, types, strings)
     let blob = T::mtf_type_blob();
+    out.write_all(blob)?;
 
-    // Write metadata size so readers know where it starts
+    // Write metadata size last so readers know where it starts
     let metadata_size = blob.len() as u32;
     out.write_all(&metadata_size.to_le_bytes())?;
 
-    // Write metadata
-    out.write_all(blob)?;
-
     Ok(())
 }
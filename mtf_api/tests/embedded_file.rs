@@ -0,0 +1,54 @@
+use bytemuck_derive::{Pod, Zeroable};
+use mtf_api::{DynamicContainer, MTF, MTFType, write_slice_with_mtf};
+use tempfile::TempDir;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable, MTF)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+#[test]
+fn write_slice_with_mtf_round_trips_through_from_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("points.bin");
+    let points = [Point { x: 1, y: 2 }, Point { x: 30, y: 40 }];
+    write_slice_with_mtf(std::fs::File::create(&path).unwrap(), &points).unwrap();
+
+    // [DATA][METADATA][METADATA_SIZE]
+    let bytes = std::fs::read(&path).unwrap();
+    let blob = Point::mtf_type_blob();
+    let size = u32::from_le_bytes(bytes[bytes.len() - 4..].try_into().unwrap());
+    assert_eq!(size as usize, blob.len());
+    assert_eq!(&bytes[16..16 + blob.len()], blob);
+
+    let container = DynamicContainer::from_file(&path).unwrap();
+    assert_eq!(container.len(), 2);
+    assert_eq!(container.type_name().unwrap(), "Point");
+    assert_eq!(container.field::<u32>(1, "x"), Some(&30));
+    assert_eq!(container.field::<u32>(1, "y"), Some(&40));
+    assert_eq!(container.raw(), bytemuck::cast_slice::<_, u8>(&points));
+}
+
+#[test]
+fn from_file_reads_pre_0_2_layout() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("legacy.bin");
+    let points = [Point { x: 5, y: 6 }, Point { x: 7, y: 8 }];
+
+    // [DATA][METADATA_SIZE][METADATA], as written before 0.2
+    let blob = Point::mtf_type_blob();
+    let mut bytes = bytemuck::cast_slice::<_, u8>(&points).to_vec();
+    bytes.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(blob);
+    std::fs::write(&path, &bytes).unwrap();
+
+    let container = DynamicContainer::from_file(&path).unwrap();
+    assert_eq!(container.len(), 2);
+    assert_eq!(container.field::<u32>(1, "y"), Some(&8));
+    assert_eq!(container.raw(), bytemuck::cast_slice::<_, u8>(&points));
+
+    let embedded = DynamicContainer::from_embedded(bytes).unwrap();
+    assert_eq!(embedded.field::<u32>(0, "x"), Some(&5));
+}
@@ -40,6 +40,43 @@ fn type_size_and_check(ty: &Type) -> Result<usize, String> {
     }
 }
 
+/// Build a single-type MTF blob from (field name, offset bits, size bits) entries.
+fn build_blob(name: &str, fields: &[(String, usize, usize)], size_bits: usize) -> Vec<u8> {
+    // Build string table: type name first, then field names
+    let mut strings = Vec::new();
+    let type_name_offset = 0u32;
+
+    strings.extend_from_slice(name.as_bytes());
+    strings.push(0);
+
+    let mut field_name_offsets = Vec::new();
+    for (fname, _, _) in fields {
+        let offset = strings.len() as u32;
+        field_name_offsets.push(offset);
+        strings.extend_from_slice(fname.as_bytes());
+        strings.push(0);
+    }
+
+    // [MAGIC][VERSION][TYPE_COUNT][TYPES...][STRING_TABLE_SIZE][STRING_TABLE]
+    let mut blob = Vec::new();
+    blob.extend_from_slice(b"MTF\0");
+    blob.extend_from_slice(&1u32.to_le_bytes()); // Version
+    blob.extend_from_slice(&1u32.to_le_bytes()); // Type count
+    blob.extend_from_slice(&type_name_offset.to_le_bytes()); // name_offset
+    blob.extend_from_slice(&(size_bits as u32).to_le_bytes()); // size_bits
+    blob.extend_from_slice(&(fields.len() as u32).to_le_bytes()); // field_count
+
+    for (i, (_fname, offset_bits, field_bits)) in fields.iter().enumerate() {
+        blob.extend_from_slice(&field_name_offsets[i].to_le_bytes());
+        blob.extend_from_slice(&(*offset_bits as u32).to_le_bytes());
+        blob.extend_from_slice(&(*field_bits as u32).to_le_bytes());
+    }
+
+    blob.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+    blob.extend_from_slice(&strings);
+    blob
+}
+
 /// Check if the type has #[repr(C)] or #[repr(C, packed)]
 fn check_repr_c(input: &DeriveInput) -> bool {
     input.attrs.iter().any(|attr| {
@@ -96,42 +133,16 @@ pub fn derive_mtf(input: TokenStream) -> TokenStream {
             .into();
     }
 
-    // Build string table: type name first, then field names
-    let mut strings = Vec::new();
-    let type_name_offset = 0u32;
-
-    strings.extend_from_slice(name.as_bytes());
-    strings.push(0);
-
-    let mut field_name_offsets = Vec::new();
-    for (fname, _) in &fields_info {
-        let offset = strings.len() as u32;
-        field_name_offsets.push(offset);
-        strings.extend_from_slice(fname.as_bytes());
-        strings.push(0);
-    }
-
-    // Build MTF blob (as before)...
-    // [MAGIC][VERSION][TYPE_COUNT][TYPES...][STRING_TABLE_SIZE][STRING_TABLE]
-    let mut blob = Vec::new();
-    blob.extend_from_slice(b"MTF\0");
-    blob.extend_from_slice(&1u32.to_le_bytes()); // Version
-    blob.extend_from_slice(&1u32.to_le_bytes()); // Type count
-    blob.extend_from_slice(&type_name_offset.to_le_bytes()); // name_offset
-    blob.extend_from_slice(&((total_size * 8) as u32).to_le_bytes()); // size_bits
-    blob.extend_from_slice(&(fields_info.len() as u32).to_le_bytes()); // field_count
-
     let mut offset_bits = 0usize;
-    for (i, (_fname, sz)) in fields_info.iter().enumerate() {
-        let name_off = field_name_offsets[i];
-        blob.extend_from_slice(&name_off.to_le_bytes());
-        blob.extend_from_slice(&(offset_bits as u32).to_le_bytes());
-        blob.extend_from_slice(&((sz * 8) as u32).to_le_bytes());
-        offset_bits += sz * 8;
-    }
-
-    blob.extend_from_slice(&(strings.len() as u32).to_le_bytes());
-    blob.extend_from_slice(&strings);
+    let layout: Vec<(String, usize, usize)> = fields_info
+        .into_iter()
+        .map(|(fname, sz)| {
+            let field = (fname, offset_bits, sz * 8);
+            offset_bits += sz * 8;
+            field
+        })
+        .collect();
+    let blob = build_blob(&name, &layout, total_size * 8);
 
     let blob_bytes = blob.iter().map(|b| quote! { #b }).collect::<Vec<_>>();
 
@@ -164,6 +175,206 @@ pub fn derive_mtf(input: TokenStream) -> TokenStream {
     expanded.into()
 }
 
+/// Parse `#[mtf(bits = N)]` from a field's attributes.
+fn field_bits_attr(field: &syn::Field) -> syn::Result<Option<usize>> {
+    let mut bits = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("mtf")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bits") {
+                let lit: syn::LitInt = meta.value()?.parse()?;
+                bits = Some(lit.base10_parse::<usize>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `bits = N`"))
+            }
+        })?;
+    }
+    Ok(bits)
+}
+
+/// Derive MTF metadata plus `pack`/`unpack` for a struct of bit fields.
+///
+/// Each field is an unsigned integer or `bool` stored in `#[mtf(bits = N)]`
+/// bits (default: the full width of its type). Fields are packed back to back,
+/// least-significant bit first, in declaration order, which is the layout a
+/// `packed_bits::BitWriter` produces when the fields are written in order. The
+/// MTF blob records the exact `offset_bits` and `size_bits` of every field, and
+/// the packed record is rounded up to whole bytes.
+///
+/// Generates `PACKED_BITS`, `PACKED_BYTES`, `pack(&self) -> mtf::Result<Vec<u8>>`
+/// (failing with `FieldOverflow` if a value does not fit) and
+/// `unpack(&[u8]) -> mtf::Result<Self>`.
+#[proc_macro_derive(MTFBits, attributes(mtf))]
+pub fn derive_mtf_bits(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_mtf_bits(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_mtf_bits(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let named = match &input.data {
+        Data::Struct(ds) => match &ds.fields {
+            Fields::Named(named) => named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "Only named fields supported",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Only structs supported",
+            ));
+        }
+    };
+
+    let mut layout = Vec::<(String, usize, usize)>::new();
+    let mut packs = Vec::new();
+    let mut unpacks = Vec::new();
+    let mut offset_bits = 0usize;
+    for f in named.named.iter() {
+        let fident = f.ident.as_ref().unwrap();
+        let fname = fident.to_string();
+        let ty = &f.ty;
+        let ty_name = match ty {
+            Type::Path(TypePath { path, .. }) => path.segments.last().unwrap().ident.to_string(),
+            _ => String::new(),
+        };
+        let is_bool = ty_name == "bool";
+        let type_bits = match ty_name.as_str() {
+            "bool" => 1,
+            "u8" | "u16" | "u32" | "u64" => primitive_size_bytes(&ty_name).unwrap() * 8,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "bit fields must be u8, u16, u32, u64 or bool",
+                ));
+            }
+        };
+        let bits = field_bits_attr(f)?.unwrap_or(type_bits);
+        if bits == 0 || bits > type_bits {
+            return Err(syn::Error::new_spanned(
+                f,
+                format!("`{}` needs 1..={} bits, got {}", fname, type_bits, bits),
+            ));
+        }
+
+        let overflow_check = if bits < type_bits {
+            let max = (1u64 << bits) - 1;
+            let bits_u32 = bits as u32;
+            quote! {
+                if value > #max {
+                    return Err(mtf::MTFError::FieldOverflow(#fname, #bits_u32));
+                }
+            }
+        } else {
+            quote! {}
+        };
+        packs.push(quote! {
+            let value = u64::from(self.#fident);
+            #overflow_check
+            mtf::write_bits(&mut out, #offset_bits, #bits, value);
+        });
+        let read = quote! { mtf::read_bits(bytes, #offset_bits, #bits) };
+        unpacks.push(if is_bool {
+            quote! { #fident: #read != 0 }
+        } else {
+            quote! { #fident: #read as #ty }
+        });
+
+        layout.push((fname, offset_bits, bits));
+        offset_bits += bits;
+    }
+
+    let name = input.ident.to_string();
+    let blob = build_blob(&name, &layout, offset_bits);
+    let blob_bytes = blob.iter().map(|b| quote! { #b });
+    let packed_bytes = offset_bits.div_ceil(8);
+    let ident = &input.ident;
+
+    Ok(quote! {
+        impl mtf::MTFType for #ident {
+            fn mtf_type_blob() -> &'static [u8] {
+                &[ #( #blob_bytes ),* ]
+            }
+
+            fn mtf_string_table() -> &'static [u8] {
+                &[]
+            }
+        }
+
+        impl #ident {
+            /// Number of meaningful bits in a packed record.
+            pub const PACKED_BITS: usize = #offset_bits;
+            /// Size of a packed record in bytes.
+            pub const PACKED_BYTES: usize = #packed_bytes;
+
+            /// Pack the fields into `PACKED_BYTES` bytes.
+            pub fn pack(&self) -> mtf::Result<Vec<u8>> {
+                let mut out = vec![0u8; #packed_bytes];
+                #( #packs )*
+                Ok(out)
+            }
+
+            /// Unpack a record from the first `PACKED_BYTES` bytes of `bytes`.
+            pub fn unpack(bytes: &[u8]) -> mtf::Result<Self> {
+                if bytes.len() < #packed_bytes {
+                    return Err(mtf::MTFError::UnexpectedEof);
+                }
+                Ok(Self { #( #unpacks ),* })
+            }
+        }
+    })
+}
+
+/// Derive `mtf::ByteSwap`, reversing the byte order of every field in place.
+///
+/// Every field must implement `mtf::ByteSwap` itself: primitives, arrays of them,
+/// or nested structs with this derive. Fields are copied out and back, so
+/// `#[repr(C, packed)]` structs are supported.
+#[proc_macro_derive(ByteSwap)]
+pub fn derive_byte_swap(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let fields = match &input.data {
+        Data::Struct(ds) => &ds.fields,
+        _ => {
+            return syn::Error::new_spanned(&input.ident, "Only structs supported")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let swaps = fields.iter().enumerate().map(|(i, f)| {
+        let member = match &f.ident {
+            Some(ident) => quote! { #ident },
+            None => {
+                let index = syn::Index::from(i);
+                quote! { #index }
+            }
+        };
+        quote! {
+            let mut value = self.#member;
+            mtf::ByteSwap::byte_swap(&mut value);
+            self.#member = value;
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics mtf::ByteSwap for #ident #ty_generics #where_clause {
+            fn byte_swap(&mut self) {
+                #( #swaps )*
+            }
+        }
+    }
+    .into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,259 @@
+//! Sequential bit streams with a per-field width.
+//!
+//! Unlike [`PackedBits<N>`](crate::PackedBits), every field written to a
+//! [`BitWriter`] chooses its own width, so mixed-width protocol frames can be
+//! built field by field. Bits are packed least-significant first, the same
+//! layout `PackedBits` uses, and [`BitReader`] reads them back in order.
+//!
+//! ```
+//! use packed_bits::{BitReader, BitWriter};
+//!
+//! let mut w = BitWriter::new();
+//! w.write(5, 3).unwrap(); // 3-bit opcode
+//! w.write_bool(true).unwrap(); // flag
+//! w.write(1000, 12).unwrap(); // 12-bit length
+//! w.write_bytes(b"hi"); // byte-aligned payload
+//! let frame = w.finish();
+//!
+//! let mut r = BitReader::new(&frame);
+//! assert_eq!(r.read(3).unwrap(), 5);
+//! assert!(r.read_bool().unwrap());
+//! assert_eq!(r.read(12).unwrap(), 1000);
+//! assert_eq!(r.read_bytes(2).unwrap(), b"hi");
+//! ```
+
+use crate::PackedBitsError;
+
+fn check_width(bits: usize) -> Result<(), PackedBitsError> {
+    if bits == 0 || bits > 32 {
+        return Err(PackedBitsError::InvalidBitWidth(bits));
+    }
+    Ok(())
+}
+
+fn mask(bits: usize) -> u64 {
+    (1u64 << bits) - 1
+}
+
+/// Appends fields of arbitrary width (1..=32 bits) to a byte buffer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitWriter {
+    data: Vec<u8>,
+    bit_len: usize,
+}
+
+impl BitWriter {
+    /// Creates an empty writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty writer with room for `bytes` bytes.
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(bytes),
+            bit_len: 0,
+        }
+    }
+
+    /// Appends the low `bits` bits of `value` (must fit in `bits` bits).
+    pub fn write(&mut self, value: u32, bits: usize) -> Result<(), PackedBitsError> {
+        check_width(bits)?;
+        if value as u64 > mask(bits) {
+            return Err(PackedBitsError::ValueOverflow(value, bits));
+        }
+
+        let byte_pos = self.bit_len / 8;
+        let bit_offset = self.bit_len % 8;
+        self.bit_len += bits;
+        self.data.resize(self.bit_len.div_ceil(8), 0);
+
+        let v = (value as u64) << bit_offset;
+        for i in 0..(bits + bit_offset).div_ceil(8) {
+            self.data[byte_pos + i] |= ((v >> (i * 8)) & 0xFF) as u8;
+        }
+        Ok(())
+    }
+
+    /// Appends a single bit.
+    pub fn write_bool(&mut self, value: bool) -> Result<(), PackedBitsError> {
+        self.write(value as u32, 1)
+    }
+
+    /// Pads with zero bits up to the next byte boundary.
+    pub fn align(&mut self) {
+        self.bit_len = self.data.len() * 8;
+    }
+
+    /// Aligns, then appends `bytes` unchanged.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.align();
+        self.data.extend_from_slice(bytes);
+        self.bit_len = self.data.len() * 8;
+    }
+
+    /// Returns the number of bits written, including alignment padding.
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Returns true if the next field starts on a byte boundary.
+    pub fn is_aligned(&self) -> bool {
+        self.bit_len.is_multiple_of(8)
+    }
+
+    /// Returns the bytes written so far; a partial last byte is zero-padded.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the buffer, with any partial last byte zero-padded.
+    pub fn finish(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Reads fields back from a buffer produced by [`BitWriter`].
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    bit_pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Creates a reader positioned at the first bit of `data`.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, bit_pos: 0 }
+    }
+
+    /// Reads the next `bits`-bit field.
+    pub fn read(&mut self, bits: usize) -> Result<u32, PackedBitsError> {
+        check_width(bits)?;
+        if bits > self.remaining_bits() {
+            return Err(PackedBitsError::EndOfStream(bits, self.bit_pos));
+        }
+
+        let byte_pos = self.bit_pos / 8;
+        let bit_offset = self.bit_pos % 8;
+        let mut val: u64 = 0;
+        for i in 0..(bits + bit_offset).div_ceil(8) {
+            val |= (self.data[byte_pos + i] as u64) << (i * 8);
+        }
+        self.bit_pos += bits;
+        Ok(((val >> bit_offset) & mask(bits)) as u32)
+    }
+
+    /// Reads a single bit.
+    pub fn read_bool(&mut self) -> Result<bool, PackedBitsError> {
+        Ok(self.read(1)? != 0)
+    }
+
+    /// Skips to the next byte boundary, mirroring [`BitWriter::align`].
+    pub fn align(&mut self) {
+        self.bit_pos = self.bit_pos.next_multiple_of(8).min(self.data.len() * 8);
+    }
+
+    /// Aligns, then reads `len` whole bytes, mirroring [`BitWriter::write_bytes`].
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], PackedBitsError> {
+        self.align();
+        let start = self.bit_pos / 8;
+        let end = PackedBitsError::EndOfStream(len.saturating_mul(8), self.bit_pos);
+        let bytes = self.data.get(start..start.saturating_add(len)).ok_or(end)?;
+        self.bit_pos += len * 8;
+        Ok(bytes)
+    }
+
+    /// Returns the position of the next bit to read.
+    pub fn bit_position(&self) -> usize {
+        self.bit_pos
+    }
+
+    /// Returns the number of unread bits, including any trailing padding.
+    pub fn remaining_bits(&self) -> usize {
+        self.data.len() * 8 - self.bit_pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_widths_round_trip() {
+        let fields = [
+            (1u32, 1),
+            (0x1F, 5),
+            (0xABCDE, 20),
+            (u32::MAX, 32),
+            (3, 2),
+            (0, 7),
+        ];
+        let mut w = BitWriter::new();
+        for &(value, bits) in &fields {
+            w.write(value, bits).unwrap();
+        }
+        assert_eq!(w.bit_len(), 67);
+        assert!(!w.is_aligned());
+        let bytes = w.finish();
+        assert_eq!(bytes.len(), 9);
+
+        let mut r = BitReader::new(&bytes);
+        for &(value, bits) in &fields {
+            assert_eq!(r.read(bits).unwrap(), value);
+        }
+        assert_eq!(r.remaining_bits(), 5);
+        assert!(matches!(
+            r.read(6),
+            Err(PackedBitsError::EndOfStream(6, 67))
+        ));
+    }
+
+    #[test]
+    fn test_layout_matches_packed_bits() {
+        let mut bits = crate::PackedBits::<5>::new().unwrap();
+        let mut w = BitWriter::new();
+        for v in [3, 17, 31, 0, 9] {
+            bits.push(v).unwrap();
+            w.write(v, 5).unwrap();
+        }
+        assert_eq!(w.as_bytes(), bits.as_bytes());
+    }
+
+    #[test]
+    fn test_alignment_and_bytes() {
+        let mut w = BitWriter::new();
+        w.write(0b101, 3).unwrap();
+        w.write_bytes(&[0xAA, 0xBB]);
+        w.write_bool(true).unwrap();
+        w.align();
+        assert_eq!(w.bit_len(), 32);
+        assert_eq!(w.as_bytes(), &[0b101, 0xAA, 0xBB, 1]);
+
+        let bytes = w.finish();
+        let mut r = BitReader::new(&bytes);
+        assert_eq!(r.read(3).unwrap(), 0b101);
+        assert_eq!(r.read_bytes(2).unwrap(), &[0xAA, 0xBB]);
+        assert!(r.read_bool().unwrap());
+        r.align();
+        assert_eq!(r.remaining_bits(), 0);
+        assert!(r.read_bytes(1).is_err());
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let mut w = BitWriter::new();
+        assert!(matches!(
+            w.write(8, 3),
+            Err(PackedBitsError::ValueOverflow(8, 3))
+        ));
+        assert!(matches!(
+            w.write(0, 0),
+            Err(PackedBitsError::InvalidBitWidth(0))
+        ));
+        assert!(matches!(
+            w.write(0, 33),
+            Err(PackedBitsError::InvalidBitWidth(33))
+        ));
+        assert_eq!(w.bit_len(), 0);
+    }
+}
@@ -14,6 +14,12 @@ pub enum PackedBitsError {
     #[error("Insufficient bytes for {0} elements")]
     InsufficientBytes(usize),
 
+    #[error("Length mismatch: expected {0}, got {1}")]
+    LengthMismatch(usize, usize),
+
+    #[error("Cannot read {0} bits past the end of the stream at bit {1}")]
+    EndOfStream(usize, usize),
+
     #[error("Unexpected error")]
     Unexpected,
 }
@@ -0,0 +1,217 @@
+//! Conversions between `PackedBits<1>` and external bitmap types.
+//!
+//! `PackedBits<1>` stores bit `i` in byte `i / 8` at bit position `i % 8`, which
+//! is exactly the layout of `BitVec<u8, Lsb0>`, so those conversions hand the
+//! byte buffer over without repacking.
+
+use crate::{Encoding, PackedBits};
+
+impl PackedBits<1> {
+    /// Iterate over the indices of all set bits, in ascending order.
+    pub fn ones(&self) -> Ones<'_> {
+        match self.encoding {
+            Encoding::Packed => Ones::packed(&self.data[..self.len.div_ceil(8)], self.len),
+            Encoding::ByteAligned => Ones::byte_aligned(&self.data[..self.len], self.len),
+        }
+    }
+}
+
+/// Iterator over the indices of set bits, in ascending order.
+///
+/// Returned by [`PackedBits::ones`], and shared with `packed_bits_container`,
+/// whose `N = 1` data has the same layout.
+#[derive(Debug, Clone)]
+pub struct Ones<'a> {
+    bytes: std::iter::Enumerate<std::slice::Iter<'a, u8>>,
+    /// Elements per byte: 8 when packed, 1 when byte-aligned
+    bits_per_byte: usize,
+    /// Index of bit 0 of `word`
+    base: usize,
+    /// Set bits of the current byte not yet yielded
+    word: u8,
+    len: usize,
+}
+
+impl<'a> Ones<'a> {
+    /// Set bits among the first `len` bits of `data`, bit `i` in byte `i / 8`
+    /// at bit position `i % 8`.
+    pub fn packed(data: &'a [u8], len: usize) -> Self {
+        Self {
+            bytes: data.iter().enumerate(),
+            bits_per_byte: 8,
+            base: 0,
+            word: 0,
+            len,
+        }
+    }
+
+    /// Set bits among the first `len` elements of `data`, one element per byte.
+    pub fn byte_aligned(data: &'a [u8], len: usize) -> Self {
+        Self {
+            bytes: data.iter().enumerate(),
+            bits_per_byte: 1,
+            base: 0,
+            word: 0,
+            len,
+        }
+    }
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            let (i, &byte) = self.bytes.next()?;
+            self.base = i * self.bits_per_byte;
+            self.word = if self.bits_per_byte == 8 {
+                byte
+            } else {
+                (byte != 0) as u8
+            };
+        }
+        let idx = self.base + self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        if idx >= self.len {
+            // Padding bits past the end of the last byte
+            self.word = 0;
+            return None;
+        }
+        Some(idx)
+    }
+}
+
+impl std::iter::FusedIterator for Ones<'_> {}
+
+#[cfg(feature = "bitvec")]
+mod bitvec_impls {
+    use crate::{Encoding, PackedBits};
+    use bitvec::{order::Lsb0, vec::BitVec};
+
+    impl From<PackedBits<1>> for BitVec<u8, Lsb0> {
+        fn from(bits: PackedBits<1>) -> Self {
+            let bits = match bits.encoding {
+                Encoding::Packed => bits,
+                Encoding::ByteAligned => bits.reencode(Encoding::Packed),
+            };
+            let (data, len) = bits.into_bytes();
+            let mut bv = BitVec::from_vec(data);
+            bv.truncate(len);
+            bv
+        }
+    }
+
+    impl From<&PackedBits<1>> for BitVec<u8, Lsb0> {
+        fn from(bits: &PackedBits<1>) -> Self {
+            bits.clone().into()
+        }
+    }
+
+    impl From<BitVec<u8, Lsb0>> for PackedBits<1> {
+        fn from(mut bv: BitVec<u8, Lsb0>) -> Self {
+            let len = bv.len();
+            // Bits past `len` in the last byte must be zero, since `push` ORs into it.
+            bv.set_uninitialized(false);
+            Self {
+                data: bv.into_vec(),
+                len,
+                encoding: Encoding::Packed,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "roaring")]
+mod roaring_impls {
+    use crate::{Encoding, PackedBits, PackedBitsError};
+    use roaring::RoaringBitmap;
+
+    impl From<&PackedBits<1>> for RoaringBitmap {
+        /// # Panics
+        /// Panics if a set bit lies beyond `u32::MAX`, which roaring cannot represent.
+        fn from(bits: &PackedBits<1>) -> Self {
+            RoaringBitmap::from_sorted_iter(
+                bits.ones()
+                    .map(|idx| u32::try_from(idx).expect("bit index exceeds u32::MAX")),
+            )
+            .expect("set bits are yielded in ascending order")
+        }
+    }
+
+    impl PackedBits<1> {
+        /// Create a bitmap of `len` bits with the bits in `bitmap` set.
+        ///
+        /// Returns an error if `bitmap` contains an index `>= len`.
+        pub fn from_roaring(bitmap: &RoaringBitmap, len: usize) -> Result<Self, PackedBitsError> {
+            if let Some(max) = bitmap.max().filter(|&max| max as usize >= len) {
+                return Err(PackedBitsError::IndexOutOfBounds(max as usize, len));
+            }
+            let mut data = vec![0u8; len.div_ceil(8)];
+            for idx in bitmap {
+                data[idx as usize / 8] |= 1 << (idx % 8);
+            }
+            Ok(Self {
+                data,
+                len,
+                encoding: Encoding::Packed,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PackedBits;
+
+    fn sample() -> PackedBits<1> {
+        let mut bits = PackedBits::<1>::new().unwrap();
+        for i in 0..21 {
+            bits.push((i % 3 == 0) as u32).unwrap();
+        }
+        bits
+    }
+
+    #[test]
+    fn test_ones() {
+        let ones: Vec<_> = sample().ones().collect();
+        assert_eq!(ones, vec![0, 3, 6, 9, 12, 15, 18]);
+
+        let aligned = sample().reencode(crate::Encoding::ByteAligned);
+        assert_eq!(aligned.ones().collect::<Vec<_>>(), ones);
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn test_bitvec_round_trip() {
+        use bitvec::{order::Lsb0, vec::BitVec};
+
+        let bits = sample();
+        let bv: BitVec<u8, Lsb0> = (&bits).into();
+        assert_eq!(bv.len(), 21);
+        assert!(bv[3] && !bv[4]);
+
+        let mut back: PackedBits<1> = bv.into();
+        assert_eq!(back, bits);
+        back.push(1).unwrap();
+        assert_eq!(back.get(21), Some(1));
+        assert_eq!(back.get(20), Some(0));
+    }
+
+    #[cfg(feature = "roaring")]
+    #[test]
+    fn test_roaring_round_trip() {
+        use roaring::RoaringBitmap;
+
+        let bits = sample();
+        let bitmap = RoaringBitmap::from(&bits);
+        assert_eq!(bitmap.len(), 7);
+        assert!(bitmap.contains(18));
+
+        let back = PackedBits::<1>::from_roaring(&bitmap, 21).unwrap();
+        assert_eq!(
+            back.iter().collect::<Vec<_>>(),
+            bits.iter().collect::<Vec<_>>()
+        );
+        assert!(PackedBits::<1>::from_roaring(&bitmap, 10).is_err());
+    }
+}
@@ -24,24 +24,54 @@
 //! ```
 
 //use packed_bits::PackedBitsError;
+use std::ops::{Bound, Range, RangeBounds};
+
+mod bitstream;
 mod error;
+mod interop;
+mod nullable;
+#[doc(hidden)]
+pub mod widths;
+pub use bitstream::{BitReader, BitWriter};
 pub use error::PackedBitsError;
+pub use interop::Ones;
+pub use nullable::NullablePackedBits;
+
+/// How elements are laid out in the byte buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Elements are packed back to back using exactly `N` bits each.
+    #[default]
+    Packed,
+    /// Each element occupies `ceil(N / 8)` whole bytes (little-endian).
+    ///
+    /// Trades up to 7 bits of padding per element for plain byte loads and
+    /// stores in `get`/`set`, with no shifting or masking across byte boundaries.
+    ByteAligned,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PackedBits<const N: usize> {
     data: Vec<u8>,
     len: usize, // number of N-bit elements
+    encoding: Encoding,
 }
 
 impl<const N: usize> PackedBits<N> {
     /// Creates an empty container.
     pub fn new() -> Result<Self, PackedBitsError> {
+        Self::with_encoding(Encoding::Packed)
+    }
+
+    /// Creates an empty container using the given element encoding.
+    pub fn with_encoding(encoding: Encoding) -> Result<Self, PackedBitsError> {
         if N == 0 || N > 32 {
             return Err(PackedBitsError::InvalidBitWidth(N));
         }
         Ok(Self {
             data: Vec::new(),
             len: 0,
+            encoding,
         })
     }
 
@@ -54,6 +84,7 @@ impl<const N: usize> PackedBits<N> {
         Ok(Self {
             data: Vec::with_capacity(byte_capacity),
             len: 0,
+            encoding: Encoding::Packed,
         })
     }
 
@@ -61,14 +92,53 @@ impl<const N: usize> PackedBits<N> {
     ///
     /// Useful for deserialization.
     pub fn from_bytes(data: Vec<u8>, len: usize) -> Result<Self, PackedBitsError> {
+        Self::from_bytes_with_encoding(data, len, Encoding::Packed)
+    }
+
+    /// Creates a new container from a raw byte buffer laid out with `encoding`.
+    pub fn from_bytes_with_encoding(
+        data: Vec<u8>,
+        len: usize,
+        encoding: Encoding,
+    ) -> Result<Self, PackedBitsError> {
         if N == 0 || N > 32 {
             return Err(PackedBitsError::InvalidBitWidth(N));
         }
-        let min_bytes = (len * N).div_ceil(8);
+        let min_bytes = (len * Self::stride_bits(encoding)).div_ceil(8);
         if data.len() < min_bytes {
             return Err(PackedBitsError::InsufficientBytes(len));
         }
-        Ok(Self { data, len })
+        Ok(Self {
+            data,
+            len,
+            encoding,
+        })
+    }
+
+    /// Returns the element encoding of this container.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Returns a copy of this container re-encoded with `encoding`.
+    pub fn reencode(&self, encoding: Encoding) -> Self {
+        if encoding == self.encoding {
+            return self.clone();
+        }
+        let mut out = Self::with_encoding(encoding).expect("bit width already validated");
+        out.reserve(self.len);
+        for v in self.iter() {
+            out.push(v).expect("value already fits in N bits");
+        }
+        out
+    }
+
+    /// Number of bits each element occupies in the buffer.
+    fn stride_bits(encoding: Encoding) -> usize {
+        match encoding {
+            Encoding::Packed => N,
+            Encoding::ByteAligned => N.div_ceil(8) * 8,
+        }
     }
 
     /// Returns a reference to the underlying byte slice.
@@ -82,7 +152,7 @@ impl<const N: usize> PackedBits<N> {
 
     /// Reserves capacity for at least `additional` more elements.
     pub fn reserve(&mut self, additional: usize) {
-        let total_bits = (self.len + additional) * N;
+        let total_bits = (self.len + additional) * Self::stride_bits(self.encoding);
         let required_bytes = total_bits.div_ceil(8);
         if self.data.capacity() < required_bytes {
             self.data.reserve(required_bytes - self.data.len());
@@ -96,6 +166,18 @@ impl<const N: usize> PackedBits<N> {
             return Err(PackedBitsError::ValueOverflow(value, N));
         }
 
+        if self.encoding == Encoding::ByteAligned {
+            let width = N.div_ceil(8);
+            let start = self.len * width;
+            if self.data.len() < start + width {
+                self.data.resize(start + width, 0);
+            }
+            let bytes = value.to_le_bytes();
+            self.data[start..start + width].copy_from_slice(&bytes[..width]);
+            self.len += 1;
+            return Ok(());
+        }
+
         let bit_pos = self.len * N;
         let byte_pos = bit_pos / 8;
         let bit_offset = bit_pos % 8;
@@ -106,12 +188,26 @@ impl<const N: usize> PackedBits<N> {
             self.data.resize(required_bytes, 0);
         }
 
-        let v = (value as u64) << bit_offset;
-        let num_bytes = (N + bit_offset).div_ceil(8);
-        debug_assert!(num_bytes <= 5);
+        if widths::specialized::<N>() {
+            widths::set::<N>(&mut self.data, self.len, value);
+            self.len += 1;
+            return Ok(());
+        }
 
-        for i in 0..num_bytes {
-            self.data[byte_pos + i] |= ((v >> (i * 8)) & 0xFF) as u8;
+        // One word store when 8 bytes are available, byte-wise near the end
+        let v = (value as u64) << bit_offset;
+        match Self::word_mut(&mut self.data, byte_pos) {
+            Some(word) => {
+                let w = u64::from_le_bytes(*word) | v;
+                *word = w.to_le_bytes();
+            }
+            None => {
+                let num_bytes = (N + bit_offset).div_ceil(8);
+                debug_assert!(num_bytes <= 5);
+                for i in 0..num_bytes {
+                    self.data[byte_pos + i] |= ((v >> (i * 8)) & 0xFF) as u8;
+                }
+            }
         }
 
         self.len += 1;
@@ -124,10 +220,36 @@ impl<const N: usize> PackedBits<N> {
             return None;
         }
 
+        if self.encoding == Encoding::ByteAligned {
+            let width = N.div_ceil(8);
+            let mut bytes = [0u8; 4];
+            bytes[..width].copy_from_slice(&self.data[index * width..(index + 1) * width]);
+            return Some(u32::from_le_bytes(bytes));
+        }
+
+        if widths::specialized::<N>() {
+            return Some(widths::get::<N>(&self.data, index));
+        }
+
         let bit_pos = index * N;
         let byte_pos = bit_pos / 8;
         let bit_offset = bit_pos % 8;
 
+        let val = match Self::word(&self.data, byte_pos) {
+            Some(word) => {
+                let val = u64::from_le_bytes(word) >> bit_offset;
+                debug_assert_eq!(val & Self::mask(), self.get_bytewise(byte_pos, bit_offset));
+                val
+            }
+            None => self.get_bytewise(byte_pos, bit_offset),
+        };
+        Some((val & Self::mask()) as u32)
+    }
+
+    /// Slow path of [`get`](Self::get): assembles the value byte by byte.
+    ///
+    /// Used near the end of the buffer, where a whole word cannot be loaded.
+    fn get_bytewise(&self, byte_pos: usize, bit_offset: usize) -> u64 {
         let mut val: u64 = 0;
         let num_bytes = (N + bit_offset).div_ceil(8);
         debug_assert!(num_bytes <= 5);
@@ -138,14 +260,30 @@ impl<const N: usize> PackedBits<N> {
             }
         }
 
-        val >>= bit_offset;
-        let mask = if N == 32 {
-            u32::MAX as u64
-        } else {
-            (1u64 << N) - 1
-        };
+        (val >> bit_offset) & Self::mask()
+    }
+
+    /// Mask of the low `N` bits.
+    fn mask() -> u64 {
+        if N >= 64 { u64::MAX } else { (1u64 << N) - 1 }
+    }
 
-        Some((val & mask) as u32)
+    /// The 8 bytes at `byte_pos`, or `None` if they run past the end of `data`.
+    ///
+    /// Always `None` under miri, which keeps the byte-wise paths covered there.
+    fn word(data: &[u8], byte_pos: usize) -> Option<[u8; 8]> {
+        if cfg!(miri) {
+            return None;
+        }
+        data.get(byte_pos..byte_pos + 8)?.try_into().ok()
+    }
+
+    /// Mutable version of [`word`](Self::word).
+    fn word_mut(data: &mut [u8], byte_pos: usize) -> Option<&mut [u8; 8]> {
+        if cfg!(miri) {
+            return None;
+        }
+        data.get_mut(byte_pos..byte_pos + 8)?.try_into().ok()
     }
 
     /// Sets the value at the specified index.
@@ -159,12 +297,30 @@ impl<const N: usize> PackedBits<N> {
             return Err(PackedBitsError::ValueOverflow(value, N));
         }
 
+        if self.encoding == Encoding::ByteAligned {
+            let width = N.div_ceil(8);
+            let bytes = value.to_le_bytes();
+            self.data[index * width..(index + 1) * width].copy_from_slice(&bytes[..width]);
+            return Ok(());
+        }
+
+        if widths::specialized::<N>() {
+            widths::set::<N>(&mut self.data, index, value);
+            return Ok(());
+        }
+
         let bit_pos = index * N;
         let byte_pos = bit_pos / 8;
         let bit_offset = bit_pos % 8;
 
         let v = (value as u64) << bit_offset;
 
+        if let Some(word) = Self::word_mut(&mut self.data, byte_pos) {
+            let w = u64::from_le_bytes(*word) & !(Self::mask() << bit_offset) | v;
+            *word = w.to_le_bytes();
+            return Ok(());
+        }
+
         let mask: u64 = if N == 32 && bit_offset == 0 {
             u32::MAX as u64
         } else if N + bit_offset >= 64 {
@@ -205,7 +361,7 @@ impl<const N: usize> PackedBits<N> {
 
     /// Returns the capacity in elements before reallocation.
     pub fn capacity(&self) -> usize {
-        (self.data.capacity() * 8) / N
+        (self.data.capacity() * 8) / Self::stride_bits(self.encoding)
     }
 
     /// Returns an iterator over the packed values.
@@ -216,6 +372,84 @@ impl<const N: usize> PackedBits<N> {
         }
     }
 
+    /// Copies the values in `range` into `out`.
+    ///
+    /// `out` must be exactly `range.len()` elements long.
+    pub fn copy_to_slice(
+        &self,
+        range: impl RangeBounds<usize>,
+        out: &mut [u32],
+    ) -> Result<(), PackedBitsError> {
+        let range = self.checked_range(range, out.len())?;
+        for (dst, i) in out.iter_mut().zip(range) {
+            *dst = self.get(i).expect("index checked against len");
+        }
+        Ok(())
+    }
+
+    /// Copies the values in `range` into `out`, narrowing to `u8`.
+    ///
+    /// Returns [`PackedBitsError::ValueOverflow`] if a value does not fit in 8 bits;
+    /// `out` may be partially written in that case.
+    pub fn copy_to_u8(
+        &self,
+        range: impl RangeBounds<usize>,
+        out: &mut [u8],
+    ) -> Result<(), PackedBitsError> {
+        let range = self.checked_range(range, out.len())?;
+        for (dst, i) in out.iter_mut().zip(range) {
+            let v = self.get(i).expect("index checked against len");
+            *dst = u8::try_from(v).map_err(|_| PackedBitsError::ValueOverflow(v, 8))?;
+        }
+        Ok(())
+    }
+
+    /// Copies the values in `range` into `out`, narrowing to `u16`.
+    ///
+    /// Returns [`PackedBitsError::ValueOverflow`] if a value does not fit in 16 bits;
+    /// `out` may be partially written in that case.
+    pub fn copy_to_u16(
+        &self,
+        range: impl RangeBounds<usize>,
+        out: &mut [u16],
+    ) -> Result<(), PackedBitsError> {
+        let range = self.checked_range(range, out.len())?;
+        for (dst, i) in out.iter_mut().zip(range) {
+            let v = self.get(i).expect("index checked against len");
+            *dst = u16::try_from(v).map_err(|_| PackedBitsError::ValueOverflow(v, 16))?;
+        }
+        Ok(())
+    }
+
+    /// Resolves `range` against `len` and checks it matches an output of `out_len`.
+    fn checked_range(
+        &self,
+        range: impl RangeBounds<usize>,
+        out_len: usize,
+    ) -> Result<Range<usize>, PackedBitsError> {
+        let out_of_bounds = |index| PackedBitsError::IndexOutOfBounds(index, self.len);
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s.checked_add(1).ok_or(out_of_bounds(s))?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e.checked_add(1).ok_or(out_of_bounds(e))?,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => self.len,
+        };
+        if end > self.len {
+            return Err(out_of_bounds(end));
+        }
+        if start > end {
+            return Err(out_of_bounds(start));
+        }
+        if end - start != out_len {
+            return Err(PackedBitsError::LengthMismatch(end - start, out_len));
+        }
+        Ok(start..end)
+    }
+
     /// Extends the container with multiple values.
     pub fn extend_from_slice(&mut self, values: &[u32]) -> Result<(), PackedBitsError> {
         for &v in values {
@@ -329,6 +563,87 @@ mod tests {
         matches!(err, PackedBitsError::ValueOverflow(_, _));
     }
 
+    #[test]
+    fn test_byte_aligned_encoding() {
+        let mut bits = PackedBits::<12>::with_encoding(Encoding::ByteAligned).unwrap();
+        bits.extend_from_slice(&[0xABC, 0x123, 0xFFF]).unwrap();
+        assert_eq!(bits.as_bytes().len(), 6);
+        assert_eq!(bits.get(1), Some(0x123));
+
+        bits.set(1, 0x456).unwrap();
+        assert_eq!(bits.iter().collect::<Vec<_>>(), vec![0xABC, 0x456, 0xFFF]);
+        assert!(bits.push(0x1000).is_err());
+
+        let packed = bits.reencode(Encoding::Packed);
+        assert_eq!(packed.encoding(), Encoding::Packed);
+        assert_eq!(packed.as_bytes().len(), 5);
+        assert_eq!(packed.iter().collect::<Vec<_>>(), vec![0xABC, 0x456, 0xFFF]);
+
+        let (data, len) = bits.into_bytes();
+        let restored =
+            PackedBits::<12>::from_bytes_with_encoding(data, len, Encoding::ByteAligned).unwrap();
+        assert_eq!(restored.get(2), Some(0xFFF));
+    }
+
+    #[test]
+    fn test_byte_aligned_push_with_spare_bytes() {
+        // Buffer holds room for four 12-bit elements but only two are in use
+        let mut bits = PackedBits::<12>::from_bytes_with_encoding(
+            vec![1, 0, 2, 0, 0, 0, 0, 0],
+            2,
+            Encoding::ByteAligned,
+        )
+        .unwrap();
+        bits.push(7).unwrap();
+        assert_eq!(bits.get(2), Some(7));
+        assert_eq!(bits.as_bytes().len(), 8);
+        assert_eq!(bits.iter().collect::<Vec<_>>(), vec![1, 2, 7]);
+    }
+
+    #[test]
+    fn test_copy_to_slice() {
+        let mut bits = PackedBits::<10>::new().unwrap();
+        bits.extend_from_slice(&[1, 200, 1000, 3, 4]).unwrap();
+
+        let mut out = [0u32; 3];
+        bits.copy_to_slice(1..4, &mut out).unwrap();
+        assert_eq!(out, [200, 1000, 3]);
+
+        let mut all = [0u16; 5];
+        bits.copy_to_u16(.., &mut all).unwrap();
+        assert_eq!(all, [1, 200, 1000, 3, 4]);
+
+        let mut narrow = [0u8; 2];
+        bits.copy_to_u8(3.., &mut narrow).unwrap();
+        assert_eq!(narrow, [3, 4]);
+        assert!(matches!(
+            bits.copy_to_u8(1..3, &mut narrow),
+            Err(PackedBitsError::ValueOverflow(1000, 8))
+        ));
+
+        assert!(matches!(
+            bits.copy_to_slice(4..6, &mut [0u32; 2]),
+            Err(PackedBitsError::IndexOutOfBounds(6, 5))
+        ));
+        assert!(matches!(
+            bits.copy_to_slice(..=usize::MAX, &mut []),
+            Err(PackedBitsError::IndexOutOfBounds(usize::MAX, 5))
+        ));
+        assert!(matches!(
+            bits.copy_to_slice((Bound::Excluded(usize::MAX), Bound::Unbounded), &mut []),
+            Err(PackedBitsError::IndexOutOfBounds(usize::MAX, 5))
+        ));
+        // A start past the end names the start, not the end
+        assert!(matches!(
+            bits.copy_to_slice((Bound::Included(4), Bound::Excluded(2)), &mut []),
+            Err(PackedBitsError::IndexOutOfBounds(4, 5))
+        ));
+        assert!(matches!(
+            bits.copy_to_slice(0..2, &mut out),
+            Err(PackedBitsError::LengthMismatch(2, 3))
+        ));
+    }
+
     #[test]
     fn test_extend_and_as_bytes() {
         let mut bits = PackedBits::<3>::new().unwrap();
@@ -336,4 +651,45 @@ mod tests {
         assert_eq!(bits.len(), 5);
         assert!(!bits.as_bytes().is_empty());
     }
+
+    fn check_word_paths<const N: usize>() {
+        let max = PackedBits::<N>::mask() as u32;
+        let values: Vec<u32> = (0..97u32)
+            .map(|i| i.wrapping_mul(2_654_435_761) & max)
+            .collect();
+
+        let mut bits = PackedBits::<N>::new().unwrap();
+        for &v in &values {
+            bits.push(v).unwrap();
+        }
+        // The buffer is not padded past the last element
+        assert_eq!(bits.as_bytes().len(), (values.len() * N).div_ceil(8));
+        for (i, &v) in values.iter().enumerate() {
+            assert_eq!(bits.get(i), Some(v), "N={} i={}", N, i);
+        }
+
+        for i in (0..values.len()).step_by(3) {
+            bits.set(i, max - values[i]).unwrap();
+        }
+        for (i, &v) in values.iter().enumerate() {
+            let expected = if i % 3 == 0 { max - v } else { v };
+            assert_eq!(bits.get(i), Some(expected), "N={} i={}", N, i);
+        }
+    }
+
+    #[test]
+    fn test_word_paths_match_bytewise() {
+        check_word_paths::<1>();
+        check_word_paths::<2>();
+        check_word_paths::<4>();
+        check_word_paths::<7>();
+        check_word_paths::<8>();
+        check_word_paths::<9>();
+        check_word_paths::<13>();
+        check_word_paths::<16>();
+        check_word_paths::<17>();
+        check_word_paths::<24>();
+        check_word_paths::<31>();
+        check_word_paths::<32>();
+    }
 }
@@ -0,0 +1,197 @@
+//! N-bit values with a companion validity bitmap.
+
+use crate::{PackedBits, PackedBitsError};
+
+/// A [`PackedBits<N>`] column paired with a 1-bit validity bitmap.
+///
+/// Null slots still occupy `N` bits in the value column (stored as zero), so
+/// indices line up between the two columns.
+///
+/// ```
+/// use packed_bits::NullablePackedBits;
+///
+/// let mut col = NullablePackedBits::<6>::new().unwrap();
+/// col.push(42).unwrap();
+/// col.push_null().unwrap();
+///
+/// assert_eq!(col.get(0), Some(Some(42)));
+/// assert_eq!(col.get(1), Some(None)); // null
+/// assert_eq!(col.get(2), None); // out of bounds
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NullablePackedBits<const N: usize> {
+    validity: PackedBits<1>,
+    values: PackedBits<N>,
+}
+
+impl<const N: usize> NullablePackedBits<N> {
+    /// Creates an empty column.
+    pub fn new() -> Result<Self, PackedBitsError> {
+        Ok(Self {
+            validity: PackedBits::new()?,
+            values: PackedBits::new()?,
+        })
+    }
+
+    /// Creates a column with pre-allocated capacity for `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Result<Self, PackedBitsError> {
+        Ok(Self {
+            validity: PackedBits::with_capacity(capacity)?,
+            values: PackedBits::with_capacity(capacity)?,
+        })
+    }
+
+    /// Creates a column from an existing validity bitmap and value column.
+    ///
+    /// Both must have the same length.
+    pub fn from_parts(
+        validity: PackedBits<1>,
+        values: PackedBits<N>,
+    ) -> Result<Self, PackedBitsError> {
+        if validity.len() != values.len() {
+            return Err(PackedBitsError::LengthMismatch(
+                validity.len(),
+                values.len(),
+            ));
+        }
+        Ok(Self { validity, values })
+    }
+
+    /// Splits the column into its validity bitmap and value column.
+    pub fn into_parts(self) -> (PackedBits<1>, PackedBits<N>) {
+        (self.validity, self.values)
+    }
+
+    /// Returns the validity bitmap (1 = value present, 0 = null).
+    pub fn validity(&self) -> &PackedBits<1> {
+        &self.validity
+    }
+
+    /// Returns the value column (nulls are stored as 0).
+    pub fn values(&self) -> &PackedBits<N> {
+        &self.values
+    }
+
+    /// Pushes a non-null value (must fit in `N` bits).
+    pub fn push(&mut self, value: u32) -> Result<(), PackedBitsError> {
+        self.values.push(value)?;
+        self.validity.push(1)
+    }
+
+    /// Pushes a null.
+    pub fn push_null(&mut self) -> Result<(), PackedBitsError> {
+        self.values.push(0)?;
+        self.validity.push(0)
+    }
+
+    /// Pushes either a value or a null.
+    pub fn push_option(&mut self, value: Option<u32>) -> Result<(), PackedBitsError> {
+        match value {
+            Some(v) => self.push(v),
+            None => self.push_null(),
+        }
+    }
+
+    /// Gets the element at `index`.
+    ///
+    /// Returns `None` if out of bounds, `Some(None)` for a null and
+    /// `Some(Some(value))` otherwise.
+    pub fn get(&self, index: usize) -> Option<Option<u32>> {
+        match self.validity.get(index)? {
+            0 => Some(None),
+            _ => Some(self.values.get(index)),
+        }
+    }
+
+    /// Sets the element at `index` to a value or null.
+    pub fn set(&mut self, index: usize, value: Option<u32>) -> Result<(), PackedBitsError> {
+        self.values.set(index, value.unwrap_or(0))?;
+        self.validity.set(index, value.is_some() as u32)
+    }
+
+    /// Returns `true` if the element at `index` is null (or out of bounds).
+    pub fn is_null(&self, index: usize) -> bool {
+        self.validity.get(index) != Some(1)
+    }
+
+    /// Returns the number of null elements.
+    pub fn null_count(&self) -> usize {
+        self.validity.iter().filter(|&v| v == 0).count()
+    }
+
+    /// Returns the number of elements (including nulls).
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the column has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Clears all elements.
+    pub fn clear(&mut self) {
+        self.validity.clear();
+        self.values.clear();
+    }
+
+    /// Returns an iterator over the elements, yielding `None` for nulls.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Option<u32>> + '_ {
+        self.validity
+            .iter()
+            .zip(self.values.iter())
+            .map(|(valid, v)| (valid != 0).then_some(v))
+    }
+}
+
+impl<const N: usize> Default for NullablePackedBits<N> {
+    fn default() -> Self {
+        Self::new().expect("invalid bit width for NullablePackedBits default")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_get_nulls() {
+        let mut col = NullablePackedBits::<5>::new().unwrap();
+        col.push(31).unwrap();
+        col.push_null().unwrap();
+        col.push_option(Some(7)).unwrap();
+        col.push_option(None).unwrap();
+
+        assert_eq!(col.len(), 4);
+        assert_eq!(col.null_count(), 2);
+        assert_eq!(col.get(0), Some(Some(31)));
+        assert_eq!(col.get(1), Some(None));
+        assert_eq!(col.get(4), None);
+        assert!(col.is_null(3));
+        assert_eq!(
+            col.iter().collect::<Vec<_>>(),
+            vec![Some(31), None, Some(7), None]
+        );
+        assert!(col.push(32).is_err());
+    }
+
+    #[test]
+    fn test_set_and_parts() {
+        let mut col = NullablePackedBits::<5>::new().unwrap();
+        col.push(1).unwrap();
+        col.push(2).unwrap();
+        col.set(0, None).unwrap();
+        col.set(1, Some(9)).unwrap();
+        assert_eq!(col.iter().collect::<Vec<_>>(), vec![None, Some(9)]);
+
+        let (validity, values) = col.clone().into_parts();
+        let rebuilt = NullablePackedBits::from_parts(validity, values).unwrap();
+        assert_eq!(rebuilt, col);
+
+        let short = PackedBits::<1>::new().unwrap();
+        assert!(NullablePackedBits::from_parts(short, PackedBits::<5>::new().unwrap()).is_ok());
+        let mut longer = PackedBits::<5>::new().unwrap();
+        longer.push(1).unwrap();
+        assert!(NullablePackedBits::from_parts(PackedBits::<1>::new().unwrap(), longer).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Loop-free element access for the common widths 1, 2, 4, 8, 16 and 32.
+//!
+//! At these widths elements never straddle a byte boundary:
+//! sub-byte widths divide a byte evenly, and whole-byte widths are plain
+//! little-endian integers. Since `N` is a const generic, the width checks fold
+//! away at compile time and only the matching arm is left in each
+//! monomorphized `get`/`set`. Enabled by the `width-specializations` feature.
+//!
+//! Public only so `packed_bits_container` can share these fast paths; not part
+//! of the supported API.
+
+/// Returns true if `N` has a specialized fast path (and the feature is on).
+#[inline(always)]
+pub fn specialized<const N: usize>() -> bool {
+    cfg!(feature = "width-specializations") && matches!(N, 1 | 2 | 4 | 8 | 16 | 32)
+}
+
+/// Reads element `index` of `data`; `specialized::<N>()` must hold.
+#[inline(always)]
+pub fn get<const N: usize>(data: &[u8], index: usize) -> u32 {
+    match N {
+        1 | 2 | 4 => {
+            let per_byte = 8 / N;
+            let shift = (index % per_byte) * N;
+            ((data[index / per_byte] >> shift) & sub_byte_mask::<N>()) as u32
+        }
+        8 => data[index] as u32,
+        16 => u16::from_le_bytes([data[2 * index], data[2 * index + 1]]) as u32,
+        _ => {
+            let start = 4 * index;
+            u32::from_le_bytes(data[start..start + 4].try_into().expect("4 bytes"))
+        }
+    }
+}
+
+/// Writes `value` (already range-checked) to element `index` of `data`;
+/// `specialized::<N>()` must hold.
+#[inline(always)]
+pub fn set<const N: usize>(data: &mut [u8], index: usize, value: u32) {
+    match N {
+        1 | 2 | 4 => {
+            let per_byte = 8 / N;
+            let shift = (index % per_byte) * N;
+            let byte = &mut data[index / per_byte];
+            *byte = (*byte & !(sub_byte_mask::<N>() << shift)) | ((value as u8) << shift);
+        }
+        8 => data[index] = value as u8,
+        16 => data[2 * index..2 * index + 2].copy_from_slice(&(value as u16).to_le_bytes()),
+        _ => data[4 * index..4 * index + 4].copy_from_slice(&value.to_le_bytes()),
+    }
+}
+
+/// Mask of the low `N` bits of a byte, for `N < 8`.
+#[inline(always)]
+fn sub_byte_mask<const N: usize>() -> u8 {
+    (1u8 << N.min(7)) - 1
+}
@@ -17,21 +17,26 @@
 //! When persisting to storage, the format is:
 //! ```text
 //! [MAGIC: 4 bytes "PKBT"]
-//! [N: u32 (little-endian)]
+//! [N: u32 (little-endian), bit 31 set for Encoding::ByteAligned]
 //! [LEN: u32 (little-endian)]
 //! [DATA: variable length bytes]
 //! ```
 
-use raw_bytes_container::RawBytesContainer;
+pub use packed_bits::Encoding;
+use packed_bits::widths;
+use raw_bytes_container::{RawBytesContainer, Recovery};
 
 const MAGIC: &[u8; 4] = b"PKBT";
 const HEADER_SIZE: usize = 12; // 4 (magic) + 4 (N) + 4 (len)
+/// Set in the N word of the header for [`Encoding::ByteAligned`] data.
+pub const BYTE_ALIGNED_FLAG: u32 = 1 << 31;
 
 /// A bit-packed container using N bits per element, backed by RawBytesContainer.
 #[derive(Debug)]
 pub struct PackedBitsContainer<const N: usize> {
     storage: RawBytesContainer<u8>,
     len: usize, // number of N-bit elements
+    encoding: Encoding,
 }
 
 use thiserror::Error;
@@ -53,6 +58,21 @@ pub enum PackedBitsError {
 
     #[error("failed to resize storage")]
     ResizeFailed,
+
+    #[error("index {index} out of bounds for length {len}")]
+    IndexOutOfBounds { index: usize, len: usize },
+
+    #[error("value {value} does not fit in {bits} bits")]
+    ValueOverflow { value: u32, bits: usize },
+
+    #[error("unsupported format version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("checksum mismatch: expected {expected:#010x}, got {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+
+    #[error("invalid flag name table: {0}")]
+    InvalidFlagNames(String),
 }
 
 type Result<T> = std::result::Result<T, PackedBitsError>;
@@ -60,12 +80,24 @@ type Result<T> = std::result::Result<T, PackedBitsError>;
 impl<const N: usize> PackedBitsContainer<N> {
     /// Create an empty in-memory container with metadata header.
     pub fn new_in_memory() -> Self {
+        Self::with_encoding(Encoding::Packed)
+    }
+
+    /// Create an empty in-memory container using the given element encoding.
+    ///
+    /// The encoding is recorded in the header, so [`from_storage`](Self::from_storage)
+    /// restores it.
+    pub fn with_encoding(encoding: Encoding) -> Self {
         assert!(N > 0 && N <= 32, "N must be 1..=32");
 
         let mut storage = RawBytesContainer::from_vec(vec![0; HEADER_SIZE]);
-        Self::write_header(&mut storage, 0).expect("failed to write header");
+        Self::write_header(&mut storage, 0, encoding).expect("failed to write header");
 
-        Self { storage, len: 0 }
+        Self {
+            storage,
+            len: 0,
+            encoding,
+        }
     }
 
     /// Create an in-memory container with pre-allocated capacity.
@@ -75,9 +107,40 @@ impl<const N: usize> PackedBitsContainer<N> {
         let data_bytes = (capacity * N).div_ceil(8);
         let total_bytes = HEADER_SIZE + data_bytes;
         let mut storage = RawBytesContainer::from_vec(vec![0; total_bytes]);
-        Self::write_header(&mut storage, 0).expect("failed to write header");
+        Self::write_header(&mut storage, 0, Encoding::Packed).expect("failed to write header");
+
+        Self {
+            storage,
+            len: 0,
+            encoding: Encoding::Packed,
+        }
+    }
+
+    /// Returns the element encoding of this container.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Returns an in-memory copy of this container re-encoded with `encoding`.
+    pub fn reencode(&self, encoding: Encoding) -> Self {
+        let mut out = Self::with_encoding(encoding);
+        for v in self.iter() {
+            out.push(v).expect("in-memory storage grows");
+        }
+        out
+    }
+
+    /// Number of bits each element occupies in the data.
+    fn stride(&self) -> usize {
+        match self.encoding {
+            Encoding::Packed => N,
+            Encoding::ByteAligned => N.div_ceil(8) * 8,
+        }
+    }
 
-        Self { storage, len: 0 }
+    /// Whether the loop-free width paths apply, which assume back-to-back elements.
+    fn fast_path(&self) -> bool {
+        widths::specialized::<N>() && self.stride() == N
     }
 
     /// Create from an existing RawBytesContainer with header validation.
@@ -95,19 +158,60 @@ impl<const N: usize> PackedBitsContainer<N> {
             return Err(PackedBitsError::InvalidMagic);
         }
 
-        // Read N
-        let stored_n = u32::from_le_bytes([slice[4], slice[5], slice[6], slice[7]]);
+        // Read N and the encoding flag
+        let n_word = u32::from_le_bytes([slice[4], slice[5], slice[6], slice[7]]);
+        let stored_n = n_word & !BYTE_ALIGNED_FLAG;
         if stored_n as usize != N {
             return Err(PackedBitsError::InvalidN {
                 expected: N,
                 found: stored_n,
             });
         }
+        let encoding = if n_word & BYTE_ALIGNED_FLAG != 0 {
+            Encoding::ByteAligned
+        } else {
+            Encoding::Packed
+        };
 
         // Read len
         let len = u32::from_le_bytes([slice[8], slice[9], slice[10], slice[11]]) as usize;
 
-        Ok(Self { storage, len })
+        Ok(Self {
+            storage,
+            len,
+            encoding,
+        })
+    }
+
+    /// Create from possibly truncated storage, keeping every element that is fully present.
+    ///
+    /// The header must be intact, but a length that claims more elements than the
+    /// data holds is clamped instead of rejected, so a file cut short by a crash still
+    /// yields its leading elements. The returned [`Recovery`] reports how many
+    /// elements were salvaged out of the recorded length.
+    pub fn recover(storage: RawBytesContainer<u8>) -> Result<(Self, Recovery)> {
+        let container = Self::from_storage(storage)?;
+        let (len, stride) = (container.len, container.stride());
+
+        let data_len = container.storage.len() - HEADER_SIZE;
+        let recovered = len.min(data_len * 8 / stride);
+        let recovery = Recovery {
+            recovered,
+            expected: Some(len),
+            discarded_bytes: if recovered < len {
+                data_len - (recovered * stride).div_ceil(8)
+            } else {
+                0
+            },
+        };
+
+        Ok((
+            Self {
+                len: recovered,
+                ..container
+            },
+            recovery,
+        ))
     }
 
     /// Create from raw storage without header (legacy compatibility).
@@ -116,11 +220,46 @@ impl<const N: usize> PackedBitsContainer<N> {
         Self {
             storage,
             len: len_elements,
+            encoding: Encoding::Packed,
+        }
+    }
+
+    /// Create an in-memory container from already-packed element bytes (without header).
+    ///
+    /// Returns [`PackedBitsError::StorageTooSmall`] if `data` holds fewer than `len`
+    /// elements, including when `len * N` overflows.
+    pub fn from_packed_bytes(data: &[u8], len: usize) -> Result<Self> {
+        assert!(N > 0 && N <= 32, "N must be 1..=32");
+
+        let bits = len.checked_mul(N).ok_or(PackedBitsError::StorageTooSmall)?;
+        if data.len() < bits.div_ceil(8) {
+            return Err(PackedBitsError::StorageTooSmall);
         }
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + data.len());
+        bytes.resize(HEADER_SIZE, 0);
+        bytes.extend_from_slice(data);
+        let mut storage = RawBytesContainer::from_vec(bytes);
+        Self::write_header(&mut storage, len, Encoding::Packed)?;
+
+        Ok(Self {
+            storage,
+            len,
+            encoding: Encoding::Packed,
+        })
+    }
+
+    /// The element bytes following the header, laid out per [`encoding`](Self::encoding).
+    pub fn data_bytes(&self) -> &[u8] {
+        &self.storage.as_slice()[HEADER_SIZE..]
     }
 
     /// Write header to storage.
-    fn write_header(storage: &mut RawBytesContainer<u8>, len: usize) -> Result<()> {
+    fn write_header(
+        storage: &mut RawBytesContainer<u8>,
+        len: usize,
+        encoding: Encoding,
+    ) -> Result<()> {
         let slice = storage
             .as_slice_mut()
             .ok_or(PackedBitsError::StorageReadOnly)?;
@@ -130,7 +269,11 @@ impl<const N: usize> PackedBitsContainer<N> {
         }
 
         slice[0..4].copy_from_slice(MAGIC);
-        slice[4..8].copy_from_slice(&(N as u32).to_le_bytes());
+        let flag = match encoding {
+            Encoding::Packed => 0,
+            Encoding::ByteAligned => BYTE_ALIGNED_FLAG,
+        };
+        slice[4..8].copy_from_slice(&(N as u32 | flag).to_le_bytes());
         slice[8..12].copy_from_slice(&(len as u32).to_le_bytes());
 
         Ok(())
@@ -175,8 +318,18 @@ impl<const N: usize> PackedBitsContainer<N> {
         let max_val = if N == 32 { u32::MAX } else { (1u32 << N) - 1 };
         assert!(value <= max_val, "value must fit in {} bits", N);
 
-        let bit_pos = self.len * N;
-        self.ensure_capacity(bit_pos + N)?;
+        let bit_pos = self.len * self.stride();
+        self.ensure_capacity(bit_pos + self.stride())?;
+
+        if self.fast_path() {
+            let slice = self
+                .storage
+                .as_slice_mut()
+                .ok_or(PackedBitsError::StorageReadOnly)?;
+            widths::set::<N>(&mut slice[HEADER_SIZE..], self.len, value);
+            self.len += 1;
+            return self.update_len_in_header();
+        }
 
         let byte_pos = HEADER_SIZE + bit_pos / 8;
         let bit_offset = bit_pos % 8;
@@ -207,7 +360,14 @@ impl<const N: usize> PackedBitsContainer<N> {
             return None;
         }
 
-        let bit_pos = index * N;
+        if self.fast_path() {
+            return Some(widths::get::<N>(
+                &self.storage.as_slice()[HEADER_SIZE..],
+                index,
+            ));
+        }
+
+        let bit_pos = index * self.stride();
         let byte_pos = HEADER_SIZE + bit_pos / 8;
         let bit_offset = bit_pos % 8;
         let mut val: u64 = 0;
@@ -240,7 +400,16 @@ impl<const N: usize> PackedBitsContainer<N> {
         let max_val = if N == 32 { u32::MAX } else { (1u32 << N) - 1 };
         assert!(value <= max_val, "value must fit in {} bits", N);
 
-        let bit_pos = index * N;
+        if self.fast_path() {
+            let slice = self
+                .storage
+                .as_slice_mut()
+                .ok_or(PackedBitsError::StorageReadOnly)?;
+            widths::set::<N>(&mut slice[HEADER_SIZE..], index, value);
+            return Ok(());
+        }
+
+        let bit_pos = index * self.stride();
         let byte_pos = HEADER_SIZE + bit_pos / 8;
         let bit_offset = bit_pos % 8;
 
@@ -274,6 +443,40 @@ impl<const N: usize> PackedBitsContainer<N> {
         Ok(())
     }
 
+    /// Write several `(index, value)` pairs with a single borrow of the storage.
+    ///
+    /// Indices and values must already be validated by the caller.
+    pub(crate) fn write_many(&mut self, updates: &[(usize, u32)]) -> Result<()> {
+        let stride = self.stride();
+        let slice = self
+            .storage
+            .as_slice_mut()
+            .ok_or(PackedBitsError::StorageReadOnly)?;
+
+        let field_mask: u64 = if N == 32 {
+            u32::MAX as u64
+        } else {
+            (1u64 << N) - 1
+        };
+
+        for &(index, value) in updates {
+            let bit_pos = index * stride;
+            let byte_pos = HEADER_SIZE + bit_pos / 8;
+            let bit_offset = bit_pos % 8;
+            let num_bytes = (N + bit_offset).div_ceil(8);
+
+            // Load the covering bytes as one little-endian word, patch, store back.
+            let mut word_bytes = [0u8; 8];
+            word_bytes[..num_bytes].copy_from_slice(&slice[byte_pos..byte_pos + num_bytes]);
+            let mut word = u64::from_le_bytes(word_bytes);
+            word &= !(field_mask << bit_offset);
+            word |= (value as u64) << bit_offset;
+            slice[byte_pos..byte_pos + num_bytes].copy_from_slice(&word.to_le_bytes()[..num_bytes]);
+        }
+
+        Ok(())
+    }
+
     /// Returns the number of stored elements.
     pub fn len(&self) -> usize {
         self.len
@@ -297,7 +500,7 @@ impl<const N: usize> PackedBitsContainer<N> {
     /// Returns the capacity in elements before reallocation.
     pub fn capacity(&self) -> usize {
         let data_bytes = self.storage.as_slice().len().saturating_sub(HEADER_SIZE);
-        (data_bytes * 8) / N
+        (data_bytes * 8) / self.stride()
     }
 
     /// Returns an iterator over the packed values.
@@ -386,6 +589,29 @@ mod tests {
         assert_eq!(pb2.get(1), Some(50));
     }
 
+    #[test]
+    fn test_byte_aligned_encoding() {
+        let mut pb = PackedBitsContainer::<12>::with_encoding(Encoding::ByteAligned);
+        for v in [0xABC, 0x123, 0xFFF] {
+            pb.push(v).unwrap();
+        }
+        pb.set(1, 0x456).unwrap();
+
+        // Each 12-bit element takes two whole bytes
+        assert_eq!(pb.data_bytes(), &[0xBC, 0x0A, 0x56, 0x04, 0xFF, 0x0F]);
+
+        // The encoding survives a round trip through storage
+        let storage = RawBytesContainer::from_vec(pb.storage().as_slice().to_vec());
+        let pb2 = PackedBitsContainer::<12>::from_storage(storage).unwrap();
+        assert_eq!(pb2.encoding(), Encoding::ByteAligned);
+        assert_eq!(pb2.iter().collect::<Vec<_>>(), vec![0xABC, 0x456, 0xFFF]);
+
+        let packed = pb2.reencode(Encoding::Packed);
+        assert_eq!(packed.encoding(), Encoding::Packed);
+        assert_eq!(packed.data_bytes().len(), 5);
+        assert_eq!(packed.iter().collect::<Vec<_>>(), vec![0xABC, 0x456, 0xFFF]);
+    }
+
     #[test]
     fn test_n32() {
         let mut pb = PackedBitsContainer::<32>::new_in_memory();
@@ -440,4 +666,81 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn test_recover_truncated() {
+        let mut pb = PackedBitsContainer::<12>::new_in_memory();
+        for i in 0..10 {
+            pb.push(i * 300).unwrap();
+        }
+        let bytes = pb.storage().as_slice().to_vec();
+
+        let (intact, recovery) =
+            PackedBitsContainer::<12>::recover(RawBytesContainer::from_vec(bytes.clone())).unwrap();
+        assert_eq!(intact.len(), 10);
+        assert!(recovery.is_complete());
+
+        // Cut the file in the middle of element 6
+        let truncated = bytes[..HEADER_SIZE + 10].to_vec();
+        assert!(
+            PackedBitsContainer::<12>::from_storage(RawBytesContainer::from_vec(truncated.clone()))
+                .is_ok()
+        );
+        let (pb, recovery) =
+            PackedBitsContainer::<12>::recover(RawBytesContainer::from_vec(truncated)).unwrap();
+        assert_eq!(pb.len(), 6);
+        assert_eq!(
+            pb.iter().collect::<Vec<_>>(),
+            vec![0, 300, 600, 900, 1200, 1500]
+        );
+        assert_eq!(recovery.recovered, 6);
+        assert_eq!(recovery.expected, Some(10));
+        assert_eq!(recovery.lost(), 4);
+        assert_eq!(recovery.discarded_bytes, 1);
+
+        // A damaged header is still fatal
+        let mut bad = bytes.clone();
+        bad[0] = 0;
+        assert!(matches!(
+            PackedBitsContainer::<12>::recover(RawBytesContainer::from_vec(bad)),
+            Err(PackedBitsError::InvalidMagic)
+        ));
+    }
+
+    fn check_layout<const N: usize>() {
+        let mask = if N == 32 { u32::MAX } else { (1u32 << N) - 1 };
+        let values: Vec<u32> = (0..41u32)
+            .map(|i| i.wrapping_mul(2_654_435_761) & mask)
+            .collect();
+
+        let mut pb = PackedBitsContainer::<N>::new_in_memory();
+        for &v in &values {
+            pb.push(v).unwrap();
+        }
+        pb.set(3, mask - values[3]).unwrap();
+
+        // Same LSB-first layout, whichever path wrote it
+        let mut expected = vec![0u8; (values.len() * N).div_ceil(8)];
+        for (i, &v) in values.iter().enumerate() {
+            let v = if i == 3 { mask - v } else { v };
+            for b in 0..N {
+                let bit = i * N + b;
+                expected[bit / 8] |= (((v >> b) & 1) as u8) << (bit % 8);
+            }
+        }
+        assert_eq!(pb.data_bytes(), &expected[..], "N={}", N);
+        assert_eq!(pb.get(3), Some(mask - values[3]));
+        assert_eq!(pb.get(40), Some(values[40]));
+    }
+
+    #[test]
+    fn test_common_width_layout() {
+        check_layout::<1>();
+        check_layout::<2>();
+        check_layout::<4>();
+        check_layout::<8>();
+        check_layout::<16>();
+        check_layout::<32>();
+        check_layout::<5>();
+    }
 }
@@ -27,7 +27,40 @@
 //! assert_eq!(flags, vec![FLAG1, FLAG2]);
 //! ```
 
-use crate::{PackedBitsContainer, PackedBitsError};
+use crate::{Encoding, PackedBitsContainer, PackedBitsError};
+use raw_bytes_container::checksum::crc32;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Magic of a serialized [`FlagsContainer`], distinct from the PKBT magic of
+/// plain [`PackedBitsContainer`] storage.
+pub const FLAGS_MAGIC: &[u8; 4] = b"PKFL";
+
+/// Current version of the flags file format.
+pub const FLAGS_VERSION: u32 = 1;
+
+// 4 (magic) + 4 (version) + 4 (N) + 4 (len) + 4 (name count) + 4 (name table bytes) + 4 (crc32)
+const FLAGS_HEADER_SIZE: usize = 28;
+
+/// Which format a serialized bit container uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitsFormat {
+    /// Plain `PackedBitsContainer` storage (PKBT header).
+    PackedBits,
+    /// Serialized `FlagsContainer` (PKFL header).
+    Flags,
+}
+
+impl BitsFormat {
+    /// Detect the format from the leading magic bytes.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes.get(..4)? {
+            b"PKBT" => Some(BitsFormat::PackedBits),
+            m if m == FLAGS_MAGIC => Some(BitsFormat::Flags),
+            _ => None,
+        }
+    }
+}
 
 type Result<T> = std::result::Result<T, PackedBitsError>;
 
@@ -37,6 +70,8 @@ type Result<T> = std::result::Result<T, PackedBitsError>;
 #[derive(Debug)]
 pub struct FlagsContainer<const N: usize> {
     bits: PackedBitsContainer<N>,
+    /// Optional names of the flag bits, bit 0 first.
+    names: Vec<String>,
 }
 
 impl<const N: usize> FlagsContainer<N> {
@@ -44,6 +79,7 @@ impl<const N: usize> FlagsContainer<N> {
     pub fn new_in_memory() -> Self {
         Self {
             bits: PackedBitsContainer::<N>::new_in_memory(),
+            names: Vec::new(),
         }
     }
 
@@ -51,9 +87,23 @@ impl<const N: usize> FlagsContainer<N> {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             bits: PackedBitsContainer::<N>::with_capacity(capacity),
+            names: Vec::new(),
+        }
+    }
+
+    /// Wraps an existing bit container, e.g. one loaded from storage.
+    pub fn from_packed_bits(bits: PackedBitsContainer<N>) -> Self {
+        Self {
+            bits,
+            names: Vec::new(),
         }
     }
 
+    /// Consumes the container, returning the underlying bit container.
+    pub fn into_packed_bits(self) -> PackedBitsContainer<N> {
+        self.bits
+    }
+
     /// Push a new flag bitmask.
     pub fn push(&mut self, flags: u32) -> Result<()> {
         self.bits.push(flags)
@@ -94,6 +144,51 @@ impl<const N: usize> FlagsContainer<N> {
         }
     }
 
+    /// Applies `f` to the masks of all elements in `indices`.
+    ///
+    /// The update is all-or-nothing: every new mask is computed and validated
+    /// first, and only then written back in a single pass over the storage. If
+    /// an index is out of bounds or `f` returns a mask that does not fit in `N`
+    /// bits, an error is returned and no element is modified.
+    ///
+    /// Duplicate indices are applied in order, each seeing the previous result.
+    pub fn update_many<F>(&mut self, indices: &[usize], mut f: F) -> Result<()>
+    where
+        F: FnMut(u32) -> u32,
+    {
+        if !self.bits.storage().is_mutable() {
+            return Err(PackedBitsError::StorageReadOnly);
+        }
+
+        let max_val = if N == 32 { u32::MAX } else { (1u32 << N) - 1 };
+        let mut updates: Vec<(usize, u32)> = Vec::with_capacity(indices.len());
+        // A repeated index must see the value staged for it earlier in this call.
+        let mut staged: HashMap<usize, u32> = HashMap::new();
+        for &index in indices {
+            let current = match staged.get(&index) {
+                Some(&mask) => mask,
+                None => self
+                    .bits
+                    .get(index)
+                    .ok_or(PackedBitsError::IndexOutOfBounds {
+                        index,
+                        len: self.len(),
+                    })?,
+            };
+            let new_mask = f(current);
+            if new_mask > max_val {
+                return Err(PackedBitsError::ValueOverflow {
+                    value: new_mask,
+                    bits: N,
+                });
+            }
+            staged.insert(index, new_mask);
+            updates.push((index, new_mask));
+        }
+
+        self.bits.write_many(&updates)
+    }
+
     /// Returns the bitmask for an element.
     pub fn get(&self, index: usize) -> Option<u32> {
         self.bits.get(index)
@@ -124,6 +219,166 @@ impl<const N: usize> FlagsContainer<N> {
         &self.bits
     }
 
+    /// Names the flag bits, bit 0 first. At most `N` names are allowed.
+    pub fn set_flag_names<S: Into<String>>(
+        &mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> Result<()> {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+        if names.len() > N {
+            return Err(PackedBitsError::InvalidFlagNames(format!(
+                "{} names for {} bits",
+                names.len(),
+                N
+            )));
+        }
+        if names.iter().any(|n| n.contains('\0')) {
+            return Err(PackedBitsError::InvalidFlagNames(
+                "names must not contain NUL".to_string(),
+            ));
+        }
+        self.names = names;
+        Ok(())
+    }
+
+    /// Names of the flag bits, bit 0 first (empty if none were set).
+    pub fn flag_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Name of the flag with the given single-bit `mask`, if named.
+    pub fn flag_name(&self, mask: u32) -> Option<&str> {
+        if !mask.is_power_of_two() {
+            return None;
+        }
+        self.names
+            .get(mask.trailing_zeros() as usize)
+            .map(String::as_str)
+    }
+
+    /// Serialize to the PKFL format.
+    ///
+    /// ```text
+    /// [MAGIC: 4 bytes "PKFL"]
+    /// [VERSION: u32][N: u32][LEN: u32]
+    /// [NAME_COUNT: u32][NAME_TABLE_LEN: u32]
+    /// [CRC32: u32 over NAME_TABLE and DATA]
+    /// [NAME_TABLE: NUL-terminated UTF-8 names]
+    /// [DATA: packed N-bit elements]
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut table = Vec::new();
+        for name in &self.names {
+            table.extend_from_slice(name.as_bytes());
+            table.push(0);
+        }
+        // PKFL data is always packed
+        let packed;
+        let bits = match self.bits.encoding() {
+            Encoding::Packed => &self.bits,
+            Encoding::ByteAligned => {
+                packed = self.bits.reencode(Encoding::Packed);
+                &packed
+            }
+        };
+        let data_len = (self.len() * N).div_ceil(8);
+        let data = &bits.data_bytes()[..data_len];
+
+        let mut crc_input = Vec::with_capacity(table.len() + data.len());
+        crc_input.extend_from_slice(&table);
+        crc_input.extend_from_slice(data);
+
+        let mut out = Vec::with_capacity(FLAGS_HEADER_SIZE + crc_input.len());
+        out.extend_from_slice(FLAGS_MAGIC);
+        out.extend_from_slice(&FLAGS_VERSION.to_le_bytes());
+        out.extend_from_slice(&(N as u32).to_le_bytes());
+        out.extend_from_slice(&(self.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(self.names.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(table.len() as u32).to_le_bytes());
+        out.extend_from_slice(&crc32(&crc_input).to_le_bytes());
+        out.extend_from_slice(&crc_input);
+        out
+    }
+
+    /// Load from PKFL bytes, or from plain PKBT storage (without flag names).
+    ///
+    /// Use [`BitsFormat::detect`] to find out which format a file holds.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match BitsFormat::detect(bytes) {
+            Some(BitsFormat::PackedBits) => {
+                Ok(Self::from_packed_bits(PackedBitsContainer::from_storage(
+                    raw_bytes_container::RawBytesContainer::from_slice(bytes),
+                )?))
+            }
+            Some(BitsFormat::Flags) => Self::from_flags_bytes(bytes),
+            None if bytes.len() < 4 => Err(PackedBitsError::StorageTooSmall),
+            None => Err(PackedBitsError::InvalidMagic),
+        }
+    }
+
+    fn from_flags_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < FLAGS_HEADER_SIZE {
+            return Err(PackedBitsError::StorageTooSmall);
+        }
+        let word = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+
+        let version = word(4);
+        if version == 0 || version > FLAGS_VERSION {
+            return Err(PackedBitsError::UnsupportedVersion(version));
+        }
+        let stored_n = word(8);
+        if stored_n as usize != N {
+            return Err(PackedBitsError::InvalidN {
+                expected: N,
+                found: stored_n,
+            });
+        }
+        let len = word(12) as usize;
+        let name_count = word(16) as usize;
+        let table_len = word(20) as usize;
+        let expected = word(24);
+
+        let body = &bytes[FLAGS_HEADER_SIZE..];
+        let data_len = (len * N).div_ceil(8);
+        if body.len() != table_len + data_len {
+            return Err(PackedBitsError::StorageTooSmall);
+        }
+        let actual = crc32(body);
+        if actual != expected {
+            return Err(PackedBitsError::ChecksumMismatch { expected, actual });
+        }
+
+        let table = std::str::from_utf8(&body[..table_len])
+            .map_err(|_| PackedBitsError::InvalidFlagNames("not UTF-8".to_string()))?;
+        let names: Vec<&str> = table.split_terminator('\0').collect();
+        if names.len() != name_count {
+            return Err(PackedBitsError::InvalidFlagNames(format!(
+                "expected {} names, found {}",
+                name_count,
+                names.len()
+            )));
+        }
+
+        let mut flags = Self::from_packed_bits(PackedBitsContainer::from_packed_bytes(
+            &body[table_len..],
+            len,
+        )?);
+        flags.set_flag_names(names)?;
+        Ok(flags)
+    }
+
+    /// Write the PKFL format to `path`.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+
+    /// Read a PKFL (or legacy PKBT) file.
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
     /// Returns an iterator over the set flag bits of one element.
     pub fn iter_flags(&self, index: usize) -> Option<FlagsIter> {
         self.get(index).map(FlagsIter::new)
@@ -177,6 +432,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn update_many_applies_and_rolls_back() -> Result<()> {
+        let mut fc = FlagsContainer::<3>::new_in_memory();
+        for _ in 0..6 {
+            fc.push(FLAG0)?;
+        }
+
+        fc.update_many(&[1, 2, 4], |m| (m & !FLAG0) | FLAG2)?;
+        let masks: Vec<_> = fc.iter().collect();
+        assert_eq!(masks, vec![FLAG0, FLAG2, FLAG2, FLAG0, FLAG2, FLAG0]);
+
+        // Overflowing mask: nothing is written.
+        let err = fc.update_many(&[0, 3], |m| m << 3).unwrap_err();
+        assert_eq!(err, PackedBitsError::ValueOverflow { value: 8, bits: 3 });
+        assert_eq!(fc.iter().collect::<Vec<_>>(), masks);
+
+        // Out-of-bounds index: nothing is written.
+        assert!(fc.update_many(&[0, 9], |_| FLAG1).is_err());
+        assert_eq!(fc.iter().collect::<Vec<_>>(), masks);
+
+        // Duplicates compose.
+        fc.update_many(&[5, 5], |m| m << 1)?;
+        assert_eq!(fc.get(5), Some(FLAG2));
+        Ok(())
+    }
+
     #[test]
     fn iter_flags_works() -> Result<()> {
         let mut fc = FlagsContainer::<3>::new_in_memory();
@@ -191,4 +472,53 @@ mod tests {
         assert_eq!(all_flags, vec![vec![FLAG0, FLAG2], vec![FLAG1]]);
         Ok(())
     }
+
+    #[test]
+    fn pkfl_round_trip_and_legacy_load() -> Result<()> {
+        let mut fc = FlagsContainer::<3>::new_in_memory();
+        fc.set_flag_names(["visited", "looted", "locked"])?;
+        fc.push(FLAG0 | FLAG2)?;
+        fc.push(FLAG1)?;
+
+        let bytes = fc.to_bytes();
+        assert_eq!(BitsFormat::detect(&bytes), Some(BitsFormat::Flags));
+        let loaded = FlagsContainer::<3>::from_bytes(&bytes)?;
+        assert_eq!(
+            loaded.iter().collect::<Vec<_>>(),
+            vec![FLAG0 | FLAG2, FLAG1]
+        );
+        assert_eq!(loaded.flag_name(FLAG1), Some("looted"));
+        assert_eq!(loaded.flag_name(FLAG0 | FLAG1), None);
+
+        // Plain packed bits cannot mistake a flags file for their own format.
+        let storage = raw_bytes_container::RawBytesContainer::from_vec(bytes.clone());
+        assert_eq!(
+            PackedBitsContainer::<3>::from_storage(storage).unwrap_err(),
+            PackedBitsError::InvalidMagic
+        );
+
+        let mut corrupt = bytes.clone();
+        *corrupt.last_mut().unwrap() ^= 0x01;
+        assert!(matches!(
+            FlagsContainer::<3>::from_bytes(&corrupt),
+            Err(PackedBitsError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            FlagsContainer::<4>::from_bytes(&bytes),
+            Err(PackedBitsError::InvalidN {
+                expected: 4,
+                found: 3
+            })
+        ));
+
+        // Legacy PKBT storage still loads, without names.
+        let legacy = fc.packed_bits().storage().as_slice();
+        assert_eq!(BitsFormat::detect(legacy), Some(BitsFormat::PackedBits));
+        let loaded = FlagsContainer::<3>::from_bytes(legacy)?;
+        assert_eq!(loaded.get(0), Some(FLAG0 | FLAG2));
+        assert!(loaded.flag_names().is_empty());
+
+        assert!(fc.set_flag_names(["a", "b", "c", "d"]).is_err());
+        Ok(())
+    }
 }
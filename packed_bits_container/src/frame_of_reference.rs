@@ -0,0 +1,486 @@
+//! FrameOfReferenceColumn: block-wise frame-of-reference (PFOR) encoding for `u32` columns.
+//!
+//! Values are grouped into blocks of `block_size` elements. Each full block stores its
+//! minimum and packs `value - min` using the narrowest bit width that is cheaper than
+//! storing the block's outliers separately. Outliers ("exceptions") are kept as full
+//! `u32` values, so a single spike does not widen the whole block.
+//!
+//! The most recent, partially filled block is kept unencoded until it fills up.
+//!
+//! # File format
+//!
+//! ```text
+//! [MAGIC: 4 bytes "PFOR"]
+//! [VERSION: u32][BLOCK_SIZE: u32][LEN: u64]
+//! [BLOCK_COUNT: u32][EXCEPTION_COUNT: u32][TAIL_COUNT: u32][PAYLOAD_LEN: u32]
+//! [BLOCKS: BLOCK_COUNT x (min: u32, payload_offset: u32, exception_start: u32, width: u32)]
+//! [EXCEPTIONS: EXCEPTION_COUNT x (index_in_block: u32, value: u32)]
+//! [TAIL: TAIL_COUNT x u32]
+//! [PAYLOAD: PAYLOAD_LEN bytes]
+//! ```
+//!
+//! All integers are little-endian.
+
+use crate::PackedBitsError;
+use raw_bytes_container::RawBytesContainer;
+
+const MAGIC: &[u8; 4] = b"PFOR";
+const VERSION: u32 = 1;
+const HEADER_SIZE: usize = 36;
+const BLOCK_META_SIZE: usize = 16;
+const EXCEPTION_SIZE: usize = 8;
+
+/// Default number of values per block.
+pub const DEFAULT_BLOCK_SIZE: usize = 128;
+
+type Result<T> = std::result::Result<T, PackedBitsError>;
+
+/// Metadata of one encoded block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BlockMeta {
+    min: u32,
+    payload_offset: u32,
+    exception_start: u32,
+    width: u32,
+}
+
+/// A `u32` column compressed with patched frame-of-reference encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameOfReferenceColumn {
+    block_size: usize,
+    len: usize,
+    blocks: Vec<BlockMeta>,
+    /// `(index within block, value)` pairs, grouped by block in block order.
+    exceptions: Vec<(u32, u32)>,
+    /// Values of the current, not yet encoded block.
+    tail: Vec<u32>,
+    payload: Vec<u8>,
+}
+
+impl Default for FrameOfReferenceColumn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameOfReferenceColumn {
+    /// Create an empty column using [`DEFAULT_BLOCK_SIZE`].
+    pub fn new() -> Self {
+        Self::with_block_size(DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Create an empty column with `block_size` values per block.
+    pub fn with_block_size(block_size: usize) -> Self {
+        assert!(
+            block_size > 0 && block_size <= u32::MAX as usize,
+            "block size must be 1..=u32::MAX"
+        );
+        Self {
+            block_size,
+            len: 0,
+            blocks: Vec::new(),
+            exceptions: Vec::new(),
+            tail: Vec::with_capacity(block_size),
+            payload: Vec::new(),
+        }
+    }
+
+    /// Number of values per block.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns the number of stored values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the column has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of outliers stored outside the packed blocks.
+    pub fn exception_count(&self) -> usize {
+        self.exceptions.len()
+    }
+
+    /// Encoded size in bytes (what [`to_storage`](Self::to_storage) writes).
+    pub fn encoded_size(&self) -> usize {
+        HEADER_SIZE
+            + self.blocks.len() * BLOCK_META_SIZE
+            + self.exceptions.len() * EXCEPTION_SIZE
+            + self.tail.len() * 4
+            + self.payload.len()
+    }
+
+    /// Append a value.
+    pub fn push(&mut self, value: u32) {
+        self.tail.push(value);
+        self.len += 1;
+        if self.tail.len() == self.block_size {
+            self.seal_tail();
+        }
+    }
+
+    /// Append multiple values.
+    pub fn extend_from_slice(&mut self, values: &[u32]) {
+        for &v in values {
+            self.push(v);
+        }
+    }
+
+    /// Get the value at `index`.
+    pub fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.len {
+            return None;
+        }
+        let block_idx = index / self.block_size;
+        let in_block = index % self.block_size;
+
+        let Some(block) = self.blocks.get(block_idx) else {
+            return self.tail.get(in_block).copied();
+        };
+
+        let exceptions = self.block_exceptions(block_idx);
+        if let Ok(pos) = exceptions.binary_search_by_key(&(in_block as u32), |&(i, _)| i) {
+            return Some(exceptions[pos].1);
+        }
+
+        let bit_pos = block.payload_offset as usize * 8 + in_block * block.width as usize;
+        block
+            .min
+            .checked_add(read_bits(&self.payload, bit_pos, block.width as usize))
+    }
+
+    /// Returns an iterator over the values.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.len).map(move |i| self.get(i).expect("index in bounds"))
+    }
+
+    /// Exceptions belonging to block `block_idx`.
+    fn block_exceptions(&self, block_idx: usize) -> &[(u32, u32)] {
+        let start = self.blocks[block_idx].exception_start as usize;
+        let end = self
+            .blocks
+            .get(block_idx + 1)
+            .map_or(self.exceptions.len(), |b| b.exception_start as usize);
+        &self.exceptions[start..end]
+    }
+
+    /// Encode the full tail block.
+    fn seal_tail(&mut self) {
+        let values = std::mem::take(&mut self.tail);
+        let min = values.iter().copied().min().unwrap_or(0);
+        let width = choose_width(&values, min);
+        let limit = if width == 32 {
+            u32::MAX
+        } else {
+            (1u32 << width) - 1
+        };
+        // Keep `min + limit` within u32 so every packed offset decodes without overflow
+        let min = min.min(u32::MAX - limit);
+
+        let payload_offset = self.payload.len();
+        let exception_start = self.exceptions.len();
+        let bit_base = payload_offset * 8;
+        self.payload
+            .resize(payload_offset + (values.len() * width).div_ceil(8), 0);
+
+        for (i, &v) in values.iter().enumerate() {
+            let offset = v - min;
+            if offset > limit {
+                self.exceptions.push((i as u32, v));
+            } else {
+                write_bits(&mut self.payload, bit_base + i * width, width, offset);
+            }
+        }
+
+        self.blocks.push(BlockMeta {
+            min,
+            payload_offset: payload_offset as u32,
+            exception_start: exception_start as u32,
+            width: width as u32,
+        });
+        self.tail = values;
+        self.tail.clear();
+    }
+
+    /// Serialize the column into an in-memory byte container.
+    pub fn to_storage(&self) -> RawBytesContainer<u8> {
+        let mut out = Vec::with_capacity(self.encoded_size());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&(self.block_size as u32).to_le_bytes());
+        out.extend_from_slice(&(self.len as u64).to_le_bytes());
+        out.extend_from_slice(&(self.blocks.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(self.exceptions.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(self.tail.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(self.payload.len() as u32).to_le_bytes());
+
+        for b in &self.blocks {
+            out.extend_from_slice(&b.min.to_le_bytes());
+            out.extend_from_slice(&b.payload_offset.to_le_bytes());
+            out.extend_from_slice(&b.exception_start.to_le_bytes());
+            out.extend_from_slice(&b.width.to_le_bytes());
+        }
+        for &(i, v) in &self.exceptions {
+            out.extend_from_slice(&i.to_le_bytes());
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        for &v in &self.tail {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        out.extend_from_slice(&self.payload);
+
+        RawBytesContainer::from_vec(out)
+    }
+
+    /// Load a column from storage written by [`to_storage`](Self::to_storage).
+    ///
+    /// The storage can be in-memory or memory-mapped; the data is copied out.
+    pub fn from_storage(storage: &RawBytesContainer<u8>) -> Result<Self> {
+        let bytes = storage.as_slice();
+        if bytes.len() < HEADER_SIZE {
+            return Err(PackedBitsError::StorageTooSmall);
+        }
+        if &bytes[0..4] != MAGIC || read_u32(bytes, 4) != VERSION {
+            return Err(PackedBitsError::InvalidMagic);
+        }
+
+        let block_size = read_u32(bytes, 8) as usize;
+        let len = u64::from_le_bytes(bytes[12..20].try_into().unwrap()) as usize;
+        let block_count = read_u32(bytes, 20) as usize;
+        let exception_count = read_u32(bytes, 24) as usize;
+        let tail_count = read_u32(bytes, 28) as usize;
+        let payload_len = read_u32(bytes, 32) as usize;
+
+        let blocks_start = HEADER_SIZE;
+        let exceptions_start = blocks_start + block_count * BLOCK_META_SIZE;
+        let tail_start = exceptions_start + exception_count * EXCEPTION_SIZE;
+        let payload_start = tail_start + tail_count * 4;
+        if block_size == 0
+            || payload_start + payload_len > bytes.len()
+            || len != block_count * block_size + tail_count
+            || tail_count >= block_size
+        {
+            return Err(PackedBitsError::StorageTooSmall);
+        }
+
+        let blocks: Vec<BlockMeta> = bytes[blocks_start..exceptions_start]
+            .chunks_exact(BLOCK_META_SIZE)
+            .map(|c| BlockMeta {
+                min: read_u32(c, 0),
+                payload_offset: read_u32(c, 4),
+                exception_start: read_u32(c, 8),
+                width: read_u32(c, 12),
+            })
+            .collect();
+        let exceptions = bytes[exceptions_start..tail_start]
+            .chunks_exact(EXCEPTION_SIZE)
+            .map(|c| (read_u32(c, 0), read_u32(c, 4)))
+            .collect();
+        let mut tail = Vec::with_capacity(block_size);
+        tail.extend(
+            bytes[tail_start..payload_start]
+                .chunks_exact(4)
+                .map(|c| read_u32(c, 0)),
+        );
+        let payload = bytes[payload_start..payload_start + payload_len].to_vec();
+
+        let column = Self {
+            block_size,
+            len,
+            blocks,
+            exceptions,
+            tail,
+            payload,
+        };
+        column.validate_blocks()?;
+        Ok(column)
+    }
+
+    /// Check that block metadata stays within the payload and exception table, that
+    /// every packed offset decodes without overflow, and that each block's exception
+    /// indices are in order and within the block.
+    fn validate_blocks(&self) -> Result<()> {
+        let mut prev_exception = 0;
+        for b in &self.blocks {
+            let payload_end =
+                b.payload_offset as usize + (self.block_size * b.width as usize).div_ceil(8);
+            if b.width > 32
+                || payload_end > self.payload.len()
+                || b.min as u64 + ((1u64 << b.width) - 1) > u32::MAX as u64
+                || (b.exception_start as usize) < prev_exception
+                || b.exception_start as usize > self.exceptions.len()
+            {
+                return Err(PackedBitsError::StorageTooSmall);
+            }
+            prev_exception = b.exception_start as usize;
+        }
+
+        for block_idx in 0..self.blocks.len() {
+            let exceptions = self.block_exceptions(block_idx);
+            let in_order = exceptions.windows(2).all(|w| w[0].0 < w[1].0);
+            let in_block = exceptions
+                .last()
+                .is_none_or(|&(i, _)| (i as usize) < self.block_size);
+            if !in_order || !in_block {
+                return Err(PackedBitsError::StorageTooSmall);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<u32> for FrameOfReferenceColumn {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut column = Self::new();
+        for v in iter {
+            column.push(v);
+        }
+        column
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+/// Pick the bit width that minimizes packed bits plus exception storage.
+fn choose_width(values: &[u32], min: u32) -> usize {
+    // Number of offsets needing exactly `w` bits, for w in 0..=32.
+    let mut histogram = [0usize; 33];
+    for &v in values {
+        histogram[(32 - (v - min).leading_zeros()) as usize] += 1;
+    }
+
+    let exception_bits = EXCEPTION_SIZE * 8;
+    let mut best = (usize::MAX, 32);
+    let mut exceptions_above = 0;
+    for width in (0..=32).rev() {
+        let cost = values.len() * width + exceptions_above * exception_bits;
+        if cost <= best.0 {
+            best = (cost, width);
+        }
+        exceptions_above += histogram[width];
+    }
+    best.1
+}
+
+fn write_bits(buf: &mut [u8], bit_pos: usize, width: usize, value: u32) {
+    let v = (value as u64) << (bit_pos % 8);
+    let byte_pos = bit_pos / 8;
+    for i in 0..(width + bit_pos % 8).div_ceil(8) {
+        buf[byte_pos + i] |= ((v >> (i * 8)) & 0xFF) as u8;
+    }
+}
+
+fn read_bits(buf: &[u8], bit_pos: usize, width: usize) -> u32 {
+    if width == 0 {
+        return 0;
+    }
+    let byte_pos = bit_pos / 8;
+    let bit_offset = bit_pos % 8;
+    let mut val: u64 = 0;
+    for i in 0..(width + bit_offset).div_ceil(8) {
+        val |= (buf[byte_pos + i] as u64) << (i * 8);
+    }
+    ((val >> bit_offset) & ((1u64 << width) - 1)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor_values(n: usize) -> Vec<u32> {
+        (0..n)
+            .map(|i| {
+                if i % 97 == 13 {
+                    4_000_000_000 // occasional outlier
+                } else {
+                    20_000 + (i as u32 * 7) % 50
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_push_and_get() {
+        let values = sensor_values(1000);
+        let mut column = FrameOfReferenceColumn::with_block_size(64);
+        column.extend_from_slice(&values);
+
+        assert_eq!(column.len(), 1000);
+        assert!(column.exception_count() > 0);
+        assert_eq!(column.iter().collect::<Vec<_>>(), values);
+        assert_eq!(column.get(1000), None);
+        assert!(column.encoded_size() < values.len() * 4 / 3);
+    }
+
+    #[test]
+    fn test_storage_round_trip() {
+        let values = sensor_values(300);
+        let column: FrameOfReferenceColumn = values.iter().copied().collect();
+
+        let storage = column.to_storage();
+        let loaded = FrameOfReferenceColumn::from_storage(&storage).unwrap();
+        assert_eq!(loaded, column);
+
+        let mut loaded = loaded;
+        loaded.push(7);
+        assert_eq!(loaded.get(300), Some(7));
+    }
+
+    #[test]
+    fn test_invalid_storage() {
+        let storage = RawBytesContainer::from_vec(vec![0u8; 64]);
+        assert_eq!(
+            FrameOfReferenceColumn::from_storage(&storage),
+            Err(PackedBitsError::InvalidMagic)
+        );
+    }
+
+    #[test]
+    fn test_values_near_u32_max() {
+        let values = [u32::MAX - 2, u32::MAX - 1, u32::MAX, u32::MAX - 2];
+        let mut column = FrameOfReferenceColumn::with_block_size(4);
+        column.extend_from_slice(&values);
+
+        let loaded = FrameOfReferenceColumn::from_storage(&column.to_storage()).unwrap();
+        assert_eq!(loaded.iter().collect::<Vec<_>>(), values);
+    }
+
+    #[test]
+    fn test_corrupt_blocks_rejected() {
+        let mut column = FrameOfReferenceColumn::with_block_size(4);
+        column.extend_from_slice(&[10, 11, 12, 1_000_000, 13, 14, 15, 16]);
+        let bytes = column.to_storage().as_slice().to_vec();
+        let exceptions_start = HEADER_SIZE + 2 * BLOCK_META_SIZE;
+        let corrupt = |patch: &dyn Fn(&mut Vec<u8>)| {
+            let mut bytes = bytes.clone();
+            patch(&mut bytes);
+            FrameOfReferenceColumn::from_storage(&RawBytesContainer::from_vec(bytes))
+        };
+
+        // A block minimum so large that `min + offset` would overflow
+        assert_eq!(
+            corrupt(&|b| b[HEADER_SIZE..HEADER_SIZE + 4].copy_from_slice(&u32::MAX.to_le_bytes())),
+            Err(PackedBitsError::StorageTooSmall)
+        );
+        // An exception index past the end of its block
+        assert_eq!(
+            corrupt(&|b| {
+                b[exceptions_start..exceptions_start + 4].copy_from_slice(&4u32.to_le_bytes())
+            }),
+            Err(PackedBitsError::StorageTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_choose_width() {
+        assert_eq!(choose_width(&[5, 5, 5], 5), 0);
+        assert_eq!(choose_width(&[0, 1, 2, 3], 0), 2);
+        assert_eq!(choose_width(&[u32::MAX, 0], 0), 0);
+    }
+}
@@ -0,0 +1,161 @@
+//! Conversions between `PackedBitsContainer<1>` and external bitmap types.
+//!
+//! With `N = 1` the data bytes after the header hold bit `i` in byte `i / 8` at
+//! bit position `i % 8`, the same layout as `BitVec<u8, Lsb0>`.
+
+use crate::{Encoding, PackedBitsContainer};
+use packed_bits::Ones;
+
+impl PackedBitsContainer<1> {
+    /// Iterate over the indices of all set bits, in ascending order.
+    pub fn ones(&self) -> Ones<'_> {
+        let len = self.len();
+        match self.encoding() {
+            Encoding::Packed => Ones::packed(&self.data_bytes()[..len.div_ceil(8)], len),
+            Encoding::ByteAligned => Ones::byte_aligned(&self.data_bytes()[..len], len),
+        }
+    }
+}
+
+#[cfg(feature = "bitvec")]
+mod bitvec_impls {
+    use crate::{Encoding, PackedBitsContainer};
+    use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
+
+    impl From<&PackedBitsContainer<1>> for BitVec<u8, Lsb0> {
+        fn from(container: &PackedBitsContainer<1>) -> Self {
+            if container.encoding() == Encoding::ByteAligned {
+                return Self::from(&container.reencode(Encoding::Packed));
+            }
+            let len = container.len();
+            BitSlice::<u8, Lsb0>::from_slice(&container.data_bytes()[..len.div_ceil(8)])[..len]
+                .to_bitvec()
+        }
+    }
+
+    impl From<&BitSlice<u8, Lsb0>> for PackedBitsContainer<1> {
+        fn from(bits: &BitSlice<u8, Lsb0>) -> Self {
+            let mut bv = bits.to_bitvec();
+            // Bits past `len` in the last byte must be zero, since `push` ORs into it.
+            bv.set_uninitialized(false);
+            PackedBitsContainer::from_packed_bytes(bv.as_raw_slice(), bits.len())
+                .expect("bitvec holds enough bytes for its length")
+        }
+    }
+}
+
+#[cfg(feature = "roaring")]
+mod roaring_impls {
+    use crate::{PackedBitsContainer, PackedBitsError};
+    use roaring::RoaringBitmap;
+
+    impl From<&PackedBitsContainer<1>> for RoaringBitmap {
+        /// # Panics
+        /// Panics if a set bit lies beyond `u32::MAX`, which roaring cannot represent.
+        fn from(container: &PackedBitsContainer<1>) -> Self {
+            RoaringBitmap::from_sorted_iter(
+                container
+                    .ones()
+                    .map(|idx| u32::try_from(idx).expect("bit index exceeds u32::MAX")),
+            )
+            .expect("set bits are yielded in ascending order")
+        }
+    }
+
+    impl PackedBitsContainer<1> {
+        /// Create an in-memory container of `len` bits with the bits in `bitmap` set.
+        ///
+        /// Returns [`PackedBitsError::IndexOutOfBounds`] if `bitmap` contains an index `>= len`.
+        pub fn from_roaring(bitmap: &RoaringBitmap, len: usize) -> Result<Self, PackedBitsError> {
+            if let Some(max) = bitmap.max().filter(|&max| max as usize >= len) {
+                return Err(PackedBitsError::IndexOutOfBounds {
+                    index: max as usize,
+                    len,
+                });
+            }
+            let mut data = vec![0u8; len.div_ceil(8)];
+            for idx in bitmap {
+                data[idx as usize / 8] |= 1 << (idx % 8);
+            }
+            Self::from_packed_bytes(&data, len)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PackedBitsContainer;
+
+    fn sample() -> PackedBitsContainer<1> {
+        let mut pb = PackedBitsContainer::<1>::new_in_memory();
+        for i in 0..21 {
+            pb.push((i % 3 == 0) as u32).unwrap();
+        }
+        pb
+    }
+
+    #[test]
+    fn test_ones() {
+        let ones: Vec<_> = sample().ones().collect();
+        assert_eq!(ones, vec![0, 3, 6, 9, 12, 15, 18]);
+
+        // Padding bits past `len` are never reported
+        let pb = PackedBitsContainer::<1>::from_packed_bytes(&[0xff], 3).unwrap();
+        assert_eq!(pb.ones().collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_ones_byte_aligned() {
+        let aligned = sample().reencode(crate::Encoding::ByteAligned);
+        let ones: Vec<_> = aligned.ones().collect();
+        assert_eq!(ones, vec![0, 3, 6, 9, 12, 15, 18]);
+    }
+
+    #[test]
+    fn test_from_packed_bytes_overflow() {
+        assert_eq!(
+            PackedBitsContainer::<4>::from_packed_bytes(&[0], usize::MAX).unwrap_err(),
+            crate::PackedBitsError::StorageTooSmall
+        );
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn test_bitvec_round_trip() {
+        use bitvec::{order::Lsb0, vec::BitVec};
+
+        let pb = sample();
+        let bv = BitVec::<u8, Lsb0>::from(&pb);
+        assert_eq!(bv.len(), 21);
+        assert!(bv[3] && !bv[4]);
+
+        let mut back = PackedBitsContainer::<1>::from(bv.as_bitslice());
+        assert_eq!(
+            back.iter().collect::<Vec<_>>(),
+            pb.iter().collect::<Vec<_>>()
+        );
+        back.push(1).unwrap();
+        assert_eq!(back.get(21), Some(1));
+    }
+
+    #[cfg(feature = "roaring")]
+    #[test]
+    fn test_roaring_round_trip() {
+        use crate::PackedBitsError;
+        use roaring::RoaringBitmap;
+
+        let pb = sample();
+        let bitmap = RoaringBitmap::from(&pb);
+        assert_eq!(bitmap.len(), 7);
+
+        let back = PackedBitsContainer::<1>::from_roaring(&bitmap, 21).unwrap();
+        assert_eq!(
+            back.iter().collect::<Vec<_>>(),
+            pb.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            PackedBitsContainer::<1>::from_roaring(&bitmap, 10).unwrap_err(),
+            PackedBitsError::IndexOutOfBounds { index: 18, len: 10 }
+        );
+    }
+}
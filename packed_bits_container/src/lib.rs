@@ -2,5 +2,8 @@
 
 pub mod container;
 pub mod flags;
+pub mod frame_of_reference;
+mod interop;
 
 pub use container::*;
+pub use frame_of_reference::FrameOfReferenceColumn;
@@ -0,0 +1,133 @@
+//! Storage behind a [`PackedStructContainer`](crate::PackedStructContainer).
+//!
+//! Most containers fill a [`RawBytesContainer`] exactly. Files made by
+//! [`create_mmap`](crate::PackedStructContainer::create_mmap) are an [`MmapVec`]
+//! instead: a length header followed by room for more elements, so they can grow
+//! on disk. Both expose the byte-level operations the container is built on.
+
+use bytemuck::Pod;
+use raw_bytes_container::{ContainerError, MmapVec, RawBytesContainer};
+
+#[derive(Debug)]
+pub(crate) enum Backing<T: Pod> {
+    /// The elements are the whole container.
+    Raw(RawBytesContainer<u8>),
+    /// A length-tracked, growable mapping.
+    Vec(MmapVec<T>),
+}
+
+impl<T: Pod> From<RawBytesContainer<u8>> for Backing<T> {
+    fn from(storage: RawBytesContainer<u8>) -> Self {
+        Backing::Raw(storage)
+    }
+}
+
+impl<T: Pod> Backing<T> {
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        match self {
+            Backing::Raw(storage) => storage.as_slice(),
+            Backing::Vec(vec) => bytemuck::cast_slice(vec.as_slice()),
+        }
+    }
+
+    pub(crate) fn as_slice_mut(&mut self) -> Option<&mut [u8]> {
+        match self {
+            Backing::Raw(storage) => storage.as_slice_mut(),
+            Backing::Vec(vec) => Some(bytemuck::cast_slice_mut(vec.as_mut_slice())),
+        }
+    }
+
+    pub(crate) fn as_slice_mut_checked(&mut self) -> Result<&mut [u8], ContainerError> {
+        match self {
+            Backing::Raw(storage) => storage.as_slice_mut_checked(),
+            Backing::Vec(vec) => Ok(bytemuck::cast_slice_mut(vec.as_mut_slice())),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Capacity in bytes, or `None` if the storage cannot grow.
+    pub(crate) fn capacity(&self) -> Option<usize> {
+        match self {
+            Backing::Raw(storage) => storage.capacity(),
+            Backing::Vec(vec) => Some(vec.capacity() * std::mem::size_of::<T>()),
+        }
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) -> Result<(), ContainerError> {
+        match self {
+            Backing::Raw(storage) => storage.reserve(additional),
+            Backing::Vec(vec) => vec.reserve(additional / std::mem::size_of::<T>()),
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) -> Result<(), ContainerError> {
+        match self {
+            Backing::Raw(storage) => storage.shrink_to_fit(),
+            Backing::Vec(vec) => vec.shrink_to_fit(),
+        }
+    }
+
+    /// Append whole elements given as bytes, growing read-write mapped files.
+    pub(crate) fn append(&mut self, new: &[u8]) -> Result<(), ContainerError> {
+        match self {
+            Backing::Raw(storage) if storage.capacity().is_none() => storage.append_mapped(new),
+            Backing::Raw(storage) => storage.append(new),
+            Backing::Vec(vec) => vec.extend_from_slice(bytemuck::cast_slice(new)),
+        }
+    }
+
+    /// Resize to `new_len` bytes, zero-filling any new elements.
+    pub(crate) fn resize_zeroed(&mut self, new_len: usize) -> Result<(), ContainerError> {
+        match self {
+            Backing::Raw(storage) => storage.resize_zeroed(new_len),
+            Backing::Vec(vec) => {
+                let new_len = new_len / std::mem::size_of::<T>();
+                match new_len.checked_sub(vec.len()) {
+                    // Slots past the length may hold stale elements, so write zeros
+                    Some(grow) => vec.extend_from_slice(&vec![T::zeroed(); grow]),
+                    None => {
+                        vec.truncate(new_len);
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) -> Result<(), ContainerError> {
+        match self {
+            Backing::Raw(storage) => storage.truncate(len),
+            Backing::Vec(vec) => {
+                vec.truncate(len / std::mem::size_of::<T>());
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) -> Result<(), ContainerError> {
+        match self {
+            Backing::Raw(storage) => storage.resize(0, 0),
+            Backing::Vec(vec) => {
+                vec.clear();
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn change_generation(&self) -> u64 {
+        match self {
+            Backing::Raw(storage) => storage.change_generation(),
+            Backing::Vec(vec) => vec.change_generation(),
+        }
+    }
+
+    pub(crate) fn flush(&self) -> Result<(), ContainerError> {
+        match self {
+            Backing::Raw(storage) => storage.flush(),
+            Backing::Vec(vec) => vec.flush(),
+        }
+    }
+}
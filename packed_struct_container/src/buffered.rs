@@ -0,0 +1,21 @@
+//! Block-wise appends for [`PackedStructContainer`].
+
+use crate::PackedStructContainer;
+use bytemuck::Pod;
+use raw_bytes_container::{AppendSink, ContainerError};
+
+/// Batches small appends to a [`PackedStructContainer`] and commits them in blocks.
+///
+/// Created by [`PackedStructContainer::append_buffered`]. This is the
+/// [`raw_bytes_container::BufferedAppender`] committing through
+/// [`PackedStructContainer::append`], so it covers both in-memory storage and
+/// growable mapped files. Call [`commit`](raw_bytes_container::BufferedAppender::commit)
+/// before dropping it to see errors from the last block.
+pub type BufferedAppender<'a, T> =
+    raw_bytes_container::BufferedAppender<'a, T, PackedStructContainer<T>>;
+
+impl<T: Pod + Copy> AppendSink<T> for PackedStructContainer<T> {
+    fn append_items(&mut self, values: &[T]) -> Result<(), ContainerError> {
+        self.append(values)
+    }
+}
@@ -0,0 +1,256 @@
+//! One entry point for constructing a [`PackedStructContainer`].
+
+use bytemuck::Pod;
+use raw_bytes_container::{Backend, ContainerError, MmapVec, RawBytesContainer};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use crate::PackedStructContainer;
+use crate::backing::Backing;
+
+/// Builder for a [`PackedStructContainer`].
+///
+/// Created by [`PackedStructContainer::builder`]. Picks the backend and applies
+/// capacity, file creation, header and read-only options in one place, instead of
+/// choosing between [`with_capacity`](PackedStructContainer::with_capacity), the
+/// `open_mmap_*` constructors and [`create_mmap`](PackedStructContainer::create_mmap).
+///
+/// Mapped files are plain arrays of elements by default, as read by
+/// [`open_mmap_read`](PackedStructContainer::open_mmap_read). With
+/// [`header`](Self::header) they carry a length header instead and can grow on
+/// disk, like files made by [`create_mmap`](PackedStructContainer::create_mmap).
+///
+/// # Example
+/// ```
+/// use packed_struct_container::PackedStructContainer;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("events.vec");
+///
+/// let mut events = PackedStructContainer::<[u32; 2]>::builder()
+///     .mmap(&path)
+///     .header(true)
+///     .create_if_missing(true)
+///     .capacity(64)
+///     .build()
+///     .unwrap();
+/// events.push([1, 2]).unwrap();
+/// drop(events);
+///
+/// let events = PackedStructContainer::<[u32; 2]>::builder()
+///     .mmap(&path)
+///     .header(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(events[0], [1, 2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PackedStructContainerBuilder<T: Pod + Copy> {
+    backend: Backend,
+    capacity: usize,
+    create_if_missing: bool,
+    header: bool,
+    read_only: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod + Copy> Default for PackedStructContainerBuilder<T> {
+    fn default() -> Self {
+        Self {
+            backend: Backend::Memory,
+            capacity: 0,
+            create_if_missing: false,
+            header: false,
+            read_only: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Pod + Copy> PackedStructContainerBuilder<T> {
+    /// Default builder: an empty, writable in-memory container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the backend.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Keep the elements in memory.
+    pub fn memory(self) -> Self {
+        self.backend(Backend::Memory)
+    }
+
+    /// Map the file at `path`.
+    pub fn mmap(self, path: impl AsRef<Path>) -> Self {
+        self.backend(Backend::Mmap(path.as_ref().to_path_buf()))
+    }
+
+    /// Capacity to reserve up front, in elements (in-memory storage and files with
+    /// a [`header`](Self::header) only).
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Create the mapped file, empty, if it does not exist, instead of failing.
+    /// Existing files are never truncated.
+    pub fn create_if_missing(mut self, create: bool) -> Self {
+        self.create_if_missing = create;
+        self
+    }
+
+    /// Store a length header in the mapped file so it can grow and shrink on disk;
+    /// see [`create_mmap`](PackedStructContainer::create_mmap).
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Build a read-only container.
+    ///
+    /// Files are mapped read-only; in-memory storage is sealed after being set up.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Construct the container.
+    ///
+    /// # Errors
+    /// Fails if the backend cannot be set up, or if an option does not apply to it,
+    /// such as a header without a file or a read-only file with a header.
+    pub fn build(self) -> Result<PackedStructContainer<T>, ContainerError> {
+        PackedStructContainer::<T>::validate_alignment()?;
+        let storage = if self.header {
+            Backing::Vec(self.build_vec()?)
+        } else {
+            let capacity = self
+                .capacity
+                .checked_mul(std::mem::size_of::<T>())
+                .ok_or(ContainerError::UnsupportedOperation("Capacity overflow"))?;
+            RawBytesContainer::<u8>::builder()
+                .backend(self.backend)
+                .capacity(capacity)
+                .create_if_missing(self.create_if_missing)
+                .read_only(self.read_only)
+                .build()?
+                .into()
+        };
+        Ok(PackedStructContainer {
+            storage,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Open or create a length-tracked file.
+    fn build_vec(&self) -> Result<MmapVec<T>, ContainerError> {
+        let Backend::Mmap(path) = &self.backend else {
+            return Err(ContainerError::UnsupportedOperation(
+                "Length header requires an mmap file backend",
+            ));
+        };
+        if self.read_only {
+            return Err(ContainerError::UnsupportedOperation(
+                "Files with a length header cannot be mapped read-only",
+            ));
+        }
+
+        if self.create_if_missing && !path.try_exists()? {
+            return MmapVec::create_with_capacity(path, self.capacity);
+        }
+        let mut vec = MmapVec::open(path)?;
+        vec.reserve(self.capacity.saturating_sub(vec.len()))?;
+        Ok(vec)
+    }
+}
+
+impl<T: Pod + Copy> PackedStructContainer<T> {
+    /// Start building a container; see [`PackedStructContainerBuilder`].
+    pub fn builder() -> PackedStructContainerBuilder<T> {
+        PackedStructContainerBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_backends() {
+        let memory = PackedStructContainer::<u32>::builder()
+            .capacity(16)
+            .build()
+            .unwrap();
+        assert!(memory.capacity().unwrap() >= 16);
+
+        let mut sealed = PackedStructContainer::<u32>::builder()
+            .read_only(true)
+            .build()
+            .unwrap();
+        assert!(sealed.push(1).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plain.bin");
+        assert!(
+            PackedStructContainer::<u32>::builder()
+                .mmap(&path)
+                .build()
+                .is_err()
+        );
+        let mut plain = PackedStructContainer::<u32>::builder()
+            .mmap(&path)
+            .create_if_missing(true)
+            .build()
+            .unwrap();
+        plain.append(&[1, 2, 3]).unwrap();
+        drop(plain);
+        let mut read = PackedStructContainer::<u32>::builder()
+            .mmap(&path)
+            .read_only(true)
+            .build()
+            .unwrap();
+        assert_eq!(read.as_slice(), &[1, 2, 3]);
+        assert!(read.as_slice_mut().is_none());
+
+        let path = dir.path().join("headered.vec");
+        let mut vec = PackedStructContainer::<u32>::builder()
+            .mmap(&path)
+            .header(true)
+            .create_if_missing(true)
+            .capacity(8)
+            .build()
+            .unwrap();
+        assert_eq!(vec.capacity(), Some(8));
+        vec.push(7).unwrap();
+        drop(vec);
+        let vec = PackedStructContainer::<u32>::builder()
+            .mmap(&path)
+            .header(true)
+            .create_if_missing(true)
+            .capacity(100)
+            .build()
+            .unwrap();
+        assert_eq!(vec.as_slice(), &[7]);
+        assert!(vec.capacity().unwrap() >= 100);
+
+        // Options that do not apply to the backend are rejected
+        assert!(
+            PackedStructContainer::<u32>::builder()
+                .header(true)
+                .build()
+                .is_err()
+        );
+        assert!(
+            PackedStructContainer::<u32>::builder()
+                .mmap(&path)
+                .header(true)
+                .read_only(true)
+                .build()
+                .is_err()
+        );
+    }
+}
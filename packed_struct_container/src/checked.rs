@@ -0,0 +1,139 @@
+//! Element validation for data loaded from files.
+
+use bytemuck::Pod;
+use raw_bytes_container::ContainerError;
+
+use crate::PackedStructContainer;
+
+/// A Pod type with invariants beyond "any bit pattern is valid".
+///
+/// Every bit pattern is a valid `T` as far as the compiler is concerned, but not
+/// necessarily as far as the program is: a NaN position or an out-of-range
+/// enum tag read from a corrupt file should be caught when the file is opened
+/// rather than deep in the code that consumes it.
+///
+/// # Example
+/// ```
+/// use packed_struct_container::CheckedPod;
+/// use bytemuck_derive::{Pod, Zeroable};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Pod, Zeroable)]
+/// struct Position {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// impl CheckedPod for Position {
+///     fn validate(&self) -> bool {
+///         self.x.is_finite() && self.y.is_finite()
+///     }
+/// }
+///
+/// assert!(!Position { x: f32::NAN, y: 0.0 }.validate());
+/// ```
+pub trait CheckedPod: Pod {
+    /// Returns true if the value satisfies the type's invariants.
+    fn validate(&self) -> bool;
+}
+
+impl<T: CheckedPod> PackedStructContainer<T> {
+    /// Like [`open_mmap_read`](Self::open_mmap_read), but checks every element with
+    /// [`CheckedPod::validate`] before returning.
+    ///
+    /// This touches the whole file once; use [`get_checked`](Self::get_checked) on
+    /// a plain mapping to validate only the elements actually read.
+    ///
+    /// # Errors
+    /// Returns [`ContainerError::InvalidFormat`] naming the first invalid element,
+    /// and an error if the file cannot be mapped.
+    pub fn open_mmap_read_validated<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> Result<Self, ContainerError> {
+        let container = Self::open_mmap_read(path)?;
+        container.validate_elements()?;
+        Ok(container)
+    }
+
+    /// Index of the first element that fails [`CheckedPod::validate`], if any.
+    pub fn first_invalid(&self) -> Option<usize> {
+        self.as_slice().iter().position(|value| !value.validate())
+    }
+
+    /// Check every element with [`CheckedPod::validate`].
+    ///
+    /// # Errors
+    /// Returns [`ContainerError::InvalidFormat`] naming the first invalid element.
+    pub fn validate_elements(&self) -> Result<(), ContainerError> {
+        match self.first_invalid() {
+            Some(index) => Err(ContainerError::InvalidFormat(format!(
+                "element {} of {} failed validation",
+                index,
+                self.len()
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Get element by index, validating it on access.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    ///
+    /// # Errors
+    /// Returns [`ContainerError::InvalidFormat`] if the element fails
+    /// [`CheckedPod::validate`].
+    pub fn get_checked(&self, index: usize) -> Option<Result<T, ContainerError>> {
+        let value = self.get(index)?;
+        Some(if value.validate() {
+            Ok(value)
+        } else {
+            Err(ContainerError::InvalidFormat(format!(
+                "element {} failed validation",
+                index
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck_derive::{Pod, Zeroable};
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    struct Position {
+        x: f32,
+        y: f32,
+    }
+
+    impl CheckedPod for Position {
+        fn validate(&self) -> bool {
+            self.x.is_finite() && self.y.is_finite()
+        }
+    }
+
+    #[test]
+    fn test_open_mmap_read_validated() {
+        let mut positions = vec![Position { x: 1.0, y: 2.0 }; 4];
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::cast_slice(&positions)).unwrap();
+        let loaded =
+            PackedStructContainer::<Position>::open_mmap_read_validated(file.path()).unwrap();
+        assert_eq!(loaded.len(), 4);
+        assert_eq!(loaded.first_invalid(), None);
+
+        positions[2].y = f32::NAN;
+        std::fs::write(file.path(), bytemuck::cast_slice(&positions)).unwrap();
+        let err =
+            PackedStructContainer::<Position>::open_mmap_read_validated(file.path()).unwrap_err();
+        assert!(err.to_string().contains("element 2 of 4"), "{err}");
+
+        // A plain mapping validates lazily
+        let mapped = PackedStructContainer::<Position>::open_mmap_read(file.path()).unwrap();
+        assert_eq!(mapped.get_checked(1).unwrap().unwrap(), positions[1]);
+        assert!(mapped.get_checked(2).unwrap().is_err());
+        assert!(mapped.get_checked(4).is_none());
+        assert_eq!(mapped.first_invalid(), Some(2));
+    }
+}
@@ -0,0 +1,117 @@
+//! In-place byte-order conversion of every element.
+
+use bytemuck::Pod;
+use mtf::ByteSwap;
+use raw_bytes_container::ContainerError;
+
+use crate::PackedStructContainer;
+
+impl<T: Pod + Copy + ByteSwap> PackedStructContainer<T> {
+    /// Reverse the byte order of every field of every element in place.
+    ///
+    /// Call once after loading data written on a target of the other endianness,
+    /// instead of converting on every access. `T` implements [`ByteSwap`] through
+    /// `#[derive(ByteSwap)]` from `mtf_derive`.
+    ///
+    /// ```
+    /// use bytemuck_derive::{Pod, Zeroable};
+    /// use mtf_derive::ByteSwap;
+    /// use packed_struct_container::PackedStructContainer;
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Copy, Pod, Zeroable, ByteSwap)]
+    /// struct Sample {
+    ///     id: u32,
+    ///     pos: [f32; 2],
+    /// }
+    ///
+    /// // As written by a big-endian machine
+    /// let be = Sample { id: u32::from_be(7), pos: [f32::from_bits(1.5f32.to_bits().to_be()); 2] };
+    /// let mut samples = PackedStructContainer::from_slice(&[be]);
+    /// if cfg!(target_endian = "little") {
+    ///     samples.convert_endianness().unwrap();
+    /// }
+    /// assert_eq!(samples[0].id, 7);
+    /// assert_eq!(samples[0].pos, [1.5, 1.5]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the storage is read-only.
+    pub fn convert_endianness(&mut self) -> Result<(), ContainerError> {
+        self.writable_slice("Cannot convert endianness of read-only storage")?
+            .iter_mut()
+            .for_each(ByteSwap::byte_swap);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck_derive::{Pod, Zeroable};
+    use mtf_derive::ByteSwap;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, ByteSwap)]
+    struct Inner {
+        a: u16,
+        b: [u8; 2],
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, ByteSwap)]
+    struct Record {
+        id: u64,
+        inner: Inner,
+        weights: [f32; 2],
+        tag: i32,
+    }
+
+    #[repr(C, packed)]
+    #[derive(Clone, Copy, Pod, Zeroable, ByteSwap)]
+    struct Packed {
+        flag: u8,
+        value: u32,
+        tail: [u8; 3],
+    }
+
+    #[test]
+    fn test_convert_endianness() {
+        let record = Record {
+            id: 0x0102_0304_0506_0708,
+            inner: Inner {
+                a: 0x0a0b,
+                b: [1, 2],
+            },
+            weights: [1.5, -2.0],
+            tag: -3,
+        };
+        let mut container = PackedStructContainer::from_slice(&[record, record]);
+
+        container.convert_endianness().unwrap();
+        let swapped = container[1];
+        assert_eq!(swapped.id, 0x0807_0605_0403_0201);
+        assert_eq!(swapped.inner.a, 0x0b0a);
+        assert_eq!(swapped.inner.b, [1, 2]);
+        assert_eq!(swapped.weights[0].to_bits(), 1.5f32.to_bits().swap_bytes());
+        assert_eq!(swapped.tag, (-3i32).swap_bytes());
+
+        // Swapping twice restores the original
+        container.convert_endianness().unwrap();
+        assert_eq!(container.as_slice(), &[record, record]);
+
+        let mut packed = PackedStructContainer::from_slice(&[Packed {
+            flag: 1,
+            value: 0x1122_3344,
+            tail: [5, 6, 7],
+        }]);
+        packed.convert_endianness().unwrap();
+        let Packed { value, .. } = packed[0];
+        assert_eq!(value, 0x4433_2211);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::bytes_of(&record)).unwrap();
+        let mut mapped = PackedStructContainer::<Record>::open_mmap_read(file.path()).unwrap();
+        assert!(mapped.convert_endianness().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Byte export with padded element strides, e.g. for GPU vertex buffers.
+
+use bytemuck::Pod;
+use std::borrow::Cow;
+use std::io::Write;
+
+use crate::PackedStructContainer;
+
+impl<T: Pod + Copy> PackedStructContainer<T> {
+    /// Distance between elements once each is padded to a multiple of `alignment`.
+    ///
+    /// # Panics
+    /// Panics if `alignment` is 0.
+    pub fn stride_for(alignment: usize) -> usize {
+        assert!(alignment > 0, "alignment must be non-zero");
+        std::mem::size_of::<T>().next_multiple_of(alignment)
+    }
+
+    /// The elements as bytes, each zero-padded to a multiple of `alignment`.
+    ///
+    /// Borrows the storage when `T` is already a multiple of `alignment`, and copies
+    /// otherwise. Suits GPU APIs that require e.g. a 16-byte vertex stride.
+    ///
+    /// ```
+    /// use packed_struct_container::PackedStructContainer;
+    ///
+    /// let vertices = PackedStructContainer::from_slice(&[[1.0f32; 3], [2.0; 3]]);
+    /// let bytes = vertices.as_bytes_with_stride(16);
+    /// assert_eq!(bytes.len(), 32);
+    /// assert_eq!(&bytes[12..16], &[0; 4]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `alignment` is 0.
+    pub fn as_bytes_with_stride(&self, alignment: usize) -> Cow<'_, [u8]> {
+        let stride = Self::stride_for(alignment);
+        let bytes = self.storage.as_slice();
+        if stride == std::mem::size_of::<T>() {
+            return Cow::Borrowed(bytes);
+        }
+
+        let mut out = vec![0; self.len() * stride];
+        for (dst, src) in out
+            .chunks_exact_mut(stride)
+            .zip(bytes.chunks_exact(std::mem::size_of::<T>()))
+        {
+            dst[..src.len()].copy_from_slice(src);
+        }
+        Cow::Owned(out)
+    }
+
+    /// Write the elements to `writer`, each zero-padded to a multiple of `alignment`,
+    /// without building the padded buffer in memory.
+    ///
+    /// Writes the same bytes as [`as_bytes_with_stride`](Self::as_bytes_with_stride);
+    /// use it to fill a mapped upload buffer directly.
+    ///
+    /// # Panics
+    /// Panics if `alignment` is 0.
+    pub fn write_with_stride<W: Write>(
+        &self,
+        mut writer: W,
+        alignment: usize,
+    ) -> std::io::Result<()> {
+        let stride = Self::stride_for(alignment);
+        let bytes = self.storage.as_slice();
+        let size = std::mem::size_of::<T>();
+        if stride == size {
+            return writer.write_all(bytes);
+        }
+
+        let padding = vec![0; stride - size];
+        for element in bytes.chunks_exact(size) {
+            writer.write_all(element)?;
+            writer.write_all(&padding)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_with_stride() {
+        let vertices = PackedStructContainer::from_slice(&[[1u8, 2, 3], [4, 5, 6]]);
+        assert_eq!(PackedStructContainer::<[u8; 3]>::stride_for(4), 4);
+
+        let padded = vertices.as_bytes_with_stride(4);
+        assert!(matches!(padded, Cow::Owned(_)));
+        assert_eq!(&*padded, &[1, 2, 3, 0, 4, 5, 6, 0]);
+
+        let mut written = Vec::new();
+        vertices.write_with_stride(&mut written, 4).unwrap();
+        assert_eq!(written, &*padded);
+
+        // Already aligned: no copy, no padding
+        let tight = vertices.as_bytes_with_stride(3);
+        assert!(matches!(tight, Cow::Borrowed(_)));
+        assert_eq!(tight.len(), 6);
+
+        let empty = PackedStructContainer::<[u8; 3]>::new();
+        assert!(empty.as_bytes_with_stride(16).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "alignment must be non-zero")]
+    fn test_zero_alignment() {
+        PackedStructContainer::<u32>::new().as_bytes_with_stride(0);
+    }
+}
@@ -0,0 +1,107 @@
+//! Read-only strided views over foreign fixed-record binary files.
+
+use bytemuck::Pod;
+use raw_bytes_container::{ContainerError, RawBytesContainer};
+use std::marker::PhantomData;
+
+use crate::PackedStructContainer;
+
+/// A memory-mapped file of fixed-size records, exposing the `T` prefix of each.
+///
+/// Created by [`PackedStructContainer::open_foreign`]. Records start at
+/// `data_offset` and are `record_stride` bytes apart; only the first
+/// `size_of::<T>()` bytes of each record are read. Records are not required to
+/// be aligned, so elements are returned by value.
+#[derive(Debug)]
+pub struct ForeignRecords<T: Pod + Copy> {
+    storage: RawBytesContainer<u8>,
+    record_stride: usize,
+    data_offset: usize,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod + Copy> ForeignRecords<T> {
+    pub(crate) fn open<P: AsRef<std::path::Path>>(
+        path: P,
+        record_stride: usize,
+        data_offset: usize,
+    ) -> Result<Self, ContainerError> {
+        let size = std::mem::size_of::<T>();
+        if record_stride < size || record_stride == 0 {
+            return Err(ContainerError::InvalidFormat(format!(
+                "record stride {} is smaller than element size {}",
+                record_stride, size
+            )));
+        }
+
+        let storage = RawBytesContainer::<u8>::open_mmap_read(path)?;
+        let Some(body) = storage.len().checked_sub(data_offset) else {
+            return Err(ContainerError::InvalidFormat(format!(
+                "data offset {} is beyond end of file ({} bytes)",
+                data_offset,
+                storage.len()
+            )));
+        };
+
+        // A trailing partial record is still usable if its T prefix fits
+        let mut len = body / record_stride;
+        if body % record_stride >= size && size > 0 {
+            len += 1;
+        }
+
+        Ok(Self {
+            storage,
+            record_stride,
+            data_offset,
+            len,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Distance between consecutive records, in bytes.
+    pub fn record_stride(&self) -> usize {
+        self.record_stride
+    }
+
+    /// Byte offset of the first record.
+    pub fn data_offset(&self) -> usize {
+        self.data_offset
+    }
+
+    /// Read the `T` prefix of record `index`.
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        let start = self.data_offset + index * self.record_stride;
+        let bytes = &self.storage.as_slice()[start..start + std::mem::size_of::<T>()];
+        Some(bytemuck::pod_read_unaligned(bytes))
+    }
+
+    /// Iterate over the `T` prefixes of all records.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.len).map(move |i| self.get(i).unwrap())
+    }
+
+    /// Copy all records into a densely packed in-memory container.
+    pub fn to_container(&self) -> PackedStructContainer<T> {
+        let values: Vec<T> = self.iter().collect();
+        PackedStructContainer::from_slice(&values)
+    }
+
+    /// Raw bytes of the whole file, including any foreign header.
+    pub fn raw_bytes(&self) -> &[u8] {
+        self.storage.as_slice()
+    }
+}
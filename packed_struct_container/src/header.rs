@@ -0,0 +1,87 @@
+//! Versioned header for typed files written by [`PackedStructContainer::save`].
+//!
+//! ```text
+//! [FileHeader: "PSCF", version][ELEM_SIZE: u32][ELEM_ALIGN: u32]
+//! [COUNT: u64][TYPE_HASH: u64][ELEMENTS]
+//! ```
+//!
+//! [`PackedStructContainer::save`]: crate::PackedStructContainer::save
+
+use bytemuck::Pod;
+use bytemuck_derive::{Pod, Zeroable};
+use raw_bytes_container::{ContainerError, FileHeader, sectioned::type_hash};
+
+/// Magic bytes of a typed file.
+pub const TYPED_FILE_MAGIC: &[u8; 4] = b"PSCF";
+
+/// Current typed file version.
+pub const TYPED_FILE_VERSION: u32 = 1;
+
+/// Header describing the element type and count of a typed file.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct TypedFileHeader {
+    pub file: FileHeader,
+    /// `size_of::<T>()` of the stored elements.
+    pub elem_size: u32,
+    /// `align_of::<T>()` of the stored elements.
+    pub elem_align: u32,
+    /// Number of elements following the header.
+    pub count: u64,
+    /// [`type_hash`] of the element type, or 0 if not recorded.
+    pub type_hash: u64,
+}
+
+impl TypedFileHeader {
+    /// Size of the header in bytes; the elements start right after it.
+    pub const SIZE: usize = std::mem::size_of::<TypedFileHeader>();
+
+    /// Header for `count` elements of `T`, recording the type hash if `with_type_hash`.
+    pub fn for_type<T: Pod>(count: usize, with_type_hash: bool) -> Self {
+        Self {
+            file: FileHeader::new(*TYPED_FILE_MAGIC, TYPED_FILE_VERSION),
+            elem_size: std::mem::size_of::<T>() as u32,
+            elem_align: std::mem::align_of::<T>() as u32,
+            count: count as u64,
+            type_hash: if with_type_hash { type_hash::<T>() } else { 0 },
+        }
+    }
+
+    /// Read a header from the start of `bytes`.
+    pub fn read(bytes: &[u8]) -> Result<Self, ContainerError> {
+        if bytes.len() < Self::SIZE {
+            return Err(ContainerError::InvalidFormat(format!(
+                "file too small for typed header: {} bytes",
+                bytes.len()
+            )));
+        }
+        Ok(bytemuck::pod_read_unaligned(&bytes[..Self::SIZE]))
+    }
+
+    /// Check the magic and version, and that the file holds elements of `T`.
+    ///
+    /// The type hash is only compared when the file recorded one.
+    pub fn validate<T: Pod>(&self) -> Result<(), ContainerError> {
+        self.file.validate(TYPED_FILE_MAGIC, TYPED_FILE_VERSION)?;
+        let type_name = std::any::type_name::<T>();
+        let (size, align) = (std::mem::size_of::<T>(), std::mem::align_of::<T>());
+        if self.elem_size as usize != size || self.elem_align as usize != align {
+            return Err(ContainerError::TypeMismatch(format!(
+                "file holds {}-byte elements (align {}), but {} is {} bytes (align {})",
+                self.elem_size, self.elem_align, type_name, size, align
+            )));
+        }
+        if self.type_hash != 0 && self.type_hash != type_hash::<T>() {
+            return Err(ContainerError::TypeMismatch(format!(
+                "file was written with a different element type than {}",
+                type_name
+            )));
+        }
+        Ok(())
+    }
+
+    /// View the header as raw bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(self)
+    }
+}
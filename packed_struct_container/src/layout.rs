@@ -0,0 +1,231 @@
+//! Layout checks for element types stored in a [`PackedStructContainer`].
+//!
+//! [`PackedStructContainer`]: crate::PackedStructContainer
+
+use raw_bytes_container::ContainerError;
+
+/// Layout facts about an element type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutReport {
+    /// `size_of::<T>()`.
+    pub size: usize,
+    /// `align_of::<T>()`.
+    pub align: usize,
+    /// Bytes not covered by any field, if field layout is known.
+    ///
+    /// `None` when no field metadata was available (see [`check_layout_mtf`]).
+    pub padding_bytes: Option<usize>,
+}
+
+impl LayoutReport {
+    /// Returns true if the type is known to contain padding bytes.
+    ///
+    /// Padding bytes are written to files verbatim and can leak
+    /// uninitialized-looking garbage.
+    pub fn has_padding(&self) -> bool {
+        self.padding_bytes.is_some_and(|p| p > 0)
+    }
+}
+
+/// Check that `T` can be stored and cast safely.
+///
+/// Verifies that the alignment is at most 8, which is what byte storage
+/// guarantees. Rust already makes every size a multiple of its alignment, so
+/// arrays of `T` always pack without gaps.
+pub fn check_layout<T>() -> Result<LayoutReport, ContainerError> {
+    let size = std::mem::size_of::<T>();
+    let align = std::mem::align_of::<T>();
+
+    if align > 8 {
+        return Err(ContainerError::AlignmentError(format!(
+            "alignment {} of {} is too strict for byte storage (max 8)",
+            align,
+            std::any::type_name::<T>()
+        )));
+    }
+
+    Ok(LayoutReport {
+        size,
+        align,
+        padding_bytes: None,
+    })
+}
+
+/// Like [`check_layout`], but also uses the MTF field metadata of `T` to
+/// detect padding bytes.
+///
+/// Padding is reported through [`LayoutReport::padding_bytes`]. With the
+/// `strict-layout` feature enabled it is an error instead.
+#[cfg(feature = "mtf")]
+pub fn check_layout_mtf<T: mtf::MTFType>() -> Result<LayoutReport, ContainerError> {
+    let mut report = check_layout::<T>()?;
+
+    let (types, _) = mtf::read_mtf(T::mtf_type_blob())
+        .map_err(|e| ContainerError::AlignmentError(format!("invalid MTF metadata: {}", e)))?;
+    let type_def = types.first().ok_or_else(|| {
+        ContainerError::AlignmentError("MTF metadata contains no types".to_string())
+    })?;
+
+    let field_bytes: usize = type_def
+        .fields
+        .iter()
+        .map(|f| (f.size_bits as usize).div_ceil(8))
+        .sum();
+    let padding = report.size.saturating_sub(field_bytes);
+    report.padding_bytes = Some(padding);
+
+    if cfg!(feature = "strict-layout") && padding > 0 {
+        return Err(ContainerError::AlignmentError(format!(
+            "{} contains {} padding byte(s)",
+            std::any::type_name::<T>(),
+            padding
+        )));
+    }
+
+    Ok(report)
+}
+
+/// Run [`check_layout_mtf`] for a container being built or saved.
+///
+/// Padding is an error under `strict-layout`; otherwise a warning is printed to
+/// stderr, once per type.
+#[cfg(feature = "mtf")]
+pub(crate) fn enforce_layout_mtf<T: mtf::MTFType + 'static>() -> Result<LayoutReport, ContainerError>
+{
+    use std::any::TypeId;
+    use std::collections::HashSet;
+    use std::sync::{Mutex, PoisonError};
+
+    static WARNED: Mutex<Option<HashSet<TypeId>>> = Mutex::new(None);
+
+    let report = check_layout_mtf::<T>()?;
+    if report.has_padding() {
+        let mut warned = WARNED.lock().unwrap_or_else(PoisonError::into_inner);
+        if warned
+            .get_or_insert_with(HashSet::new)
+            .insert(TypeId::of::<T>())
+        {
+            eprintln!(
+                "warning: {} contains {} padding byte(s) that are stored verbatim",
+                std::any::type_name::<T>(),
+                report.padding_bytes.unwrap_or(0)
+            );
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_layout() {
+        let report = check_layout::<[u32; 3]>().unwrap();
+        assert_eq!(report.size, 12);
+        assert_eq!(report.align, 4);
+        assert!(!report.has_padding());
+
+        #[repr(C, align(16))]
+        struct Overaligned {
+            _x: u8,
+        }
+        assert!(check_layout::<Overaligned>().is_err());
+    }
+
+    #[test]
+    fn test_constructor_rejects_overaligned() {
+        use crate::PackedStructContainer;
+        use bytemuck_derive::{Pod, Zeroable};
+
+        #[repr(C, align(16))]
+        #[derive(Clone, Copy, Pod, Zeroable)]
+        struct Wide([u8; 16]);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), [0u8; 32]).unwrap();
+        assert!(matches!(
+            PackedStructContainer::<Wide>::open_mmap_read(file.path()),
+            Err(ContainerError::AlignmentError(_))
+        ));
+        assert!(matches!(
+            PackedStructContainer::<Wide>::builder().build(),
+            Err(ContainerError::AlignmentError(_))
+        ));
+    }
+
+    #[cfg(feature = "mtf")]
+    #[test]
+    fn test_schema_constructors_check_padding() {
+        use crate::PackedStructContainer;
+        use bytemuck_derive::{Pod, Zeroable};
+        use mtf_derive::MTF;
+
+        #[repr(C)]
+        #[derive(MTF)]
+        #[allow(dead_code)]
+        struct Head {
+            a: u32,
+        }
+
+        // Pod, but its MTF metadata only describes `a`, leaving 4 bytes undescribed
+        #[repr(C)]
+        #[derive(Clone, Copy, Pod, Zeroable)]
+        struct Sparse {
+            a: u32,
+            b: u32,
+        }
+
+        impl mtf::MTFType for Sparse {
+            fn mtf_type_blob() -> &'static [u8] {
+                Head::mtf_type_blob()
+            }
+            fn mtf_string_table() -> &'static [u8] {
+                Head::mtf_string_table()
+            }
+        }
+
+        let container = PackedStructContainer::from_slice(&[Sparse { a: 1, b: 2 }]);
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let saved = container.save_with_schema(file.path());
+        let opened = PackedStructContainer::<Sparse>::open_with_schema(file.path());
+        if cfg!(feature = "strict-layout") {
+            assert!(matches!(saved, Err(ContainerError::AlignmentError(_))));
+            assert!(matches!(opened, Err(ContainerError::AlignmentError(_))));
+        } else {
+            saved.unwrap();
+            assert_eq!(opened.unwrap()[0].b, 2);
+        }
+    }
+
+    #[cfg(feature = "mtf")]
+    #[test]
+    fn test_check_layout_mtf_padding() {
+        use mtf_derive::MTF;
+
+        #[repr(C)]
+        #[derive(MTF)]
+        #[allow(dead_code)]
+        struct Tight {
+            a: u32,
+            b: u32,
+        }
+
+        #[repr(C)]
+        #[derive(MTF)]
+        #[allow(dead_code)]
+        struct Padded {
+            a: u8,
+            b: u32,
+        }
+
+        assert_eq!(check_layout_mtf::<Tight>().unwrap().padding_bytes, Some(0));
+
+        let padded = check_layout_mtf::<Padded>();
+        if cfg!(feature = "strict-layout") {
+            assert!(padded.is_err());
+        } else {
+            assert_eq!(padded.unwrap().padding_bytes, Some(3));
+        }
+    }
+}
@@ -4,15 +4,64 @@
 //! arrays of Pod types, supporting both in-memory and memory-mapped storage.
 
 use bytemuck::Pod;
-use raw_bytes_container::RawBytesContainer;
+use raw_bytes_container::{MmapVec, RawBytesContainer};
 use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Bound, Deref, DerefMut, RangeBounds};
+
+mod backing;
+pub mod buffered;
+pub mod builder;
+pub mod checked;
+mod export;
+pub mod foreign;
+pub mod header;
+pub mod layout;
+pub mod multi;
+pub mod projection;
+pub mod shared;
+pub mod view;
+use backing::Backing;
+pub use buffered::BufferedAppender;
+pub use builder::PackedStructContainerBuilder;
+pub use checked::CheckedPod;
+pub use foreign::ForeignRecords;
+pub use header::TypedFileHeader;
+pub use layout::{LayoutReport, check_layout};
+pub use multi::MultiContainer;
+pub use projection::Projection;
+pub use shared::SharedPackedStructContainer;
+pub use view::{View, ViewMut};
+
+#[cfg(feature = "mtf")]
+pub use layout::check_layout_mtf;
+
+#[cfg(feature = "mtf")]
+mod endian;
+
+#[cfg(feature = "rayon")]
+mod par;
+
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+#[cfg(feature = "mtf")]
+pub mod schema;
+#[cfg(feature = "mtf")]
+pub use schema::check_schema_compatible;
+
+#[cfg(feature = "mtf")]
+pub mod table;
+#[cfg(feature = "mtf")]
+pub use table::{Column, Table};
 
 /// A container of packed Pod structs.
 ///
 /// Can be backed by in-memory storage or memory-mapped files.
 /// Provides zero-cost abstraction over byte arrays with type safety.
 ///
+/// In-memory storage and files made by [`create_mmap`](Self::create_mmap) are
+/// growable; other mappings have a fixed size and cannot be appended to.
+///
 /// # Example
 /// ```
 /// use packed_struct_container::PackedStructContainer;
@@ -33,7 +82,7 @@ use std::ops::{Deref, DerefMut};
 /// ```
 #[derive(Debug)]
 pub struct PackedStructContainer<T: Pod + Copy> {
-    storage: RawBytesContainer<u8>,
+    storage: Backing<T>,
     _marker: PhantomData<T>,
 }
 
@@ -45,19 +94,20 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
 
     /// Create an in-memory container with pre-allocated capacity for `capacity` elements.
     pub fn with_capacity(capacity: usize) -> Self {
+        let () = Self::LAYOUT_OK;
         let byte_capacity = capacity * std::mem::size_of::<T>();
         Self {
-            storage: RawBytesContainer::from_vec(Vec::with_capacity(byte_capacity)),
+            storage: RawBytesContainer::from_vec(Vec::with_capacity(byte_capacity)).into(),
             _marker: PhantomData,
         }
     }
 
     /// Create from a slice (in-memory).
     pub fn from_slice(data: &[T]) -> Self {
-        Self::validate_alignment();
+        let () = Self::LAYOUT_OK;
         let bytes = bytemuck::cast_slice(data).to_vec();
         Self {
-            storage: RawBytesContainer::from_vec(bytes),
+            storage: RawBytesContainer::from_vec(bytes).into(),
             _marker: PhantomData,
         }
     }
@@ -67,13 +117,23 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
         Self::from_slice(values)
     }
 
+    /// Copy the elements into a new in-memory container, whatever the backend.
+    ///
+    /// Snapshots a memory-mapped container so it can be processed in the
+    /// background without keeping the file mapped. The copy is detached: writes
+    /// to it never reach the file. There is deliberately no [`Clone`] impl, so
+    /// this copy is always explicit.
+    pub fn deep_clone(&self) -> Self {
+        Self::from_slice(self.as_slice())
+    }
+
     /// Open a memory-mapped file read-only.
     pub fn open_mmap_read<P: AsRef<std::path::Path>>(
         path: P,
     ) -> Result<Self, raw_bytes_container::ContainerError> {
-        Self::validate_alignment();
+        Self::validate_alignment()?;
         Ok(Self {
-            storage: RawBytesContainer::open_mmap_read(path)?,
+            storage: RawBytesContainer::open_mmap_read(path)?.into(),
             _marker: PhantomData,
         })
     }
@@ -82,21 +142,165 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
     pub fn open_mmap_rw<P: AsRef<std::path::Path>>(
         path: P,
     ) -> Result<Self, raw_bytes_container::ContainerError> {
-        Self::validate_alignment();
+        Self::validate_alignment()?;
+        Ok(Self {
+            storage: RawBytesContainer::open_mmap_rw(path)?.into(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Create (or truncate) a memory-mapped file with room for `capacity` elements.
+    ///
+    /// The file starts with a small header holding the logical length, so the
+    /// container starts empty and can be pushed into, grown and shrunk directly on
+    /// disk like an in-memory one; the file grows geometrically once `capacity` is
+    /// exceeded. Reopen it with [`open_mmap`](Self::open_mmap).
+    ///
+    /// See [`MmapVec`] for the file layout. Such files are not readable with
+    /// [`open_mmap_read`](Self::open_mmap_read) or [`open_mmap_rw`](Self::open_mmap_rw),
+    /// which expect the elements to fill the whole file.
+    pub fn create_mmap<P: AsRef<std::path::Path>>(
+        path: P,
+        capacity: usize,
+    ) -> Result<Self, raw_bytes_container::ContainerError> {
+        Self::validate_alignment()?;
+        Ok(Self {
+            storage: Backing::Vec(MmapVec::create_with_capacity(path, capacity)?),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Map a file created by [`create_mmap`](Self::create_mmap) read-write.
+    ///
+    /// # Errors
+    /// Fails if the file has no length header or the length exceeds its capacity.
+    pub fn open_mmap<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> Result<Self, raw_bytes_container::ContainerError> {
+        Self::validate_alignment()?;
         Ok(Self {
-            storage: RawBytesContainer::open_mmap_rw(path)?,
+            storage: Backing::Vec(MmapVec::open(path)?),
             _marker: PhantomData,
         })
     }
 
+    /// Write the elements to `path`, preceded by a [`TypedFileHeader`] recording the
+    /// element size, alignment, count and type hash.
+    ///
+    /// Read the file back with [`open_checked`](Self::open_checked). The type hash is
+    /// derived from the type name, which is not guaranteed stable across compiler
+    /// versions; use [`save_without_type_hash`](Self::save_without_type_hash) for
+    /// files that must outlive the toolchain.
+    pub fn save<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        self.save_header(path, TypedFileHeader::for_type::<T>(self.len(), true))
+    }
+
+    /// Like [`save`](Self::save), but only the element size, alignment and count are
+    /// checked on open.
+    pub fn save_without_type_hash<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        self.save_header(path, TypedFileHeader::for_type::<T>(self.len(), false))
+    }
+
+    fn save_header<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        header: TypedFileHeader,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        use std::io::Write;
+
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(header.as_bytes())?;
+        file.write_all(self.storage.as_slice())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Load a file written by [`save`](Self::save) into memory, validating its header.
+    ///
+    /// # Errors
+    /// Returns [`ContainerError::TypeMismatch`](raw_bytes_container::ContainerError::TypeMismatch)
+    /// if the file holds a different element type, e.g. a `Vec3` file opened as
+    /// `Vec4`, and an error if the header is missing or the element count does not
+    /// match the file size.
+    pub fn open_checked<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> Result<Self, raw_bytes_container::ContainerError> {
+        let bytes = std::fs::read(path)?;
+        let header = TypedFileHeader::read(&bytes)?;
+        Self::from_typed_bytes(bytes, &header)
+    }
+
+    /// Build an in-memory container from a typed file image whose elements end
+    /// exactly where `bytes` ends.
+    pub(crate) fn from_typed_bytes(
+        mut bytes: Vec<u8>,
+        header: &TypedFileHeader,
+    ) -> Result<Self, raw_bytes_container::ContainerError> {
+        Self::validate_alignment()?;
+        header.validate::<T>()?;
+
+        let payload = bytes.len() - TypedFileHeader::SIZE;
+        let expected = (header.count as usize).checked_mul(std::mem::size_of::<T>());
+        if expected != Some(payload) {
+            return Err(raw_bytes_container::ContainerError::InvalidFormat(format!(
+                "header claims {} elements, but the file holds {} bytes of data",
+                header.count, payload
+            )));
+        }
+        bytes.drain(..TypedFileHeader::SIZE);
+        Ok(Self {
+            storage: RawBytesContainer::from_vec(bytes).into(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Open a foreign binary file of fixed-size records read-only.
+    ///
+    /// Records start at `data_offset` (skipping any foreign header) and are
+    /// `record_stride` bytes apart, which may be larger than `T`. The returned
+    /// [`ForeignRecords`] view exposes only the `T` prefix of each record, so
+    /// third-party logs can be read without converting them first.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be mapped, `record_stride` is
+    /// smaller than `T`, or `data_offset` lies beyond the end of the file.
+    pub fn open_foreign<P: AsRef<std::path::Path>>(
+        path: P,
+        record_stride: usize,
+        data_offset: usize,
+    ) -> Result<ForeignRecords<T>, raw_bytes_container::ContainerError> {
+        Self::validate_alignment()?;
+        ForeignRecords::open(path, record_stride, data_offset)
+    }
+
+    /// Compile-time form of [`check_layout`] for the constructors that cannot fail.
+    const LAYOUT_OK: () = assert!(
+        std::mem::align_of::<T>() <= 8,
+        "element alignment is too strict for byte storage (max 8)"
+    );
+
     /// Validate that T has proper alignment for byte-level casting.
-    fn validate_alignment() {
-        // bytemuck already validates this at compile time via Pod trait,
-        // but we add a runtime check for extra safety
-        assert!(
-            std::mem::align_of::<T>() <= 8,
-            "Type alignment too strict for safe casting"
-        );
+    ///
+    /// # Errors
+    /// Returns the error of [`check_layout`] if it rejects `T`.
+    pub(crate) fn validate_alignment() -> Result<(), raw_bytes_container::ContainerError> {
+        // bytemuck already validates most of this at compile time via the Pod
+        // trait, but byte storage adds its own alignment limit
+        check_layout::<T>().map(|_| ())
+    }
+
+    /// Check the layout of the element type `T`.
+    ///
+    /// See [`check_layout`]; with the `mtf` feature, [`check_layout_mtf`] can
+    /// additionally detect padding bytes for MTF-derived types.
+    pub fn validate_layout() -> Result<LayoutReport, raw_bytes_container::ContainerError> {
+        check_layout::<T>()
     }
 
     /// Returns the number of elements in the container.
@@ -109,12 +313,35 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
         self.len() == 0
     }
 
-    // Returns the capacity in elements before reallocation. (NOT CRRECT)
-    // currently returns len() in bytes / size_of::<T>()
-    // it can't be implemented accurately without access to the underlying Vec's capacity.
-    // pub fn capacity(&self) -> usize {
-    //     self.storage.as_slice().len() / std::mem::size_of::<T>()
-    // }
+    /// Number of elements the container can hold before reallocating.
+    ///
+    /// Returns `None` for memory-mapped storage, which never reallocates, except for
+    /// files made by [`create_mmap`](Self::create_mmap).
+    pub fn capacity(&self) -> Option<usize> {
+        Some(self.storage.capacity()? / std::mem::size_of::<T>())
+    }
+
+    /// Reserve room for at least `additional` more elements (growable storage only).
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping.
+    pub fn reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        let bytes = additional.checked_mul(std::mem::size_of::<T>()).ok_or(
+            raw_bytes_container::ContainerError::UnsupportedOperation("Capacity overflow"),
+        )?;
+        self.storage.reserve(bytes)
+    }
+
+    /// Release unused capacity (growable storage only).
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping.
+    pub fn shrink_to_fit(&mut self) -> Result<(), raw_bytes_container::ContainerError> {
+        self.storage.shrink_to_fit()
+    }
 
     /// Access as slice of T.
     pub fn as_slice(&self) -> &[T] {
@@ -140,7 +367,12 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
         self.as_slice_mut()?.get_mut(index)
     }
 
-    /// Append new elements (in-memory only).
+    /// Append new elements.
+    ///
+    /// On a mapping opened with [`open_mmap_rw`](Self::open_mmap_rw) the file grows
+    /// geometrically (see [`RawBytesContainer::append_mapped`]), so recorders can
+    /// stream straight into a capture file; the spare room is cut off again on
+    /// [`flush`](Self::flush) and drop.
     ///
     /// # Errors
     /// Returns an error if the storage is read-only or cannot be resized.
@@ -149,6 +381,26 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
         self.storage.append(new_bytes)
     }
 
+    /// Start a buffered append session (growable storage only).
+    ///
+    /// Records pushed through the returned [`BufferedAppender`] are committed
+    /// in blocks of `block_size`, reducing per-push overhead when logging many
+    /// tiny records.
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping.
+    pub fn append_buffered(
+        &mut self,
+        block_size: usize,
+    ) -> Result<BufferedAppender<'_, T>, raw_bytes_container::ContainerError> {
+        if self.storage.capacity().is_none() {
+            return Err(raw_bytes_container::ContainerError::UnsupportedOperation(
+                "Append not supported on mmap storage",
+            ));
+        }
+        Ok(BufferedAppender::new(self, block_size))
+    }
+
     /// Append a single element.
     ///
     /// # Errors
@@ -157,6 +409,183 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
         self.append(&[value])
     }
 
+    /// Insert `value` at `index`, shifting later elements up (growable storage only).
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping or `index > len()`.
+    pub fn insert(
+        &mut self,
+        index: usize,
+        value: T,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        self.check_positional(
+            index,
+            self.len() + 1,
+            "Insert not supported on mmap storage",
+        )?;
+        let size = std::mem::size_of::<T>();
+        let old_bytes = self.storage.len();
+        self.storage.resize_zeroed(old_bytes + size)?;
+        let bytes = self.storage.as_slice_mut_checked()?;
+        bytes.copy_within(index * size..old_bytes, (index + 1) * size);
+        bytes[index * size..(index + 1) * size].copy_from_slice(bytemuck::bytes_of(&value));
+        Ok(())
+    }
+
+    /// Remove and return the element at `index`, shifting later elements down
+    /// (growable storage only).
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping or `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Result<T, raw_bytes_container::ContainerError> {
+        self.check_positional(index, self.len(), "Remove not supported on mmap storage")?;
+        let size = std::mem::size_of::<T>();
+        let value = self.as_slice()[index];
+        let old_bytes = self.storage.len();
+        self.storage
+            .as_slice_mut_checked()?
+            .copy_within((index + 1) * size..old_bytes, index * size);
+        self.storage.truncate(old_bytes - size)?;
+        Ok(value)
+    }
+
+    /// Remove the elements in `range` and return them by value, shifting later
+    /// elements down (growable storage only).
+    ///
+    /// The removed elements are copied out before the storage is compacted, so the
+    /// returned iterator does not borrow the container; hand it to a worker while
+    /// the container keeps accepting records.
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping or `range` is out of
+    /// bounds.
+    pub fn drain<R: RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> Result<std::vec::IntoIter<T>, raw_bytes_container::ContainerError> {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        self.check_positional(end, len + 1, "Drain not supported on mmap storage")?;
+        if start > end {
+            return Err(raw_bytes_container::ContainerError::IndexOutOfBounds {
+                index: start,
+                len,
+            });
+        }
+
+        let drained = self.as_slice()[start..end].to_vec();
+        let size = std::mem::size_of::<T>();
+        let old_bytes = self.storage.len();
+        self.storage
+            .as_slice_mut_checked()?
+            .copy_within(end * size..old_bytes, start * size);
+        self.storage.truncate(old_bytes - drained.len() * size)?;
+        Ok(drained.into_iter())
+    }
+
+    /// Remove and return the element at `index`, replacing it with the last element
+    /// (growable storage only).
+    ///
+    /// O(1), but does not preserve ordering.
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping or `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, raw_bytes_container::ContainerError> {
+        self.check_positional(index, self.len(), "Remove not supported on mmap storage")?;
+        let last = self.len() - 1;
+        let values = self.as_slice_mut().expect("in-memory storage is writable");
+        let value = values[index];
+        values[index] = values[last];
+        self.storage.truncate(last * std::mem::size_of::<T>())?;
+        Ok(value)
+    }
+
+    /// Remove and return the last element, or `None` if the container is empty
+    /// (growable storage only).
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping, which cannot shrink.
+    pub fn pop(&mut self) -> Result<Option<T>, raw_bytes_container::ContainerError> {
+        if self.storage.capacity().is_none() {
+            return Err(raw_bytes_container::ContainerError::UnsupportedOperation(
+                "Pop not supported on mmap storage",
+            ));
+        }
+        let Some(last) = self.len().checked_sub(1) else {
+            return Ok(None);
+        };
+        let value = self.as_slice()[last];
+        self.storage.truncate(last * std::mem::size_of::<T>())?;
+        Ok(Some(value))
+    }
+
+    /// Shorten the container to `len` elements, keeping its capacity; no-op if it is
+    /// already shorter (growable storage only).
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping.
+    pub fn truncate(&mut self, len: usize) -> Result<(), raw_bytes_container::ContainerError> {
+        let bytes = len.saturating_mul(std::mem::size_of::<T>());
+        self.storage.truncate(bytes)
+    }
+
+    /// Keep only the elements for which `keep` returns true, compacting them to the
+    /// front in place and preserving their order, then truncate to that length
+    /// (growable storage only). Returns the number kept.
+    ///
+    /// # Errors
+    /// Returns an error if the storage is a fixed-size mapping, which cannot shrink.
+    pub fn retain<F>(&mut self, mut keep: F) -> Result<usize, raw_bytes_container::ContainerError>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        if self.storage.capacity().is_none() {
+            return Err(raw_bytes_container::ContainerError::UnsupportedOperation(
+                "Retain not supported on mmap storage",
+            ));
+        }
+        let values = self.as_slice_mut().expect("growable storage is writable");
+        let mut kept = 0;
+        for i in 0..values.len() {
+            if keep(&values[i]) {
+                values[kept] = values[i];
+                kept += 1;
+            }
+        }
+        self.truncate(kept)?;
+        Ok(kept)
+    }
+
+    /// Check that the storage can grow and shrink and that `index < bound`.
+    fn check_positional(
+        &self,
+        index: usize,
+        bound: usize,
+        message: &'static str,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        if self.storage.capacity().is_none() {
+            return Err(raw_bytes_container::ContainerError::UnsupportedOperation(
+                message,
+            ));
+        }
+        if index >= bound {
+            return Err(raw_bytes_container::ContainerError::IndexOutOfBounds {
+                index,
+                len: self.len(),
+            });
+        }
+        Ok(())
+    }
+
     /// Extend from an iterator.
     ///
     /// # Errors
@@ -169,12 +598,57 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
         self.append(&values)
     }
 
-    /// Clear all elements (in-memory only).
+    /// Read `count` elements from `reader` straight into the storage (growable
+    /// storage only).
+    ///
+    /// The storage grows in bounded chunks as data arrives, so a large `count` from
+    /// an untrusted stream does not allocate up front. If the stream ends early or
+    /// fails, the container is restored to its previous length and no partial
+    /// elements are kept.
+    ///
+    /// # Errors
+    /// Returns [`ContainerError::Io`](raw_bytes_container::ContainerError::Io) if
+    /// reading fails or the stream holds fewer than `count` elements, and an error
+    /// if the storage cannot grow.
+    pub fn extend_from_reader<R: std::io::Read>(
+        &mut self,
+        mut reader: R,
+        count: usize,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        const CHUNK_BYTES: usize = 64 * 1024;
+
+        let size = std::mem::size_of::<T>();
+        let old_bytes = self.storage.len();
+        let mut remaining = count.checked_mul(size).ok_or(
+            raw_bytes_container::ContainerError::UnsupportedOperation("Capacity overflow"),
+        )?;
+        let chunk = (CHUNK_BYTES / size.max(1)).max(1) * size;
+
+        while remaining > 0 {
+            let start = self.storage.len();
+            let step = remaining.min(chunk);
+            let read = self.storage.resize_zeroed(start + step).and_then(|()| {
+                let bytes = self.storage.as_slice_mut_checked()?;
+                Ok(reader.read_exact(&mut bytes[start..start + step])?)
+            });
+            if let Err(e) = read {
+                // Fixed-size storage never grew, so only roll back what was added
+                if self.storage.len() > old_bytes {
+                    self.storage.truncate(old_bytes)?;
+                }
+                return Err(e);
+            }
+            remaining -= step;
+        }
+        Ok(())
+    }
+
+    /// Clear all elements (growable storage only).
     ///
     /// # Errors
     /// Returns an error if the storage is read-only.
     pub fn clear(&mut self) -> Result<(), raw_bytes_container::ContainerError> {
-        self.storage.resize(0, 0)
+        self.storage.clear()
     }
 
     /// Flush changes to disk (for memory-mapped files).
@@ -182,20 +656,187 @@ impl<T: Pod + Copy> PackedStructContainer<T> {
         self.storage.flush()
     }
 
+    /// A value that changes whenever the elements may have been mutated and is
+    /// not reset by [`flush`](Self::flush); see
+    /// [`RawBytesContainer::change_generation`].
+    pub fn change_generation(&self) -> u64 {
+        self.storage.change_generation()
+    }
+
+    /// The elements as raw bytes, whatever the backend.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.storage.as_slice()
+    }
+
     /// Expose underlying storage for advanced use.
-    pub fn storage(&self) -> &RawBytesContainer<u8> {
-        &self.storage
+    ///
+    /// Returns `None` if the container was made by [`create_mmap`](Self::create_mmap)
+    /// or a builder with a [`header`](PackedStructContainerBuilder::header), whose
+    /// file is not a plain array of elements. [`as_bytes`](Self::as_bytes) gives the
+    /// element bytes of any container.
+    pub fn storage(&self) -> Option<&RawBytesContainer<u8>> {
+        match &self.storage {
+            Backing::Raw(storage) => Some(storage),
+            Backing::Vec(_) => None,
+        }
     }
 
-    /// Mutable access to underlying storage for advanced use.
-    pub fn storage_mut(&mut self) -> &mut RawBytesContainer<u8> {
-        &mut self.storage
+    /// Mutable access to underlying storage for advanced use; `None` for the same
+    /// containers as [`storage`](Self::storage).
+    pub fn storage_mut(&mut self) -> Option<&mut RawBytesContainer<u8>> {
+        match &mut self.storage {
+            Backing::Raw(storage) => Some(storage),
+            Backing::Vec(_) => None,
+        }
     }
 
     /// Returns an iterator over the elements.
     pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, T>> {
         self.as_slice().iter().copied()
     }
+
+    /// Iterate over consecutive tiles of `chunk_size` elements; the last may be
+    /// shorter.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is 0.
+    pub fn chunks(&self, chunk_size: usize) -> std::slice::Chunks<'_, T> {
+        self.as_slice().chunks(chunk_size)
+    }
+
+    /// Mutable version of [`chunks`](Self::chunks).
+    ///
+    /// Returns `None` if the storage is read-only.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is 0.
+    pub fn chunks_mut(&mut self, chunk_size: usize) -> Option<std::slice::ChunksMut<'_, T>> {
+        Some(self.as_slice_mut()?.chunks_mut(chunk_size))
+    }
+
+    /// Iterate over all overlapping windows of `size` elements.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    pub fn windows(&self, size: usize) -> std::slice::Windows<'_, T> {
+        self.as_slice().windows(size)
+    }
+
+    /// Copy the elements at `indices` into a new in-memory container.
+    ///
+    /// Runs of consecutive indices are copied with a single memcpy each, so
+    /// gathering mostly-sorted index lists (e.g. live entities after deletions)
+    /// is close to the cost of a plain slice copy.
+    ///
+    /// # Panics
+    /// Panics if any index is out of bounds.
+    pub fn gather(&self, indices: &[usize]) -> Self {
+        let src = self.as_slice();
+        let mut out = Vec::with_capacity(indices.len());
+        for (_, start, len) in contiguous_runs(indices) {
+            out.extend_from_slice(&src[start..start + len]);
+        }
+        Self::from_slice(&out)
+    }
+
+    /// Write `other[i]` to `self[indices[i]]` for every `i`.
+    ///
+    /// The inverse of [`gather`](Self::gather). Runs of consecutive destination
+    /// indices are copied with a single memcpy each.
+    ///
+    /// # Errors
+    /// Returns an error if the storage is read-only.
+    ///
+    /// # Panics
+    /// Panics if `indices.len() != other.len()` or any index is out of bounds.
+    pub fn scatter_from(
+        &mut self,
+        indices: &[usize],
+        other: &Self,
+    ) -> Result<(), raw_bytes_container::ContainerError> {
+        assert_eq!(
+            indices.len(),
+            other.len(),
+            "scatter_from: {} indices for {} source elements",
+            indices.len(),
+            other.len()
+        );
+        let src = other.as_slice();
+        let dst = self.as_slice_mut().ok_or(
+            raw_bytes_container::ContainerError::UnsupportedOperation(
+                "Cannot scatter into read-only storage",
+            ),
+        )?;
+        for (pos, start, len) in contiguous_runs(indices) {
+            dst[start..start + len].copy_from_slice(&src[pos..pos + len]);
+        }
+        Ok(())
+    }
+
+    /// Sort in place with `compare`, without preserving the order of equal elements.
+    ///
+    /// # Errors
+    /// Returns an error if the storage is read-only.
+    pub fn sort_unstable_by<F>(
+        &mut self,
+        compare: F,
+    ) -> Result<(), raw_bytes_container::ContainerError>
+    where
+        F: FnMut(&T, &T) -> std::cmp::Ordering,
+    {
+        self.writable_slice("Cannot sort read-only storage")?
+            .sort_unstable_by(compare);
+        Ok(())
+    }
+
+    /// Sort in place by the key `f` extracts, keeping equal elements in order.
+    ///
+    /// # Errors
+    /// Returns an error if the storage is read-only.
+    pub fn sort_by_key<K, F>(&mut self, f: F) -> Result<(), raw_bytes_container::ContainerError>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.writable_slice("Cannot sort read-only storage")?
+            .sort_by_key(f);
+        Ok(())
+    }
+
+    /// Binary search sorted elements with `f`; see [`slice::binary_search_by`].
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> std::cmp::Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+
+    pub(crate) fn writable_slice(
+        &mut self,
+        message: &'static str,
+    ) -> Result<&mut [T], raw_bytes_container::ContainerError> {
+        self.as_slice_mut()
+            .ok_or(raw_bytes_container::ContainerError::UnsupportedOperation(
+                message,
+            ))
+    }
+}
+
+/// Split an index list into runs of consecutive indices.
+///
+/// Yields `(position in indices, first index, run length)`.
+fn contiguous_runs(indices: &[usize]) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        let start = *indices.get(pos)?;
+        let mut len = 1;
+        while indices.get(pos + len) == Some(&(start + len)) {
+            len += 1;
+        }
+        let run = (pos, start, len);
+        pos += len;
+        Some(run)
+    })
 }
 
 impl<T: Pod + Copy> Default for PackedStructContainer<T> {
@@ -345,6 +986,110 @@ mod tests {
                 .unwrap();
         }
         assert_eq!(container.len(), 10);
+        assert!(container.capacity().unwrap() >= 100);
+    }
+
+    #[test]
+    fn test_reserve_and_shrink() {
+        let mut container = PackedStructContainer::<Point>::new();
+        assert_eq!(container.capacity(), Some(0));
+
+        container.reserve(50).unwrap();
+        let capacity = container.capacity().unwrap();
+        assert!(capacity >= 50);
+        let addr = container.as_slice().as_ptr();
+        for i in 0..50 {
+            container
+                .push(Point {
+                    x: i as f32,
+                    y: 0.0,
+                })
+                .unwrap();
+        }
+        assert_eq!(container.capacity(), Some(capacity));
+        assert_eq!(container.as_slice().as_ptr(), addr);
+
+        container.push(Point { x: 50.0, y: 0.0 }).unwrap();
+        container.shrink_to_fit().unwrap();
+        assert_eq!(container.capacity(), Some(51));
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::cast_slice(container.as_slice())).unwrap();
+        let mut mapped = PackedStructContainer::<Point>::open_mmap_rw(file.path()).unwrap();
+        assert_eq!(mapped.capacity(), None);
+        assert!(mapped.reserve(1).is_err());
+        assert!(mapped.shrink_to_fit().is_err());
+    }
+
+    #[test]
+    fn test_gather_scatter() {
+        let points: Vec<_> = (0..8)
+            .map(|i| Point {
+                x: i as f32,
+                y: -(i as f32),
+            })
+            .collect();
+        let mut container = PackedStructContainer::from_slice(&points);
+
+        let gathered = container.gather(&[1, 2, 3, 6, 0]);
+        let xs: Vec<_> = gathered.iter().map(|p| p.x).collect();
+        assert_eq!(xs, vec![1.0, 2.0, 3.0, 6.0, 0.0]);
+
+        let replacement = PackedStructContainer::from_slice(&[
+            Point { x: 10.0, y: 0.0 },
+            Point { x: 11.0, y: 0.0 },
+            Point { x: 12.0, y: 0.0 },
+        ]);
+        container.scatter_from(&[4, 5, 7], &replacement).unwrap();
+        let xs: Vec<_> = container.iter().map(|p| p.x).collect();
+        assert_eq!(xs, vec![0.0, 1.0, 2.0, 3.0, 10.0, 11.0, 6.0, 12.0]);
+    }
+
+    #[test]
+    fn test_append_buffered() {
+        let mut container = PackedStructContainer::new();
+        {
+            let mut appender = container.append_buffered(3).unwrap();
+            for i in 0..7 {
+                appender
+                    .push(Point {
+                        x: i as f32,
+                        y: 0.0,
+                    })
+                    .unwrap();
+            }
+            assert_eq!(appender.pending(), 1);
+        }
+        assert_eq!(container.len(), 7);
+        assert_eq!(container[6].x, 6.0);
+    }
+
+    #[test]
+    fn test_open_foreign() {
+        // 6-byte foreign header, then 12-byte records: Point followed by a u32 tag
+        let mut bytes = b"HEADER".to_vec();
+        for i in 0..3u32 {
+            let p = Point {
+                x: i as f32,
+                y: -(i as f32),
+            };
+            bytes.extend_from_slice(bytemuck::bytes_of(&p));
+            bytes.extend_from_slice(&(100 + i).to_le_bytes());
+        }
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &bytes).unwrap();
+
+        let records = PackedStructContainer::<Point>::open_foreign(file.path(), 12, 6).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records.get(2), Some(Point { x: 2.0, y: -2.0 }));
+        assert_eq!(records.get(3), None);
+        assert_eq!(
+            records.to_container().as_slice()[1],
+            Point { x: 1.0, y: -1.0 }
+        );
+
+        assert!(PackedStructContainer::<Point>::open_foreign(file.path(), 4, 6).is_err());
+        assert!(PackedStructContainer::<Point>::open_foreign(file.path(), 12, 100).is_err());
     }
 
     #[test]
@@ -359,4 +1104,414 @@ mod tests {
         assert_eq!(container.len(), 0);
         assert!(container.is_empty());
     }
+
+    #[test]
+    fn test_insert_remove() {
+        let p = |x: f32| Point { x, y: -x };
+        let mut container = PackedStructContainer::from_slice(&[p(0.0), p(1.0), p(2.0)]);
+
+        container.insert(1, p(10.0)).unwrap();
+        container.insert(4, p(20.0)).unwrap();
+        let xs: Vec<f32> = container.iter().map(|q| q.x).collect();
+        assert_eq!(xs, [0.0, 10.0, 1.0, 2.0, 20.0]);
+        assert!(container.insert(6, p(0.0)).is_err());
+
+        assert_eq!(container.remove(1).unwrap(), p(10.0));
+        assert_eq!(container.swap_remove(0).unwrap(), p(0.0));
+        let xs: Vec<f32> = container.iter().map(|q| q.x).collect();
+        assert_eq!(xs, [20.0, 1.0, 2.0]);
+        assert!(container.remove(3).is_err());
+        assert_eq!(container.swap_remove(2).unwrap(), p(2.0));
+        assert_eq!(container.len(), 2);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::cast_slice(container.as_slice())).unwrap();
+        let mut mapped = PackedStructContainer::<Point>::open_mmap_rw(file.path()).unwrap();
+        assert!(mapped.insert(0, p(5.0)).is_err());
+        assert!(mapped.remove(0).is_err());
+        assert!(mapped.swap_remove(0).is_err());
+        assert_eq!(mapped[0], p(20.0));
+    }
+
+    #[test]
+    fn test_drain() {
+        let p = |x: f32| Point { x, y: 0.0 };
+        let mut queue =
+            PackedStructContainer::from_slice(&(0..6).map(|i| p(i as f32)).collect::<Vec<_>>());
+
+        let batch: Vec<f32> = queue.drain(1..3).unwrap().map(|q| q.x).collect();
+        assert_eq!(batch, [1.0, 2.0]);
+        let xs: Vec<f32> = queue.iter().map(|q| q.x).collect();
+        assert_eq!(xs, [0.0, 3.0, 4.0, 5.0]);
+
+        assert_eq!(queue.drain(4..).unwrap().count(), 0);
+        assert!(queue.drain(..5).is_err());
+        assert_eq!(queue.drain(..).unwrap().len(), 4);
+        assert!(queue.is_empty());
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), [0u8; 16]).unwrap();
+        let mut mapped = PackedStructContainer::<Point>::open_mmap_rw(file.path()).unwrap();
+        assert!(mapped.drain(..1).is_err());
+        assert_eq!(mapped.len(), 2);
+    }
+
+    #[test]
+    fn test_deep_clone() {
+        let points = [Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }];
+        let mut container = PackedStructContainer::from_slice(&points);
+        let copy = container.deep_clone();
+        container[0].x = 10.0;
+        assert_eq!(copy.as_slice(), &points);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::cast_slice(&points)).unwrap();
+        let mapped = PackedStructContainer::<Point>::open_mmap_read(file.path()).unwrap();
+        let mut snapshot = mapped.deep_clone();
+        drop(mapped);
+        assert!(snapshot.capacity().is_some());
+        snapshot.push(Point { x: 5.0, y: 6.0 }).unwrap();
+        assert_eq!(&snapshot[..2], &points);
+        assert_eq!(std::fs::metadata(file.path()).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_pop_truncate() {
+        let mut pool = PackedStructContainer::<Point>::with_capacity(8);
+        for i in 0..5 {
+            pool.push(Point {
+                x: i as f32,
+                y: 0.0,
+            })
+            .unwrap();
+        }
+
+        assert_eq!(pool.pop().unwrap(), Some(Point { x: 4.0, y: 0.0 }));
+        pool.truncate(2).unwrap();
+        pool.truncate(10).unwrap();
+        assert_eq!(pool.len(), 2);
+        assert!(pool.capacity().unwrap() >= 8);
+        assert_eq!(pool.pop().unwrap().unwrap().x, 1.0);
+        assert_eq!(pool.pop().unwrap().unwrap().x, 0.0);
+        assert_eq!(pool.pop().unwrap(), None);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), [0u8; 16]).unwrap();
+        let mut mapped = PackedStructContainer::<Point>::open_mmap_rw(file.path()).unwrap();
+        assert!(matches!(
+            mapped.pop(),
+            Err(raw_bytes_container::ContainerError::UnsupportedOperation(_))
+        ));
+        assert!(mapped.truncate(1).is_err());
+        assert_eq!(mapped.len(), 2);
+    }
+
+    #[test]
+    fn test_retain() {
+        let points: Vec<Point> = (0..6)
+            .map(|i| Point {
+                x: i as f32,
+                y: 1.0,
+            })
+            .collect();
+        let mut container = PackedStructContainer::from_slice(&points);
+        assert_eq!(container.retain(|p| p.x as i32 % 2 == 1).unwrap(), 3);
+        let xs: Vec<f32> = container.iter().map(|p| p.x).collect();
+        assert_eq!(xs, [1.0, 3.0, 5.0]);
+
+        // A fixed-size mapping cannot shrink, so it is left untouched
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::cast_slice(&points)).unwrap();
+        let mut mapped = PackedStructContainer::<Point>::open_mmap_rw(file.path()).unwrap();
+        assert!(matches!(
+            mapped.retain(|p| p.x >= 4.0),
+            Err(raw_bytes_container::ContainerError::UnsupportedOperation(_))
+        ));
+        assert_eq!(mapped.as_slice(), points.as_slice());
+
+        let mut read = PackedStructContainer::<Point>::open_mmap_read(file.path()).unwrap();
+        assert!(read.retain(|_| true).is_err());
+    }
+
+    #[test]
+    fn test_extend_from_reader() {
+        let points = [Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }];
+        let bytes = bytemuck::cast_slice::<Point, u8>(&points);
+
+        let mut container = PackedStructContainer::from_slice(&points[..1]);
+        container.extend_from_reader(bytes, 2).unwrap();
+        assert_eq!(container.as_slice(), &[points[0], points[0], points[1]]);
+
+        // A short stream leaves the container untouched
+        let err = container.extend_from_reader(&bytes[..12], 2).unwrap_err();
+        assert!(matches!(err, raw_bytes_container::ContainerError::Io(_)));
+        assert_eq!(container.len(), 3);
+
+        let mut empty = PackedStructContainer::<Point>::new();
+        empty.extend_from_reader(std::io::empty(), 0).unwrap();
+        assert!(empty.is_empty());
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytes).unwrap();
+        let mut mapped = PackedStructContainer::<Point>::open_mmap_read(file.path()).unwrap();
+        assert!(mapped.extend_from_reader(bytes, 1).is_err());
+        assert_eq!(mapped.len(), 2);
+    }
+
+    #[test]
+    fn test_chunks_and_windows() {
+        let mut container = PackedStructContainer::from_slice(
+            &(0..5)
+                .map(|i| Point {
+                    x: i as f32,
+                    y: 0.0,
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let lens: Vec<usize> = container.chunks(2).map(|c| c.len()).collect();
+        assert_eq!(lens, [2, 2, 1]);
+
+        for (i, chunk) in container.chunks_mut(2).unwrap().enumerate() {
+            chunk.iter_mut().for_each(|p| p.y = i as f32);
+        }
+        assert_eq!(container[4].y, 2.0);
+
+        let rising = container.windows(2).all(|w| w[0].x < w[1].x);
+        assert!(rising);
+        assert_eq!(container.windows(6).count(), 0);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), container.as_bytes()).unwrap();
+        let mut mapped = PackedStructContainer::<Point>::open_mmap_read(file.path()).unwrap();
+        assert!(mapped.chunks_mut(2).is_none());
+    }
+
+    #[test]
+    fn test_project() {
+        let points = PackedStructContainer::from_slice(&[
+            Point { x: 1.0, y: 10.0 },
+            Point { x: 2.0, y: 20.0 },
+            Point { x: 3.0, y: 30.0 },
+        ]);
+
+        let ys = project!(points, Point, y);
+        assert_eq!(ys.len(), 3);
+        assert_eq!(ys.stride(), 8);
+        assert_eq!(ys.get(1), Some(20.0));
+        assert_eq!(ys.get(3), None);
+        assert_eq!(ys.iter().rev().collect::<Vec<_>>(), [30.0, 20.0, 10.0]);
+
+        // Unaligned and sub-field offsets are read by value
+        let bytes = points.project::<[u8; 2]>(3);
+        assert_eq!(bytes.get(0), Some([1.0f32.to_ne_bytes()[3], 0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_project_out_of_range() {
+        PackedStructContainer::<Point>::new().project::<f32>(6);
+    }
+
+    #[test]
+    fn test_append_mmap_rw() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.bin");
+        std::fs::write(&path, bytemuck::bytes_of(&Point { x: 1.0, y: 1.0 })).unwrap();
+
+        let mut capture = PackedStructContainer::<Point>::open_mmap_rw(&path).unwrap();
+        capture.push(Point { x: 2.0, y: 2.0 }).unwrap();
+        capture
+            .extend((3..6).map(|i| Point {
+                x: i as f32,
+                y: 0.0,
+            }))
+            .unwrap();
+        assert_eq!(capture.len(), 5);
+        assert_eq!(capture[1].y, 2.0);
+        capture.flush().unwrap();
+        drop(capture);
+
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 5 * 8);
+        let mut read = PackedStructContainer::<Point>::open_mmap_read(&path).unwrap();
+        let xs: Vec<f32> = read.iter().map(|p| p.x).collect();
+        assert_eq!(xs, [1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert!(read.push(Point { x: 6.0, y: 0.0 }).is_err());
+    }
+
+    #[test]
+    fn test_create_mmap_push() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.vec");
+
+        let mut container = PackedStructContainer::<Point>::create_mmap(&path, 4).unwrap();
+        assert!(container.is_empty());
+        assert!(container.storage().is_none());
+        assert!(container.storage_mut().is_none());
+        assert!(container.as_bytes().is_empty());
+        assert_eq!(container.capacity(), Some(4));
+
+        // Grows past the initial capacity on disk
+        for i in 0..10 {
+            container
+                .push(Point {
+                    x: i as f32,
+                    y: 0.0,
+                })
+                .unwrap();
+        }
+        assert_eq!(container.len(), 10);
+        assert!(container.capacity().unwrap() >= 10);
+
+        container.insert(0, Point { x: -1.0, y: 0.0 }).unwrap();
+        assert_eq!(container.remove(1).unwrap().x, 0.0);
+        assert_eq!(container.pop().unwrap().unwrap().x, 9.0);
+        container.truncate(5).unwrap();
+        container[4].y = 7.0;
+        container.flush().unwrap();
+        drop(container);
+
+        let mut container = PackedStructContainer::<Point>::open_mmap(&path).unwrap();
+        let xs: Vec<f32> = container.iter().map(|p| p.x).collect();
+        assert_eq!(xs, [-1.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(container[4].y, 7.0);
+
+        // Positional edits keep working after reopening
+        container.insert(5, Point { x: 5.0, y: 0.0 }).unwrap();
+        assert_eq!(container.retain(|p| p.x > 0.0).unwrap(), 5);
+        container.clear().unwrap();
+        assert!(container.is_empty());
+    }
+
+    #[test]
+    fn test_save_open_checked() {
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+        struct Vec3 {
+            x: f32,
+            y: f32,
+            z: f32,
+        }
+
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+        struct Vec4 {
+            x: f32,
+            y: f32,
+            z: f32,
+            w: f32,
+        }
+
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+        struct Rgb {
+            r: f32,
+            g: f32,
+            b: f32,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("points.psc");
+        let points = [
+            Vec3 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0,
+            },
+            Vec3 {
+                x: 4.0,
+                y: 5.0,
+                z: 6.0,
+            },
+        ];
+        PackedStructContainer::from_slice(&points)
+            .save(&path)
+            .unwrap();
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len() as usize,
+            TypedFileHeader::SIZE + 24
+        );
+
+        let loaded = PackedStructContainer::<Vec3>::open_checked(&path).unwrap();
+        assert_eq!(loaded.as_slice(), &points);
+
+        // Wrong size, and same size but a different type
+        let err = PackedStructContainer::<Vec4>::open_checked(&path).unwrap_err();
+        assert!(err.to_string().contains("12-byte"), "{err}");
+        assert!(PackedStructContainer::<Rgb>::open_checked(&path).is_err());
+
+        // Without the hash only the layout is checked
+        PackedStructContainer::from_slice(&points)
+            .save_without_type_hash(&path)
+            .unwrap();
+        assert_eq!(
+            PackedStructContainer::<Rgb>::open_checked(&path)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // Truncated data and headerless files are rejected
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 4]).unwrap();
+        assert!(PackedStructContainer::<Vec3>::open_checked(&path).is_err());
+        std::fs::write(&path, bytemuck::cast_slice::<Vec3, u8>(&points)).unwrap();
+        assert!(PackedStructContainer::<Vec3>::open_checked(&path).is_err());
+    }
+
+    #[test]
+    fn test_sort_and_search() {
+        let mut container = PackedStructContainer::from_slice(&[
+            Point { x: 3.0, y: 0.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 2.0, y: 2.0 },
+            Point { x: 1.0, y: 3.0 },
+        ]);
+
+        container.sort_by_key(|p| p.x as i32).unwrap();
+        let ys: Vec<f32> = container.iter().map(|p| p.y).collect();
+        assert_eq!(ys, [1.0, 3.0, 2.0, 0.0]);
+        assert_eq!(container.binary_search_by(|p| p.x.total_cmp(&2.0)), Ok(2));
+        assert_eq!(container.binary_search_by(|p| p.x.total_cmp(&4.0)), Err(4));
+
+        container
+            .sort_unstable_by(|a, b| b.y.total_cmp(&a.y))
+            .unwrap();
+        assert_eq!(container[0].y, 3.0);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::cast_slice(container.as_slice())).unwrap();
+        let mut read = PackedStructContainer::<Point>::open_mmap_read(file.path()).unwrap();
+        assert!(read.sort_by_key(|p| p.x as i32).is_err());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_iter() {
+        use rayon::prelude::*;
+
+        let mut container = PackedStructContainer::from_slice(&[[1u16, 2], [3, 4], [5, 6]]);
+        container
+            .par_iter_mut()
+            .unwrap()
+            .for_each(|pair| pair.swap(0, 1));
+        let firsts: Vec<u16> = container.par_iter().map(|[a, _]| a).collect();
+        assert_eq!(firsts, vec![2, 4, 6]);
+        assert_eq!((&container).into_par_iter().count(), 3);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_chunks() {
+        use rayon::prelude::*;
+
+        let mut container = PackedStructContainer::from_slice(&[1u32; 10]);
+        container
+            .par_chunks_mut(3)
+            .unwrap()
+            .enumerate()
+            .for_each(|(i, tile)| tile.iter_mut().for_each(|v| *v = i as u32));
+        let sums: Vec<u32> = container.par_chunks(3).map(|t| t.iter().sum()).collect();
+        assert_eq!(sums, vec![0, 3, 6, 3]);
+    }
 }
@@ -0,0 +1,271 @@
+//! Several typed containers kept together in one file.
+//!
+//! Stored in the [`SectionedFile`] format: a small table of contents naming each
+//! section and recording its element type, followed by the section data.
+
+use bytemuck::Pod;
+use raw_bytes_container::{ContainerError, SectionedFile, SectionedFileBuilder};
+use std::any::Any;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::PackedStructContainer;
+
+/// A type-erased [`PackedStructContainer`].
+trait AnySection: Any + Send + Sync {
+    fn add_to(&self, name: &str, builder: &mut SectionedFileBuilder);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Pod + Send + Sync> AnySection for PackedStructContainer<T> {
+    fn add_to(&self, name: &str, builder: &mut SectionedFileBuilder) {
+        builder.add_section(name, self.as_slice());
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+struct Section {
+    name: String,
+    /// Set on insert, or copied out of the opened file on first typed access.
+    container: OnceLock<Box<dyn AnySection>>,
+}
+
+impl std::fmt::Debug for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Section")
+            .field("name", &self.name)
+            .field("loaded", &self.container.get().is_some())
+            .finish()
+    }
+}
+
+/// Named [`PackedStructContainer`]s of different element types, saved to and
+/// loaded from a single file.
+///
+/// Sections of an opened file are checked against the requested type and copied
+/// into memory the first time they are accessed; sections never accessed are
+/// written back unchanged by [`save`](Self::save).
+///
+/// ```
+/// use packed_struct_container::{MultiContainer, PackedStructContainer};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("level.sect");
+///
+/// let mut level = MultiContainer::new();
+/// level.insert("heights", PackedStructContainer::from_slice(&[1.0f32, 2.0]));
+/// level.insert("tiles", PackedStructContainer::from_slice(&[[0u8; 4]; 3]));
+/// level.save(&path).unwrap();
+///
+/// let level = MultiContainer::open(&path).unwrap();
+/// assert_eq!(level.section::<f32>("heights").unwrap().len(), 2);
+/// assert!(level.section::<u32>("heights").is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct MultiContainer {
+    file: Option<SectionedFile>,
+    sections: Vec<Section>,
+}
+
+impl MultiContainer {
+    /// Create an empty container with no sections.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a file written by [`save`](Self::save) (or a [`SectionedFileBuilder`]).
+    ///
+    /// The file stays mapped read-only until the container is dropped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        let file = SectionedFile::open(path)?;
+        let sections = file
+            .sections()
+            .into_iter()
+            .map(|info| Section {
+                name: info.name,
+                container: OnceLock::new(),
+            })
+            .collect();
+        Ok(Self {
+            file: Some(file),
+            sections,
+        })
+    }
+
+    /// Add a section called `name`, replacing any existing section of that name.
+    pub fn insert<T: Pod + Send + Sync>(
+        &mut self,
+        name: impl Into<String>,
+        container: PackedStructContainer<T>,
+    ) {
+        let name = name.into();
+        self.sections.retain(|s| s.name != name);
+        self.sections.push(Section {
+            name,
+            container: OnceLock::from(Box::new(container) as Box<dyn AnySection>),
+        });
+    }
+
+    /// Remove the section called `name`, returning whether it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.sections.len();
+        self.sections.retain(|s| s.name != name);
+        self.sections.len() != len
+    }
+
+    /// Returns true if a section called `name` exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.sections.iter().any(|s| s.name == name)
+    }
+
+    /// Names of all sections, in file order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().map(|s| s.name.as_str())
+    }
+
+    /// Number of sections.
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    /// Returns true if there are no sections.
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// The section called `name` as a container of `T`.
+    ///
+    /// # Errors
+    /// Returns [`ContainerError::NotFound`] if there is no such section and
+    /// [`ContainerError::TypeMismatch`] if it holds a different element type.
+    pub fn section<T: Pod + Send + Sync>(
+        &self,
+        name: &str,
+    ) -> Result<&PackedStructContainer<T>, ContainerError> {
+        let section = self.load::<T>(name)?;
+        section
+            .as_any()
+            .downcast_ref()
+            .ok_or_else(|| Self::mismatch::<T>(name))
+    }
+
+    /// Mutable version of [`section`](Self::section).
+    pub fn section_mut<T: Pod + Send + Sync>(
+        &mut self,
+        name: &str,
+    ) -> Result<&mut PackedStructContainer<T>, ContainerError> {
+        self.load::<T>(name)?;
+        let section = self
+            .sections
+            .iter_mut()
+            .find(|s| s.name == name)
+            .and_then(|s| s.container.get_mut())
+            .expect("section was just loaded");
+        section
+            .as_any_mut()
+            .downcast_mut()
+            .ok_or_else(|| Self::mismatch::<T>(name))
+    }
+
+    /// Write all sections to `path`, atomically replacing any existing file.
+    ///
+    /// `path` may be the file this container was opened from; sections not yet
+    /// accessed keep reading from the old contents.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ContainerError> {
+        let mut builder = SectionedFileBuilder::new();
+        for section in &self.sections {
+            match (section.container.get(), &self.file) {
+                (Some(container), _) => container.add_to(&section.name, &mut builder),
+                (None, Some(file)) => {
+                    builder.copy_section(file, &section.name)?;
+                }
+                (None, None) => unreachable!("in-memory sections are always set"),
+            }
+        }
+        builder.write_atomic(path)
+    }
+
+    /// The section called `name`, copying it out of the file as `T` if needed.
+    fn load<T: Pod + Send + Sync>(&self, name: &str) -> Result<&dyn AnySection, ContainerError> {
+        let section = self
+            .sections
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| ContainerError::NotFound(name.to_string()))?;
+        if let Some(container) = section.container.get() {
+            return Ok(container.as_ref());
+        }
+
+        let file = self
+            .file
+            .as_ref()
+            .expect("in-memory sections are always set");
+        let elements = file.open_section::<T>(name)?;
+        let container = section
+            .container
+            .get_or_init(|| Box::new(PackedStructContainer::from_slice(elements)));
+        Ok(container.as_ref())
+    }
+
+    fn mismatch<T>(name: &str) -> ContainerError {
+        ContainerError::TypeMismatch(format!(
+            "section '{}' does not hold elements of type {}",
+            name,
+            std::any::type_name::<T>()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_container_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("world.sect");
+
+        let mut world = MultiContainer::new();
+        world.insert("ids", PackedStructContainer::from_slice(&[1u32, 2, 3]));
+        world.insert(
+            "positions",
+            PackedStructContainer::from_slice(&[[0.5f32; 3]; 2]),
+        );
+        world.insert("flags", PackedStructContainer::from_slice(&[1u8]));
+        assert!(world.remove("flags"));
+        assert!(!world.remove("flags"));
+        world.save(&path).unwrap();
+
+        let mut world = MultiContainer::open(&path).unwrap();
+        assert_eq!(world.names().collect::<Vec<_>>(), ["ids", "positions"]);
+        assert!(matches!(
+            world.section::<u64>("ids"),
+            Err(ContainerError::TypeMismatch(_))
+        ));
+        assert!(matches!(
+            world.section::<u32>("missing"),
+            Err(ContainerError::NotFound(_))
+        ));
+
+        world.section_mut::<u32>("ids").unwrap().push(4).unwrap();
+        assert!(world.section_mut::<f32>("ids").is_err());
+
+        // Untouched sections are copied through, touched ones rewritten
+        world.save(&path).unwrap();
+        drop(world);
+        let world = MultiContainer::open(&path).unwrap();
+        assert_eq!(
+            world.section::<u32>("ids").unwrap().as_slice(),
+            &[1, 2, 3, 4]
+        );
+        assert_eq!(world.section::<[f32; 3]>("positions").unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,79 @@
+//! Rayon parallel iterators for [`PackedStructContainer`].
+
+use bytemuck::Pod;
+use rayon::iter::{Copied, IntoParallelIterator, ParallelIterator};
+use rayon::slice::{ParallelSlice, ParallelSliceMut};
+
+use crate::PackedStructContainer;
+
+impl<'a, T: Pod + Copy + Send + Sync> IntoParallelIterator for &'a PackedStructContainer<T> {
+    type Iter = Copied<rayon::slice::Iter<'a, T>>;
+    type Item = T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice().into_par_iter().copied()
+    }
+}
+
+impl<T: Pod + Copy> PackedStructContainer<T> {
+    /// Parallel version of [`iter`](Self::iter), yielding copies of the elements.
+    ///
+    /// ```
+    /// use packed_struct_container::PackedStructContainer;
+    /// use rayon::prelude::*;
+    ///
+    /// let container = PackedStructContainer::from_slice(&[[1u32, 2], [3, 4]]);
+    /// let sum: u32 = container.par_iter().map(|[a, b]| a * b).sum();
+    /// assert_eq!(sum, 14);
+    /// ```
+    pub fn par_iter(&self) -> Copied<rayon::slice::Iter<'_, T>>
+    where
+        T: Send + Sync,
+    {
+        self.into_par_iter()
+    }
+
+    /// Parallel iterator over mutable elements.
+    ///
+    /// Returns `None` if the storage is read-only.
+    pub fn par_iter_mut(&mut self) -> Option<rayon::slice::IterMut<'_, T>>
+    where
+        T: Send,
+    {
+        Some(self.as_slice_mut()?.into_par_iter())
+    }
+
+    /// Parallel version of [`chunks`](Self::chunks), for processing tiles of
+    /// `chunk_size` elements on separate threads.
+    ///
+    /// ```
+    /// use packed_struct_container::PackedStructContainer;
+    /// use rayon::prelude::*;
+    ///
+    /// let container = PackedStructContainer::from_slice(&[1u32, 2, 3, 4, 5]);
+    /// let sums: Vec<u32> = container.par_chunks(2).map(|c| c.iter().sum()).collect();
+    /// assert_eq!(sums, [3, 7, 5]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is 0.
+    pub fn par_chunks(&self, chunk_size: usize) -> rayon::slice::Chunks<'_, T>
+    where
+        T: Sync,
+    {
+        self.as_slice().par_chunks(chunk_size)
+    }
+
+    /// Parallel version of [`chunks_mut`](Self::chunks_mut).
+    ///
+    /// Returns `None` if the storage is read-only.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is 0.
+    pub fn par_chunks_mut(&mut self, chunk_size: usize) -> Option<rayon::slice::ChunksMut<'_, T>>
+    where
+        T: Send,
+    {
+        Some(self.as_slice_mut()?.par_chunks_mut(chunk_size))
+    }
+}
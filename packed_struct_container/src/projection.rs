@@ -0,0 +1,157 @@
+//! Strided views over a single field of every element.
+
+use bytemuck::Pod;
+use std::marker::PhantomData;
+
+use crate::PackedStructContainer;
+
+/// A read-only view over one field of every element of a container.
+///
+/// Created by [`PackedStructContainer::project`] or the [`project!`](crate::project)
+/// macro. Reads go straight to the container's bytes and copy only the field, so
+/// scanning one column of a large mapped array never copies whole elements.
+#[derive(Debug, Clone, Copy)]
+pub struct Projection<'a, F: Pod> {
+    bytes: &'a [u8],
+    offset: usize,
+    stride: usize,
+    len: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, F: Pod> Projection<'a, F> {
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Byte offset of the field within each element.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Distance between consecutive fields, in bytes (the element size).
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Read the field of element `index`.
+    pub fn get(&self, index: usize) -> Option<F> {
+        if index >= self.len {
+            return None;
+        }
+        let start = index * self.stride + self.offset;
+        let bytes = &self.bytes[start..start + std::mem::size_of::<F>()];
+        Some(bytemuck::pod_read_unaligned(bytes))
+    }
+
+    /// Iterate over the field of every element.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = F> + DoubleEndedIterator + 'a {
+        let this = *self;
+        (0..self.len).map(move |i| this.get(i).unwrap())
+    }
+
+    /// Copy the field of every element into a `Vec`.
+    pub fn to_vec(&self) -> Vec<F> {
+        self.iter().collect()
+    }
+}
+
+impl<T: Pod + Copy> PackedStructContainer<T> {
+    /// View the `F` at `byte_offset` within every element.
+    ///
+    /// Prefer the [`project!`](crate::project) macro, which derives the offset and
+    /// field type from the field name.
+    ///
+    /// ```
+    /// use bytemuck_derive::{Pod, Zeroable};
+    /// use packed_struct_container::PackedStructContainer;
+    ///
+    /// #[repr(C)]
+    /// #[derive(Clone, Copy, Pod, Zeroable)]
+    /// struct Trade {
+    ///     price: f64,
+    ///     qty: u32,
+    ///     venue: u32,
+    /// }
+    ///
+    /// let trades = PackedStructContainer::from_slice(&[
+    ///     Trade { price: 10.0, qty: 3, venue: 1 },
+    ///     Trade { price: 12.5, qty: 4, venue: 2 },
+    /// ]);
+    /// let qty = trades.project::<u32>(std::mem::offset_of!(Trade, qty));
+    /// assert_eq!(qty.iter().sum::<u32>(), 7);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if an `F` at `byte_offset` does not fit within `T`.
+    pub fn project<F: Pod>(&self, byte_offset: usize) -> Projection<'_, F> {
+        let size = std::mem::size_of::<T>();
+        assert!(
+            byte_offset
+                .checked_add(std::mem::size_of::<F>())
+                .is_some_and(|end| end <= size),
+            "{}-byte field at offset {} does not fit in {}-byte element",
+            std::mem::size_of::<F>(),
+            byte_offset,
+            size
+        );
+        Projection {
+            bytes: self.storage.as_slice(),
+            offset: byte_offset,
+            stride: size,
+            len: self.len(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like [`project`](Self::project), taking the field type from `_field`.
+    ///
+    /// Used by the [`project!`](crate::project) macro.
+    #[doc(hidden)]
+    pub fn project_field<F: Pod>(
+        &self,
+        byte_offset: usize,
+        _field: fn(&T) -> &F,
+    ) -> Projection<'_, F> {
+        self.project(byte_offset)
+    }
+}
+
+/// View one named field of every element of a [`PackedStructContainer`].
+///
+/// Expands to [`PackedStructContainer::project`] with the offset and type of
+/// `$field`, so neither can be gotten wrong.
+///
+/// ```
+/// use bytemuck_derive::{Pod, Zeroable};
+/// use packed_struct_container::{PackedStructContainer, project};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Pod, Zeroable)]
+/// struct Trade {
+///     price: f64,
+///     qty: u32,
+///     venue: u32,
+/// }
+///
+/// let trades = PackedStructContainer::from_slice(&[
+///     Trade { price: 10.0, qty: 3, venue: 1 },
+///     Trade { price: 12.5, qty: 4, venue: 2 },
+/// ]);
+/// let prices = project!(trades, Trade, price);
+/// assert_eq!(prices.to_vec(), [10.0, 12.5]);
+/// ```
+#[macro_export]
+macro_rules! project {
+    ($container:expr, $ty:ty, $field:ident) => {
+        $container.project_field(::core::mem::offset_of!($ty, $field), |element: &$ty| {
+            &element.$field
+        })
+    };
+}
@@ -0,0 +1,216 @@
+//! Typed files that embed the MTF schema of their element type.
+//!
+//! ```text
+//! [TypedFileHeader][ELEMENTS][MTF BLOB][BLOB_SIZE: u32]
+//! ```
+//!
+//! The header guards the element size and alignment; the embedded schema also
+//! catches field reordering, renames and retyping between builds that keep the
+//! struct size unchanged.
+
+use bytemuck::Pod;
+use mtf::{MTFType, TypeDef};
+use raw_bytes_container::ContainerError;
+use std::io::Write;
+use std::path::Path;
+
+use crate::{PackedStructContainer, TypedFileHeader};
+
+const BLOB_SIZE_LEN: usize = std::mem::size_of::<u32>();
+
+/// Field name, bit offset and bit size.
+type FieldLayout = (String, u32, u32);
+
+/// Size in bits of the first type in `blob`, and the layout of its fields.
+fn schema_fields(blob: &[u8]) -> Result<(u32, Vec<FieldLayout>), ContainerError> {
+    let invalid = |msg: String| ContainerError::InvalidFormat(msg);
+    let (types, strings) =
+        mtf::read_mtf(blob).map_err(|e| invalid(format!("invalid MTF metadata: {}", e)))?;
+    let TypeDef {
+        size_bits, fields, ..
+    } = types
+        .into_iter()
+        .next()
+        .ok_or_else(|| invalid("MTF metadata contains no types".to_string()))?;
+
+    let fields = fields
+        .iter()
+        .map(|f| {
+            let name = mtf::read_string(strings, f.name_offset)
+                .map_err(|e| invalid(format!("invalid MTF field name: {}", e)))?;
+            Ok((name.to_string(), f.offset_bits, f.size_bits))
+        })
+        .collect::<Result<_, ContainerError>>()?;
+    Ok((size_bits, fields))
+}
+
+/// Check that the MTF schema `blob` stored in a file describes the same layout
+/// as `T`.
+///
+/// Field names, bit offsets and bit sizes must all match; the type name is
+/// ignored so that renaming a struct does not invalidate existing files.
+///
+/// # Errors
+/// Returns [`ContainerError::TypeMismatch`] naming the first difference, or
+/// [`ContainerError::InvalidFormat`] if either schema cannot be parsed.
+pub fn check_schema_compatible<T: MTFType>(blob: &[u8]) -> Result<(), ContainerError> {
+    let (stored_size, stored) = schema_fields(blob)?;
+    let (size, fields) = schema_fields(T::mtf_type_blob())?;
+    let mismatch = |msg: String| {
+        Err(ContainerError::TypeMismatch(format!(
+            "layout of {} differs from the file: {}",
+            std::any::type_name::<T>(),
+            msg
+        )))
+    };
+
+    if stored_size != size {
+        return mismatch(format!("{} bits stored, {} bits now", stored_size, size));
+    }
+    for (name, offset, bits) in &fields {
+        match stored.iter().find(|(stored_name, ..)| stored_name == name) {
+            None => return mismatch(format!("field {} is not in the file", name)),
+            Some((_, stored_offset, stored_bits))
+                if (stored_offset, stored_bits) != (offset, bits) =>
+            {
+                return mismatch(format!(
+                    "field {} moved from bits {}..{} to {}..{}",
+                    name,
+                    stored_offset,
+                    stored_offset + stored_bits,
+                    offset,
+                    offset + bits
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    if let Some((name, ..)) = stored
+        .iter()
+        .find(|(stored_name, ..)| !fields.iter().any(|(n, ..)| n == stored_name))
+    {
+        return mismatch(format!("field {} was removed", name));
+    }
+    Ok(())
+}
+
+impl<T: Pod + MTFType> PackedStructContainer<T> {
+    /// Write the elements to `path` like [`save`](Self::save), followed by the
+    /// MTF schema of `T`.
+    ///
+    /// Read the file back with [`open_with_schema`](Self::open_with_schema).
+    ///
+    /// # Errors
+    /// Fails under the `strict-layout` feature if `T` contains padding bytes;
+    /// otherwise padding is only warned about.
+    pub fn save_with_schema<P: AsRef<Path>>(&self, path: P) -> Result<(), ContainerError> {
+        crate::layout::enforce_layout_mtf::<T>()?;
+        let blob = T::mtf_type_blob();
+        let header = TypedFileHeader::for_type::<T>(self.len(), false);
+
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(header.as_bytes())?;
+        file.write_all(self.storage.as_slice())?;
+        file.write_all(blob)?;
+        file.write_all(&(blob.len() as u32).to_le_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Load a file written by [`save_with_schema`](Self::save_with_schema) into
+    /// memory, checking the embedded schema against the layout of `T`.
+    ///
+    /// # Errors
+    /// Returns [`ContainerError::TypeMismatch`] if the struct layout changed
+    /// since the file was written (see [`check_schema_compatible`]), and
+    /// [`ContainerError::InvalidFormat`] if the file has no embedded schema. Under
+    /// the `strict-layout` feature, also fails if `T` contains padding bytes.
+    pub fn open_with_schema<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        crate::layout::enforce_layout_mtf::<T>()?;
+        let mut bytes = std::fs::read(path)?;
+        let header = TypedFileHeader::read(&bytes)?;
+
+        let trailer_start = bytes.len() - BLOB_SIZE_LEN;
+        let blob_len = (trailer_start >= TypedFileHeader::SIZE)
+            .then(|| u32::from_le_bytes(bytes[trailer_start..].try_into().unwrap()) as usize)
+            .filter(|&len| len <= trailer_start - TypedFileHeader::SIZE)
+            .ok_or_else(|| {
+                ContainerError::InvalidFormat("file has no embedded MTF schema".to_string())
+            })?;
+        let blob_start = trailer_start - blob_len;
+        check_schema_compatible::<T>(&bytes[blob_start..trailer_start])?;
+
+        bytes.truncate(blob_start);
+        Self::from_typed_bytes(bytes, &header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck_derive::{Pod, Zeroable};
+    use mtf_derive::MTF;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, MTF)]
+    struct Particle {
+        pos: [f32; 2],
+        mass: f32,
+        id: u32,
+    }
+
+    mod drifted {
+        use bytemuck_derive::{Pod, Zeroable};
+        use mtf_derive::MTF;
+
+        // Same size as `Particle`, but `mass` and `id` swapped places
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, MTF)]
+        pub struct Particle {
+            pub pos: [f32; 2],
+            pub id: u32,
+            pub mass: f32,
+        }
+    }
+
+    #[test]
+    fn test_save_open_with_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("particles.psc");
+        let particles = [
+            Particle {
+                pos: [1.0, 2.0],
+                mass: 0.5,
+                id: 7,
+            },
+            Particle {
+                pos: [3.0, 4.0],
+                mass: 1.5,
+                id: 8,
+            },
+        ];
+        PackedStructContainer::from_slice(&particles)
+            .save_with_schema(&path)
+            .unwrap();
+
+        let loaded = PackedStructContainer::<Particle>::open_with_schema(&path).unwrap();
+        assert_eq!(loaded.as_slice(), &particles);
+
+        let err = PackedStructContainer::<drifted::Particle>::open_with_schema(&path).unwrap_err();
+        assert!(matches!(err, ContainerError::TypeMismatch(_)), "{err}");
+        assert!(err.to_string().contains("field id moved"), "{err}");
+
+        // Files without a schema are rejected rather than misread
+        PackedStructContainer::from_slice(&particles)
+            .save(&path)
+            .unwrap();
+        assert!(PackedStructContainer::<Particle>::open_with_schema(&path).is_err());
+    }
+
+    #[test]
+    fn test_check_schema_compatible() {
+        check_schema_compatible::<Particle>(Particle::mtf_type_blob()).unwrap();
+        assert!(check_schema_compatible::<drifted::Particle>(Particle::mtf_type_blob()).is_err());
+        assert!(check_schema_compatible::<Particle>(b"not a schema").is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Serde support: a container serializes as a sequence of its elements.
+
+use bytemuck::Pod;
+use raw_bytes_container::RawBytesContainer;
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::PackedStructContainer;
+
+impl<T: Pod + Copy + Serialize> Serialize for PackedStructContainer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for element in self.as_slice() {
+            seq.serialize_element(element)?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes into an in-memory container.
+impl<'de, T: Pod + Copy + Deserialize<'de>> Deserialize<'de> for PackedStructContainer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(ElementsVisitor(PhantomData))
+    }
+}
+
+struct ElementsVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Pod + Copy + Deserialize<'de>> Visitor<'de> for ElementsVisitor<T> {
+    type Value = PackedStructContainer<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of elements")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        PackedStructContainer::<T>::validate_alignment().map_err(serde::de::Error::custom)?;
+        // Collect straight into bytes to avoid a second copy
+        let hint = seq.size_hint().unwrap_or(0).min(4096);
+        let mut bytes = Vec::with_capacity(hint * std::mem::size_of::<T>());
+        while let Some(element) = seq.next_element::<T>()? {
+            bytes.extend_from_slice(bytemuck::bytes_of(&element));
+        }
+        Ok(PackedStructContainer {
+            storage: RawBytesContainer::from_vec(bytes).into(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck_derive::{Pod, Zeroable};
+
+    #[repr(C)]
+    #[derive(
+        Clone, Copy, Debug, PartialEq, Pod, Zeroable, serde::Serialize, serde::Deserialize,
+    )]
+    struct Sample {
+        id: u32,
+        value: f32,
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let container = PackedStructContainer::from_slice(&[
+            Sample { id: 1, value: 0.5 },
+            Sample { id: 2, value: 1.5 },
+        ]);
+
+        let json = serde_json::to_string(&container).unwrap();
+        assert_eq!(json, r#"[{"id":1,"value":0.5},{"id":2,"value":1.5}]"#);
+
+        let back: PackedStructContainer<Sample> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.as_slice(), container.as_slice());
+        assert_eq!(back.capacity(), Some(2));
+
+        let empty: PackedStructContainer<Sample> = serde_json::from_str("[]").unwrap();
+        assert!(empty.is_empty());
+        assert!(serde_json::from_str::<PackedStructContainer<Sample>>(r#"{"id":1}"#).is_err());
+    }
+}
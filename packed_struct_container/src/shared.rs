@@ -0,0 +1,148 @@
+//! Cheaply clonable, read-only handles to a container.
+
+use bytemuck::Pod;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::PackedStructContainer;
+
+/// A read-only [`PackedStructContainer`] shared through an [`Arc`].
+///
+/// Created by [`PackedStructContainer::into_shared`] or
+/// [`open_mmap_read_shared`](PackedStructContainer::open_mmap_read_shared).
+/// Cloning only bumps a reference count, and each clone owns its handle, so it
+/// can be moved into a worker thread and read there with the usual slice API
+/// without borrowing from the thread that opened the file.
+///
+/// # Example
+/// ```
+/// use packed_struct_container::PackedStructContainer;
+///
+/// let shared = PackedStructContainer::from_slice(&[1u32, 2, 3]).into_shared();
+/// let worker = {
+///     let shared = shared.clone();
+///     std::thread::spawn(move || shared.iter().sum::<u32>())
+/// };
+/// assert_eq!(worker.join().unwrap(), 6);
+/// assert_eq!(shared[2], 3);
+/// ```
+#[derive(Debug)]
+pub struct SharedPackedStructContainer<T: Pod + Copy> {
+    inner: Arc<PackedStructContainer<T>>,
+}
+
+impl<T: Pod + Copy> SharedPackedStructContainer<T> {
+    /// Returns the number of elements in the container.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the container is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Access as slice of T.
+    pub fn as_slice(&self) -> &[T] {
+        self.inner.as_slice()
+    }
+
+    /// Get element by index.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.inner.get(index)
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, T>> {
+        self.inner.iter()
+    }
+
+    /// Number of handles sharing the container, including this one.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Take the container back if this is the only handle, or return the handle
+    /// unchanged otherwise.
+    pub fn try_unwrap(self) -> Result<PackedStructContainer<T>, Self> {
+        Arc::try_unwrap(self.inner).map_err(|inner| Self { inner })
+    }
+}
+
+impl<T: Pod + Copy> PackedStructContainer<T> {
+    /// Move the container behind a cheaply clonable, read-only handle.
+    pub fn into_shared(self) -> SharedPackedStructContainer<T> {
+        SharedPackedStructContainer {
+            inner: Arc::new(self),
+        }
+    }
+
+    /// Open a memory-mapped file read-only as a shared handle.
+    ///
+    /// Equivalent to [`open_mmap_read`](Self::open_mmap_read) followed by
+    /// [`into_shared`](Self::into_shared).
+    pub fn open_mmap_read_shared<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> Result<SharedPackedStructContainer<T>, raw_bytes_container::ContainerError> {
+        Ok(Self::open_mmap_read(path)?.into_shared())
+    }
+}
+
+impl<T: Pod + Copy> Clone for SharedPackedStructContainer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Pod + Copy> From<PackedStructContainer<T>> for SharedPackedStructContainer<T> {
+    fn from(container: PackedStructContainer<T>) -> Self {
+        container.into_shared()
+    }
+}
+
+impl<T: Pod + Copy> Deref for SharedPackedStructContainer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<'a, T: Pod + Copy> IntoIterator for &'a SharedPackedStructContainer<T> {
+    type Item = T;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_mmap_across_threads() {
+        let values: Vec<u64> = (0..1000).collect();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), bytemuck::cast_slice(&values)).unwrap();
+
+        let shared = PackedStructContainer::<u64>::open_mmap_read_shared(file.path()).unwrap();
+        let workers: Vec<_> = (0..4)
+            .map(|w| {
+                let shared = shared.clone();
+                std::thread::spawn(move || shared[w * 250..(w + 1) * 250].iter().sum::<u64>())
+            })
+            .collect();
+        let total: u64 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+        assert_eq!(total, values.iter().sum::<u64>());
+
+        let other = shared.clone();
+        assert_eq!(shared.handle_count(), 2);
+        let shared = shared.try_unwrap().unwrap_err();
+        drop(other);
+        assert_eq!(shared.try_unwrap().unwrap().len(), 1000);
+    }
+}
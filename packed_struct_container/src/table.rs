@@ -0,0 +1,334 @@
+//! Columnar (structure-of-arrays) copies of a [`PackedStructContainer`].
+//!
+//! A [`Table`] splits every field of an MTF-described struct into its own
+//! contiguous column, using the field offsets from the MTF metadata. Columns
+//! compress better than interleaved records and can be processed one field at
+//! a time with SIMD-friendly loops. Converting is an explicit copy in both
+//! directions; the container itself stays array-of-structs.
+//!
+//! [`PackedStructContainer`]: crate::PackedStructContainer
+
+use bytemuck::Pod;
+use mtf::MTFType;
+use raw_bytes_container::{ContainerError, RawBytesContainer};
+use std::marker::PhantomData;
+
+use crate::PackedStructContainer;
+
+/// One field of every record, stored contiguously.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    name: String,
+    offset: usize,
+    size: usize,
+    data: Vec<u8>,
+}
+
+impl Column {
+    /// Field name from the MTF metadata.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Byte offset of the field within a record.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Size of one value, in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of values.
+    pub fn len(&self) -> usize {
+        self.data.len().checked_div(self.size).unwrap_or(0)
+    }
+
+    /// Returns true if the column holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Bytes of the value at `index`.
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        let start = index.checked_mul(self.size)?;
+        self.data.get(start..start + self.size)
+    }
+
+    /// All values, back to back.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Mutable access to all values, back to back.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// View the column as a slice of `U`.
+    ///
+    /// Returns `None` if `U` is not the size of the field or the column is not
+    /// aligned for `U`.
+    pub fn as_slice<U: Pod>(&self) -> Option<&[U]> {
+        if std::mem::size_of::<U>() != self.size {
+            return None;
+        }
+        bytemuck::try_cast_slice(&self.data).ok()
+    }
+
+    /// Mutable version of [`as_slice`](Self::as_slice).
+    pub fn as_slice_mut<U: Pod>(&mut self) -> Option<&mut [U]> {
+        if std::mem::size_of::<U>() != self.size {
+            return None;
+        }
+        bytemuck::try_cast_slice_mut(&mut self.data).ok()
+    }
+}
+
+/// Per-field columns of a struct container.
+///
+/// # Example
+/// ```
+/// use bytemuck_derive::{Pod, Zeroable};
+/// use mtf_derive::MTF;
+/// use packed_struct_container::{PackedStructContainer, Table};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, MTF)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// let points = PackedStructContainer::from_slice(&[
+///     Point { x: 1.0, y: 2.0 },
+///     Point { x: 3.0, y: 4.0 },
+/// ]);
+///
+/// let mut table = Table::from_aos(&points).unwrap();
+/// let ys = table.column_mut("y").unwrap().as_slice_mut::<f32>().unwrap();
+/// ys.iter_mut().for_each(|y| *y *= 10.0);
+///
+/// let points = table.to_aos::<Point>().unwrap();
+/// assert_eq!(points[1], Point { x: 3.0, y: 40.0 });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    record_size: usize,
+    len: usize,
+    columns: Vec<Column>,
+}
+
+/// Byte-level field layout of `T`, from its MTF metadata.
+fn byte_fields<T: MTFType>() -> Result<Vec<(String, usize, usize)>, ContainerError> {
+    let invalid = |msg: String| ContainerError::InvalidFormat(msg);
+    let (types, strings) = mtf::read_mtf(T::mtf_type_blob())
+        .map_err(|e| invalid(format!("invalid MTF metadata: {}", e)))?;
+    let type_def = types
+        .first()
+        .ok_or_else(|| invalid("MTF metadata contains no types".to_string()))?;
+
+    let record_size = std::mem::size_of::<T>();
+    type_def
+        .fields
+        .iter()
+        .map(|f| {
+            let name = mtf::read_string(strings, f.name_offset)
+                .map_err(|e| invalid(format!("invalid MTF field name: {}", e)))?
+                .to_string();
+            if !f.offset_bits.is_multiple_of(8) || !f.size_bits.is_multiple_of(8) {
+                return Err(invalid(format!("field {} is not byte-aligned", name)));
+            }
+            let (offset, size) = (f.offset_bits as usize / 8, f.size_bits as usize / 8);
+            if offset + size > record_size {
+                return Err(invalid(format!(
+                    "field {} extends past the end of the record",
+                    name
+                )));
+            }
+            Ok((name, offset, size))
+        })
+        .collect()
+}
+
+impl Table {
+    /// Split a struct container into one column per MTF field.
+    ///
+    /// Padding bytes between fields are dropped.
+    ///
+    /// # Errors
+    /// Returns an error if the MTF metadata of `T` is invalid or describes
+    /// fields that are not whole bytes (e.g. from `#[derive(MTFBits)]`).
+    pub fn from_aos<T: Pod + MTFType>(
+        container: &PackedStructContainer<T>,
+    ) -> Result<Self, ContainerError> {
+        let record_size = std::mem::size_of::<T>();
+        let len = container.len();
+        let records: &[u8] = bytemuck::cast_slice(container.as_slice());
+
+        let columns = byte_fields::<T>()?
+            .into_iter()
+            .map(|(name, offset, size)| {
+                let mut data = Vec::with_capacity(len * size);
+                for record in records.chunks_exact(record_size) {
+                    data.extend_from_slice(&record[offset..offset + size]);
+                }
+                Column {
+                    name,
+                    offset,
+                    size,
+                    data,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            record_size,
+            len,
+            columns,
+        })
+    }
+
+    /// Reassemble the columns into a struct container.
+    ///
+    /// Padding bytes are zeroed.
+    ///
+    /// # Errors
+    /// Returns an error if the columns do not match the MTF layout of `T`.
+    pub fn to_aos<T: Pod + MTFType>(&self) -> Result<PackedStructContainer<T>, ContainerError> {
+        let fields = byte_fields::<T>()?;
+        let matches = self.record_size == std::mem::size_of::<T>()
+            && fields.len() == self.columns.len()
+            && fields
+                .iter()
+                .zip(&self.columns)
+                .all(|((name, offset, size), c)| {
+                    *name == c.name && *offset == c.offset && *size == c.size
+                });
+        if !matches {
+            return Err(ContainerError::InvalidFormat(format!(
+                "table layout does not match {}",
+                std::any::type_name::<T>()
+            )));
+        }
+
+        let mut bytes = vec![0u8; self.len * self.record_size];
+        for column in self.columns.iter().filter(|c| c.size > 0) {
+            for (record, value) in bytes
+                .chunks_exact_mut(self.record_size)
+                .zip(column.data.chunks_exact(column.size))
+            {
+                record[column.offset..column.offset + column.size].copy_from_slice(value);
+            }
+        }
+
+        crate::layout::enforce_layout_mtf::<T>()?;
+        Ok(PackedStructContainer {
+            storage: RawBytesContainer::from_vec(bytes).into(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Size of one record in the original container, including padding.
+    pub fn record_size(&self) -> usize {
+        self.record_size
+    }
+
+    /// All columns, in MTF field order.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Look up a column by field name.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Mutable access to a column by field name.
+    ///
+    /// The column's length and layout cannot be changed through this.
+    pub fn column_mut(&mut self, name: &str) -> Option<&mut Column> {
+        self.columns.iter_mut().find(|c| c.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck_derive::{Pod, Zeroable};
+    use mtf_derive::MTF;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, MTF)]
+    struct Sample {
+        id: u32,
+        flags: u16,
+        kind: u16,
+        value: u64,
+    }
+
+    fn samples() -> PackedStructContainer<Sample> {
+        PackedStructContainer::from_slice(
+            &(0..5)
+                .map(|i| Sample {
+                    id: i,
+                    flags: 0x100 | i as u16,
+                    kind: 7,
+                    value: u64::from(i) << 40,
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let container = samples();
+        let table = Table::from_aos(&container).unwrap();
+        assert_eq!(table.len(), 5);
+        assert_eq!(table.record_size(), 16);
+
+        let names: Vec<_> = table.columns().iter().map(Column::name).collect();
+        assert_eq!(names, vec!["id", "flags", "kind", "value"]);
+
+        let flags = table.column("flags").unwrap();
+        assert_eq!((flags.offset(), flags.size(), flags.len()), (4, 2, 5));
+        assert_eq!(flags.get(3), Some(&0x103u16.to_le_bytes()[..]));
+        assert_eq!(flags.as_bytes().len(), 10);
+
+        let back = table.to_aos::<Sample>().unwrap();
+        assert_eq!(back.as_slice(), container.as_slice());
+    }
+
+    #[test]
+    fn test_column_edit_and_mismatch() {
+        let mut table = Table::from_aos(&samples()).unwrap();
+        let column = table.column_mut("kind").unwrap();
+        assert!(column.as_slice::<u32>().is_none());
+        column.as_bytes_mut()[0] = 9;
+
+        let back = table.to_aos::<Sample>().unwrap();
+        assert_eq!(back[0].kind, 9);
+        assert_eq!(back[1].kind, 7);
+
+        #[repr(C)]
+        #[derive(Clone, Copy, Pod, Zeroable, MTF)]
+        struct Other {
+            id: u32,
+            flags: u16,
+            kind: u16,
+            other: u64,
+        }
+        assert!(table.to_aos::<Other>().is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! Borrowed views over a range of a container's elements.
+
+use bytemuck::Pod;
+use std::ops::{Bound, Deref, DerefMut, RangeBounds};
+
+use crate::PackedStructContainer;
+
+/// Convert any range into the `(Bound, Bound)` pair slices can be indexed with.
+fn bounds<R: RangeBounds<usize>>(range: R) -> (Bound<usize>, Bound<usize>) {
+    (range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+/// A read-only view over a range of elements.
+///
+/// Created by [`PackedStructContainer::slice`]. Offers the same read API as the
+/// container (`len`, `get`, `iter`, indexing) and borrows it, so functions can
+/// accept a window of a container without a separate offset and length.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a, T: Pod> {
+    elements: &'a [T],
+}
+
+impl<'a, T: Pod + Copy> View<'a, T> {
+    /// Number of elements in the view.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns true if the view has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Get element by index, relative to the start of the view.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.elements.get(index).copied()
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'a, T>> {
+        self.elements.iter().copied()
+    }
+
+    /// Access as slice of T, borrowed for as long as the container.
+    pub fn as_slice(&self) -> &'a [T] {
+        self.elements
+    }
+
+    /// A narrower view over `range`, relative to the start of this view.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> View<'a, T> {
+        View {
+            elements: &self.elements[bounds(range)],
+        }
+    }
+
+    /// Copy the viewed elements into a new in-memory container.
+    pub fn to_container(&self) -> PackedStructContainer<T> {
+        PackedStructContainer::from_slice(self.elements)
+    }
+}
+
+impl<T: Pod + Copy> Deref for View<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.elements
+    }
+}
+
+impl<'a, T: Pod + Copy> IntoIterator for View<'a, T> {
+    type Item = T;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: Pod + Copy> IntoIterator for &View<'a, T> {
+    type Item = T;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A mutable view over a range of elements.
+///
+/// Created by [`PackedStructContainer::slice_mut`], which only succeeds on
+/// writable storage, so unlike the container itself a `ViewMut` never panics on
+/// mutable access.
+#[derive(Debug)]
+pub struct ViewMut<'a, T: Pod> {
+    elements: &'a mut [T],
+}
+
+impl<'a, T: Pod + Copy> ViewMut<'a, T> {
+    /// Number of elements in the view.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns true if the view has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Get element by index, relative to the start of the view.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.elements.get(index).copied()
+    }
+
+    /// Get mutable reference to element by index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.elements.get_mut(index)
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, T>> {
+        self.elements.iter().copied()
+    }
+
+    /// Returns an iterator over mutable references to the elements.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.elements.iter_mut()
+    }
+
+    /// Access as slice of T.
+    pub fn as_slice(&self) -> &[T] {
+        self.elements
+    }
+
+    /// Access as mutable slice of T.
+    pub fn as_slice_mut(&mut self) -> &mut [T] {
+        self.elements
+    }
+
+    /// Reborrow as a read-only view.
+    pub fn as_view(&self) -> View<'_, T> {
+        View {
+            elements: self.elements,
+        }
+    }
+
+    /// A narrower read-only view over `range`, relative to the start of this view.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> View<'_, T> {
+        View {
+            elements: &self.elements[bounds(range)],
+        }
+    }
+
+    /// A narrower mutable view over `range`, relative to the start of this view.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    pub fn slice_mut<R: RangeBounds<usize>>(&mut self, range: R) -> ViewMut<'_, T> {
+        ViewMut {
+            elements: &mut self.elements[bounds(range)],
+        }
+    }
+
+    /// Split into two mutable views at `mid`, e.g. to hand halves to different
+    /// workers.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    pub fn split_at_mut(self, mid: usize) -> (ViewMut<'a, T>, ViewMut<'a, T>) {
+        let (left, right) = self.elements.split_at_mut(mid);
+        (ViewMut { elements: left }, ViewMut { elements: right })
+    }
+
+    /// Copy the viewed elements into a new in-memory container.
+    pub fn to_container(&self) -> PackedStructContainer<T> {
+        PackedStructContainer::from_slice(self.elements)
+    }
+}
+
+impl<T: Pod + Copy> Deref for ViewMut<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.elements
+    }
+}
+
+impl<T: Pod + Copy> DerefMut for ViewMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.elements
+    }
+}
+
+impl<'a, T: Pod + Copy> From<ViewMut<'a, T>> for View<'a, T> {
+    fn from(view: ViewMut<'a, T>) -> Self {
+        View {
+            elements: view.elements,
+        }
+    }
+}
+
+impl<T: Pod + Copy> PackedStructContainer<T> {
+    /// A read-only view over the elements in `range`.
+    ///
+    /// ```
+    /// use packed_struct_container::{PackedStructContainer, View};
+    ///
+    /// fn total(samples: View<'_, u32>) -> u32 {
+    ///     samples.iter().sum()
+    /// }
+    ///
+    /// let samples = PackedStructContainer::from_slice(&[1u32, 2, 3, 4, 5]);
+    /// assert_eq!(total(samples.slice(1..4)), 9);
+    /// assert_eq!(samples.slice(3..)[0], 4);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> View<'_, T> {
+        View {
+            elements: &self.as_slice()[bounds(range)],
+        }
+    }
+
+    /// A mutable view over the elements in `range`.
+    ///
+    /// Returns `None` if the storage is read-only.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    pub fn slice_mut<R: RangeBounds<usize>>(&mut self, range: R) -> Option<ViewMut<'_, T>> {
+        Some(ViewMut {
+            elements: &mut self.as_slice_mut()?[bounds(range)],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_views() {
+        let mut container = PackedStructContainer::from_slice(&[0u32, 1, 2, 3, 4, 5]);
+
+        let view = container.slice(1..5);
+        assert_eq!(view.len(), 4);
+        assert_eq!(view.get(0), Some(1));
+        assert_eq!(view.get(4), None);
+        assert_eq!(view.iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+        let inner = view.slice(..=1);
+        assert_eq!(inner.as_slice(), &[1, 2]);
+        assert_eq!(inner.to_container().as_slice(), &[1, 2]);
+        assert!(container.slice(6..).is_empty());
+
+        let mut view = container.slice_mut(2..).unwrap();
+        view[0] = 20;
+        *view.get_mut(3).unwrap() = 50;
+        let (mut left, mut right) = view.split_at_mut(2);
+        left.iter_mut().for_each(|v| *v += 1);
+        right.slice_mut(..1)[0] = 40;
+        assert_eq!(View::from(right).as_slice(), &[40, 50]);
+        assert_eq!(container.as_slice(), &[0, 1, 21, 4, 40, 50]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_slice_out_of_bounds() {
+        PackedStructContainer::from_slice(&[1u8, 2]).slice(1..3);
+    }
+
+    #[test]
+    fn test_slice_mut_read_only() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), [1u8, 2, 3]).unwrap();
+        let mut mapped = PackedStructContainer::<u8>::open_mmap_read(file.path()).unwrap();
+        assert!(mapped.slice_mut(..).is_none());
+        assert_eq!(mapped.slice(1..).as_slice(), &[2, 3]);
+    }
+}
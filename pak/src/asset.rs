@@ -2,11 +2,14 @@
 use std::path::Path;
 use std::fs;
 use crate::format::{AssetType, PakError, Result};
+use crate::format::crypto::KEY_SIZE;
 
 pub struct AssetEntry {
     pub name: String,
     pub data: Vec<u8>,
     pub asset_type: AssetType,
+    /// Key id and key to encrypt this asset with
+    pub encryption: Option<(u32, [u8; KEY_SIZE])>,
 }
 
 impl AssetEntry {
@@ -15,9 +18,16 @@ impl AssetEntry {
             name: name.into(),
             data,
             asset_type,
+            encryption: None,
         }
     }
     
+    /// Encrypt this asset with `key`; readers look the key up by `key_id`
+    pub fn encrypted(mut self, key_id: u32, key: [u8; KEY_SIZE]) -> Self {
+        self.encryption = Some((key_id, key));
+        self
+    }
+    
     pub fn from_file(path: impl AsRef<Path>, asset_type: AssetType) -> Result<Self> {
         let path = path.as_ref();
         let name = path
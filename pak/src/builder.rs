@@ -58,18 +58,18 @@
 
 //! builder.rs - PAK file builder using raw-bytes containers
 
-use std::path::Path;
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::fs::File;
 //use std::io::Write;
 use std::io::{Write, Seek};
-use bytemuck_derive::{Pod, Zeroable};
+use mtf_api::MTFType;
 
 use crate::asset::AssetEntry;
+use raw_bytes_container::RawBytesContainer;
 use crate::format::{
     PakError, Result,
-    PakHeader, TocEntry, AssetType,
-    HEADER_SIZE,
+    PakHeader, TocEntry, AssetType, StringContainer, SchemaRegistry,
+    HEADER_SIZE, TOC_ENTRY_SIZE, SCHEMA_REGISTRY_NAME,
 };
 
 /// Builder for creating PAK files
@@ -77,6 +77,8 @@ pub struct PakBuilder {
     assets: Vec<AssetEntry>,
     compression_level: i32,
     compress_threshold: usize,
+    manifest_path: Option<PathBuf>,
+    schemas: SchemaRegistry,
 }
 
 impl PakBuilder {
@@ -86,6 +88,8 @@ impl PakBuilder {
             assets: Vec::new(),
             compression_level: 3,
             compress_threshold: 512,
+            manifest_path: None,
+            schemas: SchemaRegistry::new(),
         }
     }
     
@@ -102,12 +106,61 @@ impl PakBuilder {
         self
     }
     
+    /// Also write a JSON manifest to `path` when building
+    ///
+    /// The manifest lists every asset's name, name hash, type, codec, offset,
+    /// and original and stored sizes in TOC order, so patchers and asset
+    /// databases can index the archive without parsing the binary TOC.
+    pub fn manifest(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.manifest_path = Some(path.into());
+        self
+    }
+    
+    /// Reorder assets to follow a captured access trace
+    ///
+    /// Assets are laid out in the order of their first appearance in `trace`,
+    /// so data read together at runtime ends up adjacent in the archive. Assets
+    /// not in the trace keep their relative order and go after traced ones.
+    pub fn reorder_by_trace<S: AsRef<str>>(&mut self, trace: impl IntoIterator<Item = S>) -> &mut Self {
+        let mut first_access = std::collections::HashMap::new();
+        for (pos, name) in trace.into_iter().enumerate() {
+            first_access.entry(name.as_ref().to_string()).or_insert(pos);
+        }
+        self.assets
+            .sort_by_key(|asset| first_access.get(&asset.name).copied().unwrap_or(usize::MAX));
+        self
+    }
+    
     /// Add an asset to the PAK
     pub fn add_asset(&mut self, asset: AssetEntry) -> &mut Self {
         self.assets.push(asset);
         self
     }
     
+    /// Add a slice of MTF-described structs as a typed asset
+    ///
+    /// The data is stored as raw bytes and `T`'s schema goes into the
+    /// archive's schema registry, so readers can open it with
+    /// [`PakReader::get_asset_dynamic`](crate::PakReader::get_asset_dynamic).
+    pub fn add_typed<T: bytemuck::Pod + MTFType>(
+        &mut self,
+        name: impl Into<String>,
+        items: &[T],
+        asset_type: AssetType
+    ) -> &mut Self {
+        let asset = AssetEntry::new(name, bytemuck::cast_slice(items).to_vec(), asset_type);
+        self.add_asset_with_schema(asset, T::mtf_type_blob())
+    }
+    
+    /// Add an asset typed by a complete MTF `blob`
+    ///
+    /// Each distinct blob is written once, in the reserved
+    /// [`SCHEMA_REGISTRY_NAME`] entry, however many assets use it.
+    pub fn add_asset_with_schema(&mut self, asset: AssetEntry, blob: &[u8]) -> &mut Self {
+        self.schemas.insert(&asset.name, blob);
+        self.add_asset(asset)
+    }
+    
     /// Add a directory of assets
     pub fn add_directory(
         &mut self,
@@ -132,19 +185,90 @@ impl PakBuilder {
     
     /// Build and write the PAK file
     pub fn build(&self, output: impl AsRef<Path>) -> Result<()> {
+        let plan = self.plan()?;
         let mut file = File::create(output)?;
         
         // Reserve space for header
         file.write_all(&[0u8; HEADER_SIZE])?;
         
+        // Write asset data
+        for data in &plan.blobs {
+            file.write_all(data)?;
+        }
+        
+        // Write TOC
+        for entry in &plan.toc_entries {
+            file.write_all(entry.as_bytes())?;
+        }
+        
+        // Write string table
+        file.write_all(plan.string_table.as_bytes())?;
+        
+        // Write header at the beginning
+        file.seek(std::io::SeekFrom::Start(0))?;
+        file.write_all(plan.header().as_bytes())?;
+        file.flush()?;
+        
+        self.write_manifest(&plan)
+    }
+    
+    /// Build the PAK file by writing through a read-write memory map
+    ///
+    /// The output file is preallocated to its final size and every section is
+    /// copied straight into the mapping, so the header and TOC are written in
+    /// place without seeking. Produces the same bytes as [`build`](Self::build).
+    pub fn build_mmap(&self, output: impl AsRef<Path>) -> Result<()> {
+        let plan = self.plan()?;
+        let toc_offset = plan.toc_offset as usize;
+        let string_offset = toc_offset + plan.toc_entries.len() * TOC_ENTRY_SIZE;
+        let total = string_offset + plan.string_table.len();
+        
+        let mut container = RawBytesContainer::<u8>::create_mmap_rw(output, total)
+            .map_err(|e| PakError::Io(std::io::Error::other(
+                format!("Failed to mmap PAK file: {}", e)
+            )))?;
+        let out = container.as_slice_mut().expect("rw mapping is mutable");
+        
+        out[..HEADER_SIZE].copy_from_slice(plan.header().as_bytes());
+        let mut pos = HEADER_SIZE;
+        for data in &plan.blobs {
+            out[pos..pos + data.len()].copy_from_slice(data);
+            pos += data.len();
+        }
+        for entry in &plan.toc_entries {
+            out[pos..pos + TOC_ENTRY_SIZE].copy_from_slice(entry.as_bytes());
+            pos += TOC_ENTRY_SIZE;
+        }
+        out[string_offset..].copy_from_slice(plan.string_table.as_bytes());
+        
+        container.flush()
+            .map_err(|e| PakError::Io(std::io::Error::other(e.to_string())))?;
+        
+        self.write_manifest(&plan)
+    }
+    
+    fn write_manifest(&self, plan: &BuildPlan) -> Result<()> {
+        if let Some(path) = &self.manifest_path {
+            let assets = self.assets.iter().chain(&plan.schema_registry);
+            crate::manifest::write_json(path, assets, &plan.toc_entries)?;
+        }
+        Ok(())
+    }
+    
+    /// Encode all assets and lay out the archive
+    ///
+    /// If any asset is typed, the schema registry is appended as the last entry.
+    fn plan(&self) -> Result<BuildPlan> {
+        let schema_registry = (!self.schemas.is_empty()).then(|| {
+            AssetEntry::new(SCHEMA_REGISTRY_NAME, self.schemas.to_bytes(), AssetType::Data)
+        });
         let data_offset = HEADER_SIZE as u64;
         let mut current_offset = data_offset;
-        let mut toc_entries = Vec::new();
-        let mut string_table = Vec::new();
-        let mut string_offsets = HashMap::new();
+        let mut blobs = Vec::with_capacity(self.assets.len());
+        let mut toc_entries = Vec::with_capacity(self.assets.len());
+        let mut string_table = StringContainer::new();
         
-        // Write asset data and build TOC
-        for asset in &self.assets {
+        for asset in self.assets.iter().chain(&schema_registry) {
             let entry_offset = current_offset;
             let original_size = asset.data.len() as u64;
             
@@ -182,42 +306,49 @@ impl PakBuilder {
                 (asset.data.clone(), entry)
             };
             
-            // Write asset data
-            file.write_all(&data_to_write)?;
-            toc_entries.push(toc_entry);
-            
-            // Build string table
-            if !string_offsets.contains_key(&asset.name) {
-                let str_offset = string_table.len();
-                string_offsets.insert(asset.name.clone(), str_offset);
-                string_table.extend_from_slice(asset.name.as_bytes());
-                string_table.push(0); // null terminator
-            }
+            // Encrypt after compression; ciphertext does not compress
+            let (data_to_write, toc_entry) = match &asset.encryption {
+                Some((key_id, key)) => {
+                    let nonce = crate::format::crypto::fresh_nonce()?;
+                    let mut data = data_to_write;
+                    crate::format::crypto::encrypt(key, &nonce, &mut data);
+                    (data, toc_entry.with_encryption(*key_id, nonce))
+                }
+                None => (data_to_write, toc_entry),
+            };
             
+            let toc_entry = toc_entry
+                .with_checksum(&data_to_write)
+                .with_name_ref(string_table.insert(&asset.name));
             current_offset += data_to_write.len() as u64;
+            blobs.push(data_to_write);
+            toc_entries.push(toc_entry);
         }
         
-        // Write TOC
-        let toc_offset = current_offset;
-        for entry in &toc_entries {
-            file.write_all(entry.as_bytes())?;
-        }
-        
-        // Write string table
-        file.write_all(&string_table)?;
-        
-        // Write header at the beginning
-        let header = PakHeader::new(
-            toc_entries.len() as u32,
-            toc_offset,
+        Ok(BuildPlan {
+            blobs,
+            toc_entries,
+            string_table,
             data_offset,
-        );
-        
-        file.seek(std::io::SeekFrom::Start(0))?;
-        file.write_all(header.as_bytes())?;
-        file.flush()?;
-        
-        Ok(())
+            toc_offset: current_offset,
+            schema_registry,
+        })
+    }
+}
+
+/// Encoded archive contents, ready to be written
+struct BuildPlan {
+    blobs: Vec<Vec<u8>>,
+    toc_entries: Vec<TocEntry>,
+    string_table: StringContainer,
+    data_offset: u64,
+    toc_offset: u64,
+    schema_registry: Option<AssetEntry>,
+}
+
+impl BuildPlan {
+    fn header(&self) -> PakHeader {
+        PakHeader::new(self.toc_entries.len() as u32, self.toc_offset, self.data_offset)
     }
 }
 
@@ -290,4 +421,53 @@ mod tests {
         
         Ok(())
     }
+    
+    #[test]
+    fn test_reorder_by_trace() {
+        let mut builder = PakBuilder::new();
+        for name in ["a", "b", "c", "d"] {
+            builder.add_asset(AssetEntry::new(name, vec![0], AssetType::Data));
+        }
+        builder.reorder_by_trace(["c", "a", "c", "x"]);
+        
+        let order: Vec<_> = builder.assets.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(order, vec!["c", "a", "b", "d"]);
+    }
+    
+    #[test]
+    fn test_build_mmap_matches_build() -> Result<()> {
+        use tempfile::NamedTempFile;
+        
+        let buffered = NamedTempFile::new()?;
+        let mapped = NamedTempFile::new()?;
+        let mut builder = PakBuilder::new();
+        builder.add_asset(AssetEntry::new("a.txt", b"Hello".to_vec(), AssetType::Data));
+        builder.add_asset(AssetEntry::new("big.bin", vec![3; 4096], AssetType::Data));
+        builder.build(buffered.path())?;
+        builder.build_mmap(mapped.path())?;
+        
+        assert_eq!(std::fs::read(buffered.path())?, std::fs::read(mapped.path())?);
+        let reader = crate::PakReader::open(mapped.path())?;
+        assert_eq!(reader.get_asset("big.bin")?, vec![3; 4096]);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_build_with_manifest() -> Result<()> {
+        use tempfile::NamedTempFile;
+        
+        let temp = NamedTempFile::new()?;
+        let manifest = NamedTempFile::new()?;
+        let mut builder = PakBuilder::new();
+        builder.manifest(manifest.path());
+        builder.add_asset(AssetEntry::new("test.txt", b"Hello".to_vec(), AssetType::Data));
+        builder.build(temp.path())?;
+        
+        let json = std::fs::read_to_string(manifest.path())?;
+        assert!(json.contains("\"name\": \"test.txt\""));
+        assert!(json.contains(&format!("\"offset\": {}", HEADER_SIZE)));
+        
+        Ok(())
+    }
 }
\ No newline at end of file
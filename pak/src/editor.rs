@@ -0,0 +1,197 @@
+//! editor.rs - In-place style edits of existing PAK files
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use crate::format::{
+    PakError, Result,
+    PakHeader, TocEntry, StringContainer,
+    hash_name, HEADER_SIZE,
+};
+use crate::reader::PakReader;
+
+/// Editor for renaming and aliasing assets in an existing PAK
+///
+/// Asset data is carried over untouched; only the TOC and string table are
+/// rewritten on [`save`](Self::save). An alias is an extra TOC entry that
+/// points at the same data as another entry, so old paths keep resolving
+/// after content has been reorganized.
+pub struct PakEditor {
+    data: Vec<u8>,
+    entries: Vec<(String, TocEntry)>,
+}
+
+impl PakEditor {
+    /// Load a PAK file for editing
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let reader = PakReader::open(path)?;
+        let header = reader.header();
+        let (data_offset, toc_offset) = (header.data_offset as usize, header.toc_offset as usize);
+        let data = reader.raw_bytes()
+            .get(data_offset..toc_offset)
+            .ok_or_else(|| PakError::InvalidToc("Data region out of bounds".to_string()))?
+            .to_vec();
+        
+        // Rebase entry offsets onto the data region
+        let entries = reader.named_entries()
+            .into_iter()
+            .map(|(name, mut entry)| {
+                entry.offset = { entry.offset }.checked_sub(data_offset as u64)
+                    .ok_or_else(|| PakError::InvalidToc(format!(
+                        "Asset '{}' starts before the data region", name
+                    )))?;
+                Ok((name, entry))
+            })
+            .collect::<Result<_>>()?;
+        
+        Ok(Self { data, entries })
+    }
+    
+    /// Names of all entries (including aliases), in TOC order
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|(name, _)| name.as_str()).collect()
+    }
+    
+    /// Returns true if an entry with `name` exists
+    pub fn contains(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+    
+    /// Rename the entry `old` to `new`
+    ///
+    /// Aliases of `old` are unaffected and keep pointing at the same data.
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<()> {
+        let idx = self.position(old)
+            .ok_or_else(|| PakError::AssetNotFound(old.to_string()))?;
+        if old != new && self.contains(new) {
+            return Err(PakError::NameConflict(new.to_string()));
+        }
+        
+        let (name, entry) = &mut self.entries[idx];
+        *name = new.to_string();
+        entry.name_hash = hash_name(new);
+        Ok(())
+    }
+    
+    /// Add `alias` as another name resolving to the same data as `target`
+    pub fn add_alias(&mut self, target: &str, alias: &str) -> Result<()> {
+        let idx = self.position(target)
+            .ok_or_else(|| PakError::AssetNotFound(target.to_string()))?;
+        if self.contains(alias) {
+            return Err(PakError::NameConflict(alias.to_string()));
+        }
+        
+        let mut entry = self.entries[idx].1;
+        entry.name_hash = hash_name(alias);
+        self.entries.push((alias.to_string(), entry));
+        Ok(())
+    }
+    
+    /// Other names resolving to the same data as `name` (excluding `name` itself)
+    pub fn aliases_of(&self, name: &str) -> Vec<&str> {
+        let Some(idx) = self.position(name) else {
+            return Vec::new();
+        };
+        let offset = self.entries[idx].1.offset;
+        self.entries
+            .iter()
+            .filter(|(n, e)| n != name && { e.offset } == offset)
+            .map(|(n, _)| n.as_str())
+            .collect()
+    }
+    
+    /// Write the edited archive to `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let data_offset = HEADER_SIZE as u64;
+        let toc_offset = data_offset + self.data.len() as u64;
+        
+        let mut strings = StringContainer::new();
+        let toc = self.entries
+            .iter()
+            .map(|(name, entry)| {
+                // Archives older than version 4 carry no checksums; compute them here
+                let start = entry.offset as usize;
+                let stored = start.checked_add(entry.stored_size() as usize)
+                    .and_then(|end| self.data.get(start..end))
+                    .ok_or_else(|| PakError::InvalidToc(format!(
+                        "Asset data of '{}' out of bounds", name
+                    )))?;
+                let mut entry = entry
+                    .with_checksum(stored)
+                    .with_name_ref(strings.insert(name));
+                entry.offset += data_offset;
+                Ok(entry)
+            })
+            .collect::<Result<Vec<TocEntry>>>()?;
+        
+        let header = PakHeader::new(toc.len() as u32, toc_offset, data_offset);
+        let mut file = File::create(path)?;
+        file.write_all(header.as_bytes())?;
+        file.write_all(&self.data)?;
+        for entry in &toc {
+            file.write_all(entry.as_bytes())?;
+        }
+        file.write_all(strings.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+    
+    fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|(n, _)| n == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetEntry, AssetType, PakBuilder};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_rename_and_alias() -> Result<()> {
+        let original = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        builder.add_asset(AssetEntry::new("old/a.txt", b"alpha".to_vec(), AssetType::Data));
+        builder.add_asset(AssetEntry::new("b.txt", b"beta".to_vec(), AssetType::Data));
+        builder.build(original.path())?;
+        
+        let mut editor = PakEditor::open(original.path())?;
+        editor.rename("old/a.txt", "new/a.txt")?;
+        editor.add_alias("new/a.txt", "old/a.txt")?;
+        assert!(matches!(editor.rename("b.txt", "new/a.txt"), Err(PakError::NameConflict(_))));
+        assert!(matches!(editor.add_alias("missing", "x"), Err(PakError::AssetNotFound(_))));
+        assert_eq!(editor.aliases_of("new/a.txt"), vec!["old/a.txt"]);
+        
+        let edited = NamedTempFile::new().unwrap();
+        editor.save(edited.path())?;
+        
+        let reader = PakReader::open(edited.path())?;
+        assert_eq!(reader.asset_count(), 3);
+        assert_eq!(reader.get_asset("new/a.txt")?, b"alpha");
+        assert_eq!(reader.get_asset("old/a.txt")?, b"alpha");
+        assert_eq!(reader.get_asset("b.txt")?, b"beta");
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_rejects_offset_before_data() -> Result<()> {
+        let file = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        builder.add_asset(AssetEntry::new("a.txt", b"alpha".to_vec(), AssetType::Data));
+        builder.build(file.path())?;
+
+        // Point the only TOC entry into the header
+        let toc_offset = PakReader::open(file.path())?.header().toc_offset as usize;
+        let mut bytes = std::fs::read(file.path())?;
+        bytes[toc_offset + 8..toc_offset + 16].copy_from_slice(&0u64.to_le_bytes());
+        std::fs::write(file.path(), &bytes)?;
+
+        assert!(matches!(
+            PakEditor::open(file.path()),
+            Err(PakError::InvalidToc(msg)) if msg.contains("before the data region")
+        ));
+        Ok(())
+    }
+}
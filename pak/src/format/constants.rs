@@ -1,10 +1,26 @@
 
 pub const PAK_MAGIC: &[u8; 4] = b"PAK\0";
-pub const PAK_VERSION: u32 = 1;
+pub const PAK_VERSION: u32 = 4;
+/// Last version whose TOC entries locate names by implicit string-table order
+pub const PAK_VERSION_IMPLICIT_NAMES: u32 = 1;
 pub const HEADER_SIZE: usize = 32;
-pub const TOC_ENTRY_SIZE: usize = 48;
+pub const TOC_ENTRY_SIZE: usize = 72;
+/// Last version whose TOC entries lack the encryption fields
+pub const PAK_VERSION_SHORT_TOC: u32 = 2;
+/// TOC entry size up to [`PAK_VERSION_SHORT_TOC`]
+pub const TOC_ENTRY_SIZE_V2: usize = 48;
+/// Last version whose TOC entries lack the data checksum
+pub const PAK_VERSION_NO_CHECKSUMS: u32 = 3;
+/// TOC entry size in [`PAK_VERSION_NO_CHECKSUMS`]
+pub const TOC_ENTRY_SIZE_V3: usize = 64;
 pub const FLAG_COMPRESSED: u32 = 1 << 0;
+/// Entry data is encrypted; the TOC entry carries its key id and nonce
+pub const FLAG_ENCRYPTED: u32 = 1 << 1;
+/// Header flag: TOC entries carry checksums of their stored data (version 4+)
+pub const HEADER_FLAG_CHECKSUMS: u32 = 1 << 0;
 pub const MAX_NAME_LENGTH: usize = 256;
+/// Reserved entry holding the archive's [`SchemaRegistry`](crate::SchemaRegistry)
+pub const SCHEMA_REGISTRY_NAME: &str = ".mtf/schemas";
 
 #[cfg(test)]
 mod tests {
@@ -13,8 +29,8 @@ mod tests {
     #[test]
     fn test_constants() {
         assert_eq!(PAK_MAGIC, b"PAK\0");
-        assert_eq!(PAK_VERSION, 1);
+        assert_eq!(PAK_VERSION, 4);
         assert_eq!(HEADER_SIZE, 32);
-        assert_eq!(TOC_ENTRY_SIZE, 48);
+        assert_eq!(TOC_ENTRY_SIZE, 72);
     }
 }
\ No newline at end of file
@@ -0,0 +1,98 @@
+//! Per-entry authenticated encryption (ChaCha20-Poly1305, RFC 8439)
+//!
+//! Encrypted entries are stored as ciphertext followed by a [`TAG_SIZE`]-byte
+//! Poly1305 tag, so a wrong key or altered data is detected on read.
+
+use std::collections::HashMap;
+
+use chacha20poly1305::aead::AeadInPlace;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce, Tag};
+
+use crate::format::error::{PakError, Result};
+
+/// Size of an entry key in bytes
+pub const KEY_SIZE: usize = 32;
+/// Size of an entry nonce in bytes
+pub const NONCE_SIZE: usize = 12;
+/// Size of the authentication tag appended to encrypted entry data
+pub const TAG_SIZE: usize = 16;
+
+/// Supplies decryption keys for encrypted entries at read time
+///
+/// Returning `None` keeps the entry locked, e.g. until the player owns the
+/// content it belongs to.
+pub trait KeyProvider: Send + Sync {
+    /// Key for `key_id`, as requested by the entry named `asset`
+    fn key(&self, key_id: u32, asset: &str) -> Option<[u8; KEY_SIZE]>;
+}
+
+impl KeyProvider for HashMap<u32, [u8; KEY_SIZE]> {
+    fn key(&self, key_id: u32, _asset: &str) -> Option<[u8; KEY_SIZE]> {
+        self.get(&key_id).copied()
+    }
+}
+
+/// Encrypt `data` in place and append its authentication tag
+pub fn encrypt(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], data: &mut Vec<u8>) {
+    let tag = ChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt_in_place_detached(Nonce::from_slice(nonce), &[], data)
+        .expect("entry fits in a single ChaCha20-Poly1305 message");
+    data.extend_from_slice(&tag);
+}
+
+/// Verify the tag at the end of `data` and decrypt the rest in place
+///
+/// # Errors
+/// Returns [`PakError::DecryptionFailed`] if `data` is shorter than a tag, or
+/// the key is wrong or the data was altered.
+pub fn decrypt(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], data: &mut Vec<u8>) -> Result<()> {
+    let body_len = data.len().checked_sub(TAG_SIZE).ok_or_else(|| {
+        PakError::DecryptionFailed("data is shorter than the authentication tag".to_string())
+    })?;
+    let tag = Tag::clone_from_slice(&data[body_len..]);
+    data.truncate(body_len);
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt_in_place_detached(Nonce::from_slice(nonce), &[], data, &tag)
+        .map_err(|_| {
+            PakError::DecryptionFailed("authentication tag mismatch".to_string())
+        })
+}
+
+/// A random nonce for a newly encrypted entry, from the OS CSPRNG
+pub(crate) fn fresh_nonce() -> Result<[u8; NONCE_SIZE]> {
+    let mut nonce = [0u8; NONCE_SIZE];
+    getrandom::getrandom(&mut nonce)
+        .map_err(|e| PakError::Io(std::io::Error::other(e)))?;
+    Ok(nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_tamper() {
+        let key = [9u8; KEY_SIZE];
+        let nonce = fresh_nonce().unwrap();
+        assert_ne!(nonce, fresh_nonce().unwrap());
+
+        let mut data = b"level geometry".to_vec();
+        encrypt(&key, &nonce, &mut data);
+        assert_eq!(data.len(), 14 + TAG_SIZE);
+        assert!(!data.starts_with(b"level"));
+
+        let mut plain = data.clone();
+        decrypt(&key, &nonce, &mut plain).unwrap();
+        assert_eq!(plain, b"level geometry");
+
+        let mut wrong_key = data.clone();
+        assert!(matches!(
+            decrypt(&[0; KEY_SIZE], &nonce, &mut wrong_key),
+            Err(PakError::DecryptionFailed(_))
+        ));
+        let mut tampered = data.clone();
+        tampered[0] ^= 1;
+        assert!(decrypt(&key, &nonce, &mut tampered).is_err());
+        assert!(decrypt(&key, &nonce, &mut vec![0; TAG_SIZE - 1]).is_err());
+    }
+}
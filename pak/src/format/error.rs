@@ -29,11 +29,82 @@ pub enum PakError {
     #[error("Decompression failed: {0}")]
     DecompressionFailed(String),
     
+    /// An asset with this name already exists
+    #[error("Asset name already in use: {0}")]
+    NameConflict(String),
+    
+    /// Archive version outside the range accepted by the caller
+    #[error("PAK version {version} outside accepted range {min}..={max}")]
+    VersionOutOfRange { version: u32, min: u32, max: u32 },
+    
+    /// Caller requires checksums but the archive has none
+    #[error("Archive does not carry checksums")]
+    ChecksumsRequired,
+    
+    /// Asset data does not match the checksum recorded in its TOC entry
+    #[error("Checksum mismatch for asset '{0}'")]
+    ChecksumMismatch(String),
+    
+    /// Asset stored with a codec the caller does not allow
+    #[error("Asset '{asset}' uses disallowed codec {codec:?}")]
+    CodecNotAllowed { asset: String, codec: crate::format::Codec },
+    
+    /// Encrypted asset whose key the `KeyProvider` did not supply
+    #[error("No key {key_id} available for encrypted asset '{asset}'")]
+    KeyUnavailable { asset: String, key_id: u32 },
+    
+    /// Encrypted asset whose data could not be authenticated, e.g. because
+    /// the key is wrong or the data was altered
+    #[error("Decryption failed: {0}")]
+    DecryptionFailed(String),
+    
+    /// Zero-copy access requested for an encrypted asset
+    #[error("Asset '{0}' is encrypted and cannot be borrowed")]
+    AssetEncrypted(String),
+    
+    /// Asset has no MTF schema, or its schema could not be read
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+    
     /// IO error wrapper
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 }
 
+/// `io::Error` is not `Clone`, so IO errors are cloned as their kind and message
+impl Clone for PakError {
+    fn clone(&self) -> Self {
+        match self {
+            Self::InvalidMagic => Self::InvalidMagic,
+            Self::UnsupportedVersion(v) => Self::UnsupportedVersion(*v),
+            Self::AssetNotFound(name) => Self::AssetNotFound(name.clone()),
+            Self::InvalidToc(msg) => Self::InvalidToc(msg.clone()),
+            Self::CompressionFailed(msg) => Self::CompressionFailed(msg.clone()),
+            Self::DecompressionFailed(msg) => Self::DecompressionFailed(msg.clone()),
+            Self::NameConflict(name) => Self::NameConflict(name.clone()),
+            Self::VersionOutOfRange { version, min, max } => Self::VersionOutOfRange {
+                version: *version,
+                min: *min,
+                max: *max,
+            },
+            Self::ChecksumsRequired => Self::ChecksumsRequired,
+            Self::ChecksumMismatch(name) => Self::ChecksumMismatch(name.clone()),
+            Self::CodecNotAllowed { asset, codec } => Self::CodecNotAllowed {
+                asset: asset.clone(),
+                codec: *codec,
+            },
+            Self::KeyUnavailable { asset, key_id } => Self::KeyUnavailable {
+                asset: asset.clone(),
+                key_id: *key_id,
+            },
+            Self::DecryptionFailed(msg) => Self::DecryptionFailed(msg.clone()),
+            Self::AssetEncrypted(name) => Self::AssetEncrypted(name.clone()),
+            Self::InvalidSchema(msg) => Self::InvalidSchema(msg.clone()),
+            Self::Io(e) => Self::Io(io::Error::new(e.kind(), e.to_string())),
+        }
+    }
+}
+
 /// Convenience result type
 pub type Result<T> = std::result::Result<T, PakError>;
 
@@ -60,4 +131,13 @@ mod tests {
         
         assert!(matches!(pak_err, PakError::Io(_)));
     }
+
+    #[test]
+    fn test_clone_io_error() {
+        let err = PakError::from(io::Error::new(io::ErrorKind::UnexpectedEof, "short read"));
+        let copy = err.clone();
+
+        assert!(matches!(&copy, PakError::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof));
+        assert_eq!(copy.to_string(), err.to_string());
+    }
 }
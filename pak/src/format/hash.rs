@@ -1,7 +1,12 @@
 
 pub fn hash_name(name: &str) -> u64 {
+    hash_bytes(name.as_bytes())
+}
+
+/// FNV-1a hash of `bytes`, also used as the per-entry data checksum
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
     let mut hash = 0xcbf29ce484222325u64;
-    for byte in name.as_bytes() {
+    for byte in bytes {
         hash ^= *byte as u64;
         hash = hash.wrapping_mul(0x100000001b3);
     }
@@ -1,7 +1,6 @@
 
-use bytemuck::{Pod, Zeroable};
 use bytemuck_derive::{Pod, Zeroable};
-use crate::format::constants::{PAK_MAGIC, PAK_VERSION, HEADER_SIZE};
+use crate::format::constants::{PAK_MAGIC, PAK_VERSION, PAK_VERSION_NO_CHECKSUMS, HEADER_SIZE, HEADER_FLAG_CHECKSUMS};
 use crate::format::error::{PakError, Result};
 
 #[repr(C, packed)]
@@ -23,15 +22,20 @@ impl PakHeader {
             toc_offset,
             data_offset,
             entry_count,
-            flags: 0,
+            flags: HEADER_FLAG_CHECKSUMS,
         }
     }
     
+    /// Whether the TOC entries carry checksums of their stored data
+    pub fn has_checksums(&self) -> bool {
+        self.version > PAK_VERSION_NO_CHECKSUMS && self.flags & HEADER_FLAG_CHECKSUMS != 0
+    }
+    
     pub fn validate(&self) -> Result<()> {
         if &self.magic != PAK_MAGIC {
             return Err(PakError::InvalidMagic);
         }
-        if self.version != PAK_VERSION {
+        if self.version == 0 || self.version > PAK_VERSION {
             return Err(PakError::UnsupportedVersion(self.version));
         }
         Ok(())
@@ -3,10 +3,16 @@ pub mod constants;
 pub mod header;
 pub mod toc;
 pub mod hash;
+pub mod strings;
+pub mod crypto;
+pub mod schemas;
 
 // Re-exports
 pub use error::{PakError, Result};
 pub use constants::*;
 pub use header::PakHeader;
-pub use toc::{TocEntry, AssetType};
-pub use hash::hash_name;
+pub use toc::{TocEntry, AssetType, Codec};
+pub use hash::{hash_name, hash_bytes};
+pub use strings::StringContainer;
+pub use crypto::KeyProvider;
+pub use schemas::SchemaRegistry;
@@ -0,0 +1,165 @@
+//! schemas.rs - Archive-wide registry of MTF schemas for typed assets
+
+use crate::format::{PakError, Result};
+
+/// Magic bytes at the start of an encoded registry
+pub const SCHEMA_REGISTRY_MAGIC: &[u8; 4] = b"MTFS";
+
+/// MTF schema blobs and the assets typed by them
+///
+/// Stored in the archive as the reserved entry
+/// [`SCHEMA_REGISTRY_NAME`](crate::format::SCHEMA_REGISTRY_NAME). Each distinct
+/// blob is kept once, however many assets share it.
+///
+/// Layout (little-endian):
+/// `[MAGIC][SCHEMA_COUNT: u32][BINDING_COUNT: u32]`, then per schema
+/// `[LEN: u32][MTF BLOB]`, then per binding `[SCHEMA_INDEX: u32][NAME_LEN: u32][NAME]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaRegistry {
+    blobs: Vec<Vec<u8>>,
+    bindings: Vec<(String, u32)>,
+}
+
+impl SchemaRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Type the asset `name` with the MTF `blob`; identical blobs are stored once
+    ///
+    /// A later call for the same asset replaces its schema.
+    pub fn insert(&mut self, name: &str, blob: &[u8]) {
+        let index = match self.blobs.iter().position(|b| b == blob) {
+            Some(index) => index,
+            None => {
+                self.blobs.push(blob.to_vec());
+                self.blobs.len() - 1
+            }
+        } as u32;
+
+        match self.bindings.iter_mut().find(|(n, _)| n == name) {
+            Some(binding) => binding.1 = index,
+            None => self.bindings.push((name.to_string(), index)),
+        }
+    }
+
+    /// Returns true if no asset is typed
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Distinct schema blobs, in insertion order
+    pub fn blobs(&self) -> &[Vec<u8>] {
+        &self.blobs
+    }
+
+    /// Asset names with the index of their blob in [`blobs`](Self::blobs)
+    pub fn bindings(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.bindings.iter().map(|(name, index)| (name.as_str(), *index as usize))
+    }
+
+    /// Schema blob of the asset `name`
+    pub fn schema_for(&self, name: &str) -> Option<&[u8]> {
+        self.bindings
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, index)| self.blobs[*index as usize].as_slice())
+    }
+
+    /// Encode the registry as stored in the archive
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(SCHEMA_REGISTRY_MAGIC);
+        out.extend_from_slice(&(self.blobs.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(self.bindings.len() as u32).to_le_bytes());
+        for blob in &self.blobs {
+            out.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+            out.extend_from_slice(blob);
+        }
+        for (name, index) in &self.bindings {
+            out.extend_from_slice(&index.to_le_bytes());
+            out.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+        }
+        out
+    }
+
+    /// Decode a registry read from an archive
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor { bytes, pos: 0 };
+        if cursor.take(4)? != SCHEMA_REGISTRY_MAGIC {
+            return Err(invalid("bad magic"));
+        }
+        let schema_count = cursor.u32()?;
+        let binding_count = cursor.u32()?;
+
+        let mut blobs = Vec::new();
+        for _ in 0..schema_count {
+            let len = cursor.u32()? as usize;
+            blobs.push(cursor.take(len)?.to_vec());
+        }
+
+        let mut bindings = Vec::new();
+        for _ in 0..binding_count {
+            let index = cursor.u32()?;
+            if index as usize >= blobs.len() {
+                return Err(invalid("schema index out of range"));
+            }
+            let len = cursor.u32()? as usize;
+            let name = std::str::from_utf8(cursor.take(len)?)
+                .map_err(|_| invalid("asset name is not UTF-8"))?;
+            bindings.push((name.to_string(), index));
+        }
+
+        Ok(Self { blobs, bindings })
+    }
+}
+
+fn invalid(reason: &str) -> PakError {
+    PakError::InvalidSchema(format!("schema registry: {}", reason))
+}
+
+/// Bounds-checked reads over an encoded registry
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| invalid("truncated"))?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_round_trip() {
+        let mut registry = SchemaRegistry::new();
+        registry.insert("a.bin", b"blob-a");
+        registry.insert("b.bin", b"blob-b");
+        registry.insert("c.bin", b"blob-a");
+        registry.insert("b.bin", b"blob-a");
+
+        assert_eq!(registry.blobs().len(), 2);
+        assert_eq!(registry.schema_for("b.bin"), Some(&b"blob-a"[..]));
+        assert_eq!(registry.schema_for("d.bin"), None);
+
+        let bytes = registry.to_bytes();
+        assert_eq!(SchemaRegistry::from_bytes(&bytes).unwrap(), registry);
+        assert!(SchemaRegistry::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SchemaRegistry::from_bytes(b"NOPE").is_err());
+    }
+}
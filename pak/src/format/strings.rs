@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+/// Deduplicated, NUL-terminated name storage addressed by byte offset
+///
+/// TOC entries refer to their names by `(offset, len)` into this table, so
+/// entries can be reordered, removed or renamed without the implicit
+/// "n-th string belongs to the n-th entry" coupling.
+#[derive(Debug, Clone, Default)]
+pub struct StringContainer {
+    bytes: Vec<u8>,
+    offsets: HashMap<String, u32>,
+}
+
+impl StringContainer {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Wrap existing table bytes (as read from a PAK file)
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes, offsets: HashMap::new() }
+    }
+    
+    /// Add `s`, returning its `(offset, len)`; identical strings are stored once
+    pub fn insert(&mut self, s: &str) -> (u32, u32) {
+        let len = s.len() as u32;
+        if let Some(&offset) = self.offsets.get(s) {
+            return (offset, len);
+        }
+        let offset = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(s.as_bytes());
+        self.bytes.push(0); // null terminator
+        self.offsets.insert(s.to_string(), offset);
+        (offset, len)
+    }
+    
+    /// Get the string stored at `offset` with length `len`
+    pub fn get(&self, offset: u32, len: u32) -> Option<&str> {
+        let start = offset as usize;
+        let bytes = self.bytes.get(start..start.checked_add(len as usize)?)?;
+        std::str::from_utf8(bytes).ok()
+    }
+    
+    /// Iterate over all NUL-terminated strings in table order
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.bytes
+            .split(|&b| b == 0)
+            .take(self.bytes.iter().filter(|&&b| b == 0).count())
+            .filter_map(|s| std::str::from_utf8(s).ok())
+    }
+    
+    /// Raw table bytes, as written to disk
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    
+    /// Size of the table in bytes
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+    
+    /// Returns true if the table holds no strings
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut strings = StringContainer::new();
+        let a = strings.insert("a.txt");
+        let b = strings.insert("dir/b.png");
+        assert_eq!(strings.insert("a.txt"), a);
+        
+        assert_eq!(strings.get(a.0, a.1), Some("a.txt"));
+        assert_eq!(strings.get(b.0, b.1), Some("dir/b.png"));
+        assert_eq!(strings.get(1000, 1), None);
+        assert_eq!(strings.iter().collect::<Vec<_>>(), vec!["a.txt", "dir/b.png"]);
+        
+        let reread = StringContainer::from_bytes(strings.as_bytes().to_vec());
+        assert_eq!(reread.get(b.0, b.1), Some("dir/b.png"));
+    }
+}
@@ -1,9 +1,12 @@
 
-use bytemuck::{Pod, Zeroable};
 use bytemuck_derive::{Pod, Zeroable};
-use crate::format::constants::{FLAG_COMPRESSED, TOC_ENTRY_SIZE};
+use crate::format::constants::{
+    FLAG_COMPRESSED, FLAG_ENCRYPTED, TOC_ENTRY_SIZE, TOC_ENTRY_SIZE_V2, TOC_ENTRY_SIZE_V3,
+    PAK_VERSION_SHORT_TOC, PAK_VERSION_NO_CHECKSUMS,
+};
+use crate::format::crypto::{NONCE_SIZE, TAG_SIZE};
 use crate::format::error::{PakError, Result};
-use crate::format::hash::hash_name;
+use crate::format::hash::{hash_name, hash_bytes};
 
 #[repr(C, packed)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
@@ -14,6 +17,16 @@ pub struct TocEntry {
     pub compressed_size: u64,
     pub flags: u32,
     pub type_tag: u32,
+    /// Byte offset of the name in the string table (version 2+)
+    pub name_offset: u32,
+    /// Length of the name in bytes, without terminator (version 2+)
+    pub name_len: u32,
+    /// Key id passed to the `KeyProvider` for encrypted entries (version 3+)
+    pub key_id: u32,
+    /// Nonce the entry data was encrypted with (version 3+)
+    pub nonce: [u8; NONCE_SIZE],
+    /// [`hash_bytes`] of the stored (compressed and encrypted) data (version 4+)
+    pub checksum: u64,
 }
 
 impl TocEntry {
@@ -25,6 +38,11 @@ impl TocEntry {
             compressed_size: 0,
             flags: 0,
             type_tag: asset_type as u32,
+            name_offset: 0,
+            name_len: 0,
+            key_id: 0,
+            nonce: [0; NONCE_SIZE],
+            checksum: 0,
         }
     }
     
@@ -42,6 +60,11 @@ impl TocEntry {
             compressed_size,
             flags: FLAG_COMPRESSED,
             type_tag: asset_type as u32,
+            name_offset: 0,
+            name_len: 0,
+            key_id: 0,
+            nonce: [0; NONCE_SIZE],
+            checksum: 0,
         }
     }
     
@@ -49,6 +72,54 @@ impl TocEntry {
         self.flags & FLAG_COMPRESSED != 0
     }
     
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & FLAG_ENCRYPTED != 0
+    }
+    
+    /// Number of bytes the entry occupies in the data region
+    ///
+    /// The compressed size for compressed entries, plus the authentication tag
+    /// for encrypted ones.
+    pub fn stored_size(&self) -> u64 {
+        let size = if self.is_compressed() { self.compressed_size } else { self.size };
+        if self.is_encrypted() { size + TAG_SIZE as u64 } else { size }
+    }
+    
+    /// Mark this entry as encrypted with `key_id` and `nonce`
+    pub fn with_encryption(mut self, key_id: u32, nonce: [u8; NONCE_SIZE]) -> Self {
+        self.flags |= FLAG_ENCRYPTED;
+        self.key_id = key_id;
+        self.nonce = nonce;
+        self
+    }
+    
+    /// Record the checksum of the entry's stored bytes
+    pub fn with_checksum(mut self, stored: &[u8]) -> Self {
+        self.checksum = hash_bytes(stored);
+        self
+    }
+    
+    /// Whether `stored` matches the recorded checksum
+    pub fn verify_checksum(&self, stored: &[u8]) -> bool {
+        hash_bytes(stored) == self.checksum
+    }
+    
+    /// Codec used to store this entry's data
+    pub fn codec(&self) -> Codec {
+        if self.is_compressed() {
+            Codec::Zstd
+        } else {
+            Codec::None
+        }
+    }
+    
+    /// Point this entry at its name in the string table
+    pub fn with_name_ref(mut self, (offset, len): (u32, u32)) -> Self {
+        self.name_offset = offset;
+        self.name_len = len;
+        self
+    }
+    
     pub fn as_bytes(&self) -> &[u8] {
         bytemuck::bytes_of(self)
     }
@@ -59,6 +130,40 @@ impl TocEntry {
         }
         Ok(*bytemuck::from_bytes(&bytes[..TOC_ENTRY_SIZE]))
     }
+    
+    /// Size of a TOC entry in an archive of the given format version
+    pub fn size_for_version(version: u32) -> usize {
+        if version <= PAK_VERSION_SHORT_TOC {
+            TOC_ENTRY_SIZE_V2
+        } else if version <= PAK_VERSION_NO_CHECKSUMS {
+            TOC_ENTRY_SIZE_V3
+        } else {
+            TOC_ENTRY_SIZE
+        }
+    }
+    
+    /// Parse an entry written by the given format version
+    ///
+    /// Older, shorter entries are zero-extended, i.e. read as unencrypted and
+    /// without a checksum.
+    pub fn from_versioned_bytes(bytes: &[u8], version: u32) -> Result<Self> {
+        let size = Self::size_for_version(version);
+        if bytes.len() < size {
+            return Err(PakError::InvalidToc("TOC entry too small".to_string()));
+        }
+        let mut full = [0u8; TOC_ENTRY_SIZE];
+        full[..size].copy_from_slice(&bytes[..size]);
+        Ok(bytemuck::pod_read_unaligned(&full))
+    }
+}
+
+/// Storage codec of an asset's data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Codec {
+    /// Stored uncompressed
+    None,
+    /// Zstd-compressed
+    Zstd,
 }
 
 #[repr(u32)]
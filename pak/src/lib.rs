@@ -6,20 +6,29 @@ pub mod format;
 mod builder;
 mod reader;
 mod asset;
+mod editor;
+mod manifest;
+mod streaming;
 
 // Re-export format types
 pub use format::{
     error::{PakError, Result},
     constants::*,
     header::PakHeader,
-    toc::{TocEntry, AssetType},
+    toc::{TocEntry, AssetType, Codec},
     hash::hash_name,
+    strings::StringContainer,
+    crypto::KeyProvider,
+    schemas::SchemaRegistry,
 };
 
 // Re-export builders/readers
 pub use builder::PakBuilder;
-pub use reader::PakReader;
+pub use reader::{PakReader, OpenOptions, AccessEvent, AccessObserver};
 pub use asset::AssetEntry;
+pub use editor::PakEditor;
+pub use mtf_api::{DynamicContainer, Schema};
+pub use streaming::StreamingScheduler;
 
 #[cfg(test)]
 mod tests {
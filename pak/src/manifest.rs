@@ -0,0 +1,106 @@
+//! manifest.rs - Machine-readable listing of a built PAK
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::asset::AssetEntry;
+use crate::format::{Result, TocEntry, AssetType, PAK_VERSION};
+
+/// Render a JSON manifest describing every asset in a built archive
+///
+/// Entries are listed in TOC order with the fields external tools need to
+/// index an archive without parsing the binary TOC.
+pub(crate) fn render_json<'a>(
+    assets: impl IntoIterator<Item = &'a AssetEntry>,
+    toc: &[TocEntry],
+) -> String {
+    let mut out = String::new();
+    out.push_str("{\n");
+    let _ = writeln!(out, "  \"version\": {},", PAK_VERSION);
+    let _ = writeln!(out, "  \"entry_count\": {},", toc.len());
+    out.push_str("  \"assets\": [");
+    
+    for (i, (asset, entry)) in assets.into_iter().zip(toc).enumerate() {
+        // Copy fields out of the packed struct before formatting
+        let (name_hash, offset, size) = (entry.name_hash, entry.offset, entry.size);
+        let stored_size = entry.stored_size();
+        let codec = match entry.codec() {
+            crate::format::Codec::None => "none",
+            crate::format::Codec::Zstd => "zstd",
+        };
+        
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        let _ = write!(
+            out,
+            "    {{\"name\": \"{}\", \"name_hash\": \"{:016x}\", \"type\": \"{:?}\", \
+             \"codec\": \"{}\", \"offset\": {}, \"size\": {}, \"stored_size\": {}}}",
+            escape_json(&asset.name),
+            name_hash,
+            AssetType::from(entry.type_tag),
+            codec,
+            offset,
+            size,
+            stored_size,
+        );
+    }
+    
+    if !toc.is_empty() {
+        out.push_str("\n  ");
+    }
+    out.push_str("]\n}\n");
+    out
+}
+
+/// Write the JSON manifest to `path`
+pub(crate) fn write_json<'a>(
+    path: &Path,
+    assets: impl IntoIterator<Item = &'a AssetEntry>,
+    toc: &[TocEntry],
+) -> Result<()> {
+    std::fs::write(path, render_json(assets, toc))?;
+    Ok(())
+}
+
+fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_json() {
+        let assets = vec![
+            AssetEntry::new("a\"b.txt", vec![1, 2, 3], AssetType::Data),
+            AssetEntry::new("tex.png", vec![0; 10], AssetType::Texture),
+        ];
+        let toc = vec![
+            TocEntry::new("a\"b.txt", 32, 3, AssetType::Data),
+            TocEntry::new("tex.png", 35, 10, AssetType::Texture),
+        ];
+        
+        let json = render_json(&assets, &toc);
+        assert!(json.contains("\"entry_count\": 2"));
+        assert!(json.contains("\"name\": \"a\\\"b.txt\""));
+        assert!(json.contains("\"type\": \"Texture\", \"codec\": \"none\", \"offset\": 35"));
+        assert_eq!(render_json(&[], &[]), format!(
+            "{{\n  \"version\": {},\n  \"entry_count\": 0,\n  \"assets\": []\n}}\n",
+            PAK_VERSION
+        ));
+    }
+}
@@ -38,31 +38,88 @@
 
 use std::path::Path;
 use std::collections::HashMap;
-use bytemuck_derive::{Pod, Zeroable};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use mtf_api::{DynamicContainer, Schema};
 use raw_bytes_container::RawBytesContainer;
 use crate::format::{
     PakError, Result,
-    PakHeader, TocEntry,
-    HEADER_SIZE, TOC_ENTRY_SIZE,
+    PakHeader, TocEntry, Codec, StringContainer, KeyProvider, SchemaRegistry,
+    HEADER_SIZE, SCHEMA_REGISTRY_NAME,
+    PAK_MAGIC, PAK_VERSION, PAK_VERSION_IMPLICIT_NAMES,
 };
 
+/// Policy checks applied by [`PakReader::open_with_options`]
+#[derive(Debug, Clone)]
+pub struct OpenOptions {
+    /// Lowest accepted format version
+    pub min_version: u32,
+    /// Highest accepted format version (capped at the newest version this reader understands)
+    pub max_version: u32,
+    /// Refuse archives whose assets are not covered by checksums
+    pub require_checksums: bool,
+    /// Codecs assets may be stored with
+    pub allowed_codecs: Vec<Codec>,
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self {
+            min_version: 1,
+            max_version: PAK_VERSION,
+            require_checksums: false,
+            allowed_codecs: vec![Codec::None, Codec::Zstd],
+        }
+    }
+}
+
+/// A single asset read, as reported to an access observer
+#[derive(Debug, Clone)]
+pub struct AccessEvent<'a> {
+    /// Name the asset was requested by
+    pub name: &'a str,
+    /// Offset of the stored data in the archive
+    pub offset: u64,
+    /// Bytes read from the archive
+    pub stored_size: u64,
+    /// Bytes returned to the caller (after decompression)
+    pub size: u64,
+    /// Time spent reading and decompressing
+    pub decompress_time: Duration,
+}
+
+/// Callback invoked for every asset read
+pub type AccessObserver = Arc<dyn Fn(&AccessEvent<'_>) + Send + Sync>;
+
 /// Reader for PAK files (memory-mapped for zero-copy access)
 pub struct PakReader {
     data: RawBytesContainer<u8>,
     header: PakHeader,
     toc: Vec<TocEntry>,
-    string_table: Vec<u8>,
+    string_table: StringContainer,
     name_map: HashMap<String, usize>, // name -> toc index
+    entry_names: Vec<String>, // toc index -> name
+    observer: Option<AccessObserver>,
+    key_provider: Option<Arc<dyn KeyProvider>>,
+    schemas: Vec<Schema>,
+    schema_of: HashMap<String, usize>, // asset name -> schemas index
 }
 
 impl PakReader {
     /// Open a PAK file for reading (memory-mapped)
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_options(path, &OpenOptions::default())
+    }
+    
+    /// Open a PAK file, enforcing the version, checksum and codec policy in `options`
+    ///
+    /// Each violated policy is reported with its own error variant so hosts can
+    /// tell "too old" apart from "not verified" or "uses a forbidden codec".
+    pub fn open_with_options(path: impl AsRef<Path>, options: &OpenOptions) -> Result<Self> {
         // Memory-map the file
         let data = RawBytesContainer::open_mmap_read(path)
-            .map_err(|e| PakError::Io(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            .map_err(|e| PakError::Io(std::io::Error::other(
                 format!("Failed to mmap PAK file: {}", e)
             )))?;
         
@@ -73,11 +130,29 @@ impl PakReader {
             return Err(PakError::InvalidToc("File too small".to_string()));
         }
         
-        let header = PakHeader::from_bytes(&slice[..HEADER_SIZE])?;
+        let header: PakHeader = bytemuck::pod_read_unaligned(&slice[..HEADER_SIZE]);
+        if &header.magic != PAK_MAGIC {
+            return Err(PakError::InvalidMagic);
+        }
+        
+        let version = header.version;
+        let max_version = options.max_version.min(PAK_VERSION);
+        if version < options.min_version || version > max_version {
+            return Err(PakError::VersionOutOfRange {
+                version,
+                min: options.min_version,
+                max: max_version,
+            });
+        }
+        
+        if options.require_checksums && !header.has_checksums() {
+            return Err(PakError::ChecksumsRequired);
+        }
         
         // Read TOC
         let toc_start = header.toc_offset as usize;
-        let toc_size = header.entry_count as usize * TOC_ENTRY_SIZE;
+        let entry_size = TocEntry::size_for_version(version);
+        let toc_size = header.entry_count as usize * entry_size;
         let toc_end = toc_start + toc_size;
         
         if toc_end > slice.len() {
@@ -86,39 +161,101 @@ impl PakReader {
         
         let mut toc = Vec::with_capacity(header.entry_count as usize);
         for i in 0..header.entry_count as usize {
-            let entry_start = toc_start + i * TOC_ENTRY_SIZE;
-            let entry_bytes = &slice[entry_start..entry_start + TOC_ENTRY_SIZE];
-            toc.push(TocEntry::from_bytes(entry_bytes)?);
+            let entry_start = toc_start + i * entry_size;
+            let entry_bytes = &slice[entry_start..entry_start + entry_size];
+            toc.push(TocEntry::from_versioned_bytes(entry_bytes, version)?);
         }
         
         // Read string table
         let string_start = toc_end;
-        let string_table = slice[string_start..].to_vec();
-        
-        // Build name map
-        let mut name_map = HashMap::new();
-        let mut pos = 0;
-        let mut entry_idx = 0;
-        
-        while pos < string_table.len() && entry_idx < toc.len() {
-            if let Some(end) = string_table[pos..].iter().position(|&b| b == 0) {
-                if let Ok(name) = std::str::from_utf8(&string_table[pos..pos + end]) {
-                    name_map.insert(name.to_string(), entry_idx);
-                    entry_idx += 1;
-                }
-                pos += end + 1;
-            } else {
-                break;
+        let string_table = StringContainer::from_bytes(slice[string_start..].to_vec());
+        
+        // Resolve entry names
+        let mut entry_names = vec![String::new(); toc.len()];
+        if version <= PAK_VERSION_IMPLICIT_NAMES {
+            // Version 1: the n-th string names the n-th entry
+            for (name, slot) in string_table.iter().zip(&mut entry_names) {
+                *slot = name.to_string();
+            }
+        } else {
+            for (entry_idx, entry) in toc.iter().enumerate() {
+                let name = string_table.get(entry.name_offset, entry.name_len)
+                    .ok_or_else(|| PakError::InvalidToc(format!(
+                        "Invalid name reference in entry {}", entry_idx
+                    )))?;
+                entry_names[entry_idx] = name.to_string();
             }
         }
         
-        Ok(Self {
+        // Check every entry, including ones shadowed by a later duplicate name
+        for (entry, name) in toc.iter().zip(&entry_names) {
+            let codec = entry.codec();
+            if !options.allowed_codecs.contains(&codec) {
+                return Err(PakError::CodecNotAllowed { asset: name.clone(), codec });
+            }
+        }
+        
+        // Later entries win for duplicate names
+        let name_map: HashMap<String, usize> = entry_names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.clone(), idx))
+            .collect();
+        
+        let mut reader = Self {
             data,
             header,
             toc,
             string_table,
             name_map,
-        })
+            entry_names,
+            observer: None,
+            key_provider: None,
+            schemas: Vec::new(),
+            schema_of: HashMap::new(),
+        };
+        reader.load_schemas()?;
+        Ok(reader)
+    }
+    
+    /// Parse every schema in the archive's schema registry, if it has one
+    fn load_schemas(&mut self) -> Result<()> {
+        let Some(&idx) = self.name_map.get(SCHEMA_REGISTRY_NAME) else {
+            return Ok(());
+        };
+        let registry = SchemaRegistry::from_bytes(&self.decode_entry(idx)?)?;
+        
+        self.schemas = registry.blobs()
+            .iter()
+            .map(|blob| Schema::parse(blob)
+                .map_err(|e| PakError::InvalidSchema(format!("schema registry: {}", e))))
+            .collect::<Result<_>>()?;
+        self.schema_of = registry.bindings()
+            .map(|(name, index)| (name.to_string(), index))
+            .collect();
+        Ok(())
+    }
+    
+    /// Schema of a typed asset, from the archive's schema registry
+    pub fn schema(&self, name: &str) -> Option<&Schema> {
+        self.schema_of.get(name).map(|&index| &self.schemas[index])
+    }
+    
+    /// Read a typed asset for field access by name
+    ///
+    /// Assets added with [`PakBuilder::add_typed`] are typed by the archive's
+    /// schema registry. Other assets must carry their own trailing MTF blob,
+    /// laid out as `DynamicContainer::from_file` expects.
+    ///
+    /// [`PakBuilder::add_typed`]: crate::PakBuilder::add_typed
+    pub fn get_asset_dynamic(&self, name: &str) -> Result<DynamicContainer> {
+        let data = self.get_asset(name)?;
+        match self.schema(name) {
+            Some(schema) => Ok(DynamicContainer::from_schema(data, schema.clone())),
+            None => DynamicContainer::from_embedded(data).map_err(|e| PakError::InvalidSchema(
+                format!("asset '{}' has no registered or embedded schema: {}", name, e)
+            )),
+        }
     }
     
     /// Get an asset by name
@@ -126,22 +263,118 @@ impl PakReader {
         let idx = self.name_map.get(name)
             .ok_or_else(|| PakError::AssetNotFound(name.to_string()))?;
         
-        let entry = &self.toc[*idx];
+        self.read_entry(*idx)
+    }
+    
+    /// Look up the TOC index and entry for an asset name
+    pub(crate) fn lookup(&self, name: &str) -> Option<(usize, &TocEntry)> {
+        let idx = *self.name_map.get(name)?;
+        Some((idx, &self.toc[idx]))
+    }
+    
+    /// All TOC entries with their names, in TOC order
+    pub(crate) fn named_entries(&self) -> Vec<(String, TocEntry)> {
+        let mut names = vec![None; self.toc.len()];
+        for (name, &idx) in &self.name_map {
+            names[idx] = Some(name.clone());
+        }
+        names
+            .into_iter()
+            .zip(&self.toc)
+            .filter_map(|(name, entry)| Some((name?, *entry)))
+            .collect()
+    }
+    
+    /// Raw bytes of the whole archive
+    pub(crate) fn raw_bytes(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+    
+    /// Read (and decompress if needed) the asset at TOC index `idx`
+    pub(crate) fn read_entry(&self, idx: usize) -> Result<Vec<u8>> {
+        let Some(observer) = &self.observer else {
+            return self.decode_entry(idx);
+        };
+        
+        let started = Instant::now();
+        let result = self.decode_entry(idx);
+        if let Ok(data) = &result {
+            let entry = &self.toc[idx];
+            observer(&AccessEvent {
+                name: &self.entry_names[idx],
+                offset: entry.offset,
+                stored_size: entry.stored_size(),
+                size: data.len() as u64,
+                decompress_time: started.elapsed(),
+            });
+        }
+        result
+    }
+    
+    /// Report every successful asset read to `observer`
+    ///
+    /// Events carry the asset name, sizes and read/decompression time, so real
+    /// access traces can be captured and fed to [`PakBuilder::reorder_by_trace`].
+    /// Zero-copy reads through [`get_asset_slice`](Self::get_asset_slice) are
+    /// reported with the time it took to locate the data.
+    ///
+    /// [`PakBuilder::reorder_by_trace`]: crate::PakBuilder::reorder_by_trace
+    pub fn set_access_observer(&mut self, observer: impl Fn(&AccessEvent<'_>) + Send + Sync + 'static) {
+        self.observer = Some(Arc::new(observer));
+    }
+    
+    /// Remove the access observer
+    pub fn clear_access_observer(&mut self) {
+        self.observer = None;
+    }
+    
+    /// Supply keys for encrypted entries
+    ///
+    /// Encrypted assets fail with [`PakError::KeyUnavailable`] until a provider
+    /// returns their key.
+    pub fn set_key_provider(&mut self, provider: impl KeyProvider + 'static) {
+        self.key_provider = Some(Arc::new(provider));
+    }
+    
+    /// Remove the key provider, locking encrypted entries again
+    pub fn clear_key_provider(&mut self) {
+        self.key_provider = None;
+    }
+    
+    /// Whether the named asset is encrypted
+    pub fn is_encrypted(&self, name: &str) -> Option<bool> {
+        self.lookup(name).map(|(_, entry)| entry.is_encrypted())
+    }
+    
+    fn decode_entry(&self, idx: usize) -> Result<Vec<u8>> {
+        let entry = &self.toc[idx];
         let slice = self.data.as_slice();
         
         let start = entry.offset as usize;
-        let size = if entry.is_compressed() {
-            entry.compressed_size as usize
-        } else {
-            entry.size as usize
-        };
+        let size = entry.stored_size() as usize;
         
         let end = start + size;
         if end > slice.len() {
             return Err(PakError::InvalidToc("Asset data extends beyond file".to_string()));
         }
         
-        let data = &slice[start..end];
+        let mut data = &slice[start..end];
+        self.verify_checksum(idx, data)?;
+        
+        // Decrypt if needed
+        let decrypted;
+        if entry.is_encrypted() {
+            let name = &self.entry_names[idx];
+            let key_id = entry.key_id;
+            let key = self.key_provider
+                .as_ref()
+                .and_then(|provider| provider.key(key_id, name))
+                .ok_or_else(|| PakError::KeyUnavailable { asset: name.clone(), key_id })?;
+            let mut buf = data.to_vec();
+            crate::format::crypto::decrypt(&key, &entry.nonce, &mut buf)?;
+            decrypted = buf;
+            data = &decrypted;
+        }
         
         // Decompress if needed
         if entry.is_compressed() {
@@ -161,14 +394,27 @@ impl PakReader {
         }
     }
     
+    /// Compare an entry's stored bytes against its checksum, if the archive has them
+    fn verify_checksum(&self, idx: usize, stored: &[u8]) -> Result<()> {
+        if self.header.has_checksums() && !self.toc[idx].verify_checksum(stored) {
+            return Err(PakError::ChecksumMismatch(self.entry_names[idx].clone()));
+        }
+        Ok(())
+    }
+    
     /// Get a zero-copy slice to an uncompressed asset
-    /// Returns None if asset is compressed
+    /// Returns None if asset is compressed, and `AssetEncrypted` if it is encrypted
     pub fn get_asset_slice(&self, name: &str) -> Result<Option<&[u8]>> {
+        let started = Instant::now();
         let idx = self.name_map.get(name)
             .ok_or_else(|| PakError::AssetNotFound(name.to_string()))?;
         
         let entry = &self.toc[*idx];
         
+        // Encrypted entries are usually compressed too; report the encryption
+        if entry.is_encrypted() {
+            return Err(PakError::AssetEncrypted(name.to_string()));
+        }
         if entry.is_compressed() {
             return Ok(None);
         }
@@ -180,13 +426,45 @@ impl PakReader {
         if end > slice.len() {
             return Err(PakError::InvalidToc("Asset data extends beyond file".to_string()));
         }
+        self.verify_checksum(*idx, &slice[start..end])?;
+        
+        if let Some(observer) = &self.observer {
+            observer(&AccessEvent {
+                name,
+                offset: start as u64,
+                stored_size: (end - start) as u64,
+                size: (end - start) as u64,
+                decompress_time: started.elapsed(),
+            });
+        }
         
         Ok(Some(&slice[start..end]))
     }
     
-    /// List all asset names
+    /// List all asset names, in TOC order
+    ///
+    /// Same as [`list_assets_toc_order`](Self::list_assets_toc_order); the order
+    /// is deterministic and stable across runs.
     pub fn list_assets(&self) -> Vec<String> {
-        self.name_map.keys().cloned().collect()
+        self.list_assets_toc_order()
+    }
+    
+    /// List all asset names in TOC order (the order they were added to the builder)
+    ///
+    /// The internal schema registry entry is not listed.
+    pub fn list_assets_toc_order(&self) -> Vec<String> {
+        self.entry_names
+            .iter()
+            .filter(|name| !name.is_empty() && name.as_str() != SCHEMA_REGISTRY_NAME)
+            .cloned()
+            .collect()
+    }
+    
+    /// List all asset names sorted by name (byte-wise)
+    pub fn list_assets_sorted(&self) -> Vec<String> {
+        let mut names = self.list_assets_toc_order();
+        names.sort_unstable();
+        names
     }
     
     /// Get asset metadata
@@ -212,6 +490,11 @@ impl PakReader {
     pub fn header(&self) -> &PakHeader {
         &self.header
     }
+    
+    /// Get the archive's name table
+    pub fn string_table(&self) -> &StringContainer {
+        &self.string_table
+    }
 }
 
 /// Asset metadata
@@ -284,6 +567,23 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_list_assets_ordered() -> Result<()> {
+        let temp = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        for name in ["zeta.txt", "alpha.txt", "mid.txt"] {
+            builder.add_asset(AssetEntry::new(name, vec![0], AssetType::Data));
+        }
+        builder.build(temp.path())?;
+        
+        let reader = PakReader::open(temp.path())?;
+        assert_eq!(reader.list_assets_toc_order(), vec!["zeta.txt", "alpha.txt", "mid.txt"]);
+        assert_eq!(reader.list_assets(), reader.list_assets_toc_order());
+        assert_eq!(reader.list_assets_sorted(), vec!["alpha.txt", "mid.txt", "zeta.txt"]);
+        
+        Ok(())
+    }
+    
     #[test]
     fn test_asset_not_found() -> Result<()> {
         let temp = NamedTempFile::new().unwrap();
@@ -321,6 +621,127 @@ mod tests {
         Ok(())
     }
     
+    #[test]
+    fn test_explicit_name_offsets() -> Result<()> {
+        // Duplicate names share one string; each entry still resolves through its own offset
+        let temp = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        builder.add_asset(AssetEntry::new("a.txt", vec![1], AssetType::Data));
+        builder.add_asset(AssetEntry::new("b.txt", vec![2], AssetType::Data));
+        builder.add_asset(AssetEntry::new("a.txt", vec![3], AssetType::Data));
+        builder.build(temp.path())?;
+        
+        let reader = PakReader::open(temp.path())?;
+        let version = reader.header().version;
+        assert_eq!(version, PAK_VERSION);
+        assert_eq!(reader.get_asset("b.txt")?, vec![2]);
+        assert_eq!(reader.get_asset("a.txt")?, vec![3]);
+        assert_eq!(reader.string_table().iter().count(), 2);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_access_observer() -> Result<()> {
+        use std::sync::Mutex;
+        
+        let temp = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        builder.add_asset(AssetEntry::new("a.txt", vec![1; 10], AssetType::Data));
+        builder.add_asset(AssetEntry::new("b.txt", vec![2; 20], AssetType::Data));
+        builder.build(temp.path())?;
+        
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let mut reader = PakReader::open(temp.path())?;
+        let sink = Arc::clone(&trace);
+        reader.set_access_observer(move |event| {
+            sink.lock().unwrap().push((event.name.to_string(), event.size));
+        });
+        
+        reader.get_asset("b.txt")?;
+        reader.get_asset_slice("a.txt")?;
+        assert!(reader.get_asset("missing").is_err());
+        assert_eq!(
+            *trace.lock().unwrap(),
+            vec![("b.txt".to_string(), 20), ("a.txt".to_string(), 10)]
+        );
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_open_with_options() -> Result<()> {
+        let temp = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        builder.compress_threshold(0);
+        builder.add_asset(AssetEntry::new("big.bin", vec![7; 4096], AssetType::Data));
+        builder.build(temp.path())?;
+        
+        let reader = PakReader::open_with_options(temp.path(), &OpenOptions::default())?;
+        assert_eq!(reader.get_asset("big.bin")?, vec![7; 4096]);
+        
+        let too_new = OpenOptions { min_version: PAK_VERSION + 1, ..Default::default() };
+        assert!(matches!(
+            PakReader::open_with_options(temp.path(), &too_new),
+            Err(PakError::VersionOutOfRange { .. })
+        ));
+        
+        let verified = OpenOptions { require_checksums: true, ..Default::default() };
+        let reader = PakReader::open_with_options(temp.path(), &verified)?;
+        assert!(reader.header().has_checksums());
+        
+        // Archives from before version 4 carry no checksums
+        let mut bytes = std::fs::read(temp.path())?;
+        bytes[4..8].copy_from_slice(&3u32.to_le_bytes());
+        let old = NamedTempFile::new().unwrap();
+        std::fs::write(old.path(), &bytes)?;
+        assert!(matches!(
+            PakReader::open_with_options(old.path(), &verified),
+            Err(PakError::ChecksumsRequired)
+        ));
+        
+        #[cfg(feature = "compression")]
+        {
+            let raw_only = OpenOptions { allowed_codecs: vec![Codec::None], ..Default::default() };
+            assert!(matches!(
+                PakReader::open_with_options(temp.path(), &raw_only),
+                Err(PakError::CodecNotAllowed { codec: Codec::Zstd, .. })
+            ));
+            
+            // A compressed entry shadowed by a later one of the same name is still checked
+            let shadowed = NamedTempFile::new().unwrap();
+            let mut builder = PakBuilder::new();
+            builder.compress_threshold(0);
+            builder.add_asset(AssetEntry::new("a.bin", vec![7; 4096], AssetType::Data));
+            builder.add_asset(AssetEntry::new("a.bin", vec![1], AssetType::Data));
+            builder.build(shadowed.path())?;
+            assert!(matches!(
+                PakReader::open_with_options(shadowed.path(), &raw_only),
+                Err(PakError::CodecNotAllowed { codec: Codec::Zstd, .. })
+            ));
+        }
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_checksum_mismatch() -> Result<()> {
+        let temp = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        builder.add_asset(AssetEntry::new("a.txt", b"intact".to_vec(), AssetType::Data));
+        builder.build(temp.path())?;
+        
+        let mut bytes = std::fs::read(temp.path())?;
+        bytes[HEADER_SIZE] ^= 0xff;
+        std::fs::write(temp.path(), &bytes)?;
+        
+        let reader = PakReader::open(temp.path())?;
+        assert!(matches!(reader.get_asset("a.txt"), Err(PakError::ChecksumMismatch(_))));
+        assert!(matches!(reader.get_asset_slice("a.txt"), Err(PakError::ChecksumMismatch(_))));
+        
+        Ok(())
+    }
+    
     #[test]
     fn test_get_info() -> Result<()> {
         let temp = NamedTempFile::new().unwrap();
@@ -343,4 +764,106 @@ mod tests {
         
         Ok(())
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_encrypted_entries() -> Result<()> {
+        let temp = NamedTempFile::new().unwrap();
+        let key = [7u8; 32];
+        let premium = b"premium level data ".repeat(64);
+        let mut builder = PakBuilder::new();
+        builder.add_asset(AssetEntry::new("free.txt", b"free".to_vec(), AssetType::Data));
+        builder.add_asset(
+            AssetEntry::new("dlc/level.bin", premium.clone(), AssetType::Data).encrypted(42, key)
+        );
+        builder.build(temp.path())?;
+        
+        let mut reader = PakReader::open(temp.path())?;
+        assert_eq!(reader.is_encrypted("dlc/level.bin"), Some(true));
+        assert_eq!(reader.is_encrypted("free.txt"), Some(false));
+        assert_eq!(reader.get_asset("free.txt")?, b"free");
+        assert!(matches!(
+            reader.get_asset("dlc/level.bin"),
+            Err(PakError::KeyUnavailable { key_id: 42, .. })
+        ));
+        assert!(matches!(
+            reader.get_asset_slice("dlc/level.bin"),
+            Err(PakError::AssetEncrypted(_))
+        ));
+        
+        // Plaintext never appears in the archive
+        let raw = std::fs::read(temp.path())?;
+        assert!(!raw.windows(18).any(|w| w == b"premium level data"));
+        
+        reader.set_key_provider(HashMap::from([(42, key)]));
+        assert_eq!(reader.get_asset("dlc/level.bin")?, premium);
+        
+        reader.set_key_provider(HashMap::from([(42, [0u8; 32])]));
+        assert!(matches!(
+            reader.get_asset("dlc/level.bin"),
+            Err(PakError::DecryptionFailed(_))
+        ));
+        
+        reader.clear_key_provider();
+        assert!(reader.get_asset("dlc/level.bin").is_err());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_schema_registry() -> Result<()> {
+        use bytemuck_derive::{Pod, Zeroable};
+        use mtf_api::MTF;
+        
+        #[repr(C)]
+        #[derive(Clone, Copy, Pod, Zeroable, MTF)]
+        struct Vertex {
+            x: f32,
+            y: f32,
+            color: u32,
+        }
+        
+        let temp = NamedTempFile::new().unwrap();
+        let verts = [Vertex { x: 1.0, y: 2.0, color: 7 }, Vertex { x: 3.0, y: 4.0, color: 9 }];
+        let blob = <Vertex as mtf_api::MTFType>::mtf_type_blob();
+        let mut embedded = bytemuck::bytes_of(&verts[0]).to_vec();
+        embedded.extend_from_slice(blob);
+        embedded.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+        
+        let mut builder = PakBuilder::new();
+        builder
+            .add_typed("mesh/a.vtx", &verts, AssetType::Mesh)
+            .add_typed("mesh/b.vtx", &verts[..1], AssetType::Mesh)
+            .add_asset(AssetEntry::new("legacy.vtx", embedded, AssetType::Mesh))
+            .add_asset(AssetEntry::new("plain.txt", b"hi".to_vec(), AssetType::Data));
+        builder.build(temp.path())?;
+        
+        let reader = PakReader::open(temp.path())?;
+        let registry = SchemaRegistry::from_bytes(&reader.get_asset(SCHEMA_REGISTRY_NAME)?)?;
+        assert_eq!(registry.blobs().len(), 1);
+        assert_eq!(registry.bindings().count(), 2);
+        assert_eq!(
+            reader.list_assets(),
+            vec!["mesh/a.vtx", "mesh/b.vtx", "legacy.vtx", "plain.txt"]
+        );
+        assert!(!reader.list_assets_sorted().iter().any(|name| name == SCHEMA_REGISTRY_NAME));
+        
+        // Registered assets hold raw data only
+        assert_eq!(reader.get_asset("mesh/a.vtx")?.len(), 24);
+        assert_eq!(reader.schema("mesh/b.vtx").unwrap().type_name().unwrap(), "Vertex");
+        assert!(reader.schema("legacy.vtx").is_none());
+        
+        let mesh = reader.get_asset_dynamic("mesh/a.vtx")?;
+        assert_eq!(mesh.len(), 2);
+        assert_eq!(mesh.field::<u32>(1, "color"), Some(&9));
+        
+        let legacy = reader.get_asset_dynamic("legacy.vtx")?;
+        assert_eq!(legacy.field::<f32>(0, "y"), Some(&2.0));
+        
+        assert!(matches!(
+            reader.get_asset_dynamic("plain.txt"),
+            Err(PakError::InvalidSchema(_))
+        ));
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! streaming.rs - Prioritized background asset loading on top of PakReader
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+use crate::format::{PakError, Result};
+use crate::reader::PakReader;
+
+/// Heap key: highest priority first, then lowest file offset first
+type Job = (u32, Reverse<u64>, usize);
+
+struct State {
+    queue: BinaryHeap<Job>,
+    // toc index -> receivers waiting for that asset
+    waiting: HashMap<usize, Vec<Sender<Result<Vec<u8>>>>>,
+    shutdown: bool,
+}
+
+struct Shared {
+    reader: Arc<PakReader>,
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+/// Loads assets from a PAK on a pool of worker threads, in priority order
+///
+/// Requests are served highest priority first; requests with equal priority
+/// are served in file-offset order so that workers read the archive mostly
+/// sequentially. Several requests for the same asset are coalesced into a
+/// single read and decompression. Results are delivered through channels.
+pub struct StreamingScheduler {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl StreamingScheduler {
+    /// Start a scheduler with `workers` threads (at least one)
+    pub fn new(reader: Arc<PakReader>, workers: usize) -> Self {
+        let shared = Arc::new(Shared {
+            reader,
+            state: Mutex::new(State {
+                queue: BinaryHeap::new(),
+                waiting: HashMap::new(),
+                shutdown: false,
+            }),
+            ready: Condvar::new(),
+        });
+
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                std::thread::spawn(move || worker_loop(&shared))
+            })
+            .collect();
+
+        Self { shared, workers }
+    }
+
+    /// Queue a request for `name` with the given priority (higher is sooner)
+    ///
+    /// Returns a receiver that yields the asset data once it has been loaded.
+    /// Fails immediately if the asset does not exist.
+    pub fn request(&self, name: &str, priority: u32) -> Result<Receiver<Result<Vec<u8>>>> {
+        let (idx, entry) = self.shared.reader.lookup(name)
+            .ok_or_else(|| PakError::AssetNotFound(name.to_string()))?;
+        let offset = entry.offset;
+
+        let (tx, rx) = mpsc::channel();
+        let mut state = self.shared.state.lock().unwrap();
+        state.waiting.entry(idx).or_default().push(tx);
+        // A repeated request with higher priority adds a second heap entry;
+        // whichever is popped first serves every waiter
+        state.queue.push((priority, Reverse(offset), idx));
+        drop(state);
+
+        self.shared.ready.notify_one();
+        Ok(rx)
+    }
+
+    /// Number of distinct assets waiting to be loaded
+    pub fn pending_count(&self) -> usize {
+        self.shared.state.lock().unwrap().waiting.len()
+    }
+
+    /// The reader this scheduler loads from
+    pub fn reader(&self) -> &Arc<PakReader> {
+        &self.shared.reader
+    }
+}
+
+impl Drop for StreamingScheduler {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.ready.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn worker_loop(shared: &Shared) {
+    loop {
+        let (idx, waiters) = {
+            let mut state = shared.state.lock().unwrap();
+            loop {
+                if state.shutdown {
+                    return;
+                }
+                match state.queue.pop() {
+                    // Entries for assets already taken by another worker are stale
+                    Some((_, _, idx)) => {
+                        if let Some(waiters) = state.waiting.remove(&idx) {
+                            break (idx, waiters);
+                        }
+                    }
+                    None => state = shared.ready.wait(state).unwrap(),
+                }
+            }
+        };
+
+        let result = shared.reader.read_entry(idx);
+        let (first, rest) = waiters.split_first().expect("waiters are never empty");
+        for tx in rest {
+            let _ = tx.send(result.clone());
+        }
+        let _ = first.send(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetEntry, AssetType, PakBuilder};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_streaming_scheduler() -> Result<()> {
+        let temp = NamedTempFile::new().unwrap();
+        let mut builder = PakBuilder::new();
+        for i in 0..8u8 {
+            builder.add_asset(AssetEntry::new(
+                format!("asset{}.bin", i),
+                vec![i; 1000],
+                AssetType::Data,
+            ));
+        }
+        builder.build(temp.path())?;
+
+        let reader = Arc::new(PakReader::open(temp.path())?);
+        let scheduler = StreamingScheduler::new(reader, 2);
+
+        let receivers: Vec<_> = (0..8u8)
+            .map(|i| scheduler.request(&format!("asset{}.bin", i), i as u32))
+            .collect::<Result<_>>()?;
+        let duplicate = scheduler.request("asset3.bin", 100)?;
+
+        for (i, rx) in receivers.into_iter().enumerate() {
+            assert_eq!(rx.recv().unwrap()?, vec![i as u8; 1000]);
+        }
+        assert_eq!(duplicate.recv().unwrap()?, vec![3u8; 1000]);
+        assert!(matches!(
+            scheduler.request("missing.bin", 0),
+            Err(PakError::AssetNotFound(_))
+        ));
+
+        Ok(())
+    }
+}
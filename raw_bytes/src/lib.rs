@@ -0,0 +1,9 @@
+//! # raw_bytes
+//!
+//! Shared logic behind command-line and GUI tooling for the raw-bytes formats.
+//!
+//! The [`tools`] module exposes the inspect and convert operations as plain
+//! functions returning data, so binaries only need to parse arguments and
+//! print the results.
+
+pub mod tools;
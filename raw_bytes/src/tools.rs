@@ -0,0 +1,626 @@
+// raw_bytes/src/tools.rs
+//! Inspect and convert operations for command-line and GUI front ends.
+//!
+//! - [`describe_mtf_file`] / [`describe_mtf_blob`]: schema and record count of
+//!   MTF-annotated struct files, as written by `mtf_api::write_slice_with_mtf`
+//! - [`packed_bits_header`]: header of PKBT and PKFL bit containers
+//! - [`validate_pak`] (feature `pak`): open a PAK and decode every asset
+//! - [`struct_file_to_csv`], [`struct_file_to_json`], [`csv_to_struct_file`],
+//!   [`json_to_struct_file`]: convert struct files to and from text
+//!
+//! In text form, fields of up to 64 bits are unsigned decimal integers. Wider
+//! fields (e.g. `u128` or arrays) must be byte-aligned and are written as `0x`
+//! followed by their bytes in storage order. MTF does not record signedness or
+//! float types, so signed and float fields appear as their raw bit patterns.
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use mtf::{MTFError, read_bits, read_mtf, read_string, write_bits};
+use packed_bits_container::flags::BitsFormat;
+use packed_bits_container::{BYTE_ALIGNED_FLAG, Encoding};
+use thiserror::Error;
+
+/// Errors returned by the tool functions
+#[derive(Debug, Error)]
+pub enum ToolsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("MTF error: {0}")]
+    Mtf(#[from] MTFError),
+
+    #[cfg(feature = "pak")]
+    #[error("PAK error: {0}")]
+    Pak(#[from] pak::PakError),
+
+    #[error("Invalid input: {0}")]
+    Invalid(String),
+
+    #[error("Parse error on line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, ToolsError>;
+
+fn invalid(msg: impl Into<String>) -> ToolsError {
+    ToolsError::Invalid(msg.into())
+}
+
+/// One field of an MTF type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldInfo {
+    pub name: String,
+    pub offset_bits: usize,
+    pub size_bits: usize,
+}
+
+/// Schema and size of an MTF struct file or blob
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MtfDescription {
+    pub type_name: String,
+    pub size_bits: usize,
+    /// Fields in schema order
+    pub fields: Vec<FieldInfo>,
+    /// Bytes of struct data (0 for a bare blob)
+    pub data_len: usize,
+    /// Number of whole records in the data
+    pub record_count: usize,
+}
+
+impl MtfDescription {
+    /// Size of one record in bytes
+    pub fn record_size(&self) -> usize {
+        self.size_bits.div_ceil(8)
+    }
+}
+
+/// Describe the first type in an MTF blob
+pub fn describe_mtf_blob(blob: &[u8]) -> Result<MtfDescription> {
+    let (types, strings) = read_mtf(blob)?;
+    let ty = types.first().ok_or(MTFError::UnexpectedEof)?;
+    let mut fields = Vec::with_capacity(ty.fields.len());
+    for f in &ty.fields {
+        let field = FieldInfo {
+            name: read_string(strings, f.name_offset)?.to_string(),
+            offset_bits: f.offset_bits as usize,
+            size_bits: f.size_bits as usize,
+        };
+        check_field(&field, ty.size_bits as usize)?;
+        fields.push(field);
+    }
+    Ok(MtfDescription {
+        type_name: read_string(strings, ty.name_offset)?.to_string(),
+        size_bits: ty.size_bits as usize,
+        fields,
+        data_len: 0,
+        record_count: 0,
+    })
+}
+
+/// Reject fields that are empty or extend past the end of their record
+fn check_field(field: &FieldInfo, record_bits: usize) -> Result<()> {
+    let end = field.offset_bits.checked_add(field.size_bits);
+    if field.size_bits == 0 || end.is_none_or(|end| end > record_bits) {
+        return Err(invalid(format!(
+            "field '{}' ({} bits at bit {}) does not fit in a {}-bit record",
+            field.name, field.size_bits, field.offset_bits, record_bits
+        )));
+    }
+    Ok(())
+}
+
+/// Split a struct file into its data and MTF blob
+///
+/// Expects `[DATA][METADATA][SIZE]`, as written by `mtf_api::write_slice_with_mtf`
+/// and read by `DynamicContainer::from_file`.
+fn split_mtf_file(bytes: &[u8]) -> Result<(&[u8], &[u8])> {
+    let footer = bytes
+        .len()
+        .checked_sub(4)
+        .ok_or_else(|| invalid("file too small for MTF metadata"))?;
+    let size = u32::from_le_bytes(bytes[footer..].try_into().unwrap()) as usize;
+    let data_len = footer
+        .checked_sub(size)
+        .ok_or_else(|| invalid("MTF metadata size exceeds file size"))?;
+    Ok((&bytes[..data_len], &bytes[data_len..footer]))
+}
+
+/// Describe the data in an MTF bytes buffer, failing on partial records
+fn describe_data(data: &[u8], blob: &[u8]) -> Result<MtfDescription> {
+    let mut desc = describe_mtf_blob(blob)?;
+    let size = desc.record_size();
+    if size == 0 || !data.len().is_multiple_of(size) {
+        return Err(invalid(format!(
+            "{} data bytes are not a whole number of {}-byte records",
+            data.len(),
+            size
+        )));
+    }
+    desc.data_len = data.len();
+    desc.record_count = data.len() / size;
+    Ok(desc)
+}
+
+/// Describe a struct file with embedded MTF metadata
+pub fn describe_mtf_file<P: AsRef<Path>>(path: P) -> Result<MtfDescription> {
+    let bytes = std::fs::read(path)?;
+    let (data, blob) = split_mtf_file(&bytes)?;
+    describe_data(data, blob)
+}
+
+/// Header of a serialized PackedBitsContainer (PKBT) or FlagsContainer (PKFL)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedBitsHeader {
+    pub format: BitsFormat,
+    /// Format version; PKBT has none
+    pub version: Option<u32>,
+    pub bits_per_element: u32,
+    /// Element layout; PKFL data is always packed
+    pub encoding: Encoding,
+    /// Number of elements
+    pub len: usize,
+    /// Number of flag names (PKFL only)
+    pub flag_names: usize,
+    /// Bytes of packed element data present after the header
+    pub data_len: usize,
+}
+
+impl PackedBitsHeader {
+    /// Bytes the elements need; more than `data_len` means the data is truncated
+    pub fn expected_data_len(&self) -> usize {
+        let stride = match self.encoding {
+            Encoding::Packed => self.bits_per_element as usize,
+            Encoding::ByteAligned => (self.bits_per_element as usize).div_ceil(8) * 8,
+        };
+        (self.len * stride).div_ceil(8)
+    }
+}
+
+/// Parse the header of PKBT or PKFL bytes without knowing `N`
+pub fn packed_bits_header(bytes: &[u8]) -> Result<PackedBitsHeader> {
+    let word = |i: usize| {
+        bytes
+            .get(i..i + 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .ok_or_else(|| invalid("truncated header"))
+    };
+    match BitsFormat::detect(bytes) {
+        Some(format @ BitsFormat::PackedBits) => Ok(PackedBitsHeader {
+            format,
+            version: None,
+            bits_per_element: word(4)? & !BYTE_ALIGNED_FLAG,
+            encoding: if word(4)? & BYTE_ALIGNED_FLAG != 0 {
+                Encoding::ByteAligned
+            } else {
+                Encoding::Packed
+            },
+            len: word(8)? as usize,
+            flag_names: 0,
+            data_len: bytes.len().saturating_sub(12),
+        }),
+        Some(format @ BitsFormat::Flags) => {
+            // [magic][version][N][len][name count][name table len][crc32][names][data]
+            let names_len = word(20)? as usize;
+            Ok(PackedBitsHeader {
+                format,
+                version: Some(word(4)?),
+                bits_per_element: word(8)?,
+                encoding: Encoding::Packed,
+                len: word(12)? as usize,
+                flag_names: word(16)? as usize,
+                data_len: bytes.len().saturating_sub(28 + names_len),
+            })
+        }
+        None => Err(invalid("not a PKBT or PKFL file")),
+    }
+}
+
+/// Parse the header of a PKBT or PKFL file
+pub fn packed_bits_header_file<P: AsRef<Path>>(path: P) -> Result<PackedBitsHeader> {
+    packed_bits_header(&std::fs::read(path)?)
+}
+
+/// Outcome of decoding one asset in [`validate_pak`]
+#[cfg(feature = "pak")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakAssetCheck {
+    pub name: String,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub encrypted: bool,
+    /// Why the asset failed to decode, or `None` if it decoded to `size` bytes
+    pub error: Option<String>,
+}
+
+/// Result of [`validate_pak`]
+#[cfg(feature = "pak")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakReport {
+    pub version: u32,
+    pub assets: Vec<PakAssetCheck>,
+}
+
+#[cfg(feature = "pak")]
+impl PakReport {
+    /// Whether every asset decoded
+    pub fn is_valid(&self) -> bool {
+        self.assets.iter().all(|a| a.error.is_none())
+    }
+}
+
+/// Open a PAK and decode every asset, in TOC order
+///
+/// Encrypted assets are reported as failures, since no keys are available.
+/// Only an unreadable archive (bad header or TOC) is an error.
+#[cfg(feature = "pak")]
+pub fn validate_pak<P: AsRef<Path>>(path: P) -> Result<PakReport> {
+    let reader = pak::PakReader::open(path)?;
+    let mut assets = Vec::with_capacity(reader.asset_count());
+    for name in reader.list_assets_toc_order() {
+        let info = reader
+            .get_info(&name)
+            .ok_or_else(|| invalid(format!("asset '{}' missing from TOC", name)))?;
+        let error = match reader.get_asset(&name) {
+            Ok(data) if data.len() as u64 != info.size => Some(format!(
+                "decoded to {} bytes, TOC says {}",
+                data.len(),
+                info.size
+            )),
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        assets.push(PakAssetCheck {
+            encrypted: reader.is_encrypted(&name).unwrap_or(false),
+            size: info.size,
+            compressed_size: info.compressed_size,
+            error,
+            name,
+        });
+    }
+    Ok(PakReport {
+        version: reader.header().version,
+        assets,
+    })
+}
+
+/// Text form of one field of a record
+fn format_field(record: &[u8], field: &FieldInfo) -> Result<String> {
+    check_field(field, record.len() * 8)?;
+    if field.size_bits <= 64 {
+        return Ok(read_bits(record, field.offset_bits, field.size_bits).to_string());
+    }
+    let bytes = byte_range(field)?;
+    let mut out = String::from("0x");
+    for b in &record[bytes] {
+        out.push_str(&format!("{:02x}", b));
+    }
+    Ok(out)
+}
+
+/// Store the text form of one field into a record
+fn parse_field(
+    record: &mut [u8],
+    field: &FieldInfo,
+    text: &str,
+) -> std::result::Result<(), String> {
+    check_field(field, record.len() * 8).map_err(|e| e.to_string())?;
+    if field.size_bits <= 64 {
+        let value: u64 = text.parse().map_err(|_| {
+            format!(
+                "field '{}': '{}' is not an unsigned integer",
+                field.name, text
+            )
+        })?;
+        if field.size_bits < 64 && value >> field.size_bits != 0 {
+            return Err(format!(
+                "field '{}': {} does not fit in {} bits",
+                field.name, value, field.size_bits
+            ));
+        }
+        write_bits(record, field.offset_bits, field.size_bits, value);
+        return Ok(());
+    }
+
+    let bytes = byte_range(field).map_err(|e| e.to_string())?;
+    let hex = text
+        .strip_prefix("0x")
+        .filter(|h| h.len() == bytes.len() * 2)
+        .ok_or_else(|| {
+            format!(
+                "field '{}': expected 0x and {} hex digits",
+                field.name,
+                bytes.len() * 2
+            )
+        })?;
+    for (i, dst) in record[bytes].iter_mut().enumerate() {
+        *dst = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("field '{}': invalid hex '{}'", field.name, text))?;
+    }
+    Ok(())
+}
+
+/// Byte range of a field wider than 64 bits
+fn byte_range(field: &FieldInfo) -> Result<std::ops::Range<usize>> {
+    if !field.offset_bits.is_multiple_of(8) || !field.size_bits.is_multiple_of(8) {
+        return Err(invalid(format!(
+            "field '{}' is wider than 64 bits but not byte-aligned",
+            field.name
+        )));
+    }
+    Ok(field.offset_bits / 8..(field.offset_bits + field.size_bits) / 8)
+}
+
+/// Read a struct file as rows of field text, in schema order
+fn read_rows<P: AsRef<Path>>(path: P) -> Result<(MtfDescription, Vec<Vec<String>>)> {
+    let bytes = std::fs::read(path)?;
+    let (data, blob) = split_mtf_file(&bytes)?;
+    let desc = describe_data(data, blob)?;
+    let mut rows = Vec::with_capacity(desc.record_count);
+    for record in data.chunks_exact(desc.record_size()) {
+        let row = desc
+            .fields
+            .iter()
+            .map(|f| format_field(record, f))
+            .collect::<Result<Vec<_>>>()?;
+        rows.push(row);
+    }
+    Ok((desc, rows))
+}
+
+/// Write a struct file as CSV: a header row of field names, then one row per record
+pub fn struct_file_to_csv<P: AsRef<Path>>(path: P, mut out: impl Write) -> Result<()> {
+    let (desc, rows) = read_rows(path)?;
+    let names: Vec<&str> = desc.fields.iter().map(|f| f.name.as_str()).collect();
+    writeln!(out, "{}", names.join(","))?;
+    for row in rows {
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// Write a struct file as a JSON array with one object per record
+pub fn struct_file_to_json<P: AsRef<Path>>(path: P, mut out: impl Write) -> Result<()> {
+    let (desc, rows) = read_rows(path)?;
+    writeln!(out, "[")?;
+    for (i, row) in rows.iter().enumerate() {
+        let members: Vec<String> = desc
+            .fields
+            .iter()
+            .zip(row)
+            .map(|(f, v)| {
+                if v.starts_with("0x") {
+                    format!("\"{}\": \"{}\"", f.name, v)
+                } else {
+                    format!("\"{}\": {}", f.name, v)
+                }
+            })
+            .collect();
+        let comma = if i + 1 < rows.len() { "," } else { "" };
+        writeln!(out, "  {{{}}}{}", members.join(", "), comma)?;
+    }
+    writeln!(out, "]")?;
+    Ok(())
+}
+
+/// Build records from (line, field name -> text) rows and write a struct file
+fn write_records<P: AsRef<Path>>(
+    rows: Vec<(usize, HashMap<String, String>)>,
+    blob: &[u8],
+    path: P,
+) -> Result<()> {
+    let desc = describe_mtf_blob(blob)?;
+    let size = desc.record_size();
+    let mut data = vec![0u8; rows.len() * size];
+    for ((line, mut values), record) in rows.into_iter().zip(data.chunks_exact_mut(size)) {
+        for field in &desc.fields {
+            let text = values
+                .remove(&field.name)
+                .ok_or_else(|| ToolsError::Parse {
+                    line,
+                    message: format!("missing field '{}'", field.name),
+                })?;
+            parse_field(record, field, &text)
+                .map_err(|message| ToolsError::Parse { line, message })?;
+        }
+        if let Some(name) = values.keys().next() {
+            return Err(ToolsError::Parse {
+                line,
+                message: format!("unknown field '{}'", name),
+            });
+        }
+    }
+
+    // Same layout as mtf_api::write_slice_with_mtf
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&data)?;
+    file.write_all(blob)?;
+    file.write_all(&(blob.len() as u32).to_le_bytes())?;
+    Ok(())
+}
+
+/// Convert CSV (as written by [`struct_file_to_csv`]) into a struct file
+///
+/// `blob` is the MTF schema of the records; columns are matched to fields by
+/// name, so their order does not matter. Values are never quoted in that output,
+/// so quoted fields are rejected rather than split on embedded commas.
+pub fn csv_to_struct_file<P: AsRef<Path>>(csv: &str, blob: &[u8], path: P) -> Result<()> {
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| invalid("CSV has no header row"))?;
+    if header.contains('"') {
+        return Err(invalid("quoted CSV fields are not supported"));
+    }
+    let names: Vec<&str> = header.split(',').map(str::trim).collect();
+
+    let mut rows = Vec::new();
+    for (i, line) in lines {
+        if line.contains('"') {
+            return Err(ToolsError::Parse {
+                line: i + 1,
+                message: "quoted CSV fields are not supported".to_string(),
+            });
+        }
+        let values: Vec<&str> = line.split(',').map(str::trim).collect();
+        if values.len() != names.len() {
+            return Err(ToolsError::Parse {
+                line: i + 1,
+                message: format!("expected {} columns, found {}", names.len(), values.len()),
+            });
+        }
+        let row = names
+            .iter()
+            .zip(values)
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect();
+        rows.push((i + 1, row));
+    }
+    write_records(rows, blob, path)
+}
+
+/// Convert JSON (as written by [`struct_file_to_json`]) into a struct file
+///
+/// Accepts an array of flat objects whose values are unsigned integers or
+/// strings; keys are matched to fields by name. Anything else, such as negative
+/// or fractional numbers, nested values or escape sequences, is rejected with
+/// the offending line.
+pub fn json_to_struct_file<P: AsRef<Path>>(json: &str, blob: &[u8], path: P) -> Result<()> {
+    let rows = json::parse_records(json)?;
+    write_records(rows, blob, path)
+}
+
+/// Just enough JSON for arrays of flat records
+///
+/// Only what [`struct_file_to_json`] writes is accepted; other valid JSON is an
+/// error rather than being misread.
+mod json {
+    use super::{Result, ToolsError};
+    use std::collections::HashMap;
+
+    struct Parser<'a> {
+        text: &'a str,
+        pos: usize,
+    }
+
+    impl Parser<'_> {
+        fn line(&self) -> usize {
+            self.text[..self.pos].matches('\n').count() + 1
+        }
+
+        fn error(&self, message: impl Into<String>) -> ToolsError {
+            ToolsError::Parse {
+                line: self.line(),
+                message: message.into(),
+            }
+        }
+
+        fn skip_ws(&mut self) {
+            let rest = &self.text[self.pos..];
+            self.pos += rest.len() - rest.trim_start().len();
+        }
+
+        fn peek(&mut self) -> Option<char> {
+            self.skip_ws();
+            self.text[self.pos..].chars().next()
+        }
+
+        fn expect(&mut self, c: char) -> Result<()> {
+            if self.peek() != Some(c) {
+                return Err(self.error(format!("expected '{}'", c)));
+            }
+            self.pos += 1;
+            Ok(())
+        }
+
+        /// Consume `c` if it is next
+        fn eat(&mut self, c: char) -> bool {
+            let found = self.peek() == Some(c);
+            if found {
+                self.pos += 1;
+            }
+            found
+        }
+
+        fn string(&mut self) -> Result<String> {
+            self.expect('"')?;
+            let rest = &self.text[self.pos..];
+            let end = rest
+                .find('"')
+                .ok_or_else(|| self.error("unterminated string"))?;
+            if rest[..end].contains('\\') {
+                return Err(self.error("escape sequences are not supported"));
+            }
+            self.pos += end + 1;
+            Ok(rest[..end].to_string())
+        }
+
+        fn value(&mut self) -> Result<String> {
+            if self.peek() == Some('"') {
+                return self.string();
+            }
+            let rest = &self.text[self.pos..];
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if rest.starts_with('-') {
+                return Err(self.error("negative numbers are not supported"));
+            }
+            if end == 0 {
+                return Err(self.error("expected an unsigned integer or a string"));
+            }
+            if rest[end..].starts_with(['.', 'e', 'E']) {
+                return Err(self.error("fractional numbers are not supported"));
+            }
+            self.pos += end;
+            Ok(rest[..end].to_string())
+        }
+
+        /// Parse a comma-separated list closed by `close`, calling `item` for each entry
+        fn list(
+            &mut self,
+            close: char,
+            mut item: impl FnMut(&mut Self) -> Result<()>,
+        ) -> Result<()> {
+            if self.eat(close) {
+                return Ok(());
+            }
+            loop {
+                item(self)?;
+                if self.eat(close) {
+                    return Ok(());
+                }
+                self.expect(',')?;
+            }
+        }
+    }
+
+    pub(super) fn parse_records(text: &str) -> Result<Vec<(usize, HashMap<String, String>)>> {
+        let mut p = Parser { text, pos: 0 };
+        let mut records = Vec::new();
+        p.expect('[')?;
+        p.list(']', |p| {
+            p.expect('{')?;
+            let line = p.line();
+            let mut record = HashMap::new();
+            p.list('}', |p| {
+                let key = p.string()?;
+                p.expect(':')?;
+                let value = p.value()?;
+                if record.insert(key.clone(), value).is_some() {
+                    return Err(p.error(format!("duplicate key '{}'", key)));
+                }
+                Ok(())
+            })?;
+            records.push((line, record));
+            Ok(())
+        })?;
+        if p.peek().is_some() {
+            return Err(p.error("trailing characters after JSON array"));
+        }
+        Ok(records)
+    }
+}
@@ -0,0 +1,196 @@
+use bytemuck_derive::{Pod, Zeroable};
+use mtf_api::{MTF, MTFType, write_slice_with_mtf};
+use packed_bits_container::PackedBitsContainer;
+use packed_bits_container::flags::{BitsFormat, FlagsContainer};
+use raw_bytes::tools::{self, ToolsError};
+use tempfile::TempDir;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable, MTF)]
+struct Item {
+    id: u32,
+    count: u16,
+    kind: u16,
+    tag: [u8; 12],
+}
+
+fn items() -> Vec<Item> {
+    vec![
+        Item {
+            id: 1,
+            count: 5,
+            kind: 2,
+            tag: *b"sword\0\0\0\0\0\0\0",
+        },
+        Item {
+            id: 70000,
+            count: 0,
+            kind: 65535,
+            tag: [0xab; 12],
+        },
+    ]
+}
+
+fn write_items(dir: &TempDir) -> std::path::PathBuf {
+    let path = dir.path().join("items.bin");
+    write_slice_with_mtf(std::fs::File::create(&path).unwrap(), &items()).unwrap();
+    path
+}
+
+fn read_items(path: &std::path::Path) -> Vec<Item> {
+    let desc = tools::describe_mtf_file(path).unwrap();
+    let bytes = std::fs::read(path).unwrap();
+    bytemuck::pod_collect_to_vec(&bytes[..desc.data_len])
+}
+
+#[test]
+fn describe_struct_file() {
+    let dir = TempDir::new().unwrap();
+    let desc = tools::describe_mtf_file(write_items(&dir)).unwrap();
+    assert_eq!(desc.type_name, "Item");
+    assert_eq!(desc.record_size(), 20);
+    assert_eq!(desc.record_count, 2);
+    let fields: Vec<_> = desc
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.offset_bits, f.size_bits))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("id", 0, 32),
+            ("count", 32, 16),
+            ("kind", 48, 16),
+            ("tag", 64, 96)
+        ]
+    );
+}
+
+#[test]
+fn csv_and_json_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let path = write_items(&dir);
+
+    let mut csv = Vec::new();
+    tools::struct_file_to_csv(&path, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().next(), Some("id,count,kind,tag"));
+    assert_eq!(csv.lines().nth(1), Some("1,5,2,0x73776f726400000000000000"));
+
+    let from_csv = dir.path().join("from_csv.bin");
+    tools::csv_to_struct_file(&csv, Item::mtf_type_blob(), &from_csv).unwrap();
+    assert_eq!(read_items(&from_csv), items());
+
+    let mut json = Vec::new();
+    tools::struct_file_to_json(&path, &mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    let from_json = dir.path().join("from_json.bin");
+    tools::json_to_struct_file(&json, Item::mtf_type_blob(), &from_json).unwrap();
+    assert_eq!(read_items(&from_json), items());
+    assert_eq!(
+        std::fs::read(&from_json).unwrap(),
+        std::fs::read(&path).unwrap()
+    );
+}
+
+#[test]
+fn conversion_errors_name_the_line() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("out.bin");
+    let blob = Item::mtf_type_blob();
+
+    let err =
+        tools::csv_to_struct_file("id,count,kind,tag\n1,70000,0,0x00\n", blob, &out).unwrap_err();
+    assert!(matches!(err, ToolsError::Parse { line: 2, .. }), "{err}");
+
+    let json = "[\n  {\"id\": 1, \"count\": 2, \"kind\": 3}\n]";
+    let err = tools::json_to_struct_file(json, blob, &out).unwrap_err();
+    assert!(
+        matches!(&err, ToolsError::Parse { line: 2, message } if message.contains("tag")),
+        "{err}"
+    );
+
+    assert!(tools::json_to_struct_file("[{\"id\": -1}]", blob, &out).is_err());
+    let err = tools::json_to_struct_file("[\n{\"id\": 1.5}]", blob, &out).unwrap_err();
+    assert!(matches!(err, ToolsError::Parse { line: 2, .. }), "{err}");
+
+    let err =
+        tools::csv_to_struct_file("id,count,kind,tag\n\"1,2\",3,4,0x00\n", blob, &out).unwrap_err();
+    assert!(matches!(err, ToolsError::Parse { line: 2, .. }), "{err}");
+}
+
+#[test]
+fn reject_fields_outside_the_record() {
+    use mtf::{FieldDef, TypeDef, write_mtf};
+
+    let blob_with = |offset_bits, size_bits| {
+        let types = [TypeDef {
+            name_offset: 0,
+            size_bits: 32,
+            fields: vec![FieldDef {
+                name_offset: 2,
+                offset_bits,
+                size_bits,
+            }],
+        }];
+        let mut blob = Vec::new();
+        write_mtf(&types, b"T\0x\0", &mut blob).unwrap();
+        blob
+    };
+
+    assert!(tools::describe_mtf_blob(&blob_with(0, 32)).is_ok());
+    for (offset, size) in [(0, 0), (16, 32), (u32::MAX, 8)] {
+        let err = tools::describe_mtf_blob(&blob_with(offset, size)).unwrap_err();
+        assert!(matches!(err, ToolsError::Invalid(_)), "{err}");
+    }
+}
+
+#[test]
+fn packed_bits_headers() {
+    let mut bits = PackedBitsContainer::<5>::new_in_memory();
+    for v in [1, 2, 3] {
+        bits.push(v).unwrap();
+    }
+    let header = tools::packed_bits_header(bits.storage().as_slice()).unwrap();
+    assert_eq!(header.format, BitsFormat::PackedBits);
+    assert_eq!(
+        (header.bits_per_element, header.len, header.version),
+        (5, 3, None)
+    );
+    assert!(header.data_len >= header.expected_data_len());
+
+    let mut flags = FlagsContainer::<8>::new_in_memory();
+    flags.push(0b101).unwrap();
+    flags.set_flag_names(["alive", "poisoned"]).unwrap();
+    let header = tools::packed_bits_header(&flags.to_bytes()).unwrap();
+    assert_eq!(header.format, BitsFormat::Flags);
+    assert_eq!(
+        (header.bits_per_element, header.len, header.flag_names),
+        (8, 1, 2)
+    );
+    assert_eq!(header.data_len, header.expected_data_len());
+
+    assert!(tools::packed_bits_header(b"nope").is_err());
+}
+
+#[cfg(feature = "pak")]
+#[test]
+fn validate_pak_decodes_every_asset() {
+    use pak::{AssetEntry, AssetType, PakBuilder};
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("assets.pak");
+    let mut builder = PakBuilder::new();
+    builder.add_asset(AssetEntry::new("a.txt", b"hello".to_vec(), AssetType::Data));
+    builder.add_asset(AssetEntry::new("b.bin", vec![7; 4096], AssetType::Data));
+    builder.build(&path).unwrap();
+
+    let report = tools::validate_pak(&path).unwrap();
+    assert!(report.is_valid());
+    let names: Vec<_> = report
+        .assets
+        .iter()
+        .map(|a| (a.name.as_str(), a.size))
+        .collect();
+    assert_eq!(names, vec![("a.txt", 5), ("b.bin", 4096)]);
+}
@@ -0,0 +1,54 @@
+//!  In-process  registry  of  mapped  files  opened  for  exclusive  or  shared  access.
+//!
+//!  Advisory  file  locks  are  not  reliable  within  one  process:  with  `fcntl`-style
+//!  locks  every  handle  in  the  process  shares  one  lock,  so  two  threads  can  both
+//!  "hold"  an  exclusive  lock.  [`AccessGuard`]  closes  that  gap  by  tracking,  per
+//!  canonical  path,  whether  the  file  is  open  for  a  single  writer  or  for  any
+//!  number  of  readers.
+
+use crate::ContainerError;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+///  Open  handles  per  canonical  path:  `-1`  for  a  writer,  otherwise  the  number  of  readers.
+static REGISTRY: LazyLock<Mutex<HashMap<PathBuf, isize>>> = LazyLock::new(Default::default);
+
+///  Registration  of  one  handle  in  the  registry,  released  on  drop.
+#[derive(Debug)]
+pub(crate) struct AccessGuard {
+    key: PathBuf,
+}
+
+impl AccessGuard {
+    ///  Register  `path`  for  exclusive  or  shared  access.
+    ///
+    ///  Fails  with  [`ContainerError::LockContended`]  if  the  file  is  already  open
+    ///  for  writing,  or  is  open  at  all  and  `exclusive`  is  requested.
+    pub(crate) fn acquire(path: &Path, exclusive: bool) -> Result<Self, ContainerError> {
+        let key = path.canonicalize()?;
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        let count = registry.entry(key.clone()).or_insert(0);
+        match (*count, exclusive) {
+            (0, true) => *count = -1,
+            (n, false) if n >= 0 => *count += 1,
+            _ => return Err(ContainerError::LockContended),
+        }
+        Ok(Self { key })
+    }
+}
+
+impl Drop for AccessGuard {
+    fn drop(&mut self) {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = registry.get_mut(&self.key) {
+            if *count > 1 {
+                *count -= 1;
+            } else {
+                registry.remove(&self.key);
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//!  Heap  byte  buffer  with  an  alignment  chosen  at  runtime.
+//!
+//!  `Vec<u8>`  only  guarantees  an  alignment  of  1,  so  byte  storage  that  is  reinterpreted
+//!  as  wider  types  allocates  through  this  buffer  instead.
+
+use crate::ContainerError;
+use std::alloc::{self, Layout};
+use std::ptr::NonNull;
+
+///  A  growable  byte  buffer  whose  start  is  always  aligned  to  `align`.
+pub(crate) struct AlignedBuf {
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    align: usize,
+}
+
+//  The  buffer  owns  its  bytes  like  a  `Vec<u8>`
+unsafe impl Send for AlignedBuf {}
+unsafe impl Sync for AlignedBuf {}
+
+impl AlignedBuf {
+    ///  An  empty  buffer  aligned  to  `align`,  which  must  be  a  power  of  two.
+    pub(crate) fn new(align: usize) -> Self {
+        assert!(
+            align.is_power_of_two(),
+            "alignment  must  be  a  power  of  two"
+        );
+        Self {
+            //  Never  dereferenced  while  empty,  but  slices  of  it  must  be  aligned
+            ptr: NonNull::new(std::ptr::without_provenance_mut(align))
+                .expect("align  is  non-zero"),
+            len: 0,
+            cap: 0,
+            align,
+        }
+    }
+
+    ///  A  buffer  aligned  to  `align`  holding  a  copy  of  `bytes`.
+    pub(crate) fn from_slice(bytes: &[u8], align: usize) -> Result<Self, ContainerError> {
+        let mut buf = Self::new(align);
+        buf.extend_from_slice(bytes)?;
+        Ok(buf)
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        //  SAFETY:  `ptr`  is  valid  for  `len`  initialized  bytes  (or  dangling  with  `len  ==  0`)
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [u8] {
+        //  SAFETY:  as  in  `as_slice`,  and  `&mut  self`  makes  the  borrow  unique
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    ///  Append  `bytes`,  growing  geometrically.
+    pub(crate) fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), ContainerError> {
+        let new_len = self
+            .len
+            .checked_add(bytes.len())
+            .ok_or(ContainerError::UnsupportedOperation("Capacity  overflow"))?;
+        if new_len > self.cap {
+            self.grow(new_len.max(self.cap.saturating_mul(2)))?;
+        }
+        //  SAFETY:  capacity  was  checked  above  and  `bytes`  cannot  alias  our  allocation
+        //  while  we  hold  `&mut  self`
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                self.ptr.as_ptr().add(self.len),
+                bytes.len(),
+            );
+        }
+        self.len = new_len;
+        Ok(())
+    }
+
+    ///  Append  `count`  zero  bytes.
+    pub(crate) fn extend_zeroed(&mut self, count: usize) -> Result<(), ContainerError> {
+        //  Small  counts  only  (header  padding),  so  a  temporary  is  fine
+        self.extend_from_slice(&vec![0; count])
+    }
+
+    fn grow(&mut self, cap: usize) -> Result<(), ContainerError> {
+        let layout = Layout::from_size_align(cap, self.align)
+            .map_err(|_| ContainerError::UnsupportedOperation("Capacity  overflow"))?;
+        //  SAFETY:  `cap`  is  non-zero  because  it  exceeds  the  current  capacity,  and  the  old
+        //  layout  matches  the  one  the  block  was  allocated  with
+        let ptr = unsafe {
+            if self.cap == 0 {
+                alloc::alloc(layout)
+            } else {
+                let old = Layout::from_size_align_unchecked(self.cap, self.align);
+                alloc::realloc(self.ptr.as_ptr(), old, cap)
+            }
+        };
+        self.ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        self.cap = cap;
+        Ok(())
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        if self.cap != 0 {
+            //  SAFETY:  allocated  in  `grow`  with  this  layout
+            unsafe {
+                alloc::dealloc(
+                    self.ptr.as_ptr(),
+                    Layout::from_size_align_unchecked(self.cap, self.align),
+                )
+            };
+        }
+    }
+}
+
+impl std::fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlignedBuf")
+            .field("len", &self.len)
+            .field("align", &self.align)
+            .finish()
+    }
+}
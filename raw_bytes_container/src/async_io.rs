@@ -0,0 +1,66 @@
+use crate::{ContainerError, RawBytesContainer, Storage, TrailingBytes, checksum::Trailer};
+use bytemuck::Pod;
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Async  version  of  [`open_read_copied`](Self::open_read_copied),  built  on  `tokio::fs`.
+    ///
+    ///  Loads  the  whole  file  into  an  in-memory  container  without  blocking  the
+    ///  executor  thread.  Fails  if  the  file  size  is  not  a  multiple  of  `size_of::<T>()`.
+    pub async fn read_from_path_async<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Self::read_from_path_async_with(path, TrailingBytes::Reject).await
+    }
+
+    ///  Like  [`read_from_path_async`](Self::read_from_path_async),  with  an  explicit
+    ///  trailing-byte  policy.
+    pub async fn read_from_path_async_with<P: AsRef<Path>>(
+        path: P,
+        trailing: TrailingBytes,
+    ) -> Result<Self, ContainerError> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let byte_len = file.metadata().await?.len() as usize;
+        let size = std::mem::size_of::<T>();
+
+        if trailing == TrailingBytes::Reject && !byte_len.is_multiple_of(size) {
+            return Err(ContainerError::AlignmentError(format!(
+                "File  size  {}  not  aligned  to  type  size  {}",
+                byte_len, size
+            )));
+        }
+
+        let mut data = vec![T::zeroed(); byte_len / size];
+        file.read_exact(bytemuck::cast_slice_mut(&mut data)).await?;
+        Ok(Self::from_vec(data))
+    }
+
+    ///  Async  version  of  [`write_to_file`](Self::write_to_file)  for  in-memory  and
+    ///  borrowed  storage,  built  on  `tokio::fs`.
+    ///
+    ///  Writes  the  checksum  trailer  too  if  enabled.  Mapped  storage  is  written  back
+    ///  with  [`flush`](Self::flush)  or  [`commit`](Self::commit)  instead.
+    pub async fn write_to_file_async<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(), ContainerError> {
+        let data: &[u8] = match &self.storage {
+            Storage::InMemory(vec) => bytemuck::cast_slice(vec),
+            Storage::Shared(vec) => bytemuck::cast_slice(vec),
+            Storage::Borrowed(slice) => bytemuck::cast_slice(slice),
+            _ => {
+                return Err(ContainerError::UnsupportedOperation(
+                    "Async  write  only  supported  on  in-memory  storage",
+                ));
+            }
+        };
+
+        let mut file = tokio::fs::File::create(path).await?;
+        file.write_all(data).await?;
+        if self.checksummed {
+            file.write_all(Trailer::for_data(data).as_bytes()).await?;
+        }
+        file.flush().await?;
+        *self.dirty.get_mut() = false;
+        Ok(())
+    }
+}
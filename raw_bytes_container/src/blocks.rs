@@ -0,0 +1,78 @@
+use crate::RawBytesContainer;
+use bytemuck::Pod;
+use std::slice::{ChunksExact, ChunksExactMut};
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Iterate  over  the  data  in  blocks  of  exactly  `block_size`  elements.
+    ///
+    ///  Works  the  same  on  every  storage,  so  mapped  files  can  be  processed  batch  by
+    ///  batch  without  copying.  Elements  that  do  not  fill  a  whole  block  are  left  out
+    ///  and  available  through  [`ChunksExact::remainder`].
+    ///
+    ///  #  Panics
+    ///  Panics  if  `block_size`  is  0.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  container  =  RawBytesContainer::from_vec((0u32..10).collect());
+    ///  let  mut  blocks  =  container.blocks(4);
+    ///  let  sums:  Vec<u32>  =  blocks.by_ref().map(|b|  b.iter().sum()).collect();
+    ///  assert_eq!(sums,  vec![6,  22]);
+    ///  assert_eq!(blocks.remainder(),  &[8,  9]);
+    ///  ```
+    pub fn blocks(&self, block_size: usize) -> ChunksExact<'_, T> {
+        self.as_slice().chunks_exact(block_size)
+    }
+
+    ///  Mutable  version  of  [`blocks`](Self::blocks).
+    ///
+    ///  Returns  `None`  if  the  storage  is  read-only.
+    ///
+    ///  #  Panics
+    ///  Panics  if  `block_size`  is  0.
+    pub fn blocks_mut(&mut self, block_size: usize) -> Option<ChunksExactMut<'_, T>> {
+        Some(self.as_slice_mut()?.chunks_exact_mut(block_size))
+    }
+
+    ///  Parallel  version  of  [`blocks`](Self::blocks),  processing  blocks  on  the  rayon
+    ///  thread  pool.
+    ///
+    ///  #  Panics
+    ///  Panics  if  `block_size`  is  0.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///  use  rayon::prelude::*;
+    ///
+    ///  let  container  =  RawBytesContainer::from_vec((0u64..1000).collect());
+    ///  let  total:  u64  =  container.par_blocks(64).map(|b|  b.iter().sum::<u64>()).sum();
+    ///  assert_eq!(total,  (0..960).sum());
+    ///  ```
+    #[cfg(feature = "rayon")]
+    pub fn par_blocks(&self, block_size: usize) -> rayon::slice::ChunksExact<'_, T>
+    where
+        T: Sync,
+    {
+        use rayon::slice::ParallelSlice;
+        self.as_slice().par_chunks_exact(block_size)
+    }
+
+    ///  Parallel  version  of  [`blocks_mut`](Self::blocks_mut).
+    ///
+    ///  Returns  `None`  if  the  storage  is  read-only.
+    ///
+    ///  #  Panics
+    ///  Panics  if  `block_size`  is  0.
+    #[cfg(feature = "rayon")]
+    pub fn par_blocks_mut(
+        &mut self,
+        block_size: usize,
+    ) -> Option<rayon::slice::ChunksExactMut<'_, T>>
+    where
+        T: Send,
+    {
+        use rayon::slice::ParallelSliceMut;
+        Some(self.as_slice_mut()?.par_chunks_exact_mut(block_size))
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{ContainerError, RawBytesContainer};
+use bytemuck::Pod;
+
+///  A  container  that  [`BufferedAppender`]  can  commit  blocks  to.
+///
+///  Implemented  for  [`RawBytesContainer`];  containers  layered  on  top  of  it  implement
+///  this  to  reuse  the  appender  instead  of  writing  their  own.
+pub trait AppendSink<T: Pod> {
+    ///  Append  `values`  to  the  end  of  the  container.
+    fn append_items(&mut self, values: &[T]) -> Result<(), ContainerError>;
+}
+
+impl<T: Pod> AppendSink<T> for RawBytesContainer<T> {
+    fn append_items(&mut self, values: &[T]) -> Result<(), ContainerError> {
+        self.append(values)
+    }
+}
+
+///  Batches  small  appends  to  a  container  and  commits  them  in  blocks.
+///
+///  Created  by  [`RawBytesContainer::append_buffered`].  Items  are  collected  in  an
+///  internal  buffer  and  appended  to  the  container  once  `block_size`  items  are
+///  pending,  so  many  tiny  pushes  cost  one  reallocation  check  per  block  instead  of
+///  one  per  item.
+///
+///  Pending  items  are  also  committed  when  the  appender  is  dropped,  but  `Drop`
+///  cannot  report  a  failure:  call  [`commit`](Self::commit)  before  dropping  to  see
+///  errors.  In  debug  builds  a  failed  commit  on  drop  panics  instead  of  silently
+///  losing  the  items.
+#[derive(Debug)]
+pub struct BufferedAppender<'a, T: Pod, C: AppendSink<T> + ?Sized = RawBytesContainer<T>> {
+    container: &'a mut C,
+    buffer: Vec<T>,
+    block_size: usize,
+}
+
+impl<'a, T: Pod, C: AppendSink<T> + ?Sized> BufferedAppender<'a, T, C> {
+    ///  Appender  committing  to  `container`  in  blocks  of  `block_size`  items  (at  least  1).
+    ///
+    ///  Callers  check  that  the  container  can  grow  before  creating  one.
+    pub fn new(container: &'a mut C, block_size: usize) -> Self {
+        let block_size = block_size.max(1);
+        Self {
+            container,
+            buffer: Vec::with_capacity(block_size),
+            block_size,
+        }
+    }
+
+    ///  Queue  a  single  item,  committing  the  block  if  it  is  full.
+    pub fn push(&mut self, value: T) -> Result<(), ContainerError> {
+        self.buffer.push(value);
+        if self.buffer.len() >= self.block_size {
+            self.commit()?;
+        }
+        Ok(())
+    }
+
+    ///  Queue  several  items,  committing  full  blocks  as  they  fill  up.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> Result<(), ContainerError> {
+        self.buffer.extend_from_slice(values);
+        if self.buffer.len() >= self.block_size {
+            self.commit()?;
+        }
+        Ok(())
+    }
+
+    ///  Number  of  items  queued  but  not  yet  committed.
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    ///  Configured  block  size.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    ///  Append  all  pending  items  to  the  container.
+    pub fn commit(&mut self) -> Result<(), ContainerError> {
+        if !self.buffer.is_empty() {
+            self.container.append_items(&self.buffer)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+impl<T: Pod, C: AppendSink<T> + ?Sized> Drop for BufferedAppender<'_, T, C> {
+    fn drop(&mut self) {
+        //  Growth  was  checked  when  the  appender  was  created,  so  this  only  fails  if  a
+        //  mapped  file  cannot  be  extended;  callers  who  care  commit  explicitly
+        if let Err(err) = self.commit() {
+            debug_assert!(
+                std::thread::panicking(),
+                "BufferedAppender  dropped  with  {}  uncommitted  items:  {}",
+                self.buffer.len(),
+                err
+            );
+        }
+    }
+}
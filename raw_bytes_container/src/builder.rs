@@ -0,0 +1,260 @@
+use crate::{
+    Advice, ContainerError, FileLock, MapOptions, RawBytesContainer, Storage, TrailingBytes,
+};
+use bytemuck::Pod;
+use memmap2::MmapOptions;
+use std::{
+    fs::OpenOptions,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+///  Where  a  container  built  by  [`RawBytesContainerBuilder`]  keeps  its  data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    ///  A  `Vec<T>`  on  the  heap  (the  default).
+    Memory,
+    ///  A  memory-mapped  file.
+    Mmap(PathBuf),
+    ///  An  anonymous  memory  mapping,  not  backed  by  any  file.
+    Anon,
+}
+
+///  One  entry  point  for  constructing  a  [`RawBytesContainer`].
+///
+///  Created  by  [`RawBytesContainer::builder`].  Picks  the  backend  and  applies  length,
+///  capacity,  file  creation,  read-only,  locking,  mapping  and  advice  options  in  one
+///  place,  instead  of  choosing  between  the  `from_*`,  `open_*`  and  `create_*`
+///  constructors.
+///
+///  ```
+///  use  raw_bytes_container::{Advice,  FileLock,  RawBytesContainer};
+///
+///  let  dir  =  tempfile::tempdir().unwrap();
+///  let  path  =  dir.path().join("samples.bin");
+///
+///  let  mut  samples  =  RawBytesContainer::<f32>::builder()
+///          .mmap(&path)
+///          .create_if_missing(true)
+///          .len(1024)
+///          .lock(FileLock::Exclusive)
+///          .advice(Advice::Sequential)
+///          .build()
+///          .unwrap();
+///  samples.as_slice_mut().unwrap()[0]  =  1.5;
+///  samples.flush().unwrap();
+///  drop(samples);
+///
+///  let  samples  =  RawBytesContainer::<f32>::builder()
+///          .mmap(&path)
+///          .read_only(true)
+///          .build()
+///          .unwrap();
+///  assert_eq!(samples.len(),  1024);
+///  assert_eq!(samples[0],  1.5);
+///  ```
+#[derive(Debug, Clone)]
+pub struct RawBytesContainerBuilder<T: Pod> {
+    backend: Backend,
+    len: usize,
+    capacity: usize,
+    create_if_missing: bool,
+    read_only: bool,
+    options: MapOptions,
+    advice: Option<Advice>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod> Default for RawBytesContainerBuilder<T> {
+    fn default() -> Self {
+        Self {
+            backend: Backend::Memory,
+            len: 0,
+            capacity: 0,
+            create_if_missing: false,
+            read_only: false,
+            options: MapOptions::new(),
+            advice: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Pod> RawBytesContainerBuilder<T> {
+    ///  Default  builder:  an  empty,  writable  in-memory  container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///  Set  the  backend.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    ///  Keep  the  data  in  memory.
+    pub fn memory(self) -> Self {
+        self.backend(Backend::Memory)
+    }
+
+    ///  Map  the  file  at  `path`.
+    pub fn mmap(self, path: impl AsRef<Path>) -> Self {
+        self.backend(Backend::Mmap(path.as_ref().to_path_buf()))
+    }
+
+    ///  Use  an  anonymous  mapping.
+    pub fn anon(self) -> Self {
+        self.backend(Backend::Anon)
+    }
+
+    ///  Number  of  zeroed  elements  to  start  with,  for  in-memory  and  anonymous  storage
+    ///  and  for  files  created  by  [`create_if_missing`](Self::create_if_missing).
+    ///
+    ///  Existing  files  keep  their  length.
+    pub fn len(mut self, len: usize) -> Self {
+        self.len = len;
+        self
+    }
+
+    ///  Capacity  to  reserve  up  front,  in  elements  (in-memory  storage  only).
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    ///  Create  the  mapped  file  with  [`len`](Self::len)  zeroed  elements  if  it  does  not
+    ///  exist,  instead  of  failing.  Existing  files  are  never  truncated.
+    pub fn create_if_missing(mut self, create: bool) -> Self {
+        self.create_if_missing = create;
+        self
+    }
+
+    ///  Build  a  read-only  container.
+    ///
+    ///  Files  are  mapped  read-only;  in-memory  and  anonymous  storage  is
+    ///  [sealed](RawBytesContainer::seal)  after  being  set  up.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    ///  Use  `options`  for  mapping  the  file,  replacing  any  set  so  far.
+    pub fn map_options(mut self, options: MapOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    ///  Set  the  trailing-byte  policy  for  mapped  files.
+    pub fn trailing(mut self, trailing: TrailingBytes) -> Self {
+        self.options = self.options.trailing(trailing);
+        self
+    }
+
+    ///  Expect  and  verify  a  checksum  trailer  in  the  mapped  file.  For  in-memory
+    ///  storage,  [enable](RawBytesContainer::enable_checksum)  the  trailer  on  write.
+    pub fn checksum(mut self, checksum: bool) -> Self {
+        self.options = self.options.checksum(checksum);
+        self
+    }
+
+    ///  Take  `lock`  on  the  mapped  file,  waiting  until  it  is  available.
+    pub fn lock(mut self, lock: FileLock) -> Self {
+        self.options = self.options.lock(lock);
+        self
+    }
+
+    ///  Take  `lock`  on  the  mapped  file,  failing  with
+    ///  [`ContainerError::LockContended`]  if  it  is  held  elsewhere.
+    pub fn try_lock(mut self, lock: FileLock) -> Self {
+        self.options = self.options.try_lock(lock);
+        self
+    }
+
+    ///  Request  huge  pages  for  the  mapped  file.
+    pub fn huge_pages(mut self, huge_pages: bool) -> Self {
+        self.options = self.options.huge_pages(huge_pages);
+        self
+    }
+
+    ///  Pass  `advice`  to  the  kernel  once  the  mapping  is  set  up  (mapped  storage  only).
+    pub fn advice(mut self, advice: Advice) -> Self {
+        self.advice = Some(advice);
+        self
+    }
+
+    ///  Construct  the  container.
+    ///
+    ///  #  Errors
+    ///  Fails  if  the  backend  cannot  be  set  up,  or  if  an  option  does  not  apply  to  it,
+    ///  such  as  a  file  lock  without  a  file.
+    pub fn build(self) -> Result<RawBytesContainer<T>, ContainerError> {
+        let file_backed = matches!(self.backend, Backend::Mmap(_));
+        if !file_backed && self.options.lock != FileLock::None {
+            return Err(ContainerError::UnsupportedOperation(
+                "File  lock  requires  an  mmap  file  backend",
+            ));
+        }
+
+        let mut container = match &self.backend {
+            Backend::Memory => {
+                let mut data = Vec::with_capacity(self.capacity.max(self.len));
+                data.resize(self.len, T::zeroed());
+                let mut container = RawBytesContainer::from_vec(data);
+                if self.options.checksum {
+                    container.enable_checksum()?;
+                }
+                container
+            }
+            Backend::Anon => {
+                if self.options.checksum {
+                    return Err(ContainerError::UnsupportedOperation(
+                        "Checksum  not  supported  on  anonymous  mmap",
+                    ));
+                }
+                let byte_len = self.len.checked_mul(std::mem::size_of::<T>()).ok_or(
+                    ContainerError::UnsupportedOperation("Requested  mmap  length  overflows"),
+                )?;
+                let mut container = RawBytesContainer::from_vec(Vec::new());
+                container.storage = Storage::MmapRW(MmapOptions::new().len(byte_len).map_anon()?);
+                container
+            }
+            Backend::Mmap(path) => {
+                if self.create_if_missing {
+                    self.create_file(path)?;
+                }
+                if self.read_only {
+                    RawBytesContainer::open_mmap_read_with_options(path, self.options)?
+                } else {
+                    RawBytesContainer::open_mmap_rw_with_options(path, self.options)?
+                }
+            }
+        };
+
+        if self.read_only && !file_backed {
+            container.seal()?;
+        }
+        if let Some(advice) = self.advice {
+            container.advise(advice)?;
+        }
+        Ok(container)
+    }
+
+    ///  Create  `path`  holding  `len`  zeroed  elements,  unless  it  already  exists.
+    fn create_file(&self, path: &Path) -> Result<(), ContainerError> {
+        let byte_len = self.len.checked_mul(std::mem::size_of::<T>()).ok_or(
+            ContainerError::UnsupportedOperation("Requested  mmap  length  overflows"),
+        )?;
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(file) => Ok(file.set_len(byte_len as u64)?),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Start  building  a  container;  see  [`RawBytesContainerBuilder`].
+    pub fn builder() -> RawBytesContainerBuilder<T> {
+        RawBytesContainerBuilder::new()
+    }
+}
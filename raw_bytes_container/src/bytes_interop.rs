@@ -0,0 +1,82 @@
+use crate::{ContainerError, RawBytesContainer, Storage};
+use bytemuck::Pod;
+use bytes::{Bytes, BytesMut};
+use std::sync::Arc;
+
+///  Owner  that  lets  `Bytes`  borrow  a  container's  `Vec<T>`  without  copying  it.
+struct VecOwner<T>(Vec<T>);
+
+impl<T: Pod> AsRef<[u8]> for VecOwner<T> {
+    fn as_ref(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.0)
+    }
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Create  an  in-memory  container  from  a  `Bytes`  buffer,  e.g.  one  read  off  a  socket.
+    ///
+    ///  The  buffer  is  reused  without  copying  when  it  uniquely  owns  a  `Vec`-backed
+    ///  allocation  and  `T`  has  an  alignment  of  1;  otherwise  the  bytes  are  copied  once.
+    ///
+    ///  #  Errors
+    ///  Fails  if  the  length  is  not  a  multiple  of  `size_of::<T>()`.
+    pub fn from_bytes(buf: Bytes) -> Result<Self, ContainerError> {
+        Self::from_byte_vec(Vec::from(buf))
+    }
+
+    ///  Like  [`from_bytes`](Self::from_bytes),  for  a  `BytesMut`  buffer.
+    pub fn from_bytes_mut(buf: BytesMut) -> Result<Self, ContainerError> {
+        Self::from_byte_vec(Vec::from(buf))
+    }
+
+    fn from_byte_vec(bytes: Vec<u8>) -> Result<Self, ContainerError> {
+        if !bytes.len().is_multiple_of(std::mem::size_of::<T>()) {
+            return Err(ContainerError::AlignmentError(format!(
+                "{}  bytes  not  aligned  to  type  size  {}",
+                bytes.len(),
+                std::mem::size_of::<T>()
+            )));
+        }
+        let data = bytemuck::try_cast_vec(bytes)
+            .unwrap_or_else(|(_, bytes)| bytemuck::pod_collect_to_vec(&bytes));
+        Ok(Self::from_vec(data))
+    }
+
+    ///  Convert  into  a  `Bytes`  buffer  holding  the  elements.
+    ///
+    ///  In-memory,  borrowed  and  read-only  mmap  storage  is  handed  over  without  copying:
+    ///  the  `Bytes`  keeps  the  `Vec`  or  the  mapping  alive.  Read-write  and  copy-on-write
+    ///  mappings  are  copied,  since  `Bytes`  is  immutable  and  the  mapping  would  otherwise
+    ///  stay  writable  behind  it.  A  checksum  trailer  is  not  included.
+    pub fn into_bytes(self) -> Bytes
+    where
+        T: Send,
+    {
+        let len = std::mem::size_of_val(self.as_slice());
+        match self.storage {
+            Storage::InMemory(vec) => Bytes::from_owner(VecOwner(vec)),
+            Storage::Shared(vec) => Bytes::from_owner(VecOwner(Arc::unwrap_or_clone(vec))),
+            Storage::Borrowed(slice) => Bytes::from_static(bytemuck::cast_slice(slice)),
+            Storage::MmapRO(mmap) => Bytes::from_owner(mmap).slice(..len),
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => Bytes::copy_from_slice(&mmap[..len]),
+        }
+    }
+
+    ///  Convert  into  a  `BytesMut`  buffer  holding  the  elements.
+    ///
+    ///  Reuses  the  allocation  of  in-memory  storage  when  `T`  has  an  alignment  of  1;
+    ///  copies  otherwise.
+    pub fn into_bytes_mut(self) -> BytesMut {
+        let len = std::mem::size_of_val(self.as_slice());
+        match self.storage {
+            Storage::InMemory(vec) => match bytemuck::try_cast_vec::<T, u8>(vec) {
+                Ok(bytes) => BytesMut::from(Bytes::from(bytes)),
+                Err((_, vec)) => BytesMut::from(bytemuck::cast_slice::<T, u8>(&vec)),
+            },
+            Storage::Shared(vec) => BytesMut::from(bytemuck::cast_slice::<T, u8>(&vec)),
+            Storage::Borrowed(slice) => BytesMut::from(bytemuck::cast_slice::<T, u8>(slice)),
+            Storage::MmapRO(mmap) => BytesMut::from(&mmap[..len]),
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => BytesMut::from(&mmap[..len]),
+        }
+    }
+}
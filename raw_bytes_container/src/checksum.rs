@@ -0,0 +1,92 @@
+//!  CRC32  checksums  and  the  trailer  used  by  checksummed  containers.
+//!
+//!  ```text
+//!  [DATA][TRAILER:  "RBCK",  crc32:  u32,  data_len:  u64]
+//!  ```
+
+use crate::ContainerError;
+use bytemuck_derive::{Pod, Zeroable};
+
+///  Magic  identifying  a  checksum  trailer.
+pub const TRAILER_MAGIC: &[u8; 4] = b"RBCK";
+
+///  Size  of  the  checksum  trailer  in  bytes.
+pub const TRAILER_SIZE: usize = std::mem::size_of::<Trailer>();
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+///  CRC32  (IEEE)  of  `bytes`.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc = CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+///  Trailer  appended  after  the  data  of  a  checksummed  file.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct Trailer {
+    pub magic: [u8; 4],
+    pub crc32: u32,
+    pub data_len: u64,
+}
+
+impl Trailer {
+    ///  Trailer  describing  `data`.
+    pub fn for_data(data: &[u8]) -> Self {
+        Self {
+            magic: *TRAILER_MAGIC,
+            crc32: crc32(data),
+            data_len: data.len() as u64,
+        }
+    }
+
+    ///  Check  that  this  trailer  matches  `data`.
+    pub fn verify(&self, data: &[u8]) -> Result<(), ContainerError> {
+        if &self.magic != TRAILER_MAGIC {
+            return Err(ContainerError::InvalidFormat(
+                "missing  checksum  trailer".to_string(),
+            ));
+        }
+        if self.data_len != data.len() as u64 {
+            return Err(ContainerError::InvalidFormat(format!(
+                "checksum  trailer  covers  {}  bytes,  file  holds  {}",
+                self.data_len,
+                data.len()
+            )));
+        }
+        let actual = crc32(data);
+        if actual != self.crc32 {
+            return Err(ContainerError::ChecksumMismatch {
+                expected: self.crc32,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    ///  View  the  trailer  as  raw  bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(self)
+    }
+}
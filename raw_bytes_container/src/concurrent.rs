@@ -0,0 +1,160 @@
+use crate::{ContainerError, RawBytesContainer};
+use bytemuck::Pod;
+use std::{
+    ops::{Deref, DerefMut, Range},
+    sync::{Mutex, MutexGuard, TryLockError},
+};
+
+///  A  writable  container  split  into  fixed-size  regions,  each  behind  its  own  lock.
+///
+///  Threads  can  mutate  different  regions  in  parallel  through  `&self`,  which
+///  makes  it  possible  to  update  a  large  mapped  array  in  place  from  a  thread
+///  pool.  The  last  region  may  be  shorter  than  `region_len`.
+#[derive(Debug)]
+pub struct ConcurrentRawBytes<T: Pod> {
+    container: RawBytesContainer<T>,
+    ptr: *mut T,
+    len: usize,
+    region_len: usize,
+    locks: Vec<Mutex<()>>,
+}
+
+//  SAFETY:  the  raw  pointer  refers  to  storage  owned  by  `container`,  and  every
+//  access  through  it  happens  while  holding  the  lock  of  a  disjoint  region.
+unsafe impl<T: Pod + Send> Send for ConcurrentRawBytes<T> {}
+unsafe impl<T: Pod + Send> Sync for ConcurrentRawBytes<T> {}
+
+impl<T: Pod> ConcurrentRawBytes<T> {
+    ///  Wrap  a  mutable  container,  splitting  it  into  regions  of  `region_len`  elements.
+    ///
+    ///  Fails  if  the  container  is  read-only.
+    pub fn new(
+        mut container: RawBytesContainer<T>,
+        region_len: usize,
+    ) -> Result<Self, ContainerError> {
+        let slice = container.as_slice_mut_checked()?;
+        let (ptr, len) = (slice.as_mut_ptr(), slice.len());
+        let region_len = region_len.max(1);
+        let locks = (0..len.div_ceil(region_len))
+            .map(|_| Mutex::new(()))
+            .collect();
+
+        Ok(Self {
+            container,
+            ptr,
+            len,
+            region_len,
+            locks,
+        })
+    }
+
+    ///  Total  number  of  elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    ///  Returns  true  if  there  are  no  elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    ///  Number  of  regions.
+    pub fn region_count(&self) -> usize {
+        self.locks.len()
+    }
+
+    ///  Element  range  covered  by  region  `region`.
+    ///
+    ///  #  Panics
+    ///  Panics  if  `region`  is  out  of  bounds.
+    pub fn region_range(&self, region: usize) -> Range<usize> {
+        assert!(
+            region < self.locks.len(),
+            "region  {}  out  of  bounds",
+            region
+        );
+        let start = region * self.region_len;
+        start..(start + self.region_len).min(self.len)
+    }
+
+    ///  Region  containing  element  `index`.
+    pub fn region_of(&self, index: usize) -> Option<usize> {
+        (index < self.len).then(|| index / self.region_len)
+    }
+
+    ///  Lock  region  `region`  for  exclusive  access,  blocking  until  it  is  free.
+    ///
+    ///  #  Panics
+    ///  Panics  if  `region`  is  out  of  bounds.
+    pub fn lock_region(&self, region: usize) -> RegionGuard<'_, T> {
+        let range = self.region_range(region);
+        let guard = self.locks[region]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.guard(range, guard)
+    }
+
+    ///  Lock  region  `region`  if  it  is  not  currently  locked.
+    ///
+    ///  #  Panics
+    ///  Panics  if  `region`  is  out  of  bounds.
+    pub fn try_lock_region(&self, region: usize) -> Option<RegionGuard<'_, T>> {
+        let range = self.region_range(region);
+        let guard = match self.locks[region].try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+        Some(self.guard(range, guard))
+    }
+
+    fn guard<'a>(&'a self, range: Range<usize>, lock: MutexGuard<'a, ()>) -> RegionGuard<'a, T> {
+        //  SAFETY:  `range`  lies  within  the  storage,  which  outlives  `self`,  and  the
+        //  region  lock  held  by  the  guard  makes  this  the  only  live  reference  to  it.
+        let data =
+            unsafe { std::slice::from_raw_parts_mut(self.ptr.add(range.start), range.len()) };
+        RegionGuard {
+            data,
+            offset: range.start,
+            _lock: lock,
+        }
+    }
+
+    ///  Flush  the  underlying  mmap  to  disk.
+    pub fn flush(&self) -> Result<(), ContainerError> {
+        self.container.flush()
+    }
+
+    ///  Unwrap  the  container.
+    pub fn into_inner(self) -> RawBytesContainer<T> {
+        self.container
+    }
+}
+
+///  Exclusive  access  to  one  region  of  a  [`ConcurrentRawBytes`].
+#[derive(Debug)]
+pub struct RegionGuard<'a, T> {
+    data: &'a mut [T],
+    offset: usize,
+    _lock: MutexGuard<'a, ()>,
+}
+
+impl<T> RegionGuard<'_, T> {
+    ///  Index  of  the  region's  first  element  within  the  container.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<T> Deref for RegionGuard<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.data
+    }
+}
+
+impl<T> DerefMut for RegionGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.data
+    }
+}
@@ -1,19 +1,217 @@
-use crate::{ContainerError, Storage};
+use crate::{
+    BufferedAppender, ContainerError, ContainerStats, Storage,
+    access::AccessGuard,
+    checksum::{TRAILER_SIZE, Trailer},
+    dirty::{DirtyRanges, next_generation},
+    mmap_vec::MIN_GROW_BYTES,
+    spare::SpareCapacity,
+    stats::resident_bytes,
+};
 use bytemuck::Pod;
-use memmap2::{Mmap, MmapMut};
+use memmap2::{Mmap, MmapMut, MmapOptions};
 use std::{
-    fs::{File, OpenOptions},
-    ops::Deref,
+    fs::{File, OpenOptions, TryLockError},
+    io::{Read, Write},
+    ops::{Deref, Range, RangeBounds},
     path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
+///  What  to  do  when  a  mapped  file's  size  is  not  a  multiple  of  the  element  size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingBytes {
+    ///  Refuse  to  open  the  file.
+    #[default]
+    Reject,
+    ///  Map  only  the  whole  elements;  the  remainder  is  exposed  through
+    ///  [`RawBytesContainer::trailing_bytes`].
+    Ignore,
+}
+
+///  Options  for  opening  memory-mapped  files.
+///
+///  ```
+///  use  raw_bytes_container::{MapOptions,  TrailingBytes};
+///
+///  let  options  =  MapOptions::new()
+///          .trailing(TrailingBytes::Ignore)
+///          .huge_pages(true);
+///  assert!(options.huge_pages);
+///  ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MapOptions {
+    ///  What  to  do  with  bytes  after  the  last  whole  element.
+    pub trailing: TrailingBytes,
+    ///  Request  huge  pages  to  reduce  TLB  pressure  on  large  sequential  scans.
+    ///
+    ///  On  Linux  this  asks  for  transparent  huge  pages  (`MADV_HUGEPAGE`);  the
+    ///  kernel  may  ignore  it,  e.g.  for  file  systems  without  THP  support.  On
+    ///  other  platforms  the  flag  has  no  effect,  since  large  pages  cannot  be
+    ///  used  for  ordinary  file  mappings  there.
+    pub huge_pages: bool,
+    ///  Expect  a  checksum  trailer  after  the  data  and  verify  it  on  open.
+    ///
+    ///  See  [`RawBytesContainer::enable_checksum`].
+    pub checksum: bool,
+    ///  Advisory  lock  to  take  on  the  file  before  mapping  it.
+    ///
+    ///  See  [`FileLock`].
+    pub lock: FileLock,
+    ///  Fail  with  [`ContainerError::LockContended`]  instead  of  waiting  for  the  lock.
+    pub lock_nonblocking: bool,
+    ///  Remap  automatically  when  [`RawBytesContainer::fetch`]  runs  past  the  end.
+    ///
+    ///  See  [`RawBytesContainer::remap`].
+    pub auto_remap: bool,
+}
+
+impl MapOptions {
+    ///  Default  options:  reject  trailing  bytes,  regular  pages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///  Set  the  trailing-byte  policy.
+    pub fn trailing(mut self, trailing: TrailingBytes) -> Self {
+        self.trailing = trailing;
+        self
+    }
+
+    ///  Expect  (or  stop  expecting)  a  checksum  trailer.
+    pub fn checksum(mut self, checksum: bool) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    ///  Request  (or  stop  requesting)  huge  pages.
+    pub fn huge_pages(mut self, huge_pages: bool) -> Self {
+        self.huge_pages = huge_pages;
+        self
+    }
+
+    ///  Take  `lock`  on  the  file,  waiting  until  it  is  available.
+    pub fn lock(mut self, lock: FileLock) -> Self {
+        self.lock = lock;
+        self.lock_nonblocking = false;
+        self
+    }
+
+    ///  Take  `lock`  on  the  file,  failing  with  [`ContainerError::LockContended`]
+    ///  if  another  handle  holds  a  conflicting  lock.
+    pub fn try_lock(mut self, lock: FileLock) -> Self {
+        self.lock = lock;
+        self.lock_nonblocking = true;
+        self
+    }
+
+    ///  Follow  a  file  that  grows  while  mapped  by  remapping  in
+    ///  [`RawBytesContainer::fetch`].
+    pub fn auto_remap(mut self, auto_remap: bool) -> Self {
+        self.auto_remap = auto_remap;
+        self
+    }
+
+    ///  Lock  `file`  as  requested.
+    fn acquire_lock(&self, file: &File) -> Result<(), ContainerError> {
+        let contended = |e: TryLockError| match e {
+            TryLockError::WouldBlock => ContainerError::LockContended,
+            TryLockError::Error(e) => ContainerError::Io(e),
+        };
+        match (self.lock, self.lock_nonblocking) {
+            (FileLock::None, _) => {}
+            (FileLock::Shared, false) => file.lock_shared()?,
+            (FileLock::Exclusive, false) => file.lock()?,
+            (FileLock::Shared, true) => file.try_lock_shared().map_err(contended)?,
+            (FileLock::Exclusive, true) => file.try_lock().map_err(contended)?,
+        }
+        Ok(())
+    }
+}
+
+///  Advisory  lock  taken  on  a  mapped  file  (`flock`  on  unix,  `LockFileEx`  on  Windows).
+///
+///  The  lock  is  held  until  the  container  is  dropped.  It  only  guards  against
+///  other  processes  that  also  lock  the  file;  plain  reads  and  writes  are  not
+///  blocked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FileLock {
+    ///  Take  no  lock  (the  default).
+    #[default]
+    None,
+    ///  Shared  lock:  any  number  of  readers,  no  exclusive  holder.
+    Shared,
+    ///  Exclusive  lock:  a  single  holder,  no  shared  holders.
+    Exclusive,
+}
+
+///  Access-pattern  hint  for  mmap  storage,  passed  to  `madvise`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advice {
+    ///  No  special  treatment  (the  default).
+    Normal,
+    ///  Expect  sequential  access;  read  ahead  aggressively.
+    Sequential,
+    ///  Expect  random  access;  read-ahead  is  less  useful.
+    Random,
+    ///  Expect  access  in  the  near  future.
+    WillNeed,
+    ///  Do  not  expect  access  in  the  near  future.
+    DontNeed,
+}
+
+#[cfg(unix)]
+impl Advice {
+    ///  Convert  to  the  memmap2  value.
+    ///
+    ///  Callers  must  not  pass  [`Advice::DontNeed`]  for  private  (copy-on-write)
+    ///  mappings,  where  it  would  silently  discard  modifications.
+    fn to_memmap2(self) -> memmap2::Advice {
+        match self {
+            Advice::Normal => memmap2::Advice::normal(),
+            Advice::Sequential => memmap2::Advice::sequential(),
+            Advice::Random => memmap2::Advice::random(),
+            Advice::WillNeed => memmap2::Advice::will_need(),
+            //  SAFETY:  on  shared  file  mappings  the  kernel  reloads  dropped  pages  from  the
+            //  page  cache,  so  the  contents  seen  through  existing  references  do  not  change.
+            Advice::DontNeed => unsafe { memmap2::Advice::dont_need() },
+        }
+    }
+}
+
 ///  High-level  container  for  Pod  types
 ///
 ///  A  `RawBytesContainer<T>`  can  store  items  in  memory  (`Vec<T>`),
 ///  or  as  a  memory-mapped  file  (read-only  or  read-write).
+///  It  can  also  wrap  a  `&'static  [T]`,  such  as  an  embedded  asset,  without  copying.
 #[derive(Debug)]
 pub struct RawBytesContainer<T: Pod> {
-    storage: Storage<T>,
+    pub(crate) storage: Storage<T>,
+    ///  Whether  the  data  is  followed  by  a  checksum  trailer.
+    pub(crate) checksummed: bool,
+    ///  The  mapped  file  (mmap  RO/RW  only),  kept  open  for  advisory  locks  and  remapping.
+    pub(crate) file: Option<File>,
+    ///  Trailing-byte  policy  to  apply  again  on  remap.
+    trailing: TrailingBytes,
+    ///  Whether  `fetch`  remaps  when  it  runs  past  the  end.
+    auto_remap: bool,
+    ///  Whether  in-memory  storage  must  never  reallocate  (see  `with_reserved`).
+    pinned: bool,
+    ///  Whether  read-write  mapped  storage  is  temporarily  read-only  (see  `protect_read_only`).
+    protected: bool,
+    ///  Set  on  mutable  access,  cleared  when  the  data  is  flushed  or  written  out.
+    pub(crate) dirty: AtomicBool,
+    ///  Byte  ranges  mutated  since  the  last  flush,  once  tracking  is  enabled  (mmap  RW  only).
+    pub(crate) dirty_ranges: Option<Mutex<DirtyRanges>>,
+    ///  Changes  on  every  mutable  access  and  is  never  reset  (see  `change_generation`).
+    pub(crate) generation: u64,
+    ///  Room  mapped  past  the  last  element  by  `append_mapped`  (mmap  RW  only).  Declared
+    ///  after  `storage`  so  the  file  is  trimmed  once  it  is  unmapped.
+    pub(crate) spare: Option<SpareCapacity>,
+    ///  In-process  registration  from  the  exclusive/shared  open  methods.
+    access: Option<AccessGuard>,
 }
 
 impl<T: Pod> RawBytesContainer<T> {
@@ -21,6 +219,17 @@ impl<T: Pod> RawBytesContainer<T> {
     pub fn from_slice(data: &[T]) -> Self {
         Self {
             storage: Storage::InMemory(data.to_vec()),
+            checksummed: false,
+            file: None,
+            trailing: TrailingBytes::Reject,
+            auto_remap: false,
+            pinned: false,
+            protected: false,
+            dirty: AtomicBool::new(false),
+            dirty_ranges: None,
+            generation: 0,
+            spare: None,
+            access: None,
         }
     }
 
@@ -28,81 +237,618 @@ impl<T: Pod> RawBytesContainer<T> {
     pub fn from_vec(data: Vec<T>) -> Self {
         Self {
             storage: Storage::InMemory(data),
+            checksummed: false,
+            file: None,
+            trailing: TrailingBytes::Reject,
+            auto_remap: false,
+            pinned: false,
+            protected: false,
+            dirty: AtomicBool::new(false),
+            dirty_ranges: None,
+            generation: 0,
+            spare: None,
+            access: None,
+        }
+    }
+
+    ///  Create  an  empty  in-memory  container  that  never  reallocates.
+    ///
+    ///  Room  for  at  least  `max_len`  elements  is  allocated  up  front,  rounded  up  to  whole
+    ///  4  KiB  pages.  Growth  within  that  capacity  never  moves  the  data,  so  pointers  and
+    ///  slices  into  it  stay  valid  across  appends;  growth  beyond  it  fails  with
+    ///  [`ContainerError::UnsupportedOperation`]  instead  of  reallocating.  Large
+    ///  reservations  are  cheap  on  systems  that  commit  memory  on  first  touch,  so  reserving
+    ///  e.g.  1  GiB  only  costs  the  pages  actually  written.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  mut  container  =  RawBytesContainer::<u64>::with_reserved(1000);
+    ///  container.append(&[1,  2,  3]).unwrap();
+    ///  let  first  =  container.as_slice().as_ptr();
+    ///  container.resize(1000,  0).unwrap();
+    ///  assert_eq!(container.as_slice().as_ptr(),  first);
+    ///  ```
+    pub fn with_reserved(max_len: usize) -> Self {
+        const PAGE_SIZE: usize = 4096;
+        let size = std::mem::size_of::<T>().max(1);
+        let bytes = max_len.saturating_mul(size).next_multiple_of(PAGE_SIZE);
+        let mut container = Self::from_vec(Vec::with_capacity(bytes / size));
+        container.pinned = true;
+        container
+    }
+
+    ///  Returns  true  if  created  with  [`with_reserved`](Self::with_reserved).
+    pub fn is_reserved(&self) -> bool {
+        self.pinned
+    }
+
+    ///  Wrap  a  static  slice  without  copying  (read-only).
+    ///
+    ///  For  shorter-lived  borrows  use  [`view`](Self::view)  on  an  owning  container.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  static  TABLE:  [u32;  3]  =  [1,  2,  3];
+    ///  let  container  =  RawBytesContainer::from_static(&TABLE);
+    ///  assert_eq!(container.as_slice(),  &[1,  2,  3]);
+    ///  assert!(!container.is_mutable());
+    ///  ```
+    pub fn from_static(data: &'static [T]) -> Self {
+        Self {
+            storage: Storage::Borrowed(data),
+            checksummed: false,
+            file: None,
+            trailing: TrailingBytes::Reject,
+            auto_remap: false,
+            pinned: false,
+            protected: false,
+            dirty: AtomicBool::new(false),
+            dirty_ranges: None,
+            generation: 0,
+            spare: None,
+            access: None,
+        }
+    }
+
+    ///  Wrap  static  bytes,  e.g.  from  `include_bytes!`,  as  `[T]`  without  copying.
+    ///
+    ///  Fails  with  [`ContainerError::AlignmentError`]  if  the  length  is  not  a
+    ///  multiple  of  `size_of::<T>()`  or  the  bytes  are  not  aligned  for  `T`.
+    ///  `include_bytes!`  only  guarantees  byte  alignment,  so  for  wider  `T`  embed
+    ///  the  bytes  in  an  aligned  wrapper  or  fall  back  to
+    ///  [`from_slice`](Self::from_slice)  with  a  copy.
+    pub fn from_static_bytes(bytes: &'static [u8]) -> Result<Self, ContainerError> {
+        let data = bytemuck::try_cast_slice(bytes).map_err(|e| {
+            ContainerError::AlignmentError(format!(
+                "{}  static  bytes  cannot  be  viewed  as  {}:  {:?}",
+                bytes.len(),
+                std::any::type_name::<T>(),
+                e
+            ))
+        })?;
+        Ok(Self::from_static(data))
+    }
+
+    ///  Take  ownership  of  a  byte  buffer,  e.g.  a  file  read  with  `std::fs::read`,  as
+    ///  in-memory  `[T]`  without  copying.
+    ///
+    ///  The  allocation  is  reused  through  [`bytemuck::allocation::try_cast_vec`],  which
+    ///  needs  the  length  and  capacity  to  be  multiples  of  `size_of::<T>()`  and  `T`  to
+    ///  have  the  byte  alignment  a  `Vec<u8>`  is  allocated  with.  That  holds  for  byte
+    ///  arrays  and  other  `T`  with  an  alignment  of  1;  for  wider  `T`,  copy  with
+    ///  [`from_slice`](Self::from_slice)  after  `bytemuck::pod_collect_to_vec`.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  rgb  =  RawBytesContainer::<[u8;  3]>::from_bytes_validated(vec![255,  0,  0,  0,  0,  255]).unwrap();
+    ///  assert_eq!(rgb.as_slice(),  &[[255,  0,  0],  [0,  0,  255]]);
+    ///
+    ///  let  err  =  RawBytesContainer::<[u8;  3]>::from_bytes_validated(vec![0;  4]).unwrap_err();
+    ///  assert!(err.to_string().contains("4  bytes"));
+    ///  ```
+    ///
+    ///  #  Errors
+    ///  [`ContainerError::AlignmentError`]  naming  the  offending  length,  capacity,
+    ///  element  size  and  alignment.
+    pub fn from_bytes_validated(bytes: Vec<u8>) -> Result<Self, ContainerError> {
+        let (size, align) = (std::mem::size_of::<T>(), std::mem::align_of::<T>());
+        let type_name = std::any::type_name::<T>();
+        if size == 0 || !bytes.len().is_multiple_of(size) {
+            return Err(ContainerError::AlignmentError(format!(
+                "{}  bytes  is  not  a  whole  number  of  {}  ({}  bytes  each)",
+                bytes.len(),
+                type_name,
+                size
+            )));
         }
+        let (len, capacity) = (bytes.len(), bytes.capacity());
+        let data = bytemuck::allocation::try_cast_vec(bytes).map_err(|(e, _)| {
+            ContainerError::AlignmentError(format!(
+                "cannot  reuse  {}  bytes  (capacity  {})  as  {}  (size  {},  align  {})  without  copying:  {:?}",
+                len, capacity, type_name, size, align, e
+            ))
+        })?;
+        Ok(Self::from_vec(data))
     }
 
     ///  Open  a  read-only  memory-mapped  file.
     pub fn open_mmap_read<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Self::open_mmap_read_with(path, TrailingBytes::Reject)
+    }
+
+    ///  Open  a  read-only  memory-mapped  file  with  an  explicit  trailing-byte  policy.
+    ///
+    ///  With  [`TrailingBytes::Ignore`],  a  file  whose  size  is  not  a  multiple  of
+    ///  `size_of::<T>()`  (e.g.  because  another  process  is  mid-append)  is  accepted;
+    ///  the  partial  element  is  excluded  from  the  slice  and  available  through
+    ///  [`trailing_bytes`](Self::trailing_bytes).
+    pub fn open_mmap_read_with<P: AsRef<Path>>(
+        path: P,
+        trailing: TrailingBytes,
+    ) -> Result<Self, ContainerError> {
+        Self::open_mmap_read_with_options(path, MapOptions::new().trailing(trailing))
+    }
+
+    ///  Open  a  read-only  memory-mapped  file  with  the  given  [`MapOptions`].
+    pub fn open_mmap_read_with_options<P: AsRef<Path>>(
+        path: P,
+        options: MapOptions,
+    ) -> Result<Self, ContainerError> {
         let file = File::open(path)?;
+        options.acquire_lock(&file)?;
         let mmap = unsafe { Mmap::map(&file)? };
+        let data_len = Self::payload_len(mmap.len(), options.checksum)?;
+        Self::check_mapping(mmap.as_ptr(), data_len, options.trailing)?;
 
-        //  Alignment  check
-        if mmap.len() % std::mem::size_of::<T>() != 0 {
-            return Err(ContainerError::AlignmentError(format!(
-                "File  size  {}  not  aligned  to  type  size  {}",
-                mmap.len(),
-                std::mem::size_of::<T>()
-            )));
+        let container = Self {
+            storage: Storage::MmapRO(mmap),
+            checksummed: options.checksum,
+            file: Some(file),
+            trailing: options.trailing,
+            auto_remap: options.auto_remap,
+            pinned: false,
+            protected: false,
+            dirty: AtomicBool::new(false),
+            dirty_ranges: None,
+            generation: 0,
+            spare: None,
+            access: None,
+        };
+        if options.checksum {
+            container.verify()?;
         }
+        if options.huge_pages {
+            container.request_huge_pages();
+        }
+        Ok(container)
+    }
 
-        if (mmap.as_ptr() as usize) & (std::mem::align_of::<T>() - 1) != 0 {
+    ///  Read  a  whole  file  into  an  in-memory  container.
+    ///
+    ///  Fallback  for  files  that  cannot  be  mapped  as  `[T]`:  the  data  is  copied
+    ///  into  a  properly  aligned  `Vec<T>`,  so  the  file's  address  alignment  does
+    ///  not  matter.  Fails  if  the  file  size  is  not  a  multiple  of  `size_of::<T>()`.
+    pub fn open_read_copied<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Self::open_read_copied_with(path, TrailingBytes::Reject)
+    }
+
+    ///  Like  [`open_read_copied`](Self::open_read_copied),  with  an  explicit
+    ///  trailing-byte  policy.
+    ///
+    ///  With  [`TrailingBytes::Ignore`],  bytes  after  the  last  whole  element  are
+    ///  not  read;  in-memory  containers  do  not  keep  them,  so
+    ///  [`trailing_bytes`](Self::trailing_bytes)  is  empty  afterwards.
+    pub fn open_read_copied_with<P: AsRef<Path>>(
+        path: P,
+        trailing: TrailingBytes,
+    ) -> Result<Self, ContainerError> {
+        let mut file = File::open(path)?;
+        let byte_len = file.metadata()?.len() as usize;
+        let size = std::mem::size_of::<T>();
+
+        if trailing == TrailingBytes::Reject && !byte_len.is_multiple_of(size) {
             return Err(ContainerError::AlignmentError(format!(
-                "Memory  map  address  not  aligned  to  type  alignment  {}",
-                std::mem::align_of::<T>()
+                "File  size  {}  not  aligned  to  type  size  {}",
+                byte_len, size
             )));
         }
 
-        Ok(Self {
-            storage: Storage::MmapRO(mmap),
-        })
+        let mut data = vec![T::zeroed(); byte_len / size];
+        file.read_exact(bytemuck::cast_slice_mut(&mut data))?;
+        Ok(Self::from_vec(data))
     }
 
     ///  Open  a  read-write  memory-mapped  file.
     pub fn open_mmap_rw<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Self::open_mmap_rw_with(path, TrailingBytes::Reject)
+    }
+
+    ///  Create  (or  truncate)  a  file  holding  `len`  zeroed  elements  and  map  it  read-write.
+    ///
+    ///  Lets  typed  files  be  built  directly  on  disk  instead  of  writing  an
+    ///  in-memory  container  out  afterwards.  The  file  is  only  extended  to  its  logical
+    ///  size,  not  written,  so  on  filesystems  with  sparse  file  support  it  takes  no  disk
+    ///  space  until  elements  are  stored;  see  [`punch_hole`](Self::punch_hole)  and
+    ///  [`allocated_bytes`](Self::allocated_bytes).
+    pub fn create_mmap_rw<P: AsRef<Path>>(path: P, len: usize) -> Result<Self, ContainerError> {
+        let byte_len = len.checked_mul(std::mem::size_of::<T>()).ok_or(
+            ContainerError::UnsupportedOperation("Requested  mmap  length  overflows"),
+        )?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(byte_len as u64)?;
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        Self::check_mapping(mmap.as_ptr(), mmap.len(), TrailingBytes::Reject)?;
+
+        Ok(Self {
+            storage: Storage::MmapRW(mmap),
+            checksummed: false,
+            file: Some(file),
+            trailing: TrailingBytes::Reject,
+            auto_remap: false,
+            pinned: false,
+            protected: false,
+            dirty: AtomicBool::new(false),
+            dirty_ranges: None,
+            generation: 0,
+            spare: None,
+            access: None,
+        })
+    }
+
+    ///  Open  a  read-write  memory-mapped  file  with  an  explicit  trailing-byte  policy.
+    ///
+    ///  See  [`open_mmap_read_with`](Self::open_mmap_read_with).  Trailing  bytes  are
+    ///  left  untouched  on  disk.
+    pub fn open_mmap_rw_with<P: AsRef<Path>>(
+        path: P,
+        trailing: TrailingBytes,
+    ) -> Result<Self, ContainerError> {
+        Self::open_mmap_rw_with_options(path, MapOptions::new().trailing(trailing))
+    }
+
+    ///  Open  a  read-write  memory-mapped  file  with  the  given  [`MapOptions`].
+    pub fn open_mmap_rw_with_options<P: AsRef<Path>>(
+        path: P,
+        options: MapOptions,
+    ) -> Result<Self, ContainerError> {
         let file = OpenOptions::new().read(true).write(true).open(path)?;
+        options.acquire_lock(&file)?;
         let mmap = unsafe { MmapMut::map_mut(&file)? };
+        let data_len = Self::payload_len(mmap.len(), options.checksum)?;
+        Self::check_mapping(mmap.as_ptr(), data_len, options.trailing)?;
+
+        let container = Self {
+            storage: Storage::MmapRW(mmap),
+            checksummed: options.checksum,
+            file: Some(file),
+            trailing: options.trailing,
+            auto_remap: options.auto_remap,
+            pinned: false,
+            protected: false,
+            dirty: AtomicBool::new(false),
+            dirty_ranges: None,
+            generation: 0,
+            spare: None,
+            access: None,
+        };
+        if options.checksum {
+            container.verify()?;
+        }
+        if options.huge_pages {
+            container.request_huge_pages();
+        }
+        Ok(container)
+    }
+
+    ///  Open  a  read-write  memory-mapped  file  as  its  only  writer.
+    ///
+    ///  Takes  an  exclusive  advisory  lock  on  the  file  without  waiting,  and  registers  the
+    ///  file  as  open  for  writing  in  this  process.  Any  other  handle  on  the  file,  from  this
+    ///  or  another  process,  opened  through  this  method  or
+    ///  [`open_mmap_read_shared`](Self::open_mmap_read_shared)  is  refused  with
+    ///  [`ContainerError::LockContended`]  until  the  container  is  dropped.
+    ///
+    ///  This  is  the  supported  way  to  mutate  a  file  other  processes  may  read:  writers  use
+    ///  this  method,  readers  use  `open_mmap_read_shared`,  and  neither  ever  observes  the
+    ///  other  mid-update.  Handles  opened  through  the  plain  `open_*`  methods  take  part  in
+    ///  neither  check.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::{ContainerError,  RawBytesContainer};
+    ///
+    ///  let  file  =  tempfile::NamedTempFile::new().unwrap();
+    ///  std::fs::write(file.path(),  [0u8;  16]).unwrap();
+    ///
+    ///  let  writer  =  RawBytesContainer::<u32>::open_mmap_rw_exclusive(file.path()).unwrap();
+    ///  assert!(matches!(
+    ///          RawBytesContainer::<u32>::open_mmap_read_shared(file.path()),
+    ///          Err(ContainerError::LockContended)
+    ///  ));
+    ///  drop(writer);
+    ///  let  reader  =  RawBytesContainer::<u32>::open_mmap_read_shared(file.path()).unwrap();
+    ///  assert_eq!(reader.len(),  4);
+    ///  ```
+    pub fn open_mmap_rw_exclusive<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        let access = AccessGuard::acquire(path.as_ref(), true)?;
+        let mut container =
+            Self::open_mmap_rw_with_options(path, MapOptions::new().try_lock(FileLock::Exclusive))?;
+        container.access = Some(access);
+        Ok(container)
+    }
 
-        if mmap.len() % std::mem::size_of::<T>() != 0 {
+    ///  Open  a  read-only  memory-mapped  file  shared  with  other  readers.
+    ///
+    ///  Takes  a  shared  advisory  lock  without  waiting  and  registers  the  file  as  open  for
+    ///  reading  in  this  process.  Fails  with  [`ContainerError::LockContended`]  while  a
+    ///  container  from  [`open_mmap_rw_exclusive`](Self::open_mmap_rw_exclusive)  exists
+    ///  for  the  same  file.
+    pub fn open_mmap_read_shared<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        let access = AccessGuard::acquire(path.as_ref(), false)?;
+        let mut container =
+            Self::open_mmap_read_with_options(path, MapOptions::new().try_lock(FileLock::Shared))?;
+        container.access = Some(access);
+        Ok(container)
+    }
+
+    ///  Ask  the  kernel  to  back  the  mapping  with  transparent  huge  pages.
+    ///
+    ///  Best  effort:  kernels  without  (file-backed)  THP  support  reject  the
+    ///  request,  which  is  not  an  error  since  the  mapping  works  either  way.
+    fn request_huge_pages(&self) {
+        #[cfg(target_os = "linux")]
+        {
+            let _ = match &self.storage {
+                Storage::MmapRO(mmap) => mmap.advise(memmap2::Advice::huge_page()),
+                Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => {
+                    mmap.advise(memmap2::Advice::huge_page())
+                }
+                Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => Ok(()),
+            };
+        }
+    }
+
+    ///  Open  a  memory-mapped  file  copy-on-write  (`MAP_PRIVATE`).
+    ///
+    ///  The  data  can  be  mutated  in  memory,  but  changes  are  private  to  this
+    ///  mapping  and  never  reach  the  file  on  disk.  [`flush`](Self::flush)  returns
+    ///  an  error  for  this  storage;  use  [`write_to_file`](Self::write_to_file)  to
+    ///  save  the  modified  data  elsewhere.
+    pub fn open_mmap_cow<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        let file = File::open(path)?;
+        let mmap = unsafe { MmapOptions::new().map_copy(&file)? };
+        Self::check_mapping(mmap.as_ptr(), mmap.len(), TrailingBytes::Reject)?;
+
+        Ok(Self {
+            storage: Storage::MmapCow(mmap),
+            checksummed: false,
+            file: None,
+            trailing: TrailingBytes::Reject,
+            auto_remap: false,
+            pinned: false,
+            protected: false,
+            dirty: AtomicBool::new(false),
+            dirty_ranges: None,
+            generation: 0,
+            spare: None,
+            access: None,
+        })
+    }
+
+    ///  Validate  the  size  and  address  of  a  fresh  mapping  for  `T`.
+    fn check_mapping(
+        ptr: *const u8,
+        len: usize,
+        trailing: TrailingBytes,
+    ) -> Result<(), ContainerError> {
+        if trailing == TrailingBytes::Reject && !len.is_multiple_of(std::mem::size_of::<T>()) {
             return Err(ContainerError::AlignmentError(format!(
                 "File  size  {}  not  aligned  to  type  size  {}",
-                mmap.len(),
+                len,
                 std::mem::size_of::<T>()
             )));
         }
 
-        if !(mmap.as_ptr() as usize).is_multiple_of(std::mem::align_of::<T>()) {
-            //if (mmap.as_ptr() as usize) % std::mem::align_of::<T>() != 0 {
+        if !(ptr as usize).is_multiple_of(std::mem::align_of::<T>()) {
             return Err(ContainerError::AlignmentError(format!(
                 "Memory  map  address  not  aligned  to  type  alignment  {}",
                 std::mem::align_of::<T>()
             )));
         }
 
-        Ok(Self {
-            storage: Storage::MmapRW(mmap),
-        })
+        Ok(())
+    }
+
+    ///  Length  in  bytes  of  the  whole  elements  in  a  mapping  of  `len`  bytes.
+    fn whole_elements(len: usize, checksummed: bool) -> usize {
+        let len = Self::data_len(len, checksummed);
+        len - len % std::mem::size_of::<T>()
+    }
+
+    ///  Length  in  bytes  of  a  mapping  of  `len`  bytes,  minus  any  checksum  trailer.
+    fn data_len(len: usize, checksummed: bool) -> usize {
+        if checksummed { len - TRAILER_SIZE } else { len }
+    }
+
+    ///  Bytes  of  a  writable  mapping  of  `len`  bytes  that  hold  data,  leaving  out  the  spare
+    ///  room  from  [`append_mapped`](Self::append_mapped).
+    fn in_use(spare: &Option<SpareCapacity>, len: usize) -> usize {
+        spare.as_ref().map_or(len, |spare| spare.len)
+    }
+
+    ///  Like  `data_len`,  but  fails  if  a  checksummed  mapping  has  no  room  for  the  trailer.
+    fn payload_len(len: usize, checksummed: bool) -> Result<usize, ContainerError> {
+        if checksummed && len < TRAILER_SIZE {
+            return Err(ContainerError::InvalidFormat(
+                "File  too  small  for  checksum  trailer".to_string(),
+            ));
+        }
+        Ok(Self::data_len(len, checksummed))
+    }
+
+    ///  Bytes  after  the  last  whole  element  (only  non-empty  for  mmap  storage
+    ///  opened  with  [`TrailingBytes::Ignore`]).
+    pub fn trailing_bytes(&self) -> &[u8] {
+        match &self.storage {
+            Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => &[],
+            Storage::MmapRO(mmap) => self.trailing_range(mmap),
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => {
+                self.trailing_range(&mmap[..Self::in_use(&self.spare, mmap.len())])
+            }
+        }
+    }
+
+    fn trailing_range<'a>(&self, mmap: &'a [u8]) -> &'a [u8] {
+        let end = Self::data_len(mmap.len(), self.checksummed);
+        &mmap[Self::whole_elements(mmap.len(), self.checksummed)..end]
+    }
+
+    ///  Re-check  the  file  size  and  remap  if  it  changed  (mmap  read-only  and  read-write  only).
+    ///
+    ///  Lets  a  reader  follow  a  typed  log  that  another  process  appends  to:  after
+    ///  remapping,  the  new  elements  are  visible  through  [`as_slice`](Self::as_slice).
+    ///  A  file  that  shrank  is  remapped  too,  so  the  mapping  never  extends  past  its  end.
+    ///  The  trailing-byte  policy  and  checksum  setting  from  opening  apply  again;  with  a
+    ///  checksum,  the  trailer  is  verified  after  remapping.
+    ///
+    ///  Returns  whether  the  mapping  changed.  Any  outstanding  borrows  of  the  data  end
+    ///  before  this  can  be  called,  since  it  takes  `&mut  self`.
+    pub fn remap(&mut self) -> Result<bool, ContainerError> {
+        let unsupported = ContainerError::UnsupportedOperation(
+            "Remap  only  supported  on  mmap  RO  and  RW  opened  from  a  file",
+        );
+        let (Some(file), Storage::MmapRO(_) | Storage::MmapRW(_)) = (&self.file, &self.storage)
+        else {
+            return Err(unsupported);
+        };
+        //  Give  up  the  room  from  `append_mapped`,  so  the  file  ends  at  the  last  element
+        if let Some(spare) = self.spare.take() {
+            file.set_len(spare.len as u64)?;
+        }
+        let file_len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| ContainerError::UnsupportedOperation("File  too  large  to  map"))?;
+        let mapped_len = match &self.storage {
+            Storage::MmapRO(mmap) => mmap.len(),
+            Storage::MmapRW(mmap) => mmap.len(),
+            _ => unreachable!(),
+        };
+        if file_len == mapped_len {
+            return Ok(false);
+        }
+
+        let data_len = Self::payload_len(file_len, self.checksummed)?;
+        self.storage = match self.storage {
+            Storage::MmapRO(_) => {
+                let mmap = unsafe { Mmap::map(file)? };
+                Self::check_mapping(mmap.as_ptr(), data_len, self.trailing)?;
+                Storage::MmapRO(mmap)
+            }
+            _ => {
+                let mmap = unsafe { MmapMut::map_mut(file)? };
+                Self::check_mapping(mmap.as_ptr(), data_len, self.trailing)?;
+                Storage::MmapRW(mmap)
+            }
+        };
+        if self.checksummed {
+            self.verify()?;
+        }
+        Ok(true)
+    }
+
+    ///  Like  [`get`](Self::get),  but  remaps  first  when  `index`  is  past  the  end  and  the
+    ///  container  was  opened  with  [`MapOptions::auto_remap`].
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::{MapOptions,  RawBytesContainer,  TrailingBytes};
+    ///  use  std::io::Write;
+    ///
+    ///  let  mut  log  =  tempfile::NamedTempFile::new().unwrap();
+    ///  log.write_all(&1u32.to_ne_bytes()).unwrap();
+    ///
+    ///  let  options  =  MapOptions::new().trailing(TrailingBytes::Ignore).auto_remap(true);
+    ///  let  mut  reader  =
+    ///          RawBytesContainer::<u32>::open_mmap_read_with_options(log.path(),  options).unwrap();
+    ///  assert_eq!(reader.fetch(1).unwrap(),  None);
+    ///
+    ///  log.write_all(&2u32.to_ne_bytes()).unwrap();
+    ///  assert_eq!(reader.fetch(1).unwrap(),  Some(&2));
+    ///  ```
+    pub fn fetch(&mut self, index: usize) -> Result<Option<&T>, ContainerError> {
+        if self.auto_remap && index >= self.len() {
+            self.remap()?;
+        }
+        Ok(self.get(index))
     }
 
     ///  Check  if  this  container  supports  mutation.
     pub fn is_mutable(&self) -> bool {
-        matches!(self.storage, Storage::InMemory(_) | Storage::MmapRW(_))
+        matches!(
+            self.storage,
+            Storage::InMemory(_) | Storage::Shared(_) | Storage::MmapRW(_) | Storage::MmapCow(_)
+        )
     }
 
     ///  Get  a  read-only  slice  over  the  data.
     pub fn as_slice(&self) -> &[T] {
         match &self.storage {
             Storage::InMemory(vec) => vec,
-            Storage::MmapRO(mmap) => bytemuck::cast_slice(mmap),
-            Storage::MmapRW(mmap) => bytemuck::cast_slice(&mmap[..]),
+            Storage::Shared(vec) => vec,
+            Storage::Borrowed(slice) => slice,
+            Storage::MmapRO(mmap) => {
+                bytemuck::cast_slice(&mmap[..Self::whole_elements(mmap.len(), self.checksummed)])
+            }
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => {
+                let len = Self::in_use(&self.spare, mmap.len());
+                bytemuck::cast_slice(&mmap[..Self::whole_elements(len, self.checksummed)])
+            }
         }
     }
 
     ///  Get  a  mutable  slice,  if  storage  is  writable.
+    ///
+    ///  With  [`enable_dirty_tracking`](Self::enable_dirty_tracking),  this  marks  every  element
+    ///  as  dirty;  [`get_mut`](Self::get_mut)  and  [`view_mut`](Self::view_mut)  mark  only  what
+    ///  they  hand  out.
     pub fn as_slice_mut(&mut self) -> Option<&mut [T]> {
+        self.mark_dirty(0..self.len());
+        self.slice_mut_unmarked()
+    }
+
+    ///  Record  a  mutation  of  the  elements  in  `range`  (no-op  on  read-only  storage).
+    pub(crate) fn mark_dirty(&mut self, range: Range<usize>) {
+        if !self.is_mutable() {
+            return;
+        }
+        *self.dirty.get_mut() = true;
+        self.generation = next_generation();
+        if let Some(ranges) = &mut self.dirty_ranges {
+            let size = std::mem::size_of::<T>();
+            ranges
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(range.start * size..range.end * size);
+        }
+    }
+
+    ///  The  mutable  slice,  without  marking  anything  dirty.  Callers  mark  what  they  touch.
+    pub(crate) fn slice_mut_unmarked(&mut self) -> Option<&mut [T]> {
+        let checksummed = self.checksummed;
         match &mut self.storage {
             Storage::InMemory(vec) => Some(vec),
-            Storage::MmapRW(mmap) => Some(bytemuck::cast_slice_mut(&mut mmap[..])),
-            Storage::MmapRO(_) => None,
+            Storage::Shared(vec) => Some(Arc::make_mut(vec).as_mut_slice()),
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => {
+                let end = Self::whole_elements(Self::in_use(&self.spare, mmap.len()), checksummed);
+                Some(bytemuck::cast_slice_mut(&mut mmap[..end]))
+            }
+            Storage::MmapRO(_) | Storage::Borrowed(_) => None,
         }
     }
 
@@ -116,8 +862,12 @@ impl<T: Pod> RawBytesContainer<T> {
 
     ///  Append  new  items  (only  works  on  in-memory  storage).
     pub fn append(&mut self, new: &[T]) -> Result<(), ContainerError> {
+        self.unshare();
         match &mut self.storage {
             Storage::InMemory(vec) => {
+                Self::check_reserved(self.pinned, vec, vec.len().saturating_add(new.len()))?;
+                *self.dirty.get_mut() = true;
+                self.generation = next_generation();
                 vec.extend_from_slice(new);
                 Ok(())
             }
@@ -127,13 +877,101 @@ impl<T: Pod> RawBytesContainer<T> {
         }
     }
 
+    ///  Append  to  a  read-write  mapping  by  extending  the  file  and  remapping  it.
+    ///
+    ///  Lets  a  long-running  recorder  stream  elements  straight  into  a  mapped  capture
+    ///  file.  Like  [`MmapVec`](crate::mmap_vec::MmapVec),  the  file  grows  geometrically,  so
+    ///  most  appends  only  copy  the  new  elements;  the  spare  room  past  the  last  element
+    ///  is  cut  off  again  by  [`flush`](Self::flush)  and  when  the  container  is  dropped.
+    ///  Until  then  the  file  on  disk  ends  in  zeroed  spare  elements.
+    ///
+    ///  #  Errors
+    ///  Returns  [`ContainerError::UnsupportedOperation`]  unless  the  storage  is  a
+    ///  read-write  mapping  opened  from  a  file,  without  a  checksum  trailer  or  trailing
+    ///  bytes  after  the  last  whole  element.
+    pub fn append_mapped(&mut self, new: &[T]) -> Result<(), ContainerError> {
+        let (Some(file), Storage::MmapRW(mmap)) = (&self.file, &self.storage) else {
+            return Err(ContainerError::UnsupportedOperation(
+                "Append  to  file  only  supported  on  mmap  RW  opened  from  a  file",
+            ));
+        };
+        if self.checksummed {
+            return Err(ContainerError::UnsupportedOperation(
+                "Cannot  append  to  a  checksummed  mapping",
+            ));
+        }
+        let size = std::mem::size_of::<T>();
+        let mapped = mmap.len();
+        let in_use = Self::in_use(&self.spare, mapped);
+        if !in_use.is_multiple_of(size) {
+            return Err(ContainerError::UnsupportedOperation(
+                "Cannot  append  after  trailing  bytes",
+            ));
+        }
+        if new.is_empty() {
+            return Ok(());
+        }
+
+        let old_len = in_use / size;
+        let byte_len = old_len
+            .checked_add(new.len())
+            .and_then(|n| n.checked_mul(size))
+            .ok_or(ContainerError::UnsupportedOperation("Capacity  overflow"))?;
+        let mut spare = match self.spare.take() {
+            Some(spare) => spare,
+            None => SpareCapacity::new(file, in_use, mapped)?,
+        };
+        if byte_len > mapped {
+            let capacity = byte_len
+                .max(mapped.saturating_mul(2))
+                .max(MIN_GROW_BYTES)
+                .next_multiple_of(size);
+            spare.resize_file(capacity)?;
+            let mmap = unsafe { MmapMut::map_mut(file)? };
+            Self::check_mapping(mmap.as_ptr(), capacity, self.trailing)?;
+            self.storage = Storage::MmapRW(mmap);
+        } else {
+            spare.cover(byte_len, mapped)?;
+        }
+        spare.len = byte_len;
+        self.spare = Some(spare);
+        self.mark_dirty(old_len..old_len + new.len());
+        self.slice_mut_unmarked()
+            .expect("read-write  mapping  is  writable")[old_len..]
+            .copy_from_slice(new);
+        Ok(())
+    }
+
+    ///  Start  a  buffered  append  session  (only  works  on  in-memory  storage).
+    ///
+    ///  Items  pushed  through  the  returned  [`BufferedAppender`]  are  committed  in
+    ///  blocks  of  `block_size`  items,  which  keeps  per-push  overhead  low  when
+    ///  logging  many  tiny  records.  Remaining  items  are  committed  when  the
+    ///  appender  is  dropped.
+    pub fn append_buffered(
+        &mut self,
+        block_size: usize,
+    ) -> Result<BufferedAppender<'_, T>, ContainerError> {
+        self.unshare();
+        match &self.storage {
+            Storage::InMemory(_) => Ok(BufferedAppender::new(self, block_size)),
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Append  not  supported  on  mmap  storage",
+            )),
+        }
+    }
+
     ///  Resize  (only  works  on  in-memory  storage).
     pub fn resize(&mut self, new_len: usize, value: T) -> Result<(), ContainerError>
     where
         T: Copy,
     {
+        self.unshare();
         match &mut self.storage {
             Storage::InMemory(vec) => {
+                Self::check_reserved(self.pinned, vec, new_len)?;
+                *self.dirty.get_mut() = true;
+                self.generation = next_generation();
                 vec.resize(new_len, value);
                 Ok(())
             }
@@ -143,47 +981,740 @@ impl<T: Pod> RawBytesContainer<T> {
         }
     }
 
-    ///  Write  contents  to  file,  or  flush  mmap  if  writable.
-    pub fn write_to_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ContainerError> {
+    ///  Resize,  filling  new  elements  with  zero  bytes  (only  works  on  in-memory  storage).
+    ///
+    ///  Equivalent  to  `resize(new_len,  T::zeroed())`,  but  fills  the  new  tail  with  a
+    ///  single  memset  instead  of  copying  `value`  element  by  element.
+    pub fn resize_zeroed(&mut self, new_len: usize) -> Result<(), ContainerError> {
+        self.unshare();
         match &mut self.storage {
             Storage::InMemory(vec) => {
-                std::fs::write(path, bytemuck::cast_slice(vec))?;
+                Self::check_reserved(self.pinned, vec, new_len)?;
+                *self.dirty.get_mut() = true;
+                self.generation = next_generation();
+                if new_len <= vec.len() {
+                    vec.truncate(new_len);
+                } else {
+                    let additional = new_len - vec.len();
+                    vec.reserve(additional);
+                    //  SAFETY:  capacity  for  `additional`  elements  was  reserved  above,  and
+                    //  all-zero  bytes  are  a  valid  `T`  because  `T:  Pod`.
+                    unsafe {
+                        vec.as_mut_ptr().add(vec.len()).write_bytes(0, additional);
+                        vec.set_len(new_len);
+                    }
+                }
                 Ok(())
             }
-            Storage::MmapRW(mmap) => {
-                mmap.flush()?;
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Resize  not  supported  on  mmap  storage",
+            )),
+        }
+    }
+
+    ///  Insert  `value`  at  `index`,  shifting  later  elements  up  (only  works  on  in-memory  storage).
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), ContainerError> {
+        let pinned = self.pinned;
+        let vec = self.vec_mut("Insert  not  supported  on  mmap  storage")?;
+        if index > vec.len() {
+            return Err(ContainerError::IndexOutOfBounds {
+                index,
+                len: vec.len(),
+            });
+        }
+        Self::check_reserved(pinned, vec, vec.len() + 1)?;
+        vec.insert(index, value);
+        Ok(())
+    }
+
+    ///  Remove  and  return  the  element  at  `index`,  shifting  later  elements  down
+    ///  (only  works  on  in-memory  storage).
+    pub fn remove(&mut self, index: usize) -> Result<T, ContainerError> {
+        let vec = self.vec_mut("Remove  not  supported  on  mmap  storage")?;
+        if index >= vec.len() {
+            return Err(ContainerError::IndexOutOfBounds {
+                index,
+                len: vec.len(),
+            });
+        }
+        Ok(vec.remove(index))
+    }
+
+    ///  Remove  and  return  the  element  at  `index`,  replacing  it  with  the  last  element
+    ///  (only  works  on  in-memory  storage).
+    ///
+    ///  O(1),  but  does  not  preserve  ordering.
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, ContainerError> {
+        let vec = self.vec_mut("Remove  not  supported  on  mmap  storage")?;
+        if index >= vec.len() {
+            return Err(ContainerError::IndexOutOfBounds {
+                index,
+                len: vec.len(),
+            });
+        }
+        Ok(vec.swap_remove(index))
+    }
+
+    ///  Shorten  the  container  to  `len`  elements;  no-op  if  it  is  already  shorter
+    ///  (only  works  on  in-memory  storage).
+    pub fn truncate(&mut self, len: usize) -> Result<(), ContainerError> {
+        self.vec_mut("Truncate  not  supported  on  mmap  storage")?
+            .truncate(len);
+        Ok(())
+    }
+
+    ///  Remove  `range`  and  return  its  elements  as  an  iterator,  like  [`Vec::drain`]
+    ///  (only  works  on  in-memory  storage).
+    ///
+    ///  The  elements  are  removed  even  if  the  iterator  is  not  fully  consumed,  and
+    ///  the  container  keeps  its  allocation.  A  range  outside  the  container  fails  with
+    ///  [`ContainerError::IndexOutOfBounds`]  naming  the  offending  bound.
+    pub fn drain(
+        &mut self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<std::vec::Drain<'_, T>, ContainerError> {
+        let vec = self.vec_mut("Drain  not  supported  on  mmap  storage")?;
+        let range = crate::view::check_range(range, vec.len())?;
+        Ok(vec.drain(range))
+    }
+
+    ///  Split  off  the  elements  from  `at`  onwards  into  a  new  container,  like
+    ///  [`Vec::split_off`]  (only  works  on  in-memory  storage).
+    ///
+    ///  For  mapped  storage  use  [`split`](Self::split),  which  returns  two  views
+    ///  instead.
+    pub fn split_off(&mut self, at: usize) -> Result<Self, ContainerError> {
+        let vec = self.vec_mut("Split  off  not  supported  on  mmap  storage")?;
+        if at > vec.len() {
+            return Err(ContainerError::IndexOutOfBounds {
+                index: at,
+                len: vec.len(),
+            });
+        }
+        Ok(Self::from_vec(vec.split_off(at)))
+    }
+
+    ///  Fail  if  growing  `vec`  to  `new_len`  would  reallocate  storage  created  by  `with_reserved`.
+    fn check_reserved(pinned: bool, vec: &Vec<T>, new_len: usize) -> Result<(), ContainerError> {
+        if pinned && new_len > vec.capacity() {
+            return Err(ContainerError::UnsupportedOperation(
+                "Growth  would  exceed  reserved  capacity",
+            ));
+        }
+        Ok(())
+    }
+
+    ///  The  backing  vector  of  in-memory  storage,  or  `UnsupportedOperation(msg)`.
+    fn vec_mut(&mut self, msg: &'static str) -> Result<&mut Vec<T>, ContainerError> {
+        self.unshare();
+        match &mut self.storage {
+            Storage::InMemory(vec) => {
+                *self.dirty.get_mut() = true;
+                self.generation = next_generation();
+                Ok(vec)
+            }
+            _ => Err(ContainerError::UnsupportedOperation(msg)),
+        }
+    }
+
+    ///  Set  the  length  without  initializing  new  elements  (only  works  on  in-memory  storage).
+    ///
+    ///  Intended  for  bulk  loads  where  every  new  element  is  overwritten  right  away,
+    ///  e.g.  by  reading  a  file  straight  into  `as_slice_mut()`.  Shrinking  simply  truncates.
+    ///
+    ///  In  debug  builds  the  new  elements  are  filled  with  `0xA5`  bytes  so  that  reading
+    ///  them  before  writing  shows  up  as  an  obvious  poison  pattern.
+    ///
+    ///  #  Safety
+    ///  Every  element  in  `old_len..new_len`  must  be  written  before  it  is  read.
+    pub unsafe fn set_len_uninit(&mut self, new_len: usize) -> Result<(), ContainerError> {
+        self.unshare();
+        match &mut self.storage {
+            Storage::InMemory(vec) => {
+                Self::check_reserved(self.pinned, vec, new_len)?;
+                *self.dirty.get_mut() = true;
+                self.generation = next_generation();
+                if new_len <= vec.len() {
+                    vec.truncate(new_len);
+                } else {
+                    let additional = new_len - vec.len();
+                    vec.reserve(additional);
+                    #[cfg(debug_assertions)]
+                    //  SAFETY:  capacity  for  `additional`  elements  was  reserved  above.
+                    unsafe {
+                        vec.as_mut_ptr()
+                            .add(vec.len())
+                            .write_bytes(0xA5, additional);
+                    }
+                    //  SAFETY:  capacity  was  reserved  above;  the  caller  guarantees  the  new
+                    //  elements  are  written  before  being  read.
+                    unsafe { vec.set_len(new_len) };
+                }
                 Ok(())
             }
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Resize  not  supported  on  mmap  storage",
+            )),
+        }
+    }
+
+    ///  Write  contents  to  file,  or  flush  mmap  if  writable.
+    ///
+    ///  Copy-on-write  mappings  are  written  to  `path`  like  in-memory  data.
+    pub fn write_to_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ContainerError> {
+        let written = match &mut self.storage {
+            Storage::InMemory(vec) => {
+                Self::write_checksummed(path, bytemuck::cast_slice(vec), self.checksummed)
+            }
+            Storage::Shared(vec) => {
+                Self::write_checksummed(path, bytemuck::cast_slice(vec), self.checksummed)
+            }
+            Storage::MmapCow(mmap) => {
+                let end = Self::data_len(mmap.len(), self.checksummed);
+                Self::write_checksummed(path, &mmap[..end], self.checksummed)
+            }
+            Storage::Borrowed(slice) => {
+                Self::write_checksummed(path, bytemuck::cast_slice(slice), self.checksummed)
+            }
+            Storage::MmapRW(_) => return self.commit(),
             Storage::MmapRO(_) => Err(ContainerError::UnsupportedOperation(
                 "Cannot  write  from  read-only  mmap",
             )),
+        };
+        written?;
+        *self.dirty.get_mut() = false;
+        Ok(())
+    }
+
+    ///  Write  contents  to  a  new  file  that  atomically  replaces  `path`.
+    ///
+    ///  The  data  (plus  checksum  trailer,  if  enabled)  goes  to  a  temporary  file  in  the  same
+    ///  directory,  which  is  synced  and  then  renamed  over  `path`.  A  crash  leaves  either  the
+    ///  old  file  or  the  complete  new  one,  never  a  torn  mix.  Works  for  every  storage  kind;
+    ///  existing  mappings  of  `path`  keep  seeing  the  old  contents.
+    ///
+    ///  An  existing  target's  permissions  are  kept;  a  new  file  is  created  with  mode  `0600`.
+    ///  Writable  mappings  stay  dirty,  since  their  own  file  has  not  been  flushed;  use
+    ///  [`flush`](Self::flush)  or  [`commit`](Self::commit)  for  that.
+    pub fn write_to_file_atomic<P: AsRef<Path>>(&self, path: P) -> Result<(), ContainerError> {
+        let path = path.as_ref();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        let data: &[u8] = bytemuck::cast_slice(self.as_slice());
+        tmp.write_all(data)?;
+        if self.checksummed {
+            tmp.write_all(Trailer::for_data(data).as_bytes())?;
+        }
+        if let Ok(meta) = std::fs::metadata(path) {
+            tmp.as_file().set_permissions(meta.permissions())?;
         }
+        tmp.as_file().sync_all()?;
+        tmp.persist(path).map_err(|e| e.error)?;
+        //  Make  the  rename  itself  durable
+        #[cfg(unix)]
+        File::open(dir)?.sync_all()?;
+        //  A  writable  mapping  is  still  unflushed:  even  when  `path`  is  its  own  file,  the
+        //  rename  leaves  the  mapping  on  the  old  inode
+        if !matches!(self.storage, Storage::MmapRW(_)) {
+            self.dirty.store(false, Ordering::Relaxed);
+        }
+        Ok(())
     }
 
     ///  Flush  writable  mmap  to  disk.
+    ///
+    ///  Copy-on-write  mappings  are  never  written  back,  so  flushing  them  is  an
+    ///  error  rather  than  a  silent  no-op.
     pub fn flush(&self) -> Result<(), ContainerError> {
         match &self.storage {
+            Storage::MmapRW(_) if self.checksummed => Err(ContainerError::UnsupportedOperation(
+                "Flush  would  leave  a  stale  checksum;  use  commit",
+            )),
+            Storage::MmapRW(mmap) => {
+                match &self.spare {
+                    Some(spare) => {
+                        mmap.flush_range(0, spare.len)?;
+                        spare.trim()?;
+                    }
+                    None => mmap.flush()?,
+                }
+                self.clear_dirty();
+                Ok(())
+            }
+            Storage::MmapCow(_) => Err(ContainerError::UnsupportedOperation(
+                "Flush  not  supported  on  copy-on-write  mmap",
+            )),
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Flush  only  supported  on  mmap  RW",
+            )),
+        }
+    }
+
+    ///  Flush  only  `len_elems`  elements  starting  at  `offset_elems`  (mmap  RW  only).
+    ///
+    ///  Cheaper  than  [`flush`](Self::flush)  after  touching  a  small  window  of  a  large
+    ///  mapping,  since  only  the  pages  covering  that  range  are  synced.
+    pub fn flush_range(&self, offset_elems: usize, len_elems: usize) -> Result<(), ContainerError> {
+        let end = offset_elems
+            .checked_add(len_elems)
+            .filter(|&end| end <= self.len())
+            .ok_or(ContainerError::UnsupportedOperation(
+                "Flush  range  out  of  bounds",
+            ))?;
+        let size = std::mem::size_of::<T>();
+        match &self.storage {
+            Storage::MmapRW(_) if self.checksummed => Err(ContainerError::UnsupportedOperation(
+                "Flush  would  leave  a  stale  checksum;  use  commit",
+            )),
             Storage::MmapRW(mmap) => {
-                mmap.flush()?;
+                mmap.flush_range(offset_elems * size, (end - offset_elems) * size)?;
                 Ok(())
             }
+            Storage::MmapCow(_) => Err(ContainerError::UnsupportedOperation(
+                "Flush  not  supported  on  copy-on-write  mmap",
+            )),
             _ => Err(ContainerError::UnsupportedOperation(
                 "Flush  only  supported  on  mmap  RW",
             )),
         }
     }
 
+    ///  Flush  the  data,  then  write  and  flush  a  fresh  checksum  trailer.
+    ///
+    ///  For  checksummed  read-write  mappings  this  replaces  [`flush`](Self::flush).
+    ///  The  data  is  synced  before  the  trailer,  so  a  crash  in  between  leaves  a
+    ///  trailer  that  no  longer  matches  and  is  caught  by  [`verify`](Self::verify).
+    ///  Without  a  checksum  this  is  the  same  as  `flush`.
+    pub fn commit(&mut self) -> Result<(), ContainerError> {
+        if !self.checksummed {
+            return self.flush();
+        }
+        match &mut self.storage {
+            Storage::MmapRW(mmap) => {
+                let end = mmap.len() - TRAILER_SIZE;
+                mmap.flush_range(0, end)?;
+                let trailer = Trailer::for_data(&mmap[..end]);
+                mmap[end..].copy_from_slice(trailer.as_bytes());
+                mmap.flush_range(end, TRAILER_SIZE)?;
+                self.clear_dirty();
+                Ok(())
+            }
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Commit  only  supported  on  mmap  RW",
+            )),
+        }
+    }
+
+    ///  Make  the  data  read-only  for  the  rest  of  the  container's  lifetime.
+    ///
+    ///  In-memory  data  is  copied  into  an  anonymous  mapping  and  read-write  mappings  are
+    ///  committed  first  if  dirty;  all  mappings  are  then  `mprotect`ed  read-only  and  become
+    ///  [`Storage::MmapRO`].  From  then  on  [`as_slice_mut`](Self::as_slice_mut)  returns
+    ///  `None`,  every  growing  or  shrinking  operation  returns
+    ///  [`ContainerError::UnsupportedOperation`],  and  a  stray  write  through  a  raw  pointer
+    ///  faults  instead  of  silently  corrupting  the  data.  There  is  no  way  to  unseal.
+    ///
+    ///  Storage  that  is  already  read-only  is  left  as  is.  A  sealed  container  can  still  be
+    ///  saved  with  [`write_to_file_atomic`](Self::write_to_file_atomic).
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  mut  table  =  RawBytesContainer::from_vec(vec![1u32,  2,  3]);
+    ///  table.seal().unwrap();
+    ///  assert_eq!(table.as_slice(),  &[1,  2,  3]);
+    ///  assert!(table.as_slice_mut().is_none());
+    ///  assert!(table.append(&[4]).is_err());
+    ///  ```
+    pub fn seal(&mut self) -> Result<(), ContainerError> {
+        if matches!(self.storage, Storage::MmapRW(_)) && *self.dirty.get_mut() {
+            self.commit()?;
+        }
+        //  A  read-only  mapping  must  end  at  the  last  element
+        if self.spare.is_some() {
+            self.remap()?;
+        }
+        match &self.storage {
+            Storage::InMemory(_) | Storage::Shared(_) => {
+                let data: &[u8] = bytemuck::cast_slice(self.as_slice());
+                let trailer = self.checksummed.then(|| Trailer::for_data(data));
+                let trailer = trailer.as_ref().map_or(&[][..], Trailer::as_bytes);
+                let mut mmap = MmapOptions::new()
+                    .len(data.len() + trailer.len())
+                    .map_anon()?;
+                mmap[..data.len()].copy_from_slice(data);
+                mmap[data.len()..].copy_from_slice(trailer);
+                self.storage = Storage::MmapRO(mmap.make_read_only()?);
+            }
+            Storage::MmapRW(_) | Storage::MmapCow(_) => {
+                let (Storage::MmapRW(mmap) | Storage::MmapCow(mmap)) =
+                    std::mem::replace(&mut self.storage, Storage::Borrowed(&[]))
+                else {
+                    unreachable!()
+                };
+                self.storage = Storage::MmapRO(mmap.make_read_only()?);
+            }
+            Storage::MmapRO(_) | Storage::Borrowed(_) => {}
+        }
+        self.pinned = false;
+        self.protected = false;
+        self.dirty_ranges = None;
+        *self.dirty.get_mut() = false;
+        Ok(())
+    }
+
+    ///  Temporarily  make  a  read-write  mapping  read-only,  until  [`unprotect`](Self::unprotect).
+    ///
+    ///  The  pages  are  `mprotect`ed  (`VirtualProtect`  on  Windows)  in  place,  so  the  data  stays
+    ///  at  the  same  address  and  nothing  is  copied.  While  protected,  the  container  behaves
+    ///  like  a  read-only  mapping:  [`as_slice_mut`](Self::as_slice_mut)  returns  `None`,
+    ///  [`flush`](Self::flush)  and  [`commit`](Self::commit)  fail,  and  a  stray  write  through  a
+    ///  raw  pointer  faults.  Protecting  an  already  protected  container  is  a  no-op.
+    ///
+    ///  Unlike  [`seal`](Self::seal),  this  is  meant  for  phases:  load  or  edit,  protect  while
+    ///  the  data  is  only  read,  then  unprotect  for  the  next  round  of  edits.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  file  =  tempfile::NamedTempFile::new().unwrap();
+    ///  let  mut  data  =  RawBytesContainer::<u32>::create_mmap_rw(file.path(),  4).unwrap();
+    ///  data.as_slice_mut().unwrap()[0]  =  1;
+    ///
+    ///  data.protect_read_only().unwrap();
+    ///  assert!(data.is_protected());
+    ///  assert!(data.get_mut(0).is_none());
+    ///
+    ///  data.unprotect().unwrap();
+    ///  data.as_slice_mut().unwrap()[1]  =  2;
+    ///  assert_eq!(data.as_slice(),  &[1,  2,  0,  0]);
+    ///  ```
+    pub fn protect_read_only(&mut self) -> Result<(), ContainerError> {
+        //  A  read-only  mapping  must  end  at  the  last  element
+        if self.spare.is_some() {
+            self.remap()?;
+        }
+        match &self.storage {
+            Storage::MmapRW(mmap) => {
+                //  memmap2  drops  the  mapping  if  its  conversion  fails,  so  change  the
+                //  protection  in  place  first;  the  conversion  then  repeats  a  call  that
+                //  already  succeeded
+                #[cfg(unix)]
+                Self::mprotect(mmap.as_ptr(), mmap.len(), false)?;
+                let Storage::MmapRW(mmap) =
+                    std::mem::replace(&mut self.storage, Storage::Borrowed(&[]))
+                else {
+                    unreachable!()
+                };
+                self.storage = Storage::MmapRO(mmap.make_read_only()?);
+                self.protected = true;
+                Ok(())
+            }
+            Storage::MmapRO(_) if self.protected => Ok(()),
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Protect  only  supported  on  mmap  RW",
+            )),
+        }
+    }
+
+    ///  Make  a  mapping  protected  by  [`protect_read_only`](Self::protect_read_only)  writable  again.
+    ///
+    ///  Unprotecting  a  read-write  mapping  that  is  not  protected  is  a  no-op;  any  other
+    ///  storage,  including  sealed  containers,  returns  an  error.
+    pub fn unprotect(&mut self) -> Result<(), ContainerError> {
+        match &self.storage {
+            Storage::MmapRO(mmap) if self.protected => {
+                #[cfg(unix)]
+                Self::mprotect(mmap.as_ptr(), mmap.len(), true)?;
+                let Storage::MmapRO(mmap) =
+                    std::mem::replace(&mut self.storage, Storage::Borrowed(&[]))
+                else {
+                    unreachable!()
+                };
+                self.storage = Storage::MmapRW(mmap.make_mut()?);
+                self.protected = false;
+                Ok(())
+            }
+            Storage::MmapRW(_) => Ok(()),
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Unprotect  only  supported  on  protected  mmap",
+            )),
+        }
+    }
+
+    ///  Set  the  protection  of  the  mapped  pages  covering  `len`  bytes  at  `ptr`  in  place.
+    #[cfg(unix)]
+    fn mprotect(ptr: *const u8, len: usize, writable: bool) -> Result<(), ContainerError> {
+        if len == 0 {
+            return Ok(());
+        }
+        let page = usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) })
+            .map_err(|_| std::io::Error::last_os_error())?;
+        let start = ptr as usize & !(page - 1);
+        let len = len + (ptr as usize - start);
+        let prot = if writable {
+            libc::PROT_READ | libc::PROT_WRITE
+        } else {
+            libc::PROT_READ
+        };
+        //  SAFETY:  the  range  lies  within  a  live  mapping  owned  by  this  container
+        if unsafe { libc::mprotect(start as *mut libc::c_void, len, prot) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    ///  Whether  the  container  is  a  read-write  mapping  currently  protected  by
+    ///  [`protect_read_only`](Self::protect_read_only).
+    pub fn is_protected(&self) -> bool {
+        self.protected
+    }
+
+    ///  Keep  a  checksum  of  the  data  and  append  it  as  a  trailer  in
+    ///  [`write_to_file`](Self::write_to_file)  (in-memory  storage  only).
+    ///
+    ///  Reopen  the  file  with  [`MapOptions::checksum`]  to  verify  the  trailer  and
+    ///  detect  torn  writes.
+    pub fn enable_checksum(&mut self) -> Result<(), ContainerError> {
+        match self.storage {
+            Storage::InMemory(_) | Storage::Shared(_) => {
+                self.checksummed = true;
+                Ok(())
+            }
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Checksums  can  only  be  enabled  on  in-memory  storage",
+            )),
+        }
+    }
+
+    ///  Whether  the  data  carries  a  checksum  trailer.
+    pub fn is_checksummed(&self) -> bool {
+        self.checksummed
+    }
+
+    ///  CRC32  of  the  data  bytes  (excluding  any  checksum  trailer).
+    pub fn checksum(&self) -> u32 {
+        crate::checksum::crc32(self.data_bytes())
+    }
+
+    ///  Check  the  stored  checksum  trailer  against  the  mapped  data.
+    ///
+    ///  Fails  with  [`ContainerError::ChecksumMismatch`]  if  the  data  changed  since
+    ///  the  trailer  was  last  written,  e.g.  after  a  torn  write.
+    pub fn verify(&self) -> Result<(), ContainerError> {
+        let mmap: &[u8] = match &self.storage {
+            _ if !self.checksummed => {
+                return Err(ContainerError::UnsupportedOperation(
+                    "Container  has  no  checksum",
+                ));
+            }
+            Storage::MmapRO(mmap) => mmap,
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => mmap,
+            Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => {
+                return Err(ContainerError::UnsupportedOperation(
+                    "In-memory  data  has  no  stored  checksum",
+                ));
+            }
+        };
+        let end = mmap.len() - TRAILER_SIZE;
+        let trailer: Trailer = bytemuck::pod_read_unaligned(&mmap[end..]);
+        trailer.verify(&mmap[..end])
+    }
+
+    ///  All  bytes  covered  by  the  checksum.
+    fn data_bytes(&self) -> &[u8] {
+        match &self.storage {
+            Storage::InMemory(vec) => bytemuck::cast_slice(vec),
+            Storage::Shared(vec) => bytemuck::cast_slice(vec),
+            Storage::Borrowed(slice) => bytemuck::cast_slice(slice),
+            Storage::MmapRO(mmap) => &mmap[..Self::data_len(mmap.len(), self.checksummed)],
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => {
+                let len = Self::in_use(&self.spare, mmap.len());
+                &mmap[..Self::data_len(len, self.checksummed)]
+            }
+        }
+    }
+
+    ///  Write  `data`  to  `path`,  followed  by  a  checksum  trailer  if  requested.
+    fn write_checksummed<P: AsRef<Path>>(
+        path: P,
+        data: &[u8],
+        checksummed: bool,
+    ) -> Result<(), ContainerError> {
+        if !checksummed {
+            std::fs::write(path, data)?;
+            return Ok(());
+        }
+        let mut file = File::create(path)?;
+        file.write_all(data)?;
+        file.write_all(Trailer::for_data(data).as_bytes())?;
+        Ok(())
+    }
+
+    ///  Tell  the  kernel  how  the  mapped  data  will  be  accessed  (mmap  storage  only).
+    ///
+    ///  Only  available  on  unix;  elsewhere  this  returns  an  error.
+    pub fn advise(&self, advice: Advice) -> Result<(), ContainerError> {
+        self.advise_range(advice, 0..self.len())
+    }
+
+    ///  Like  [`advise`](Self::advise),  but  only  for  the  elements  in  `range`.
+    pub fn advise_range(
+        &self,
+        advice: Advice,
+        range: std::ops::Range<usize>,
+    ) -> Result<(), ContainerError> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(ContainerError::UnsupportedOperation(
+                "Advice  range  out  of  bounds",
+            ));
+        }
+        let size = std::mem::size_of::<T>();
+        let (offset, len) = (range.start * size, (range.end - range.start) * size);
+
+        #[cfg(unix)]
+        {
+            match &self.storage {
+                Storage::MmapRO(mmap) => mmap.advise_range(advice.to_memmap2(), offset, len)?,
+                Storage::MmapRW(mmap) => mmap.advise_range(advice.to_memmap2(), offset, len)?,
+                Storage::MmapCow(_) if advice == Advice::DontNeed => {
+                    return Err(ContainerError::UnsupportedOperation(
+                        "DontNeed  would  discard  copy-on-write  changes",
+                    ));
+                }
+                Storage::MmapCow(mmap) => mmap.advise_range(advice.to_memmap2(), offset, len)?,
+                Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => {
+                    return Err(ContainerError::UnsupportedOperation(
+                        "Advice  only  supported  on  mmap  storage",
+                    ));
+                }
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (advice, offset, len);
+            Err(ContainerError::UnsupportedOperation(
+                "Advice  not  supported  on  this  platform",
+            ))
+        }
+    }
+
+    ///  Pin  the  mapped  pages  in  RAM  (`mlock`),  preventing  page  faults  on  access.
+    ///
+    ///  Only  supported  on  mmap  storage  on  unix.  Locking  is  subject  to  the
+    ///  process'  `RLIMIT_MEMLOCK`;  pages  stay  locked  until  [`unlock`](Self::unlock)
+    ///  is  called  or  the  container  is  dropped.
+    pub fn lock(&self) -> Result<(), ContainerError> {
+        #[cfg(unix)]
+        {
+            match &self.storage {
+                Storage::MmapRO(mmap) => mmap.lock()?,
+                Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => mmap.lock()?,
+                Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => {
+                    return Err(ContainerError::UnsupportedOperation(
+                        "Lock  only  supported  on  mmap  storage",
+                    ));
+                }
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        Err(ContainerError::UnsupportedOperation(
+            "Lock  not  supported  on  this  platform",
+        ))
+    }
+
+    ///  Release  pages  pinned  by  [`lock`](Self::lock)  (`munlock`).
+    pub fn unlock(&self) -> Result<(), ContainerError> {
+        #[cfg(unix)]
+        {
+            match &self.storage {
+                Storage::MmapRO(mmap) => mmap.unlock()?,
+                Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => mmap.unlock()?,
+                Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => {
+                    return Err(ContainerError::UnsupportedOperation(
+                        "Unlock  only  supported  on  mmap  storage",
+                    ));
+                }
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        Err(ContainerError::UnsupportedOperation(
+            "Unlock  not  supported  on  this  platform",
+        ))
+    }
+
     ///  Capacity  of  in-memory  storage  (None  for  mmap).
     pub fn capacity(&self) -> Option<usize> {
         match &self.storage {
             Storage::InMemory(vec) => Some(vec.capacity()),
+            Storage::Shared(vec) => Some(vec.capacity()),
             _ => None,
         }
     }
 
+    ///  Snapshot  of  the  container's  memory  usage,  e.g.  for  diagnostics  UIs.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::{RawBytesContainer,  StorageKind};
+    ///
+    ///  let  mut  container  =  RawBytesContainer::from_vec(vec![0u32;  4]);
+    ///  container.as_slice_mut().unwrap()[0]  =  1;
+    ///
+    ///  let  stats  =  container.stats();
+    ///  assert_eq!(stats.kind,  StorageKind::InMemory);
+    ///  assert_eq!(stats.byte_len,  16);
+    ///  assert!(stats.dirty);
+    ///  ```
+    pub fn stats(&self) -> ContainerStats {
+        let byte_len = std::mem::size_of_val(self.as_slice());
+        let resident_bytes = match &self.storage {
+            Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => Some(byte_len),
+            Storage::MmapRO(mmap) => resident_bytes(mmap),
+            Storage::MmapRW(mmap) | Storage::MmapCow(mmap) => {
+                resident_bytes(&mmap[..Self::in_use(&self.spare, mmap.len())])
+            }
+        };
+        ContainerStats {
+            kind: self.storage.kind(),
+            len: self.len(),
+            byte_len,
+            capacity: self.capacity(),
+            resident_bytes,
+            dirty: self.dirty.load(Ordering::Relaxed),
+        }
+    }
+
+    ///  Reserve  room  for  at  least  `additional`  more  elements  (in-memory  storage  only).
+    ///
+    ///  Storage  created  by  [`with_reserved`](Self::with_reserved)  never  reallocates,  so
+    ///  there  this  only  checks  that  the  reserved  capacity  suffices.
+    pub fn reserve(&mut self, additional: usize) -> Result<(), ContainerError> {
+        self.unshare();
+        match &mut self.storage {
+            Storage::InMemory(vec) => {
+                Self::check_reserved(self.pinned, vec, vec.len().saturating_add(additional))?;
+                vec.reserve(additional);
+                Ok(())
+            }
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Reserve  only  supported  on  in-memory  storage",
+            )),
+        }
+    }
+
     ///  Shrink  in-memory  storage  to  fit.
     pub fn shrink_to_fit(&mut self) -> Result<(), ContainerError> {
+        self.unshare();
         match &mut self.storage {
+            Storage::InMemory(_) if self.pinned => Err(ContainerError::UnsupportedOperation(
+                "Shrink  would  move  reserved  storage",
+            )),
             Storage::InMemory(vec) => {
                 vec.shrink_to_fit();
                 Ok(())
@@ -194,6 +1725,141 @@ impl<T: Pod> RawBytesContainer<T> {
         }
     }
 
+    ///  Reinterpret  the  container  as  elements  of  another  Pod  type,  without  copying.
+    ///
+    ///  In-memory  data  is  converted  with  [`bytemuck::try_cast_vec`],  which  needs
+    ///  `T`  and  `U`  to  have  the  same  alignment  and  the  allocation  to  divide  evenly
+    ///  into  `U`s.  Mapped  data  needs  its  elements  to  be  a  whole  number  of  suitably
+    ///  aligned  `U`s.
+    ///
+    ///  Data  that  does  not  divide  evenly  into  `U`s  is  rejected  with
+    ///  [`ContainerError::TypeMismatch`];  the  leftover  bytes  are  neither  kept  nor
+    ///  reported.  Bytes  of  a  mapping  that  were  already  outside  the  `T`  elements  (a
+    ///  partial  last  element  or  the  checksum  trailer)  are  not  counted.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  words  =  RawBytesContainer::from_vec(vec![1u32,  2]);
+    ///  let  pairs  =  words.try_cast::<[u32;  2]>().unwrap();
+    ///  assert_eq!(pairs.as_slice(),  &[[1,  2]]);
+    ///  ```
+    pub fn try_cast<U: Pod>(self) -> Result<RawBytesContainer<U>, ContainerError> {
+        let mismatch = |e: bytemuck::PodCastError| {
+            ContainerError::TypeMismatch(format!(
+                "cannot  cast  {}  to  {}:  {:?}",
+                std::any::type_name::<T>(),
+                std::any::type_name::<U>(),
+                e
+            ))
+        };
+
+        let checksummed = self.checksummed;
+        let storage = match self.storage {
+            Storage::InMemory(vec) => Storage::InMemory(
+                bytemuck::allocation::try_cast_vec(vec).map_err(|(e, _)| mismatch(e))?,
+            ),
+            Storage::Shared(vec) => Storage::InMemory(
+                bytemuck::allocation::try_cast_vec(Arc::unwrap_or_clone(vec))
+                    .map_err(|(e, _)| mismatch(e))?,
+            ),
+            Storage::Borrowed(slice) => {
+                Storage::Borrowed(bytemuck::try_cast_slice(slice).map_err(mismatch)?)
+            }
+            Storage::MmapRO(mmap) => {
+                bytemuck::try_cast_slice::<u8, U>(
+                    &mmap[..Self::whole_elements(mmap.len(), checksummed)],
+                )
+                .map_err(mismatch)?;
+                Storage::MmapRO(mmap)
+            }
+            Storage::MmapRW(mmap) => {
+                let len = Self::in_use(&self.spare, mmap.len());
+                bytemuck::try_cast_slice::<u8, U>(&mmap[..Self::whole_elements(len, checksummed)])
+                .map_err(mismatch)?;
+                Storage::MmapRW(mmap)
+            }
+            Storage::MmapCow(mmap) => {
+                bytemuck::try_cast_slice::<u8, U>(
+                    &mmap[..Self::whole_elements(mmap.len(), checksummed)],
+                )
+                .map_err(mismatch)?;
+                Storage::MmapCow(mmap)
+            }
+        };
+
+        Ok(RawBytesContainer {
+            storage,
+            checksummed,
+            file: self.file,
+            trailing: self.trailing,
+            auto_remap: self.auto_remap,
+            pinned: self.pinned,
+            protected: self.protected,
+            dirty: self.dirty,
+            dirty_ranges: self.dirty_ranges,
+            generation: self.generation,
+            spare: self.spare,
+            access: self.access,
+        })
+    }
+
+    ///  Deep-copy  the  elements  into  a  new  in-memory  container.
+    ///
+    ///  Materializes  mapped  or  borrowed  data  into  owned  memory,  e.g.  before  the  file  is
+    ///  replaced  or  to  edit  it  with  the  in-memory-only  operations.  The  copy  keeps  the
+    ///  checksum  setting;  trailing  bytes,  locks  and  mapping  options  are  not  carried  over.
+    pub fn to_in_memory(&self) -> Self {
+        let mut copy = Self::from_slice(self.as_slice());
+        copy.checksummed = self.checksummed;
+        copy
+    }
+
+    ///  Persist  the  elements  to  `path`  and  reopen  the  file  as  a  read-write  mapping.
+    ///
+    ///  The  file  is  written  with  [`write_to_file_atomic`](Self::write_to_file_atomic),
+    ///  including  a  checksum  trailer  if  enabled,  which  the  new  mapping  then  verifies  and
+    ///  maintains.  Works  for  every  storage  kind;  `self`  is  left  untouched.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  dir  =  tempfile::tempdir().unwrap();
+    ///  let  memory  =  RawBytesContainer::from_vec(vec![1u16,  2,  3]);
+    ///
+    ///  let  mut  mapped  =  memory.to_mmap(dir.path().join("data.bin")).unwrap();
+    ///  mapped.as_slice_mut().unwrap()[0]  =  7;
+    ///
+    ///  let  copy  =  mapped.to_in_memory();
+    ///  assert_eq!(copy.as_slice(),  &[7,  2,  3]);
+    ///  assert_eq!(copy.capacity(),  Some(3));
+    ///  ```
+    pub fn to_mmap<P: AsRef<Path>>(&self, path: P) -> Result<Self, ContainerError> {
+        let path = path.as_ref();
+        self.write_to_file_atomic(path)?;
+        Self::open_mmap_rw_with_options(path, MapOptions::new().checksum(self.checksummed))
+    }
+
+    ///  Convert  into  a  `Vec<T>`.
+    ///
+    ///  In-memory  storage  is  moved  out  without  copying;  mapped  storage  is  copied
+    ///  (excluding  any  checksum  trailer)  and  the  mapping  is  dropped.
+    pub fn into_vec(self) -> Vec<T> {
+        match self.storage {
+            Storage::InMemory(vec) => vec,
+            Storage::Shared(vec) => Arc::unwrap_or_clone(vec),
+            _ => self.as_slice().to_vec(),
+        }
+    }
+
+    ///  Take  the  underlying  storage  by  value.
+    ///
+    ///  A  checksummed  mapping  still  ends  with  its  checksum  trailer.
+    ///  Any  lock  taken  on  open  is  released.
+    pub fn into_storage(self) -> Storage<T> {
+        self.storage
+    }
+
     ///  Number  of  elements  in  the  container.
     pub fn len(&self) -> usize {
         self.as_slice().len()
@@ -209,9 +1875,23 @@ impl<T: Pod> RawBytesContainer<T> {
         self.as_slice().get(index)
     }
 
+    ///  Compare  the  raw  bytes  of  the  elements  with  those  of  `other`,  whatever  the
+    ///  backends  and  element  types.
+    ///
+    ///  A  single  `memcmp`,  cheaper  than  element-wise  `==`  for  large  containers.  Unlike
+    ///  `==`,  floats  compare  by  bit  pattern:  `NaN`  equals  an  identical  `NaN`,  and
+    ///  `0.0`  differs  from  `-0.0`.  Trailing  bytes  and  checksum  trailers  are  ignored.
+    pub fn bytes_eq<U: Pod>(&self, other: &RawBytesContainer<U>) -> bool {
+        bytemuck::cast_slice::<T, u8>(self.as_slice())
+            == bytemuck::cast_slice::<U, u8>(other.as_slice())
+    }
+
     ///  Get  mutable  reference  by  index  (only  if  mutable).
     pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
-        self.as_slice_mut()?.get_mut(index)
+        if index < self.len() {
+            self.mark_dirty(index..index + 1);
+        }
+        self.slice_mut_unmarked()?.get_mut(index)
     }
 }
 
@@ -228,6 +1908,41 @@ impl<T: Pod> AsRef<[T]> for RawBytesContainer<T> {
     }
 }
 
+///  Containers  are  equal  when  their  elements  are,  whatever  the  backends.
+impl<T: Pod + PartialEq> PartialEq for RawBytesContainer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Pod + Eq> Eq for RawBytesContainer<T> {}
+
+impl<T: Pod + PartialEq> PartialEq<[T]> for RawBytesContainer<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+///  Lexicographic  comparison  of  the  elements.
+impl<T: Pod + PartialOrd> PartialOrd for RawBytesContainer<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Pod + Ord> Ord for RawBytesContainer<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+///  Hashes  the  elements  like  a  slice,  so  the  hash  agrees  with  `PartialEq`.
+impl<T: Pod + std::hash::Hash> std::hash::Hash for RawBytesContainer<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
 impl<'a, T: Pod> IntoIterator for &'a RawBytesContainer<T> {
     type Item = &'a T;
     type IntoIter = std::slice::Iter<'a, T>;
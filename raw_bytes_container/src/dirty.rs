@@ -0,0 +1,151 @@
+use crate::{ContainerError, RawBytesContainer, Storage};
+use bytemuck::Pod;
+use std::{
+    ops::Range,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+///  Source  of  change  generations,  shared  by  all  containers  so  values  never  repeat.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+///  A  fresh  change  generation,  set  together  with  the  dirty  flag.
+pub(crate) fn next_generation() -> u64 {
+    NEXT_GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+///  Sorted,  non-overlapping  byte  ranges  that  were  mutated.
+#[derive(Debug, Default)]
+pub(crate) struct DirtyRanges {
+    ranges: Vec<Range<usize>>,
+}
+
+impl DirtyRanges {
+    ///  Add  `range`,  merging  it  with  any  ranges  it  overlaps  or  touches.
+    pub(crate) fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = match self.ranges[first..last] {
+            [] => range,
+            ref overlapping => {
+                overlapping[0].start.min(range.start)
+                    ..overlapping[overlapping.len() - 1].end.max(range.end)
+            }
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Start  recording  which  elements  are  mutated,  so  [`flush_dirty`](Self::flush_dirty)
+    ///  syncs  only  those  (mmap  read-write  only).
+    ///
+    ///  Mutations  made  through  [`get_mut`](Self::get_mut),  [`view_mut`](Self::view_mut),
+    ///  [`split_views_mut`](Self::split_views_mut)  and  [`writer`](Self::writer)  are  recorded
+    ///  by  element  range;  anything  that  hands  out  the  whole  slice,  such  as
+    ///  [`as_slice_mut`](Self::as_slice_mut),  marks  every  element.  Writes  through  raw
+    ///  pointers  are  not  seen.  Enabling  tracking  again  is  a  no-op.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  file  =  tempfile::NamedTempFile::new().unwrap();
+    ///  let  mut  data  =  RawBytesContainer::<u64>::create_mmap_rw(file.path(),  1  <<  16).unwrap();
+    ///  data.enable_dirty_tracking().unwrap();
+    ///
+    ///  *data.get_mut(10).unwrap()  =  1;
+    ///  *data.get_mut(11).unwrap()  =  2;
+    ///  *data.get_mut(5000).unwrap()  =  3;
+    ///  assert_eq!(data.dirty_ranges().unwrap(),  vec![10..12,  5000..5001]);
+    ///
+    ///  data.flush_dirty().unwrap();
+    ///  assert!(data.dirty_ranges().unwrap().is_empty());
+    ///  ```
+    pub fn enable_dirty_tracking(&mut self) -> Result<(), ContainerError> {
+        if !matches!(self.storage, Storage::MmapRW(_)) {
+            return Err(ContainerError::UnsupportedOperation(
+                "Dirty  tracking  only  supported  on  mmap  RW",
+            ));
+        }
+        if self.dirty_ranges.is_none() {
+            self.dirty_ranges = Some(Mutex::default());
+            //  Earlier  changes  were  not  recorded,  so  assume  they  touched  everything
+            if *self.dirty.get_mut() {
+                self.mark_dirty(0..self.len());
+            }
+        }
+        Ok(())
+    }
+
+    ///  Element  ranges  mutated  since  tracking  was  enabled  or  the  data  was  last  flushed,
+    ///  in  ascending  order,  or  `None`  if  tracking  is  not  enabled.
+    pub fn dirty_ranges(&self) -> Option<Vec<Range<usize>>> {
+        let size = std::mem::size_of::<T>();
+        let ranges = self.dirty_ranges.as_ref()?;
+        let ranges = ranges.lock().unwrap_or_else(|e| e.into_inner());
+        Some(
+            ranges
+                .ranges
+                .iter()
+                .map(|r| r.start / size..r.end.div_ceil(size))
+                .collect(),
+        )
+    }
+
+    ///  Flush  only  the  dirty  ranges  to  disk  (mmap  read-write  with  dirty  tracking  only).
+    ///
+    ///  Each  range  is  synced  separately,  so  after  a  few  edits  to  a  huge  mapping  only  the
+    ///  pages  holding  them  are  written  back.  Like  [`flush`](Self::flush),  this  fails  for  a
+    ///  checksummed  mapping,  whose  trailer  would  go  stale;  use  [`commit`](Self::commit).
+    pub fn flush_dirty(&self) -> Result<(), ContainerError> {
+        let (Storage::MmapRW(mmap), Some(ranges)) = (&self.storage, &self.dirty_ranges) else {
+            return Err(ContainerError::UnsupportedOperation(
+                "Flush  dirty  requires  mmap  RW  with  dirty  tracking  enabled",
+            ));
+        };
+        if self.checksummed {
+            return Err(ContainerError::UnsupportedOperation(
+                "Flush  would  leave  a  stale  checksum;  use  commit",
+            ));
+        }
+        let mut ranges = ranges.lock().unwrap_or_else(|e| e.into_inner());
+        //  A  remap  may  have  shrunk  the  mapping  since  the  ranges  were  recorded
+        let end = self.spare.as_ref().map_or(mmap.len(), |spare| spare.len);
+        for r in ranges.ranges.iter().filter(|r| r.start < end) {
+            mmap.flush_range(r.start, r.end.min(end) - r.start)?;
+        }
+        if let Some(spare) = &self.spare {
+            spare.trim()?;
+        }
+        ranges.ranges.clear();
+        self.dirty.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    ///  A  value  that  changes  whenever  the  data  may  have  been  mutated.
+    ///
+    ///  Unlike  the  dirty  state  behind  [`dirty_ranges`](Self::dirty_ranges),  it  is  not
+    ///  reset  by  a  flush,  so  an  observer  such  as  an  autosaver  can  compare  two  readings
+    ///  to  tell  whether  anything  changed  in  between,  whoever  flushed  the  data.  It  is  `0`
+    ///  until  the  first  mutation  and  unique  across  containers  after  that.
+    pub fn change_generation(&self) -> u64 {
+        self.generation
+    }
+
+    ///  Forget  all  recorded  mutations  after  the  whole  mapping  was  synced.
+    pub(crate) fn clear_dirty(&self) {
+        self.dirty.store(false, Ordering::Relaxed);
+        if let Some(ranges) = &self.dirty_ranges {
+            ranges
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .ranges
+                .clear();
+        }
+    }
+}
@@ -12,6 +12,24 @@ pub enum ContainerError {
 
     #[error("Alignment  error:  {0}")]
     AlignmentError(String),
+
+    #[error("Invalid  format:  {0}")]
+    InvalidFormat(String),
+
+    #[error("Not  found:  {0}")]
+    NotFound(String),
+
+    #[error("Type  mismatch:  {0}")]
+    TypeMismatch(String),
+
+    #[error("Checksum  mismatch:  expected  {expected:#010x},  got  {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+
+    #[error("Index  {index}  out  of  bounds  for  length  {len}")]
+    IndexOutOfBounds { index: usize, len: usize },
+
+    #[error("File  is  locked  by  another  handle")]
+    LockContended,
 }
 
 //  ///  Error  type  for  container  operations
@@ -0,0 +1,56 @@
+use crate::ContainerError;
+use bytemuck_derive::{Pod, Zeroable};
+
+///  Fixed-size  magic  +  version  prefix  shared  by  the  file  formats  in  this  crate.
+///
+///  Formats  built  on  top  of  it  place  their  own  metadata  directly  after  the
+///  header  and  bump  `version`  whenever  that  layout  changes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct FileHeader {
+    pub magic: [u8; 4],
+    pub version: u32,
+}
+
+impl FileHeader {
+    ///  Size  of  the  header  in  bytes.
+    pub const SIZE: usize = std::mem::size_of::<FileHeader>();
+
+    ///  Create  a  header  with  the  given  magic  and  version.
+    pub fn new(magic: [u8; 4], version: u32) -> Self {
+        Self { magic, version }
+    }
+
+    ///  Read  a  header  from  the  start  of  `bytes`.
+    pub fn read(bytes: &[u8]) -> Result<Self, ContainerError> {
+        if bytes.len() < Self::SIZE {
+            return Err(ContainerError::InvalidFormat(format!(
+                "file  too  small  for  header:  {}  bytes",
+                bytes.len()
+            )));
+        }
+        Ok(bytemuck::pod_read_unaligned(&bytes[..Self::SIZE]))
+    }
+
+    ///  Check  the  magic  and  that  the  version  is  not  newer  than  `max_version`.
+    pub fn validate(&self, magic: &[u8; 4], max_version: u32) -> Result<(), ContainerError> {
+        if &self.magic != magic {
+            return Err(ContainerError::InvalidFormat(format!(
+                "invalid  magic  {:?},  expected  {:?}",
+                self.magic, magic
+            )));
+        }
+        if self.version == 0 || self.version > max_version {
+            return Err(ContainerError::InvalidFormat(format!(
+                "unsupported  version  {}",
+                self.version
+            )));
+        }
+        Ok(())
+    }
+
+    ///  View  the  header  as  raw  bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(self)
+    }
+}
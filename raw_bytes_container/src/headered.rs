@@ -0,0 +1,276 @@
+//!  Header  +  payload  layout.
+//!
+//!  A  `HeaderedContainer<H,  T>`  stores  one  fixed-size  header  struct  followed  by  an
+//!  array  of  `T`  in  a  single  byte  buffer,  so  that  the  same  layout  works  in  memory  and
+//!  in  a  mapped  file.  In-memory  buffers  are  allocated  with  the  alignment  of  `H`  and  `T`,
+//!  however  large.
+//!
+//!  ```text
+//!  [H][PADDING  to  align_of::<T>()][T;  len]
+//!  ```
+
+use crate::aligned::AlignedBuf;
+use crate::{ContainerError, RawBytesContainer};
+use bytemuck::Pod;
+use std::{marker::PhantomData, path::Path};
+
+///  A  typed  header  followed  by  a  typed  payload  array.
+///
+///  ```
+///  use  bytemuck_derive::{Pod,  Zeroable};
+///  use  raw_bytes_container::HeaderedContainer;
+///
+///  #[repr(C)]
+///  #[derive(Clone,  Copy,  Debug,  Pod,  Zeroable)]
+///  struct  Meta  {
+///          magic:  [u8;  4],
+///          sample_rate:  u32,
+///  }
+///
+///  let  meta  =  Meta  {  magic:  *b"WAVE",  sample_rate:  48_000  };
+///  let  mut  clip  =  HeaderedContainer::<Meta,  i16>::from_parts(meta,  &[0,  1,  2]).unwrap();
+///  clip.append(&[3,  4]).unwrap();
+///  clip.header_mut().unwrap().sample_rate  =  44_100;
+///
+///  assert_eq!(clip.header().sample_rate,  44_100);
+///  assert_eq!(clip.payload(),  &[0,  1,  2,  3,  4]);
+///  ```
+#[derive(Debug)]
+pub struct HeaderedContainer<H: Pod, T: Pod> {
+    pub(crate) bytes: HeaderedBytes,
+    _marker: PhantomData<(H, T)>,
+}
+
+///  Storage  behind  a  [`HeaderedContainer`].
+#[derive(Debug)]
+pub(crate) enum HeaderedBytes {
+    ///  Growable  in-memory  bytes,  aligned  for  the  header  and  payload.
+    Memory(AlignedBuf),
+    ///  A  mapped  file  or  other  fixed-size  storage.
+    Fixed(RawBytesContainer<u8>),
+}
+
+impl HeaderedBytes {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            HeaderedBytes::Memory(buf) => buf.as_slice(),
+            HeaderedBytes::Fixed(bytes) => bytes.as_slice(),
+        }
+    }
+
+    ///  Mutable  bytes,  marking  `dirty`  as  changed;  `None`  for  read-only  storage.
+    fn as_slice_mut(&mut self, dirty: std::ops::Range<usize>) -> Option<&mut [u8]> {
+        match self {
+            HeaderedBytes::Memory(buf) => Some(buf.as_mut_slice()),
+            HeaderedBytes::Fixed(bytes) => {
+                bytes.mark_dirty(dirty);
+                bytes.slice_mut_unmarked()
+            }
+        }
+    }
+}
+
+impl<H: Pod, T: Pod> HeaderedContainer<H, T> {
+    ///  Byte  offset  of  the  payload:  the  header  size  rounded  up  to  the  alignment  of  `T`.
+    pub const PAYLOAD_OFFSET: usize =
+        std::mem::size_of::<H>().next_multiple_of(std::mem::align_of::<T>());
+
+    ///  Alignment  the  storage  needs  for  both  `H`  and  `T`.
+    const ALIGN: usize = if std::mem::align_of::<H>() > std::mem::align_of::<T>() {
+        std::mem::align_of::<H>()
+    } else {
+        std::mem::align_of::<T>()
+    };
+
+    ///  Create  an  in-memory  container  holding  `header`  and  an  empty  payload.
+    pub fn new(header: H) -> Result<Self, ContainerError> {
+        Self::from_parts(header, &[])
+    }
+
+    ///  Create  an  in-memory  container  holding  `header`  followed  by  a  copy  of  `payload`.
+    pub fn from_parts(header: H, payload: &[T]) -> Result<Self, ContainerError> {
+        let mut buf = AlignedBuf::new(Self::ALIGN);
+        buf.extend_from_slice(bytemuck::bytes_of(&header))?;
+        buf.extend_zeroed(Self::PAYLOAD_OFFSET - std::mem::size_of::<H>())?;
+        buf.extend_from_slice(bytemuck::cast_slice(payload))?;
+        Self::from_bytes(HeaderedBytes::Memory(buf))
+    }
+
+    ///  Wrap  an  existing  byte  container  laid  out  as  header  +  payload.
+    ///
+    ///  In-memory  containers  are  copied  into  storage  aligned  for  `H`  and  `T`;  mapped  ones
+    ///  are  used  in  place.
+    ///
+    ///  #  Errors
+    ///  Fails  if  the  bytes  are  too  short  for  the  header,  the  payload  is  not  a  whole
+    ///  number  of  elements,  or  mapped  storage  is  not  aligned  for  `H`  and  `T`.
+    pub fn from_container(bytes: RawBytesContainer<u8>) -> Result<Self, ContainerError> {
+        let bytes = if bytes.capacity().is_some() {
+            HeaderedBytes::Memory(AlignedBuf::from_slice(bytes.as_slice(), Self::ALIGN)?)
+        } else {
+            HeaderedBytes::Fixed(bytes)
+        };
+        Self::from_bytes(bytes)
+    }
+
+    fn from_bytes(bytes: HeaderedBytes) -> Result<Self, ContainerError> {
+        if std::mem::size_of::<T>() == 0 {
+            return Err(ContainerError::UnsupportedOperation(
+                "Zero-sized  payload  elements  are  not  supported",
+            ));
+        }
+        let container = Self {
+            bytes,
+            _marker: PhantomData,
+        };
+        container.check_layout()?;
+        Ok(container)
+    }
+
+    ///  Map  an  existing  file  read-only.
+    pub fn open_mmap_read<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Self::from_container(RawBytesContainer::open_mmap_read(path)?)
+    }
+
+    ///  Map  an  existing  file  read-write.
+    pub fn open_mmap_rw<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Self::from_container(RawBytesContainer::open_mmap_rw(path)?)
+    }
+
+    ///  Create  (or  truncate)  the  file  at  `path`,  sized  for  `header`  and  `len`  zeroed
+    ///  payload  elements,  and  map  it  read-write.
+    pub fn create_mmap_rw<P: AsRef<Path>>(
+        path: P,
+        header: H,
+        len: usize,
+    ) -> Result<Self, ContainerError> {
+        let byte_len = len
+            .checked_mul(std::mem::size_of::<T>())
+            .and_then(|n| n.checked_add(Self::PAYLOAD_OFFSET))
+            .ok_or(ContainerError::UnsupportedOperation(
+                "Requested  mmap  length  overflows",
+            ))?;
+        let bytes = RawBytesContainer::create_mmap_rw(path, byte_len)?;
+        let mut container = Self::from_container(bytes)?;
+        *container.header_mut()? = header;
+        Ok(container)
+    }
+
+    ///  Check  length  and  alignment  of  the  underlying  bytes.
+    pub(crate) fn check_layout(&self) -> Result<(), ContainerError> {
+        let bytes = self.bytes.as_slice();
+        if bytes.len() < Self::PAYLOAD_OFFSET {
+            return Err(ContainerError::InvalidFormat(format!(
+                "{}  bytes  too  small  for  {}-byte  header",
+                bytes.len(),
+                Self::PAYLOAD_OFFSET
+            )));
+        }
+        let payload_len = bytes.len() - Self::PAYLOAD_OFFSET;
+        if !payload_len.is_multiple_of(std::mem::size_of::<T>()) {
+            return Err(ContainerError::AlignmentError(format!(
+                "Payload  size  {}  not  aligned  to  type  size  {}",
+                payload_len,
+                std::mem::size_of::<T>()
+            )));
+        }
+        if !(bytes.as_ptr() as usize).is_multiple_of(Self::ALIGN) {
+            return Err(ContainerError::AlignmentError(format!(
+                "Storage  address  not  aligned  to  {}",
+                Self::ALIGN
+            )));
+        }
+        Ok(())
+    }
+
+    ///  The  header.
+    pub fn header(&self) -> &H {
+        bytemuck::from_bytes(&self.bytes.as_slice()[..std::mem::size_of::<H>()])
+    }
+
+    ///  Mutable  access  to  the  header.  Fails  on  read-only  storage.
+    pub fn header_mut(&mut self) -> Result<&mut H, ContainerError> {
+        let size = std::mem::size_of::<H>();
+        let bytes =
+            self.bytes
+                .as_slice_mut(0..size)
+                .ok_or(ContainerError::UnsupportedOperation(
+                    "Cannot  get  mutable  reference  to  read-only  storage",
+                ))?;
+        Ok(bytemuck::from_bytes_mut(&mut bytes[..size]))
+    }
+
+    ///  The  payload  elements.
+    pub fn payload(&self) -> &[T] {
+        bytemuck::cast_slice(&self.bytes.as_slice()[Self::PAYLOAD_OFFSET..])
+    }
+
+    ///  Mutable  access  to  the  payload.  Fails  on  read-only  storage.
+    pub fn payload_mut(&mut self) -> Result<&mut [T], ContainerError> {
+        let len = self.bytes.as_slice().len();
+        let bytes = self.bytes.as_slice_mut(Self::PAYLOAD_OFFSET..len).ok_or(
+            ContainerError::UnsupportedOperation(
+                "Cannot  get  mutable  reference  to  read-only  storage",
+            ),
+        )?;
+        Ok(bytemuck::cast_slice_mut(&mut bytes[Self::PAYLOAD_OFFSET..]))
+    }
+
+    ///  Number  of  payload  elements.
+    pub fn len(&self) -> usize {
+        (self.bytes.as_slice().len() - Self::PAYLOAD_OFFSET) / std::mem::size_of::<T>()
+    }
+
+    ///  Returns  true  if  the  payload  is  empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///  Append  payload  elements  (only  works  on  in-memory  storage).
+    ///
+    ///  The  container  is  left  unchanged  if  appending  fails.
+    pub fn append(&mut self, new: &[T]) -> Result<(), ContainerError> {
+        match &mut self.bytes {
+            //  The  buffer  keeps  its  alignment  when  it  grows,  and  `new`  is  whole  elements,
+            //  so  the  layout  stays  valid
+            HeaderedBytes::Memory(buf) => buf.extend_from_slice(bytemuck::cast_slice(new)),
+            HeaderedBytes::Fixed(_) => Err(ContainerError::UnsupportedOperation(
+                "Append  not  supported  on  mmap  storage",
+            )),
+        }
+    }
+
+    ///  Flush  changes  to  the  mapped  file;  see  [`RawBytesContainer::flush`].
+    pub fn flush(&self) -> Result<(), ContainerError> {
+        match &self.bytes {
+            HeaderedBytes::Memory(_) => Err(ContainerError::UnsupportedOperation(
+                "Flush  only  supported  on  mmap  RW",
+            )),
+            HeaderedBytes::Fixed(bytes) => bytes.flush(),
+        }
+    }
+
+    ///  Write  header  and  payload  to  `path`.
+    pub fn write_to_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ContainerError> {
+        match &mut self.bytes {
+            HeaderedBytes::Memory(buf) => Ok(std::fs::write(path, buf.as_slice())?),
+            HeaderedBytes::Fixed(bytes) => bytes.write_to_file(path),
+        }
+    }
+
+    ///  The  underlying  bytes,  header  included.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    ///  Unwrap  into  the  underlying  bytes,  header  included.
+    ///
+    ///  In-memory  bytes  are  copied  into  a  plain  `Vec<u8>`,  which  is  not  guaranteed  to  keep
+    ///  their  alignment.
+    pub fn into_container(self) -> RawBytesContainer<u8> {
+        match self.bytes {
+            HeaderedBytes::Memory(buf) => RawBytesContainer::from_vec(buf.as_slice().to_vec()),
+            HeaderedBytes::Fixed(bytes) => bytes,
+        }
+    }
+}
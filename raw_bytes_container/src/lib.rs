@@ -34,23 +34,94 @@
 //!
 //!  [`bytemuck`]:  https://docs.rs/bytemuck
 
+mod access;
+mod aligned;
+#[cfg(feature = "async")]
+mod async_io;
+mod blocks;
+pub mod buffered;
+pub mod builder;
+#[cfg(feature = "bytes")]
+mod bytes_interop;
+pub mod checksum;
+pub mod concurrent;
 pub mod container;
+mod dirty;
 pub mod error;
+pub mod file_header;
+pub mod headered;
+pub mod mmap_vec;
+#[cfg(feature = "rayon")]
+mod par;
+mod prefetch;
+pub mod recovery;
+pub mod sectioned;
+pub mod segmented;
+pub mod shared;
+pub mod snapshot;
+mod sort;
+mod spare;
+mod sparse;
+pub mod stats;
 pub mod storage;
+pub mod view;
+pub mod writer;
 
 //  Re-export  core  types  for  convenience
 
 ///  Main  container  type  for  working  with  POD  data  in  memory  or  memory-mapped  files.
 ///
 ///  See  [`RawBytesContainer`](crate::container::RawBytesContainer)  for  details.
-pub use container::RawBytesContainer;
+pub use container::{Advice, FileLock, MapOptions, RawBytesContainer, TrailingBytes};
+
+///  Single  entry  point  for  constructing  containers.
+pub use builder::{Backend, RawBytesContainerBuilder};
+
+///  Block-wise  appender  returned  by  [`RawBytesContainer::append_buffered`].
+pub use buffered::{AppendSink, BufferedAppender};
+
+///  Region-locked  wrapper  for  parallel  in-place  mutation.
+pub use concurrent::{ConcurrentRawBytes, RegionGuard};
 
 ///  Error  type  used  throughout  the  crate.
 ///
 ///  Wraps  I/O  errors,  alignment  errors,  and  unsupported  operation  errors.
 pub use error::ContainerError;
 
+///  Magic  +  version  prefix  used  by  the  file  formats  in  this  crate.
+pub use file_header::FileHeader;
+
+///  Typed  header  followed  by  a  typed  payload  array.
+pub use headered::HeaderedContainer;
+
+///  Push-capable  vector  stored  in  a  memory-mapped  file.
+pub use mmap_vec::MmapVec;
+
+///  Salvage  report  returned  by  lossy  loaders.
+pub use recovery::Recovery;
+
+///  Multi-section  typed  files.
+pub use sectioned::{SectionInfo, SectionedFile, SectionedFileBuilder};
+
+///  Append-friendly  in-memory  storage  built  from  fixed-size  segments.
+pub use segmented::SegmentedContainer;
+
+///  Cheaply  cloneable  read-only  handle  for  sharing  a  container  across  threads.
+pub use shared::SharedRawBytes;
+
+///  Copy-on-write  checkpoint  of  an  in-memory  container.
+pub use snapshot::Snapshot;
+
 ///  Backend  storage  variants  for  [`RawBytesContainer`].
 ///
 ///  Usually  you  don't  need  to  use  this  directly,  but  it  may  be  useful  for  inspection.
-pub use storage::Storage;
+pub use storage::{Storage, StorageKind};
+
+///  Memory  usage  report  returned  by  [`RawBytesContainer::stats`].
+pub use stats::ContainerStats;
+
+///  Bounded  windows  over  part  of  a  container.
+pub use view::View;
+
+///  Byte-level  writer  returned  by  [`RawBytesContainer::writer`].
+pub use writer::ContainerWriter;
@@ -0,0 +1,238 @@
+//!  Growable  vector  stored  in  a  memory-mapped  file.
+//!
+//!  ```text
+//!  [FileHeader:  "MVEC",  version][LEN:  u64][PADDING  to  align_of::<T>()][T;  capacity]
+//!  ```
+//!
+//!  The  file  is  sized  for  the  capacity;  only  the  first  `LEN`  elements  are  in  use.
+
+use crate::headered::HeaderedBytes;
+use crate::{ContainerError, HeaderedContainer, file_header::FileHeader};
+use bytemuck::Pod;
+use bytemuck_derive::{Pod, Zeroable};
+use std::{ops::Deref, path::Path};
+
+const MMAP_VEC_MAGIC: &[u8; 4] = b"MVEC";
+const MMAP_VEC_VERSION: u32 = 1;
+
+///  Smallest  capacity,  in  bytes,  the  file  grows  to.
+pub(crate) const MIN_GROW_BYTES: usize = 4096;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct MmapVecHeader {
+    file: FileHeader,
+    len: u64,
+}
+
+///  A  `Vec`-like  array  of  `T`  kept  in  a  memory-mapped  file.
+///
+///  The  length  lives  in  a  header  at  the  start  of  the  file  and  the  file  is  sized  for
+///  the  capacity,  growing  geometrically  when  a  push  does  not  fit,  so  pushes  and  pops
+///  work  directly  on  the  mapping  without  an  in-memory  copy  and  a
+///  [`write_to_file`](crate::RawBytesContainer::write_to_file)  round  trip.  Changes
+///  reach  the  disk  on  [`flush`](Self::flush)  or  when  the  OS  writes  the  pages  back.
+///
+///  ```
+///  use  raw_bytes_container::MmapVec;
+///
+///  let  dir  =  tempfile::tempdir().unwrap();
+///  let  path  =  dir.path().join("ticks.vec");
+///
+///  let  mut  ticks  =  MmapVec::<u64>::create(&path).unwrap();
+///  for  tick  in  0..10_000  {
+///          ticks.push(tick).unwrap();
+///  }
+///  ticks.flush().unwrap();
+///  drop(ticks);
+///
+///  let  mut  ticks  =  MmapVec::<u64>::open(&path).unwrap();
+///  assert_eq!(ticks.len(),  10_000);
+///  assert_eq!(ticks.pop(),  Some(9_999));
+///  ```
+#[derive(Debug)]
+pub struct MmapVec<T: Pod> {
+    inner: HeaderedContainer<MmapVecHeader, T>,
+}
+
+impl<T: Pod> MmapVec<T> {
+    ///  Create  (or  truncate)  the  file  at  `path`  as  an  empty  vector.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Self::create_with_capacity(path, 0)
+    }
+
+    ///  Create  (or  truncate)  the  file  at  `path`  as  an  empty  vector  with  room  for
+    ///  `capacity`  elements.
+    pub fn create_with_capacity<P: AsRef<Path>>(
+        path: P,
+        capacity: usize,
+    ) -> Result<Self, ContainerError> {
+        let header = MmapVecHeader {
+            file: FileHeader::new(*MMAP_VEC_MAGIC, MMAP_VEC_VERSION),
+            len: 0,
+        };
+        let inner = HeaderedContainer::create_mmap_rw(path, header, capacity)?;
+        Ok(Self { inner })
+    }
+
+    ///  Map  an  existing  vector  file  read-write.
+    ///
+    ///  #  Errors
+    ///  Fails  if  the  file  is  not  an  `MmapVec`  file  or  its  length  exceeds  its  capacity.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        let inner = HeaderedContainer::<MmapVecHeader, T>::open_mmap_rw(path)?;
+        let header = inner.header();
+        header.file.validate(MMAP_VEC_MAGIC, MMAP_VEC_VERSION)?;
+        if header.len > inner.len() as u64 {
+            return Err(ContainerError::InvalidFormat(format!(
+                "length  {}  exceeds  capacity  {}",
+                header.len,
+                inner.len()
+            )));
+        }
+        Ok(Self { inner })
+    }
+
+    ///  Number  of  elements.
+    pub fn len(&self) -> usize {
+        self.inner.header().len as usize
+    }
+
+    ///  Returns  true  if  there  are  no  elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///  Number  of  elements  the  file  has  room  for  without  growing.
+    pub fn capacity(&self) -> usize {
+        self.inner.len()
+    }
+
+    ///  The  elements.
+    pub fn as_slice(&self) -> &[T] {
+        &self.inner.payload()[..self.len()]
+    }
+
+    ///  The  elements,  mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let len = self.len();
+        &mut self.payload_mut()[..len]
+    }
+
+    fn payload_mut(&mut self) -> &mut [T] {
+        //  The  mapping  is  always  read-write
+        self.inner
+            .payload_mut()
+            .expect("MmapVec  mapping  is  writable")
+    }
+
+    fn set_len(&mut self, len: usize) {
+        self.inner
+            .header_mut()
+            .expect("MmapVec  mapping  is  writable")
+            .len = len as u64;
+    }
+
+    ///  Resize  the  file  to  hold  exactly  `capacity`  elements  and  remap  it.
+    fn set_capacity(&mut self, capacity: usize) -> Result<(), ContainerError> {
+        let byte_len = capacity
+            .checked_mul(std::mem::size_of::<T>())
+            .and_then(|n| n.checked_add(HeaderedContainer::<MmapVecHeader, T>::PAYLOAD_OFFSET))
+            .ok_or(ContainerError::UnsupportedOperation(
+                "Requested  mmap  length  overflows",
+            ))?;
+        let HeaderedBytes::Fixed(bytes) = &mut self.inner.bytes else {
+            unreachable!("MmapVec  is  opened  from  a  file")
+        };
+        bytes
+            .file
+            .as_ref()
+            .expect("MmapVec  is  opened  from  a  file")
+            .set_len(byte_len as u64)?;
+        bytes.remap()?;
+        self.inner.check_layout()
+    }
+
+    ///  Make  room  for  at  least  `additional`  more  elements,  growing  the  file
+    ///  geometrically.
+    pub fn reserve(&mut self, additional: usize) -> Result<(), ContainerError> {
+        let needed = self
+            .len()
+            .checked_add(additional)
+            .ok_or(ContainerError::UnsupportedOperation("Capacity  overflow"))?;
+        if needed <= self.capacity() {
+            return Ok(());
+        }
+        let min = MIN_GROW_BYTES / std::mem::size_of::<T>().max(1);
+        let capacity = needed.max(self.capacity().saturating_mul(2)).max(min);
+        self.set_capacity(capacity)
+    }
+
+    ///  Append  one  element,  growing  the  file  if  it  is  full.
+    pub fn push(&mut self, value: T) -> Result<(), ContainerError> {
+        self.extend_from_slice(std::slice::from_ref(&value))
+    }
+
+    ///  Append  several  elements,  growing  the  file  if  needed.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> Result<(), ContainerError> {
+        self.reserve(values.len())?;
+        let len = self.len();
+        self.payload_mut()[len..len + values.len()].copy_from_slice(values);
+        self.set_len(len + values.len());
+        Ok(())
+    }
+
+    ///  Remove  and  return  the  last  element.  The  file  keeps  its  capacity.
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        let value = self.inner.payload()[len - 1];
+        self.set_len(len - 1);
+        Some(value)
+    }
+
+    ///  Shorten  to  `len`  elements;  no  effect  if  already  shorter.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.set_len(len);
+        }
+    }
+
+    ///  Remove  all  elements.  The  file  keeps  its  capacity.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    ///  Shrink  the  file  to  fit  the  current  length.
+    pub fn shrink_to_fit(&mut self) -> Result<(), ContainerError> {
+        let len = self.len();
+        if len < self.capacity() {
+            self.set_capacity(len)?;
+        }
+        Ok(())
+    }
+
+    ///  See  [`RawBytesContainer::change_generation`](crate::RawBytesContainer::change_generation);
+    ///  covers  both  the  elements  and  the  length.
+    pub fn change_generation(&self) -> u64 {
+        let HeaderedBytes::Fixed(bytes) = &self.inner.bytes else {
+            unreachable!("MmapVec  is  opened  from  a  file")
+        };
+        bytes.change_generation()
+    }
+
+    ///  Flush  the  elements  and  the  length  to  disk.
+    pub fn flush(&self) -> Result<(), ContainerError> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Pod> Deref for MmapVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::RawBytesContainer;
+use bytemuck::Pod;
+use rayon::iter::IntoParallelIterator;
+
+impl<'a, T: Pod + Sync> IntoParallelIterator for &'a RawBytesContainer<T> {
+    type Iter = rayon::slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice().into_par_iter()
+    }
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Parallel  iterator  over  the  elements,  on  the  rayon  thread  pool.
+    ///
+    ///  Same  as  `rayon::prelude::IntoParallelRefIterator::par_iter`,  without  the  import.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///  use  rayon::prelude::*;
+    ///
+    ///  let  container  =  RawBytesContainer::from_vec((1u64..=100).collect());
+    ///  assert_eq!(container.par_iter().sum::<u64>(),  5050);
+    ///  ```
+    pub fn par_iter(&self) -> rayon::slice::Iter<'_, T>
+    where
+        T: Sync,
+    {
+        self.into_par_iter()
+    }
+
+    ///  Parallel  iterator  over  mutable  elements.
+    ///
+    ///  Returns  `None`  if  the  storage  is  read-only.
+    pub fn par_iter_mut(&mut self) -> Option<rayon::slice::IterMut<'_, T>>
+    where
+        T: Send,
+    {
+        Some(self.as_slice_mut()?.into_par_iter())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{ContainerError, RawBytesContainer, Storage, view::resolve_range};
+use bytemuck::Pod;
+use std::ops::RangeBounds;
+
+///  Stride  used  to  touch  pages  where  the  platform  has  no  prefetch  hint.
+#[cfg(not(unix))]
+const PAGE_STRIDE: usize = 4096;
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Start  reading  the  elements  in  `range`  into  memory  ahead  of  use.
+    ///
+    ///  On  unix  this  issues  `madvise(MADV_WILLNEED)`  for  the  mapped  pages  and  returns
+    ///  without  waiting  for  the  reads,  so  a  game  can  warm  the  next  asset  or  record
+    ///  range  while  it  is  still  busy  with  the  current  one.  On  other  platforms  the
+    ///  pages  are  touched  one  by  one  instead,  which  blocks  until  they  are  loaded.
+    ///  In-memory  and  borrowed  storage  is  already  resident,  so  this  is  a  no-op  there.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  file  =  tempfile::NamedTempFile::new().unwrap();
+    ///  let  records  =  RawBytesContainer::<u64>::create_mmap_rw(file.path(),  1  <<  16).unwrap();
+    ///  records.prefetch(4096..8192).unwrap();
+    ///  assert!(records.prefetch(0..(1  <<  17)).is_err());
+    ///  ```
+    pub fn prefetch(&self, range: impl RangeBounds<usize>) -> Result<(), ContainerError> {
+        let range = resolve_range(range, self.len()).ok_or(
+            ContainerError::UnsupportedOperation("Prefetch  range  out  of  bounds"),
+        )?;
+        if range.is_empty() {
+            return Ok(());
+        }
+        match &self.storage {
+            Storage::InMemory(_) | Storage::Shared(_) | Storage::Borrowed(_) => Ok(()),
+            #[cfg(unix)]
+            _ => self.advise_range(crate::Advice::WillNeed, range),
+            #[cfg(not(unix))]
+            _ => {
+                let bytes: &[u8] = bytemuck::cast_slice(&self.as_slice()[range]);
+                for offset in (0..bytes.len()).step_by(PAGE_STRIDE) {
+                    //  SAFETY:  `offset`  is  in  bounds  of  `bytes`.
+                    unsafe { std::ptr::read_volatile(bytes.as_ptr().add(offset)) };
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{ContainerError, RawBytesContainer, TrailingBytes};
+use bytemuck::Pod;
+use std::path::Path;
+
+///  How  much  of  a  damaged  file  a  lossy  loader  salvaged.
+///
+///  Returned  by  [`RawBytesContainer::open_mmap_read_lossy`]  and  by  the  recovery
+///  loaders  of  containers  built  on  top  of  this  crate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Recovery {
+    ///  Number  of  whole  elements  salvaged.
+    pub recovered: usize,
+    ///  Number  of  elements  the  file  claims  to  hold,  if  its  format  records  one.
+    pub expected: Option<usize>,
+    ///  Bytes  after  the  last  salvaged  element  that  were  dropped.
+    pub discarded_bytes: usize,
+}
+
+impl Recovery {
+    ///  Returns  true  if  nothing  was  lost.
+    pub fn is_complete(&self) -> bool {
+        self.discarded_bytes == 0 && self.expected.is_none_or(|n| n == self.recovered)
+    }
+
+    ///  Number  of  elements  known  to  be  missing.
+    pub fn lost(&self) -> usize {
+        self.expected
+            .map_or(0, |n| n.saturating_sub(self.recovered))
+    }
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Open  a  read-only  memory-mapped  file,  salvaging  every  whole  element.
+    ///
+    ///  Meant  for  files  cut  short  by  a  crash,  such  as  telemetry  logs:  instead  of
+    ///  failing  like  [`open_mmap_read`](Self::open_mmap_read),  a  partial  element  at  the
+    ///  end  is  dropped  and  reported.  The  dropped  bytes  stay  available  through
+    ///  [`trailing_bytes`](Self::trailing_bytes).
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  file  =  tempfile::NamedTempFile::new().unwrap();
+    ///  std::fs::write(file.path(),  [1,  0,  0,  0,  2,  0,  0,  0,  3,  0]).unwrap();
+    ///
+    ///  let  (log,  recovery)  =  RawBytesContainer::<u32>::open_mmap_read_lossy(file.path()).unwrap();
+    ///  assert_eq!(log.len(),  2);
+    ///  assert_eq!(recovery.recovered,  2);
+    ///  assert_eq!(recovery.discarded_bytes,  2);
+    ///  assert!(!recovery.is_complete());
+    ///  ```
+    pub fn open_mmap_read_lossy<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<(Self, Recovery), ContainerError> {
+        let container = Self::open_mmap_read_with(path, TrailingBytes::Ignore)?;
+        let recovery = Recovery {
+            recovered: container.len(),
+            expected: None,
+            discarded_bytes: container.trailing_bytes().len(),
+        };
+        Ok((container, recovery))
+    }
+}
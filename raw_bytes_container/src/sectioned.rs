@@ -0,0 +1,302 @@
+//!  Multi-section  typed  files.
+//!
+//!  A  `SectionedFile`  holds  several  named  arrays  of  Pod  data  in  one  file.  Each
+//!  section  records  the  element  type  hash,  element  size  and  count  so  that
+//!  [`SectionedFile::open_section`]  can  hand  out  a  typed  slice  without  copying.
+//!
+//!  ```text
+//!  [FileHeader:  "SECT",  version]
+//!  [SECTION_COUNT:  u32][RESERVED:  u32]
+//!  [SectionEntry;  SECTION_COUNT]
+//!  [NAME  TABLE:  utf-8  names,  not  terminated]
+//!  [SECTION  DATA:  each  section  aligned  to  SECTION_ALIGN]
+//!  ```
+
+use crate::{ContainerError, RawBytesContainer, file_header::FileHeader};
+use bytemuck::Pod;
+use bytemuck_derive::{Pod, Zeroable};
+use std::{fs::File, io::Write, path::Path};
+
+const SECTIONED_MAGIC: &[u8; 4] = b"SECT";
+const SECTIONED_VERSION: u32 = 1;
+const SECTION_ALIGN: usize = 16;
+const PREAMBLE_SIZE: usize = FileHeader::SIZE + 8;
+
+///  Hash  identifying  an  element  type  stored  in  a  section.
+///
+///  Derived  from  the  type  name,  size  and  alignment.  Type  names  are  not
+///  guaranteed  stable  across  compiler  versions,  so  treat  the  hash  as  a  guard
+///  against  mixing  up  types  rather  than  a  long-term  schema  identifier.
+pub fn type_hash<T: Pod>() -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    let size = std::mem::size_of::<T>() as u64;
+    let align = std::mem::align_of::<T>() as u64;
+    for byte in std::any::type_name::<T>()
+        .as_bytes()
+        .iter()
+        .chain(&size.to_le_bytes())
+        .chain(&align.to_le_bytes())
+    {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+///  On-disk  table  entry  describing  one  section.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct SectionEntry {
+    name_offset: u32,
+    name_len: u32,
+    type_hash: u64,
+    count: u64,
+    data_offset: u64,
+    elem_size: u32,
+    elem_align: u32,
+}
+
+const SECTION_ENTRY_SIZE: usize = std::mem::size_of::<SectionEntry>();
+
+///  Metadata  about  a  section,  as  listed  by  [`SectionedFile::sections`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionInfo {
+    pub name: String,
+    pub type_hash: u64,
+    pub elem_size: usize,
+    pub count: usize,
+}
+
+///  Builder  that  collects  sections  and  writes  them  to  a  file.
+#[derive(Debug, Default)]
+pub struct SectionedFileBuilder {
+    sections: Vec<(String, SectionEntry, Vec<u8>)>,
+}
+
+impl SectionedFileBuilder {
+    ///  Create  an  empty  builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///  Add  a  named  section  holding  `data`.
+    ///
+    ///  Adding  a  section  with  a  name  that  already  exists  replaces  it.
+    pub fn add_section<T: Pod>(&mut self, name: impl Into<String>, data: &[T]) -> &mut Self {
+        let name = name.into();
+        let entry = SectionEntry {
+            name_offset: 0,
+            name_len: 0,
+            type_hash: type_hash::<T>(),
+            count: data.len() as u64,
+            data_offset: 0,
+            elem_size: std::mem::size_of::<T>() as u32,
+            elem_align: std::mem::align_of::<T>() as u32,
+        };
+        let bytes = bytemuck::cast_slice(data).to_vec();
+        self.sections.retain(|(n, _, _)| *n != name);
+        self.sections.push((name, entry, bytes));
+        self
+    }
+
+    ///  Add  the  section  called  `name`  from  `file`,  keeping  its  element  type.
+    ///
+    ///  Copies  sections  between  files  without  knowing  their  element  type.  Adding  a
+    ///  section  with  a  name  that  already  exists  replaces  it.
+    pub fn copy_section(
+        &mut self,
+        file: &SectionedFile,
+        name: &str,
+    ) -> Result<&mut Self, ContainerError> {
+        let idx = file.index_of(name)?;
+        let entry = file.entries[idx];
+        let start = entry.data_offset as usize;
+        let end = start + entry.count as usize * entry.elem_size as usize;
+        let bytes = file.data.as_slice()[start..end].to_vec();
+        self.sections.retain(|(n, _, _)| n != name);
+        self.sections.push((name.to_string(), entry, bytes));
+        Ok(self)
+    }
+
+    ///  Number  of  sections  added  so  far.
+    pub fn section_count(&self) -> usize {
+        self.sections.len()
+    }
+
+    ///  Write  all  sections  to  `path`.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), ContainerError> {
+        let mut file = File::create(path)?;
+        file.write_all(&self.encode())?;
+        file.flush()?;
+        Ok(())
+    }
+
+    ///  Write  all  sections  to  a  temporary  file  next  to  `path`,  then  rename  it  over
+    ///  `path`.
+    ///
+    ///  Readers  never  see  a  half-written  file,  and  a  [`SectionedFile`]  still  mapping
+    ///  the  old  file  keeps  seeing  its  old  contents.
+    pub fn write_atomic<P: AsRef<Path>>(&self, path: P) -> Result<(), ContainerError> {
+        let path = path.as_ref();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        tmp.write_all(&self.encode())?;
+        if let Ok(meta) = std::fs::metadata(path) {
+            tmp.as_file().set_permissions(meta.permissions())?;
+        }
+        tmp.as_file().sync_all()?;
+        tmp.persist(path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    ///  Lay  out  the  whole  file  in  memory.
+    fn encode(&self) -> Vec<u8> {
+        let mut names = Vec::new();
+        let mut entries = Vec::with_capacity(self.sections.len());
+        for (name, entry, _) in &self.sections {
+            let mut entry = *entry;
+            entry.name_offset = names.len() as u32;
+            entry.name_len = name.len() as u32;
+            names.extend_from_slice(name.as_bytes());
+            entries.push(entry);
+        }
+
+        let mut offset = (PREAMBLE_SIZE + entries.len() * SECTION_ENTRY_SIZE + names.len())
+            .next_multiple_of(SECTION_ALIGN);
+        for (entry, (_, _, bytes)) in entries.iter_mut().zip(&self.sections) {
+            entry.data_offset = offset as u64;
+            offset = (offset + bytes.len()).next_multiple_of(SECTION_ALIGN);
+        }
+
+        let mut out = Vec::with_capacity(offset);
+        out.extend_from_slice(FileHeader::new(*SECTIONED_MAGIC, SECTIONED_VERSION).as_bytes());
+        out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(bytemuck::cast_slice(&entries));
+        out.extend_from_slice(&names);
+        for (entry, (_, _, bytes)) in entries.iter().zip(&self.sections) {
+            out.resize(entry.data_offset as usize, 0);
+            out.extend_from_slice(bytes);
+        }
+        out.resize(offset, 0);
+        out
+    }
+}
+
+///  A  memory-mapped  file  holding  several  named,  typed  sections.
+#[derive(Debug)]
+pub struct SectionedFile {
+    data: RawBytesContainer<u8>,
+    entries: Vec<SectionEntry>,
+    names: Vec<String>,
+}
+
+impl SectionedFile {
+    ///  Open  and  validate  a  sectioned  file  (read-only  mmap).
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        let data = RawBytesContainer::<u8>::open_mmap_read(path)?;
+        let bytes = data.as_slice();
+
+        FileHeader::read(bytes)?.validate(SECTIONED_MAGIC, SECTIONED_VERSION)?;
+        if bytes.len() < PREAMBLE_SIZE {
+            return Err(ContainerError::InvalidFormat(
+                "file  too  small  for  section  table".to_string(),
+            ));
+        }
+
+        let count = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let table_end = count
+            .checked_mul(SECTION_ENTRY_SIZE)
+            .and_then(|n| n.checked_add(PREAMBLE_SIZE))
+            .filter(|&end| end <= bytes.len())
+            .ok_or_else(|| {
+                ContainerError::InvalidFormat("section  table  extends  beyond  file".to_string())
+            })?;
+
+        let mut entries = Vec::with_capacity(count);
+        let mut names = Vec::with_capacity(count);
+        for chunk in bytes[PREAMBLE_SIZE..table_end].chunks_exact(SECTION_ENTRY_SIZE) {
+            let entry: SectionEntry = bytemuck::pod_read_unaligned(chunk);
+
+            let name_start = table_end + entry.name_offset as usize;
+            let name = bytes
+                .get(name_start..name_start + entry.name_len as usize)
+                .and_then(|b| std::str::from_utf8(b).ok())
+                .ok_or_else(|| {
+                    ContainerError::InvalidFormat("invalid  section  name".to_string())
+                })?;
+
+            let data_len = (entry.count as usize)
+                .checked_mul(entry.elem_size as usize)
+                .and_then(|n| n.checked_add(entry.data_offset as usize));
+            if data_len.is_none_or(|end| end > bytes.len()) {
+                return Err(ContainerError::InvalidFormat(format!(
+                    "section  '{}'  extends  beyond  file",
+                    name
+                )));
+            }
+
+            names.push(name.to_string());
+            entries.push(entry);
+        }
+
+        Ok(Self {
+            data,
+            entries,
+            names,
+        })
+    }
+
+    ///  List  the  sections  stored  in  the  file,  in  file  order.
+    pub fn sections(&self) -> Vec<SectionInfo> {
+        self.entries
+            .iter()
+            .zip(&self.names)
+            .map(|(entry, name)| SectionInfo {
+                name: name.clone(),
+                type_hash: entry.type_hash,
+                elem_size: entry.elem_size as usize,
+                count: entry.count as usize,
+            })
+            .collect()
+    }
+
+    ///  Returns  true  if  a  section  with  `name`  exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n == name)
+    }
+
+    ///  Get  a  typed  view  of  the  section  called  `name`.
+    ///
+    ///  Fails  if  the  section  does  not  exist  or  was  written  with  a  different
+    ///  element  type.
+    pub fn open_section<T: Pod>(&self, name: &str) -> Result<&[T], ContainerError> {
+        let entry = &self.entries[self.index_of(name)?];
+
+        if entry.type_hash != type_hash::<T>()
+            || entry.elem_size as usize != std::mem::size_of::<T>()
+        {
+            return Err(ContainerError::TypeMismatch(format!(
+                "section  '{}'  does  not  hold  elements  of  type  {}",
+                name,
+                std::any::type_name::<T>()
+            )));
+        }
+
+        let start = entry.data_offset as usize;
+        let end = start + entry.count as usize * entry.elem_size as usize;
+        bytemuck::try_cast_slice(&self.data.as_slice()[start..end])
+            .map_err(|e| ContainerError::AlignmentError(format!("section  '{}':  {}", name, e)))
+    }
+
+    ///  Position  of  the  section  called  `name`  in  the  section  table.
+    fn index_of(&self, name: &str) -> Result<usize, ContainerError> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| ContainerError::NotFound(name.to_string()))
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{ContainerError, RawBytesContainer};
+use bytemuck::Pod;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+///  Default  number  of  elements  per  segment.
+pub const DEFAULT_SEGMENT_LEN: usize = 1 << 16;
+
+///  In-memory  storage  built  from  fixed-size  segments  instead  of  one  growing  `Vec`.
+///
+///  Appending  never  moves  existing  data:  once  a  segment  is  full  a  new  one  is
+///  allocated,  so  building  a  very  large  array  incrementally  costs  no
+///  reallocation-and-copy  of  everything  written  so  far.  The  trade-off  is  that  the
+///  data  is  not  contiguous;  [`as_slice`](Self::as_slice)  only  succeeds  while  it  fits
+///  in  one  segment,  and  [`into_container`](Self::into_container)  consolidates  it  into
+///  a  regular  [`RawBytesContainer`]  on  demand.
+///
+///  Every  segment  except  the  last  holds  exactly  [`segment_len`](Self::segment_len)
+///  elements.
+#[derive(Debug, Clone)]
+pub struct SegmentedContainer<T: Pod> {
+    segments: Vec<Vec<T>>,
+    segment_len: usize,
+    len: usize,
+}
+
+impl<T: Pod> Default for SegmentedContainer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Pod> SegmentedContainer<T> {
+    ///  Create  an  empty  container  with  [`DEFAULT_SEGMENT_LEN`]  elements  per  segment.
+    pub fn new() -> Self {
+        Self::with_segment_len(DEFAULT_SEGMENT_LEN)
+    }
+
+    ///  Create  an  empty  container  with  `segment_len`  elements  per  segment  (at  least  1).
+    pub fn with_segment_len(segment_len: usize) -> Self {
+        Self {
+            segments: Vec::new(),
+            segment_len: segment_len.max(1),
+            len: 0,
+        }
+    }
+
+    ///  Number  of  elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    ///  Returns  true  if  there  are  no  elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    ///  Elements  per  segment.
+    pub fn segment_len(&self) -> usize {
+        self.segment_len
+    }
+
+    ///  Number  of  allocated  segments.
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    ///  The  segments,  in  order.
+    pub fn segments(&self) -> impl Iterator<Item = &[T]> {
+        self.segments.iter().map(Vec::as_slice)
+    }
+
+    ///  Last  segment,  allocating  a  new  one  if  it  is  full.
+    fn tail(&mut self) -> &mut Vec<T> {
+        if self
+            .segments
+            .last()
+            .is_none_or(|s| s.len() == self.segment_len)
+        {
+            self.segments.push(Vec::with_capacity(self.segment_len));
+        }
+        self.segments.last_mut().unwrap()
+    }
+
+    ///  Append  one  element.
+    pub fn push(&mut self, value: T) {
+        self.tail().push(value);
+        self.len += 1;
+    }
+
+    ///  Append  several  elements,  filling  segments  in  order.
+    pub fn extend_from_slice(&mut self, mut values: &[T]) {
+        while !values.is_empty() {
+            let segment_len = self.segment_len;
+            let tail = self.tail();
+            let take = values.len().min(segment_len - tail.len());
+            tail.extend_from_slice(&values[..take]);
+            values = &values[take..];
+            self.len += take;
+        }
+    }
+
+    ///  Element  at  `index`.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.segments
+            .get(index / self.segment_len)?
+            .get(index % self.segment_len)
+    }
+
+    ///  Mutable  element  at  `index`.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.segments
+            .get_mut(index / self.segment_len)?
+            .get_mut(index % self.segment_len)
+    }
+
+    ///  Iterate  over  all  elements  in  order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.segments.iter().flatten()
+    }
+
+    ///  Remove  all  elements  and  free  the  segments.
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.len = 0;
+    }
+
+    ///  The  elements  as  one  slice,  if  they  fit  in  a  single  segment.
+    ///
+    ///  #  Errors
+    ///  Returns  [`ContainerError::UnsupportedOperation`]  once  the  data  spans  several
+    ///  segments;  use  [`into_container`](Self::into_container)  or
+    ///  [`to_vec`](Self::to_vec)  to  get  a  contiguous  copy.
+    pub fn as_slice(&self) -> Result<&[T], ContainerError> {
+        match self.segments.as_slice() {
+            [] => Ok(&[]),
+            [segment] => Ok(segment),
+            _ => Err(ContainerError::UnsupportedOperation(
+                "Segmented  data  is  not  contiguous",
+            )),
+        }
+    }
+
+    ///  Copy  all  elements  into  one  `Vec`.
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut out = Vec::with_capacity(self.len);
+        for segment in &self.segments {
+            out.extend_from_slice(segment);
+        }
+        out
+    }
+
+    ///  Consolidate  into  a  contiguous  in-memory  [`RawBytesContainer`].
+    ///
+    ///  A  single  segment  is  moved  without  copying.
+    pub fn into_container(mut self) -> RawBytesContainer<T> {
+        if self.segments.len() == 1 {
+            return RawBytesContainer::from_vec(self.segments.pop().unwrap());
+        }
+        RawBytesContainer::from_vec(self.to_vec())
+    }
+
+    ///  Write  the  elements  to  `path`,  one  segment  at  a  time,  without  consolidating.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ContainerError> {
+        let mut out = BufWriter::new(File::create(path)?);
+        for segment in &self.segments {
+            out.write_all(bytemuck::cast_slice(segment))?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        Ok(())
+    }
+}
+
+impl<T: Pod> std::ops::Index<usize> for SegmentedContainer<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index  out  of  bounds")
+    }
+}
+
+impl<T: Pod> std::ops::IndexMut<usize> for SegmentedContainer<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index  out  of  bounds")
+    }
+}
+
+impl<T: Pod> Extend<T> for SegmentedContainer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
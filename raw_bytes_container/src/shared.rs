@@ -0,0 +1,97 @@
+use crate::{ContainerError, RawBytesContainer, view::resolve_range};
+use bytemuck::Pod;
+use std::{
+    ops::{Deref, Range, RangeBounds},
+    path::Path,
+    sync::Arc,
+};
+
+///  Cheaply  cloneable,  read-only  handle  to  a  container.
+///
+///  Clones  share  the  same  storage  through  an  [`Arc`],  so  several  threads  can
+///  read  one  mapping  without  wrapping  the  container  themselves.  A  handle  can
+///  be  narrowed  to  a  sub-range  with  [`slice`](Self::slice);  the  narrowed  handle
+///  owns  its  share  of  the  storage  and  can  be  sent  to  another  thread.
+#[derive(Debug)]
+pub struct SharedRawBytes<T: Pod> {
+    inner: Arc<RawBytesContainer<T>>,
+    range: Range<usize>,
+}
+
+impl<T: Pod> SharedRawBytes<T> {
+    ///  Share  an  existing  container.
+    pub fn new(container: RawBytesContainer<T>) -> Self {
+        let range = 0..container.len();
+        Self {
+            inner: Arc::new(container),
+            range,
+        }
+    }
+
+    ///  Open  a  read-only  memory-mapped  file  and  share  it.
+    pub fn open_mmap_read<P: AsRef<Path>>(path: P) -> Result<Self, ContainerError> {
+        Ok(Self::new(RawBytesContainer::open_mmap_read(path)?))
+    }
+
+    ///  Get  a  read-only  slice  over  the  shared  range.
+    pub fn as_slice(&self) -> &[T] {
+        &self.inner.as_slice()[self.range.clone()]
+    }
+
+    ///  Number  of  elements  in  this  handle's  range.
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    ///  Returns  true  if  this  handle's  range  is  empty.
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    ///  Element  range  of  the  underlying  container  covered  by  this  handle.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    ///  Narrow  to  `range`  (relative  to  this  handle),  or  `None`  if  out  of  bounds.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Option<Self> {
+        let sub = resolve_range(range, self.len())?;
+        Some(Self {
+            inner: Arc::clone(&self.inner),
+            range: self.range.start + sub.start..self.range.start + sub.end,
+        })
+    }
+
+    ///  Number  of  handles  sharing  the  underlying  container.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+}
+
+impl<T: Pod> Clone for SharedRawBytes<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<T: Pod> From<RawBytesContainer<T>> for SharedRawBytes<T> {
+    fn from(container: RawBytesContainer<T>) -> Self {
+        Self::new(container)
+    }
+}
+
+impl<T: Pod> Deref for SharedRawBytes<T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Pod> AsRef<[T]> for SharedRawBytes<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{ContainerError, RawBytesContainer, Storage, dirty::next_generation};
+use bytemuck::Pod;
+use std::{ops::Deref, sync::Arc};
+
+///  A  read-only  checkpoint  of  an  in-memory  container,  taken  by
+///  [`RawBytesContainer::snapshot`].
+///
+///  The  snapshot  shares  the  container's  buffer:  taking  one  costs  no  copy,  and  the
+///  buffer  is  only  copied  when  the  container  is  next  written  while  the  snapshot  is
+///  still  alive.  Cloning  a  snapshot  is  cheap  too.
+#[derive(Debug, Clone)]
+pub struct Snapshot<T: Pod> {
+    data: Arc<Vec<T>>,
+}
+
+impl<T: Pod> Snapshot<T> {
+    ///  The  elements  at  the  time  of  the  snapshot.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    ///  Number  of  elements.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    ///  Returns  true  if  the  snapshot  has  no  elements.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl<T: Pod> Deref for Snapshot<T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Capture  a  copy-on-write  checkpoint  of  in-memory  data.
+    ///
+    ///  Meant  for  undo  in  editors:  take  a  snapshot  before  each  edit  and
+    ///  [`restore`](Self::restore)  it  to  undo.  Snapshots  share  the  buffer  until  the
+    ///  next  write,  so  a  chain  of  them  only  costs  one  copy  per  edit  that  follows.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  mut  tiles  =  RawBytesContainer::from_vec(vec![0u8;  16]);
+    ///  let  before  =  tiles.snapshot().unwrap();
+    ///
+    ///  tiles.as_slice_mut().unwrap()[3]  =  7;
+    ///  assert_eq!(before[3],  0);
+    ///
+    ///  tiles.restore(&before).unwrap();
+    ///  assert_eq!(tiles[3],  0);
+    ///  ```
+    ///
+    ///  #  Errors
+    ///  Fails  for  mapped  or  borrowed  storage,  and  for  storage  created  by
+    ///  [`with_reserved`](Self::with_reserved),  whose  addresses  must  not  move.
+    pub fn snapshot(&mut self) -> Result<Snapshot<T>, ContainerError> {
+        if self.is_reserved() {
+            return Err(ContainerError::UnsupportedOperation(
+                "Snapshot  would  move  reserved  storage",
+            ));
+        }
+        let data = match &mut self.storage {
+            Storage::InMemory(vec) => Arc::new(std::mem::take(vec)),
+            Storage::Shared(data) => Arc::clone(data),
+            _ => {
+                return Err(ContainerError::UnsupportedOperation(
+                    "Snapshots  only  supported  on  in-memory  storage",
+                ));
+            }
+        };
+        self.storage = Storage::Shared(Arc::clone(&data));
+        Ok(Snapshot { data })
+    }
+
+    ///  Replace  the  contents  with  those  of  `snapshot`,  without  copying  until  the  next
+    ///  write.
+    ///
+    ///  #  Errors
+    ///  Fails  under  the  same  conditions  as  [`snapshot`](Self::snapshot).
+    pub fn restore(&mut self, snapshot: &Snapshot<T>) -> Result<(), ContainerError> {
+        if self.is_reserved() {
+            return Err(ContainerError::UnsupportedOperation(
+                "Restore  would  move  reserved  storage",
+            ));
+        }
+        if !matches!(self.storage, Storage::InMemory(_) | Storage::Shared(_)) {
+            return Err(ContainerError::UnsupportedOperation(
+                "Snapshots  only  supported  on  in-memory  storage",
+            ));
+        }
+        self.storage = Storage::Shared(Arc::clone(&snapshot.data));
+        *self.dirty.get_mut() = true;
+        self.generation = next_generation();
+        Ok(())
+    }
+
+    ///  Turn  shared  in-memory  data  back  into  an  owned  `Vec`,  copying  it  if  a  snapshot
+    ///  still  holds  it.  Called  before  operations  that  need  the  `Vec`  itself.
+    pub(crate) fn unshare(&mut self) {
+        if let Storage::Shared(data) = &mut self.storage {
+            let data = std::mem::take(data);
+            self.storage = Storage::InMemory(Arc::unwrap_or_clone(data));
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{ContainerError, RawBytesContainer};
+use bytemuck::Pod;
+use std::cmp::Ordering;
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Sort  the  elements  in  place  with  `compare`,  without  preserving  the  order  of
+    ///  equal  elements  (mutable  storage  only).
+    ///
+    ///  Works  directly  on  mapped  files,  so  a  sorted  on-disk  record  file  can  be
+    ///  maintained  without  exporting  it  to  a  `Vec`.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  file  =  tempfile::NamedTempFile::new().unwrap();
+    ///  let  mut  ids  =  RawBytesContainer::<u32>::create_mmap_rw(file.path(),  4).unwrap();
+    ///  ids.as_slice_mut().unwrap().copy_from_slice(&[40,  10,  30,  20]);
+    ///
+    ///  ids.sort_unstable_by(|a,  b|  a.cmp(b)).unwrap();
+    ///  assert_eq!(ids.binary_search_by(|id|  id.cmp(&30)),  Ok(2));
+    ///  assert_eq!(ids.binary_search_by(|id|  id.cmp(&25)),  Err(2));
+    ///  ```
+    pub fn sort_unstable_by<F>(&mut self, compare: F) -> Result<(), ContainerError>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.as_slice_mut_checked()?.sort_unstable_by(compare);
+        Ok(())
+    }
+
+    ///  Sort  the  elements  in  place  by  the  key  `f`  extracts,  keeping  equal  elements  in
+    ///  their  original  order  (mutable  storage  only).
+    pub fn sort_by_key<K, F>(&mut self, f: F) -> Result<(), ContainerError>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.as_slice_mut_checked()?.sort_by_key(f);
+        Ok(())
+    }
+
+    ///  Binary  search  sorted  elements  with  `f`;  see  [`slice::binary_search_by`].
+    ///
+    ///  Returns  `Ok`  with  the  index  of  a  match,  or  `Err`  with  the  index  where  a
+    ///  matching  element  could  be  inserted  to  keep  the  order.
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+}
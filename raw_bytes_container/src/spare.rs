@@ -0,0 +1,62 @@
+use std::{
+    fs::File,
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+///  Room  past  the  last  element  of  a  read-write  mapping  grown  by  `append_mapped`.
+///
+///  The  file  and  mapping  grow  geometrically,  so  most  appends  neither  resize  nor
+///  remap;  `len`  is  the  number  of  bytes  in  use.  The  file  is  cut  back  to  `len`  on
+///  flush  and  when  this  is  dropped,  which  happens  after  the  mapping  is  unmapped.
+#[derive(Debug)]
+pub(crate) struct SpareCapacity {
+    ///  Handle  to  the  mapped  file,  so  it  can  still  be  trimmed  on  drop.
+    file: File,
+    ///  Bytes  in  use.
+    pub(crate) len: usize,
+    ///  Current  file  length:  the  mapping  length,  or  `len`  after  a  trim.
+    file_len: AtomicUsize,
+}
+
+impl SpareCapacity {
+    pub(crate) fn new(file: &File, len: usize, file_len: usize) -> io::Result<Self> {
+        Ok(Self {
+            file: file.try_clone()?,
+            len,
+            file_len: AtomicUsize::new(file_len),
+        })
+    }
+
+    ///  Set  the  file  length  to  `len`  bytes.
+    pub(crate) fn resize_file(&self, len: usize) -> io::Result<()> {
+        self.file.set_len(len as u64)?;
+        self.file_len.store(len, Ordering::Relaxed);
+        Ok(())
+    }
+
+    ///  Make  sure  the  file  covers  the  first  `needed`  bytes  of  a  mapping  of  `mapped`
+    ///  bytes,  re-extending  it  to  the  whole  mapping  after  a  trim.
+    pub(crate) fn cover(&self, needed: usize, mapped: usize) -> io::Result<()> {
+        if self.file_len.load(Ordering::Relaxed) < needed {
+            self.resize_file(mapped)?;
+        }
+        Ok(())
+    }
+
+    ///  Cut  the  file  back  to  the  bytes  in  use.  The  spare  part  of  the  mapping  is  then
+    ///  past  the  end  of  the  file  and  must  not  be  touched  until  [`cover`](Self::cover).
+    pub(crate) fn trim(&self) -> io::Result<()> {
+        //  Windows  cannot  shrink  a  mapped  file;  there  the  tail  goes  on  drop
+        if cfg!(unix) && self.file_len.load(Ordering::Relaxed) > self.len {
+            self.resize_file(self.len)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SpareCapacity {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(self.len as u64);
+    }
+}
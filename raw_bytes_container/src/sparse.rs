@@ -0,0 +1,105 @@
+use crate::{ContainerError, RawBytesContainer, Storage, view::resolve_range};
+use bytemuck::Pod;
+use std::ops::RangeBounds;
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Release  the  disk  blocks  behind  the  elements  in  `range`,  leaving  a  hole  that  reads
+    ///  as  zeros  (mmap  read-write  only,  Linux  only).
+    ///
+    ///  The  file  keeps  its  size.  Blocks  only  partly  covered  by  the  range  are  zeroed
+    ///  instead  of  released.  Meant  for  huge,  mostly-zero  typed  arrays:  clearing  a  region
+    ///  this  way  frees  its  disk  space  instead  of  writing  zeros.  The  range  counts  as
+    ///  mutated,  so  a  checksummed  mapping  needs  a  [`commit`](Self::commit)  afterwards.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///
+    ///  let  file  =  tempfile::NamedTempFile::new().unwrap();
+    ///  let  mut  data  =  RawBytesContainer::<u64>::create_mmap_rw(file.path(),  1  <<  20).unwrap();
+    ///  data.as_slice_mut().unwrap().fill(7);
+    ///  data.flush().unwrap();
+    ///
+    ///  #  #[cfg(target_os  =  "linux")]
+    ///  #  {
+    ///  let  before  =  data.allocated_bytes().unwrap();
+    ///  data.punch_hole(..).unwrap();
+    ///  assert!(data.iter().all(|&x|  x  ==  0));
+    ///  assert!(data.allocated_bytes().unwrap()  <  before);
+    ///  #  }
+    ///  ```
+    pub fn punch_hole(&mut self, range: impl RangeBounds<usize>) -> Result<(), ContainerError> {
+        let range = resolve_range(range, self.len()).ok_or(
+            ContainerError::UnsupportedOperation("Hole  range  out  of  bounds"),
+        )?;
+        let (Storage::MmapRW(_), Some(file)) = (&self.storage, &self.file) else {
+            return Err(ContainerError::UnsupportedOperation(
+                "Punching  holes  only  supported  on  mmap  RW  opened  from  a  file",
+            ));
+        };
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::fd::AsRawFd;
+
+            let size = std::mem::size_of::<T>();
+            let offset = libc::off_t::try_from(range.start * size);
+            let len = libc::off_t::try_from(range.len() * size);
+            let (Ok(offset), Ok(len)) = (offset, len) else {
+                return Err(ContainerError::UnsupportedOperation(
+                    "Hole  range  too  large",
+                ));
+            };
+            //  SAFETY:  fallocate  only  reads  its  integer  arguments;  the  descriptor  is
+            //  owned  by  `file`,  which  is  alive  for  the  call.
+            let ret = unsafe {
+                libc::fallocate(
+                    file.as_raw_fd(),
+                    libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE,
+                    offset,
+                    len,
+                )
+            };
+            if ret != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            self.mark_dirty(range);
+            Ok(())
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = file;
+            Err(ContainerError::UnsupportedOperation(
+                "Punching  holes  only  supported  on  Linux",
+            ))
+        }
+    }
+
+    ///  Disk  space  actually  allocated  to  the  mapped  file,  in  bytes  (unix  only).
+    ///
+    ///  Smaller  than  the  file  size  for  sparse  files,  such  as  those  made  by
+    ///  [`create_mmap_rw`](Self::create_mmap_rw)  or  [`punch_hole`](Self::punch_hole).
+    ///  Fails  for  storage  without  a  backing  file.
+    pub fn allocated_bytes(&self) -> Result<u64, ContainerError> {
+        let Some(file) = &self.file else {
+            return Err(ContainerError::UnsupportedOperation(
+                "Allocated  size  only  available  for  mmap  opened  from  a  file",
+            ));
+        };
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            Ok(file.metadata()?.blocks() * 512)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = file;
+            Err(ContainerError::UnsupportedOperation(
+                "Allocated  size  only  available  on  unix",
+            ))
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::storage::StorageKind;
+
+///  Memory  usage  snapshot  of  a  container,  returned  by
+///  [`RawBytesContainer::stats`](crate::RawBytesContainer::stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainerStats {
+    ///  Backend  holding  the  data.
+    pub kind: StorageKind,
+    ///  Number  of  elements.
+    pub len: usize,
+    ///  Size  of  the  elements  in  bytes,  excluding  any  checksum  trailer  or  trailing  bytes.
+    pub byte_len: usize,
+    ///  Allocated  capacity  in  elements  (in-memory  storage  only).
+    pub capacity: Option<usize>,
+    ///  Estimated  bytes  currently  held  in  RAM.
+    ///
+    ///  For  mappings  on  unix  this  counts  the  resident  pages  (`mincore`),  which  includes
+    ///  any  trailer  or  trailing  bytes  on  those  pages;  elsewhere  it  is  `None`.  In-memory
+    ///  and  borrowed  data  count  as  fully  resident.
+    pub resident_bytes: Option<usize>,
+    ///  Whether  the  data  may  have  been  modified  since  it  was  opened,  created,  or  last
+    ///  flushed  or  written  to  a  file.
+    pub dirty: bool,
+}
+
+///  Bytes  of  `data`  in  resident  pages.  `data`  must  start  on  a  page  boundary.
+#[cfg(unix)]
+pub(crate) fn resident_bytes(data: &[u8]) -> Option<usize> {
+    if data.is_empty() {
+        return Some(0);
+    }
+    //  SAFETY:  sysconf  has  no  preconditions.
+    let page = usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).ok()?;
+    let mut pages = vec![0u8; data.len().div_ceil(page)];
+    //  SAFETY:  `data`  is  a  live  mapping  starting  on  a  page  boundary,  and  `pages`  has
+    //  one  entry  per  page  it  covers.
+    let ret = unsafe {
+        libc::mincore(
+            data.as_ptr() as *mut libc::c_void,
+            data.len(),
+            pages.as_mut_ptr() as _,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+    let resident = pages.iter().filter(|&&p| p & 1 != 0).count();
+    Some((resident * page).min(data.len()))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn resident_bytes(_data: &[u8]) -> Option<usize> {
+    None
+}
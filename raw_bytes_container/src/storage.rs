@@ -1,5 +1,6 @@
 use bytemuck::Pod;
 use memmap2::{Mmap, MmapMut};
+use std::sync::Arc;
 
 ///  Storage  variants  for  RawBytesContainer
 #[derive(Debug)]
@@ -7,4 +8,34 @@ pub enum Storage<T: Pod> {
     InMemory(Vec<T>),
     MmapRO(Mmap),
     MmapRW(MmapMut),
+    ///  Private  (copy-on-write)  mapping:  writable  in  memory,  never  written  back.
+    MmapCow(MmapMut),
+    ///  Read-only  borrowed  slice,  e.g.  an  asset  embedded  with  `include_bytes!`.
+    Borrowed(&'static [T]),
+    ///  In-memory  data  shared  with  [`Snapshot`](crate::Snapshot)s;  copied  on  the  next
+    ///  write  if  a  snapshot  still  holds  it.
+    Shared(Arc<Vec<T>>),
+}
+
+///  Which  backend  a  [`Storage`]  uses,  without  the  data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageKind {
+    InMemory,
+    MmapRO,
+    MmapRW,
+    MmapCow,
+    Borrowed,
+}
+
+impl<T: Pod> Storage<T> {
+    ///  The  backend  of  this  storage.
+    pub fn kind(&self) -> StorageKind {
+        match self {
+            Storage::InMemory(_) | Storage::Shared(_) => StorageKind::InMemory,
+            Storage::MmapRO(_) => StorageKind::MmapRO,
+            Storage::MmapRW(_) => StorageKind::MmapRW,
+            Storage::MmapCow(_) => StorageKind::MmapCow,
+            Storage::Borrowed(_) => StorageKind::Borrowed,
+        }
+    }
 }
@@ -0,0 +1,171 @@
+use crate::{ContainerError, RawBytesContainer};
+use bytemuck::Pod;
+use std::ops::{Bound, Deref, Range, RangeBounds};
+
+///  A  bounded  window  over  part  of  a  [`RawBytesContainer`].
+///
+///  Created  by  [`RawBytesContainer::view`],  [`RawBytesContainer::view_mut`],
+///  [`RawBytesContainer::split`]  or  [`RawBytesContainer::split_views_mut`].  A  view  borrows  the  storage  directly,
+///  so  regions  of  a  large  mapped  file  can  be  handed  to  different  subsystems
+///  without  copying.
+#[derive(Debug)]
+pub struct View<'a, T: Pod> {
+    data: ViewData<'a, T>,
+    offset: usize,
+}
+
+#[derive(Debug)]
+enum ViewData<'a, T> {
+    Shared(&'a [T]),
+    Mut(&'a mut [T]),
+}
+
+impl<'a, T: Pod> View<'a, T> {
+    pub(crate) fn shared(data: &'a [T], offset: usize) -> Self {
+        Self {
+            data: ViewData::Shared(data),
+            offset,
+        }
+    }
+
+    pub(crate) fn exclusive(data: &'a mut [T], offset: usize) -> Self {
+        Self {
+            data: ViewData::Mut(data),
+            offset,
+        }
+    }
+
+    ///  Index  of  the  first  element  of  this  view  within  the  container.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    ///  Element  range  covered  by  this  view  within  the  container.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.as_slice().len()
+    }
+
+    ///  Returns  true  if  the  view  can  be  mutated.
+    pub fn is_mutable(&self) -> bool {
+        matches!(self.data, ViewData::Mut(_))
+    }
+
+    ///  Get  a  read-only  slice  over  the  view.
+    pub fn as_slice(&self) -> &[T] {
+        match &self.data {
+            ViewData::Shared(s) => s,
+            ViewData::Mut(s) => s,
+        }
+    }
+
+    ///  Get  a  mutable  slice,  if  the  view  was  created  mutable.
+    pub fn as_slice_mut(&mut self) -> Option<&mut [T]> {
+        match &mut self.data {
+            ViewData::Shared(_) => None,
+            ViewData::Mut(s) => Some(s),
+        }
+    }
+}
+
+impl<T: Pod> Deref for View<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+///  Resolve  `range`  against  a  container  of  `len`  elements.
+pub(crate) fn resolve_range(range: impl RangeBounds<usize>, len: usize) -> Option<Range<usize>> {
+    check_range(range, len).ok()
+}
+
+///  Resolve  `range`  against  `len`,  naming  the  offending  bound  if  it  is  out  of  bounds.
+pub(crate) fn check_range(
+    range: impl RangeBounds<usize>,
+    len: usize,
+) -> Result<Range<usize>, ContainerError> {
+    let out_of_bounds = |index| ContainerError::IndexOutOfBounds { index, len };
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.checked_add(1).ok_or(out_of_bounds(s))?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.checked_add(1).ok_or(out_of_bounds(e))?,
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    if end > len {
+        return Err(out_of_bounds(end));
+    }
+    if start > end {
+        return Err(out_of_bounds(start));
+    }
+    Ok(start..end)
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  Get  a  read-only  view  over  `range`,  or  `None`  if  it  is  out  of  bounds.
+    pub fn view(&self, range: impl RangeBounds<usize>) -> Option<View<'_, T>> {
+        let range = resolve_range(range, self.len())?;
+        let offset = range.start;
+        Some(View::shared(&self.as_slice()[range], offset))
+    }
+
+    ///  Get  a  mutable  view  over  `range`.
+    ///
+    ///  Returns  `None`  if  the  range  is  out  of  bounds  or  the  storage  is  read-only.
+    pub fn view_mut(&mut self, range: impl RangeBounds<usize>) -> Option<View<'_, T>> {
+        let range = resolve_range(range, self.len())?;
+        let offset = range.start;
+        self.mark_dirty(range.clone());
+        Some(View::exclusive(
+            &mut self.slice_mut_unmarked()?[range],
+            offset,
+        ))
+    }
+
+    ///  Split  the  container  at  element  `at`  into  two  read-only  views,  `[0,  at)`
+    ///  and  `[at,  len)`.
+    ///
+    ///  Works  on  every  storage,  so  a  mapped  file  holding  two  logical  sections  can
+    ///  be  handed  out  as  two  views  without  copying.  Returns  `None`  if  `at  >  len`.
+    pub fn split(&self, at: usize) -> Option<(View<'_, T>, View<'_, T>)> {
+        if at > self.len() {
+            return None;
+        }
+        let (head, tail) = self.as_slice().split_at(at);
+        Some((View::shared(head, 0), View::shared(tail, at)))
+    }
+
+    ///  Split  the  container  into  several  disjoint  mutable  views.
+    ///
+    ///  `ranges`  must  be  in  ascending  order  and  must  not  overlap.  Returns  `None`
+    ///  if  that  does  not  hold,  a  range  is  out  of  bounds,  or  the  storage  is
+    ///  read-only.
+    pub fn split_views_mut(&mut self, ranges: &[Range<usize>]) -> Option<Vec<View<'_, T>>> {
+        let len = self.len();
+        let mut prev_end = 0;
+        for r in ranges {
+            if r.start < prev_end || r.start > r.end || r.end > len {
+                return None;
+            }
+            prev_end = r.end;
+        }
+
+        for r in ranges {
+            self.mark_dirty(r.clone());
+        }
+        let mut rest = self.slice_mut_unmarked()?;
+        let mut consumed = 0;
+        let mut views = Vec::with_capacity(ranges.len());
+        for r in ranges {
+            let (_, tail) = rest.split_at_mut(r.start - consumed);
+            let (head, tail) = tail.split_at_mut(r.end - r.start);
+            views.push(View::exclusive(head, r.start));
+            rest = tail;
+            consumed = r.end;
+        }
+        Some(views)
+    }
+}
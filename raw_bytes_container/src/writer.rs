@@ -0,0 +1,142 @@
+use crate::{ContainerError, RawBytesContainer, Storage};
+use bytemuck::Pod;
+use std::io::{self, Cursor, Seek, SeekFrom, Write};
+
+///  [`Write`]  and  [`Seek`]  adapter  over  the  bytes  of  a  [`RawBytesContainer`].
+///
+///  Created  by  [`RawBytesContainer::writer`].  Writes  overwrite  the  existing  bytes  at  the
+///  current  position.  In-memory  containers  grow  by  whole  elements  when  a  write  goes
+///  past  the  end;  if  it  stops  in  the  middle  of  an  element,  the  rest  of  that  element  is
+///  zero-filled.  Mapped  containers  have  a  fixed  size,  so  writing  past  their  end
+///  writes  nothing  (and  `write_all`  fails  with  [`io::ErrorKind::WriteZero`]).
+#[derive(Debug)]
+pub struct ContainerWriter<'a, T: Pod> {
+    container: &'a mut RawBytesContainer<T>,
+    pos: u64,
+}
+
+impl<'a, T: Pod> ContainerWriter<'a, T> {
+    ///  Current  position  in  bytes.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    fn byte_len(&self) -> usize {
+        self.container.len() * std::mem::size_of::<T>()
+    }
+}
+
+impl<T: Pod> Write for ContainerWriter<'_, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let size = std::mem::size_of::<T>();
+        let pos = usize::try_from(self.pos)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "position  out  of  range"))?;
+        let end = pos
+            .checked_add(buf.len())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "write  out  of  range"))?;
+
+        if end > self.byte_len()
+            && matches!(
+                self.container.storage,
+                Storage::InMemory(_) | Storage::Shared(_)
+            )
+        {
+            self.container
+                .resize_zeroed(end.div_ceil(size))
+                .map_err(io::Error::other)?;
+        }
+
+        let len = self.container.len();
+        let touched = (pos / size).min(len)..end.div_ceil(size).min(len);
+        self.container.mark_dirty(touched);
+        let bytes: &mut [u8] = match self.container.slice_mut_unmarked() {
+            Some(slice) => bytemuck::cast_slice_mut(slice),
+            None => {
+                return Err(io::Error::other(ContainerError::UnsupportedOperation(
+                    "Write  not  supported  on  read-only  storage",
+                )));
+            }
+        };
+        if pos >= bytes.len() {
+            return Ok(0);
+        }
+        let n = buf.len().min(bytes.len() - pos);
+        bytes[pos..pos + n].copy_from_slice(&buf[..n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: Pod> Seek for ContainerWriter<'_, T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            SeekFrom::End(n) => (self.byte_len() as u64, n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid  seek  to  a  negative  or  overflowing  position",
+            )),
+        }
+    }
+}
+
+impl<T: Pod> RawBytesContainer<T> {
+    ///  [`Read`](std::io::Read),  [`BufRead`](std::io::BufRead)  and  [`Seek`]  over  the
+    ///  bytes  of  the  elements,  without  copying  them.
+    ///
+    ///  Lets  streaming  code  such  as  serializers  and  hashers  consume  the  contents
+    ///  directly.  Any  checksum  trailer  or  trailing  bytes  are  not  included.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///  use  std::io::Read;
+    ///
+    ///  let  container  =  RawBytesContainer::from_vec(vec![0x0403_0201u32]);
+    ///  let  mut  bytes  =  Vec::new();
+    ///  container.reader().read_to_end(&mut  bytes).unwrap();
+    ///  assert_eq!(bytes,  0x0403_0201u32.to_ne_bytes());
+    ///  ```
+    pub fn reader(&self) -> Cursor<&[u8]> {
+        Cursor::new(bytemuck::cast_slice(self.as_slice()))
+    }
+
+    ///  [`Write`]  and  [`Seek`]  over  the  bytes  of  the  elements,  starting  at  the  beginning.
+    ///
+    ///  Fails  if  the  storage  is  read-only.  See  [`ContainerWriter`]  for  how  writes  past
+    ///  the  end  are  handled.
+    ///
+    ///  ```
+    ///  use  raw_bytes_container::RawBytesContainer;
+    ///  use  std::io::Write;
+    ///
+    ///  let  mut  container  =  RawBytesContainer::from_vec(Vec::<u16>::new());
+    ///  container.writer().unwrap().write_all(&[1,  0,  2]).unwrap();
+    ///  assert_eq!(container.len(),  2);
+    ///  assert_eq!(container.as_slice(),  &[1,  2]);
+    ///  ```
+    pub fn writer(&mut self) -> Result<ContainerWriter<'_, T>, ContainerError> {
+        if !self.is_mutable() {
+            return Err(ContainerError::UnsupportedOperation(
+                "Write  not  supported  on  read-only  storage",
+            ));
+        }
+        Ok(ContainerWriter {
+            container: self,
+            pos: 0,
+        })
+    }
+}
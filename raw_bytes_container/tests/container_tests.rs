@@ -1,7 +1,7 @@
 //use  bytemuck::{Pod,  Zeroable};
 use bytemuck_derive::Pod;
 use bytemuck_derive::Zeroable;
-use raw_bytes_container::RawBytesContainer;
+use raw_bytes_container::{RawBytesContainer, Storage};
 use tempfile::NamedTempFile;
 
 #[repr(C)]
@@ -85,3 +85,1638 @@ fn test_read_only_and_rw() {
     let slice = rw_container.as_slice();
     assert_eq!(slice[0].a, 42);
 }
+
+#[test]
+fn test_resize_zeroed_and_set_len_uninit() {
+    let mut container = RawBytesContainer::from_slice(&[Packet { a: 1, b: 2, c: 3 }]);
+
+    container.resize_zeroed(3).unwrap();
+    assert_eq!(container.len(), 3);
+    assert_eq!(container[0], Packet { a: 1, b: 2, c: 3 });
+    assert_eq!(container[2], Packet { a: 0, b: 0, c: 0 });
+
+    container.resize_zeroed(1).unwrap();
+    assert_eq!(container.len(), 1);
+
+    unsafe { container.set_len_uninit(4).unwrap() };
+    assert_eq!(container.len(), 4);
+    for (i, p) in container.as_slice_mut().unwrap()[1..]
+        .iter_mut()
+        .enumerate()
+    {
+        *p = Packet {
+            a: i as u32,
+            b: 0,
+            c: 0,
+        };
+    }
+    assert_eq!(container[3].a, 2);
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let mut ro_container = RawBytesContainer::<Packet>::open_mmap_read(temp_file.path()).unwrap();
+    assert!(ro_container.resize_zeroed(8).is_err());
+    assert!(unsafe { ro_container.set_len_uninit(8) }.is_err());
+}
+
+#[test]
+fn test_append_buffered() {
+    let mut container = RawBytesContainer::<u32>::from_vec(Vec::new());
+    {
+        let mut appender = container.append_buffered(4).unwrap();
+        for i in 0..10 {
+            appender.push(i).unwrap();
+        }
+        assert_eq!(appender.pending(), 2);
+        appender.extend_from_slice(&[10, 11]).unwrap();
+        assert_eq!(appender.pending(), 0);
+        appender.push(12).unwrap();
+    }
+    assert_eq!(
+        container.as_slice(),
+        (0..13).collect::<Vec<u32>>().as_slice()
+    );
+}
+
+#[test]
+fn test_mmap_cow() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut original = RawBytesContainer::from_slice(&[1u32, 2, 3]);
+    original.write_to_file(temp_file.path()).unwrap();
+
+    let mut cow = RawBytesContainer::<u32>::open_mmap_cow(temp_file.path()).unwrap();
+    assert!(cow.is_mutable());
+    cow.as_slice_mut().unwrap()[0] = 42;
+    assert_eq!(cow[0], 42);
+    assert!(cow.flush().is_err());
+    assert!(cow.append(&[4]).is_err());
+
+    let on_disk = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert_eq!(on_disk.as_slice(), &[1, 2, 3]);
+
+    let copy_file = NamedTempFile::new().unwrap();
+    cow.write_to_file(copy_file.path()).unwrap();
+    let copy = RawBytesContainer::<u32>::open_mmap_read(copy_file.path()).unwrap();
+    assert_eq!(copy.as_slice(), &[42, 2, 3]);
+}
+
+#[test]
+fn test_trailing_bytes_policy() {
+    use raw_bytes_container::TrailingBytes;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&7u32.to_le_bytes());
+    bytes.extend_from_slice(&8u32.to_le_bytes());
+    bytes.extend_from_slice(&[1, 2]);
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+
+    assert!(RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).is_err());
+
+    let ro = RawBytesContainer::<u32>::open_mmap_read_with(temp_file.path(), TrailingBytes::Ignore)
+        .unwrap();
+    assert_eq!(ro.as_slice(), &[7, 8]);
+    assert_eq!(ro.trailing_bytes(), &[1, 2]);
+
+    let mut rw =
+        RawBytesContainer::<u32>::open_mmap_rw_with(temp_file.path(), TrailingBytes::Ignore)
+            .unwrap();
+    rw.as_slice_mut().unwrap()[1] = 9;
+    rw.flush().unwrap();
+    assert_eq!(rw.trailing_bytes(), &[1, 2]);
+}
+
+#[test]
+fn test_create_mmap_rw() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("packets.bin");
+
+    let mut container = RawBytesContainer::<Packet>::create_mmap_rw(&path, 4).unwrap();
+    assert_eq!(container.len(), 4);
+    assert_eq!(container[3].a, 0);
+    container.get_mut(2).unwrap().a = 99;
+    container.flush().unwrap();
+    drop(container);
+
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 4 * 8);
+    let ro = RawBytesContainer::<Packet>::open_mmap_read(&path).unwrap();
+    assert_eq!(ro[2].a, 99);
+}
+
+#[test]
+fn test_views() {
+    let mut container = RawBytesContainer::from_vec((0u32..10).collect());
+
+    let view = container.view(2..5).unwrap();
+    assert_eq!(view.as_slice(), &[2, 3, 4]);
+    assert_eq!(view.offset(), 2);
+    assert!(!view.is_mutable());
+    assert!(container.view(8..11).is_none());
+
+    let mut views = container.split_views_mut(&[0..3, 5..7]).unwrap();
+    views[0].as_slice_mut().unwrap()[0] = 100;
+    views[1].as_slice_mut().unwrap()[1] = 600;
+    assert_eq!(views[1].range(), 5..7);
+    drop(views);
+    assert_eq!(container[0], 100);
+    assert_eq!(container[6], 600);
+    assert!(container.split_views_mut(&[0..3, 2..4]).is_none());
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let mut ro = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert_eq!(ro.view(..=1).unwrap().as_slice(), &[100, 1]);
+    assert!(ro.view_mut(0..1).is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_advise() {
+    use raw_bytes_container::Advice;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut container = RawBytesContainer::from_vec((0u32..4096).collect());
+    container.write_to_file(temp_file.path()).unwrap();
+    assert!(container.advise(Advice::Sequential).is_err());
+
+    let mapped = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    mapped.advise(Advice::Sequential).unwrap();
+    mapped.advise_range(Advice::WillNeed, 1024..2048).unwrap();
+    assert!(mapped.advise_range(Advice::Random, 0..5000).is_err());
+    assert_eq!(mapped[4095], 4095);
+
+    let cow = RawBytesContainer::<u32>::open_mmap_cow(temp_file.path()).unwrap();
+    assert!(cow.advise(Advice::DontNeed).is_err());
+    cow.advise(Advice::Random).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_lock_unlock() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut container = RawBytesContainer::from_vec(vec![7u32; 1024]);
+    container.write_to_file(temp_file.path()).unwrap();
+    assert!(container.lock().is_err());
+
+    let mut mapped = RawBytesContainer::<u32>::open_mmap_rw(temp_file.path()).unwrap();
+    //  A  4  KiB  mapping  fits  in  any  sane  RLIMIT_MEMLOCK
+    mapped.lock().unwrap();
+    mapped.as_slice_mut().unwrap()[0] = 8;
+    mapped.unlock().unwrap();
+    assert_eq!(mapped[0], 8);
+}
+
+#[test]
+fn test_map_options_huge_pages() {
+    use raw_bytes_container::{MapOptions, TrailingBytes};
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut bytes = vec![0u8; 4096 * 4];
+    bytes.push(1);
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+
+    let options = MapOptions::new()
+        .trailing(TrailingBytes::Ignore)
+        .huge_pages(true);
+    let ro =
+        RawBytesContainer::<u64>::open_mmap_read_with_options(temp_file.path(), options).unwrap();
+    assert_eq!(ro.len(), 2048);
+    assert_eq!(ro.trailing_bytes(), &[1]);
+
+    let mut rw =
+        RawBytesContainer::<u64>::open_mmap_rw_with_options(temp_file.path(), options).unwrap();
+    rw.as_slice_mut().unwrap()[5] = 9;
+    rw.flush().unwrap();
+}
+
+#[test]
+fn test_shared_raw_bytes() {
+    use raw_bytes_container::SharedRawBytes;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut container = RawBytesContainer::from_vec((0u32..1000).collect());
+    container.write_to_file(temp_file.path()).unwrap();
+
+    let shared = SharedRawBytes::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let part = shared.slice(i * 250..(i + 1) * 250).unwrap();
+            std::thread::spawn(move || part.iter().map(|&v| v as u64).sum::<u64>())
+        })
+        .collect();
+    let total: u64 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(total, (0..1000u64).sum());
+
+    let tail = shared.slice(990..).unwrap();
+    assert_eq!(tail.range(), 990..1000);
+    assert_eq!(
+        tail.slice(5..).unwrap().as_slice(),
+        &[995, 996, 997, 998, 999]
+    );
+    assert!(tail.slice(5..20).is_none());
+    assert_eq!(shared.clone().handle_count(), 3);
+}
+
+#[test]
+fn test_concurrent_regions() {
+    use raw_bytes_container::ConcurrentRawBytes;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut container = RawBytesContainer::from_vec(vec![0u32; 1000]);
+    container.write_to_file(temp_file.path()).unwrap();
+
+    let mapped = RawBytesContainer::<u32>::open_mmap_rw(temp_file.path()).unwrap();
+    let concurrent = ConcurrentRawBytes::new(mapped, 300).unwrap();
+    assert_eq!(concurrent.region_count(), 4);
+    assert_eq!(concurrent.region_range(3), 900..1000);
+    assert_eq!(concurrent.region_of(650), Some(2));
+
+    std::thread::scope(|s| {
+        for region in 0..concurrent.region_count() {
+            let concurrent = &concurrent;
+            s.spawn(move || {
+                let mut guard = concurrent.lock_region(region);
+                let offset = guard.offset();
+                for (i, v) in guard.iter_mut().enumerate() {
+                    *v = (offset + i) as u32;
+                }
+            });
+        }
+    });
+
+    let held = concurrent.lock_region(1);
+    assert!(concurrent.try_lock_region(1).is_none());
+    assert!(concurrent.try_lock_region(2).is_some());
+    drop(held);
+
+    concurrent.flush().unwrap();
+    let container = concurrent.into_inner();
+    assert!(container.iter().enumerate().all(|(i, &v)| v == i as u32));
+
+    let ro = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert!(ConcurrentRawBytes::new(ro, 10).is_err());
+}
+
+#[test]
+fn test_open_read_copied() {
+    use raw_bytes_container::TrailingBytes;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut bytes = Vec::new();
+    for v in [1u64, 2, 3] {
+        bytes.extend_from_slice(&v.to_le_bytes());
+    }
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+
+    let copied = RawBytesContainer::<u64>::open_read_copied(temp_file.path()).unwrap();
+    assert_eq!(copied.as_slice(), &[1, 2, 3]);
+    assert!(copied.capacity().is_some());
+
+    bytes.extend_from_slice(&[9, 9, 9]);
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+    assert!(RawBytesContainer::<u64>::open_read_copied(temp_file.path()).is_err());
+
+    let copied =
+        RawBytesContainer::<u64>::open_read_copied_with(temp_file.path(), TrailingBytes::Ignore)
+            .unwrap();
+    assert_eq!(copied.as_slice(), &[1, 2, 3]);
+    assert!(copied.trailing_bytes().is_empty());
+}
+
+#[test]
+fn test_checksummed_roundtrip() {
+    use raw_bytes_container::{ContainerError, MapOptions};
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut container = RawBytesContainer::from_slice(&[1u32, 2, 3, 4]);
+    container.enable_checksum().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    assert_eq!(std::fs::metadata(temp_file.path()).unwrap().len(), 16 + 16);
+
+    let options = MapOptions::new().checksum(true);
+    let mut rw =
+        RawBytesContainer::<u32>::open_mmap_rw_with_options(temp_file.path(), options).unwrap();
+    assert_eq!(rw.as_slice(), &[1, 2, 3, 4]);
+    assert!(rw.trailing_bytes().is_empty());
+    assert!(rw.flush().is_err());
+
+    rw.as_slice_mut().unwrap()[0] = 10;
+    assert!(matches!(
+        rw.verify(),
+        Err(ContainerError::ChecksumMismatch { .. })
+    ));
+    rw.commit().unwrap();
+    rw.verify().unwrap();
+    drop(rw);
+
+    let ro =
+        RawBytesContainer::<u32>::open_mmap_read_with_options(temp_file.path(), options).unwrap();
+    assert_eq!(ro.as_slice(), &[10, 2, 3, 4]);
+
+    //  Simulate  a  torn  write:  data  changed,  trailer  not  updated.
+    let mut bytes = std::fs::read(temp_file.path()).unwrap();
+    bytes[4] ^= 0xFF;
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+    assert!(matches!(
+        RawBytesContainer::<u32>::open_mmap_read_with_options(temp_file.path(), options),
+        Err(ContainerError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_try_cast() {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    struct Pair {
+        a: u32,
+        b: u32,
+    }
+
+    let temp_file = NamedTempFile::new().unwrap();
+    let bytes: Vec<u8> = [1u32, 2, 3, 4]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+    std::fs::write(temp_file.path(), &bytes).unwrap();
+
+    let raw = RawBytesContainer::<u8>::open_mmap_read(temp_file.path()).unwrap();
+    let pairs = raw.try_cast::<Pair>().unwrap();
+    assert_eq!(
+        pairs.as_slice(),
+        &[Pair { a: 1, b: 2 }, Pair { a: 3, b: 4 }]
+    );
+
+    //  16  bytes  are  not  a  whole  number  of  3-byte  elements;  the  cast  fails  outright
+    let raw = RawBytesContainer::<u8>::open_mmap_read(temp_file.path()).unwrap();
+    assert!(matches!(
+        raw.try_cast::<[u8; 3]>(),
+        Err(raw_bytes_container::ContainerError::TypeMismatch(_))
+    ));
+
+    let words = RawBytesContainer::from_vec(vec![1u32, 2, 3, 4]);
+    let words = words.try_cast::<[u32; 2]>().unwrap();
+    assert_eq!(words.as_slice(), &[[1, 2], [3, 4]]);
+
+    //  Vec  casts  cannot  change  alignment.
+    let words = RawBytesContainer::from_vec(vec![1u32, 2]);
+    assert!(words.try_cast::<u8>().is_err());
+}
+
+#[test]
+fn test_flush_range() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let mut rw = RawBytesContainer::<u64>::create_mmap_rw(temp_file.path(), 1024).unwrap();
+    rw.as_slice_mut().unwrap()[600] = 42;
+    rw.flush_range(600, 1).unwrap();
+    rw.flush_range(0, 1024).unwrap();
+    assert!(rw.flush_range(1000, 25).is_err());
+    assert!(rw.flush_range(usize::MAX, 2).is_err());
+    drop(rw);
+
+    let ro = RawBytesContainer::<u64>::open_mmap_read(temp_file.path()).unwrap();
+    assert_eq!(ro.as_slice()[600], 42);
+    assert!(ro.flush_range(0, 1).is_err());
+}
+
+#[test]
+fn test_insert_remove_truncate() {
+    use raw_bytes_container::ContainerError;
+
+    let mut container = RawBytesContainer::from_slice(&[1u32, 2, 3]);
+    container.insert(0, 0).unwrap();
+    container.insert(4, 4).unwrap();
+    assert_eq!(container.as_slice(), &[0, 1, 2, 3, 4]);
+    assert!(matches!(
+        container.insert(6, 9),
+        Err(ContainerError::IndexOutOfBounds { index: 6, len: 5 })
+    ));
+
+    assert_eq!(container.remove(1).unwrap(), 1);
+    assert_eq!(container.as_slice(), &[0, 2, 3, 4]);
+    assert_eq!(container.swap_remove(0).unwrap(), 0);
+    assert_eq!(container.as_slice(), &[4, 2, 3]);
+    assert!(container.remove(3).is_err());
+    assert!(container.swap_remove(3).is_err());
+
+    container.truncate(10).unwrap();
+    assert_eq!(container.len(), 3);
+    container.truncate(1).unwrap();
+    assert_eq!(container.as_slice(), &[4]);
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let mut rw = RawBytesContainer::<u32>::open_mmap_rw(temp_file.path()).unwrap();
+    assert!(rw.insert(0, 1).is_err());
+    assert!(rw.remove(0).is_err());
+    assert!(rw.swap_remove(0).is_err());
+    assert!(rw.truncate(0).is_err());
+    assert_eq!(rw.as_slice(), &[4]);
+}
+
+#[test]
+fn test_drain() {
+    use raw_bytes_container::ContainerError;
+    use std::ops::Bound;
+
+    let mut container = RawBytesContainer::from_vec((0u32..10).collect());
+    let capacity = container.capacity();
+
+    let batch: Vec<u32> = container.drain(..4).unwrap().collect();
+    assert_eq!(batch, vec![0, 1, 2, 3]);
+    assert_eq!(container.as_slice(), &[4, 5, 6, 7, 8, 9]);
+
+    //  Dropping  the  iterator  early  still  removes  the  whole  range.
+    let mut drain = container.drain(1..=2).unwrap();
+    assert_eq!(drain.next(), Some(5));
+    drop(drain);
+    assert_eq!(container.as_slice(), &[4, 7, 8, 9]);
+    assert_eq!(container.capacity(), capacity);
+
+    assert!(matches!(
+        container.drain(2..5),
+        Err(ContainerError::IndexOutOfBounds { index: 5, len: 4 })
+    ));
+    assert!(matches!(
+        container.drain((Bound::Included(3), Bound::Excluded(1))),
+        Err(ContainerError::IndexOutOfBounds { index: 3, len: 4 })
+    ));
+    assert!(matches!(
+        container.drain(..=usize::MAX),
+        Err(ContainerError::IndexOutOfBounds {
+            index: usize::MAX,
+            len: 4
+        })
+    ));
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let mut rw = RawBytesContainer::<u32>::open_mmap_rw(temp_file.path()).unwrap();
+    assert!(rw.drain(..).is_err());
+}
+
+#[test]
+fn test_into_vec_and_storage() {
+    let data = vec![1u32, 2, 3];
+    let ptr = data.as_ptr();
+    let container = RawBytesContainer::from_vec(data);
+    let vec = container.into_vec();
+    assert_eq!(vec, vec![1, 2, 3]);
+    assert_eq!(vec.as_ptr(), ptr);
+
+    let temp_file = NamedTempFile::new().unwrap();
+    RawBytesContainer::from_vec(vec)
+        .write_to_file(temp_file.path())
+        .unwrap();
+    let mmap = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert_eq!(mmap.into_vec(), vec![1, 2, 3]);
+
+    let mmap = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert!(matches!(mmap.into_storage(), Storage::MmapRO(_)));
+    let container = RawBytesContainer::from_slice(&[7u32]);
+    assert!(matches!(container.into_storage(), Storage::InMemory(v) if v == vec![7]));
+}
+
+#[test]
+fn test_split_off_and_split() {
+    let mut container = RawBytesContainer::from_vec(vec![1u32, 2, 3, 4, 5]);
+    let tail = container.split_off(3).unwrap();
+    assert_eq!(container.as_slice(), &[1, 2, 3]);
+    assert_eq!(tail.as_slice(), &[4, 5]);
+    assert!(container.split_off(4).is_err());
+
+    let empty = container.split_off(3).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(container.len(), 3);
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let mut mmap = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert!(mmap.split_off(1).is_err());
+
+    let (head, tail) = mmap.split(1).unwrap();
+    assert_eq!(head.as_slice(), &[1]);
+    assert_eq!(tail.as_slice(), &[2, 3]);
+    assert_eq!(tail.range(), 1..3);
+    assert!(!tail.is_mutable());
+    assert!(mmap.split(4).is_none());
+}
+
+#[test]
+fn test_advisory_locks() {
+    use raw_bytes_container::{ContainerError, FileLock, MapOptions};
+
+    let temp_file = NamedTempFile::new().unwrap();
+    RawBytesContainer::from_vec(vec![1u32, 2])
+        .write_to_file(temp_file.path())
+        .unwrap();
+    let shared = MapOptions::new().try_lock(FileLock::Shared);
+    let exclusive = MapOptions::new().try_lock(FileLock::Exclusive);
+
+    let reader1 =
+        RawBytesContainer::<u32>::open_mmap_read_with_options(temp_file.path(), shared).unwrap();
+    let reader2 =
+        RawBytesContainer::<u32>::open_mmap_read_with_options(temp_file.path(), shared).unwrap();
+    assert!(matches!(
+        RawBytesContainer::<u32>::open_mmap_rw_with_options(temp_file.path(), exclusive),
+        Err(ContainerError::LockContended)
+    ));
+    drop((reader1, reader2));
+
+    let mut writer =
+        RawBytesContainer::<u32>::open_mmap_rw_with_options(temp_file.path(), exclusive).unwrap();
+    writer.as_slice_mut().unwrap()[0] = 7;
+    assert!(matches!(
+        RawBytesContainer::<u32>::open_mmap_read_with_options(temp_file.path(), shared),
+        Err(ContainerError::LockContended)
+    ));
+    //  Unlocked  opens  are  not  affected  by  advisory  locks
+    assert_eq!(
+        RawBytesContainer::<u32>::open_mmap_read(temp_file.path())
+            .unwrap()
+            .as_slice(),
+        &[7, 2]
+    );
+
+    drop(writer);
+    let reader = RawBytesContainer::<u32>::open_mmap_read_with_options(
+        temp_file.path(),
+        MapOptions::new().lock(FileLock::Shared),
+    )
+    .unwrap();
+    assert_eq!(reader.as_slice(), &[7, 2]);
+}
+
+#[test]
+fn test_static_storage() {
+    static WORDS: [u32; 3] = [10, 20, 30];
+    static BYTES: &[u8] = b"abcdef";
+
+    let mut container = RawBytesContainer::from_static(&WORDS);
+    assert_eq!(container.as_slice(), &WORDS);
+    assert_eq!(container.as_slice().as_ptr(), WORDS.as_ptr());
+    assert!(!container.is_mutable());
+    assert!(container.as_slice_mut().is_none());
+    assert!(container.insert(0, 40).is_err());
+    assert!(matches!(container.into_storage(), Storage::Borrowed(s) if s.len() == 3));
+
+    let bytes = RawBytesContainer::<u8>::from_static_bytes(BYTES).unwrap();
+    assert_eq!(bytes.as_slice(), b"abcdef");
+    let pairs = bytes.try_cast::<[u8; 2]>().unwrap();
+    assert_eq!(pairs.as_slice(), &[*b"ab", *b"cd", *b"ef"]);
+    assert!(RawBytesContainer::<[u8; 4]>::from_static_bytes(BYTES).is_err());
+
+    //  Writing  out  a  borrowed  container  copies  the  data  to  the  file
+    let mut container = RawBytesContainer::from_static(&WORDS);
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let reopened = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert_eq!(reopened.as_slice(), &WORDS);
+}
+
+#[test]
+fn test_write_to_file_atomic() {
+    use raw_bytes_container::MapOptions;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.bin");
+    std::fs::write(&path, [0xFFu8; 64]).unwrap();
+    let old = RawBytesContainer::<u32>::open_mmap_read(&path).unwrap();
+
+    let mut container = RawBytesContainer::from_vec(vec![1u32, 2, 3]);
+    container.write_to_file_atomic(&path).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap().len(), 12);
+    //  The  old  mapping  still  sees  the  replaced  file
+    assert_eq!(old[0], u32::MAX);
+
+    container.enable_checksum().unwrap();
+    container.write_to_file_atomic(&path).unwrap();
+    let reopened = RawBytesContainer::<u32>::open_mmap_read_with_options(
+        &path,
+        MapOptions::new().checksum(true),
+    )
+    .unwrap();
+    assert_eq!(reopened.as_slice(), &[1, 2, 3]);
+
+    //  Only  the  target  is  left  behind;  no  temporary  files
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    //  Saving  a  writable  mapping  elsewhere  does  not  flush  its  own  file
+    let backing = dir.path().join("backing.bin");
+    std::fs::write(&backing, [0u8; 8]).unwrap();
+    let mut rw = RawBytesContainer::<u32>::open_mmap_rw(&backing).unwrap();
+    rw.as_slice_mut().unwrap()[0] = 7;
+    assert!(rw.stats().dirty);
+    rw.write_to_file_atomic(dir.path().join("copy.bin"))
+        .unwrap();
+    assert!(rw.stats().dirty);
+    rw.flush().unwrap();
+    assert!(!rw.stats().dirty);
+}
+
+#[test]
+fn test_exclusive_and_shared_access() {
+    use raw_bytes_container::ContainerError;
+
+    let temp_file = NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), [0u8; 16]).unwrap();
+    let path = temp_file.path().to_path_buf();
+
+    let mut writer = RawBytesContainer::<u32>::open_mmap_rw_exclusive(&path).unwrap();
+    writer.as_slice_mut().unwrap()[0] = 42;
+
+    //  A  second  writer  is  refused  from  another  thread  too
+    let contended = std::thread::spawn({
+        let path = path.clone();
+        move || RawBytesContainer::<u32>::open_mmap_rw_exclusive(path).map(|_| ())
+    })
+    .join()
+    .unwrap();
+    assert!(matches!(contended, Err(ContainerError::LockContended)));
+    assert!(matches!(
+        RawBytesContainer::<u32>::open_mmap_read_shared(&path),
+        Err(ContainerError::LockContended)
+    ));
+
+    writer.flush().unwrap();
+    drop(writer);
+    let a = RawBytesContainer::<u32>::open_mmap_read_shared(&path).unwrap();
+    let b = RawBytesContainer::<u32>::open_mmap_read_shared(&path).unwrap();
+    assert_eq!((a[0], b[0]), (42, 42));
+    assert!(matches!(
+        RawBytesContainer::<u32>::open_mmap_rw_exclusive(&path),
+        Err(ContainerError::LockContended)
+    ));
+
+    drop((a, b));
+    RawBytesContainer::<u32>::open_mmap_rw_exclusive(&path).unwrap();
+}
+
+#[test]
+fn test_remap_follows_growth() {
+    use raw_bytes_container::{MapOptions, TrailingBytes};
+    use std::io::Write;
+
+    let mut log = NamedTempFile::new().unwrap();
+    log.write_all(bytemuck::cast_slice(&[1u32, 2])).unwrap();
+
+    let options = MapOptions::new().trailing(TrailingBytes::Ignore);
+    let mut reader =
+        RawBytesContainer::<u32>::open_mmap_read_with_options(log.path(), options).unwrap();
+    let mut writer = RawBytesContainer::<u32>::open_mmap_rw(log.path()).unwrap();
+    assert!(!reader.remap().unwrap());
+
+    //  A  partial  element  stays  in  the  trailing  bytes  until  it  is  complete
+    log.write_all(&[3, 0]).unwrap();
+    assert!(reader.remap().unwrap());
+    assert_eq!(reader.as_slice(), &[1, 2]);
+    assert_eq!(reader.trailing_bytes(), &[3, 0]);
+    log.write_all(&[0, 0]).unwrap();
+    assert!(reader.remap().unwrap());
+    assert_eq!(reader.as_slice(), &[1, 2, 3]);
+
+    //  Without  auto_remap,  fetch  is  just  get
+    log.write_all(bytemuck::cast_slice(&[4u32])).unwrap();
+    assert_eq!(reader.fetch(3).unwrap(), None);
+    assert!(writer.remap().unwrap());
+    writer.as_slice_mut().unwrap()[3] = 5;
+    assert_eq!(reader.fetch(3).unwrap(), None);
+    reader.remap().unwrap();
+    assert_eq!(reader[3], 5);
+
+    log.as_file().set_len(4).unwrap();
+    assert!(reader.remap().unwrap());
+    assert_eq!(reader.as_slice(), &[1]);
+
+    let mut in_memory = RawBytesContainer::from_vec(vec![1u32]);
+    assert!(in_memory.remap().is_err());
+}
+
+#[test]
+fn test_reserved_storage_never_moves() {
+    let mut container = RawBytesContainer::<Packet>::with_reserved(10);
+    assert!(container.is_reserved());
+    //  Rounded  up  to  a  whole  4  KiB  page
+    assert_eq!(container.capacity(), Some(4096 / 8));
+
+    container.resize_zeroed(1).unwrap();
+    let ptr = container.as_slice().as_ptr();
+    container
+        .append(&[Packet { a: 1, b: 2, c: 3 }; 100])
+        .unwrap();
+    container.insert(0, Packet { a: 9, b: 0, c: 0 }).unwrap();
+    unsafe { container.set_len_uninit(512) }.unwrap();
+    assert_eq!(container.as_slice().as_ptr(), ptr);
+
+    assert!(container.append(&[Packet { a: 0, b: 0, c: 0 }]).is_err());
+    assert!(container.resize_zeroed(513).is_err());
+    assert!(container.reserve(1).is_err());
+    container.reserve(0).unwrap();
+    assert!(container.shrink_to_fit().is_err());
+    assert_eq!(container.len(), 512);
+    assert_eq!(container[1].a, 0);
+    assert_eq!(container[2].a, 1);
+}
+
+#[test]
+fn test_stats() {
+    use raw_bytes_container::StorageKind;
+
+    let mut container = RawBytesContainer::from_vec(Vec::with_capacity(8));
+    container.append(&[Packet { a: 1, b: 2, c: 3 }; 3]).unwrap();
+    let stats = container.stats();
+    assert_eq!(stats.kind, StorageKind::InMemory);
+    assert_eq!((stats.len, stats.byte_len), (3, 24));
+    assert!(stats.capacity.unwrap() >= 8);
+    assert_eq!(stats.resident_bytes, Some(24));
+    assert!(stats.dirty);
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    assert!(!container.stats().dirty);
+
+    let mut mapped = RawBytesContainer::<Packet>::open_mmap_rw(temp_file.path()).unwrap();
+    let stats = mapped.stats();
+    assert_eq!(
+        (stats.kind, stats.len, stats.capacity),
+        (StorageKind::MmapRW, 3, None)
+    );
+    assert!(!stats.dirty);
+    #[cfg(unix)]
+    assert!(stats.resident_bytes.is_some());
+
+    mapped.get_mut(0).unwrap().a = 7;
+    assert!(mapped.stats().dirty);
+    mapped.flush().unwrap();
+    assert!(!mapped.stats().dirty);
+
+    let ro = RawBytesContainer::<Packet>::open_mmap_read(temp_file.path()).unwrap();
+    assert_eq!(ro.stats().kind, StorageKind::MmapRO);
+    #[cfg(unix)]
+    assert!(ro.stats().resident_bytes.is_some_and(|r| r <= 24));
+}
+
+#[test]
+fn test_blocks() {
+    let mut container = RawBytesContainer::from_vec((0u32..10).collect());
+    for block in container.blocks_mut(3).unwrap() {
+        block.reverse();
+    }
+    assert_eq!(container.as_slice(), &[2, 1, 0, 5, 4, 3, 8, 7, 6, 9]);
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let mut mapped = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert!(mapped.blocks_mut(3).is_none());
+    let blocks = mapped.blocks(5);
+    assert_eq!(blocks.len(), 2);
+    assert!(blocks.remainder().is_empty());
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        let maxima: Vec<u32> = mapped
+            .par_blocks(3)
+            .map(|b| *b.iter().max().unwrap())
+            .collect();
+        assert_eq!(maxima, vec![2, 5, 8]);
+        container
+            .par_blocks_mut(2)
+            .unwrap()
+            .for_each(|b| b.sort_unstable());
+        assert_eq!(container.as_slice(), &[1, 2, 0, 5, 3, 4, 7, 8, 6, 9]);
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_iter() {
+    use rayon::prelude::*;
+
+    let mut container = RawBytesContainer::from_vec((0u32..1000).collect());
+    container.par_iter_mut().unwrap().for_each(|v| *v *= 2);
+    assert_eq!(container.par_iter().sum::<u32>(), 999_000);
+    assert_eq!(
+        (&container)
+            .into_par_iter()
+            .filter(|v| **v % 4 == 0)
+            .count(),
+        500
+    );
+
+    let temp_file = NamedTempFile::new().unwrap();
+    container.write_to_file(temp_file.path()).unwrap();
+    let mut mapped = RawBytesContainer::<u32>::open_mmap_read(temp_file.path()).unwrap();
+    assert!(mapped.par_iter_mut().is_none());
+    assert_eq!(mapped.par_iter().max(), Some(&1998));
+}
+
+#[test]
+fn test_seal() {
+    use raw_bytes_container::StorageKind;
+
+    let mut memory = RawBytesContainer::from_vec(vec![Packet { a: 1, b: 2, c: 3 }; 3]);
+    memory.enable_checksum().unwrap();
+    memory.seal().unwrap();
+    assert_eq!(memory.stats().kind, StorageKind::MmapRO);
+    assert_eq!(memory.as_slice(), &[Packet { a: 1, b: 2, c: 3 }; 3]);
+    assert!(memory.verify().is_ok());
+    assert!(memory.as_slice_mut().is_none());
+    assert!(memory.get_mut(0).is_none());
+    assert!(memory.append(&[Packet { a: 0, b: 0, c: 0 }]).is_err());
+    assert!(memory.truncate(1).is_err());
+
+    //  Sealed  data  can  still  be  saved
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("sealed.bin");
+    memory.write_to_file_atomic(&path).unwrap();
+
+    //  Dirty  mappings  are  written  back  before  sealing
+    let mut mapped = RawBytesContainer::<Packet>::open_mmap_rw(&path).unwrap();
+    mapped.as_slice_mut().unwrap()[1].a = 42;
+    mapped.seal().unwrap();
+    assert!(!mapped.is_mutable());
+    assert!(mapped.flush().is_err());
+    let reread = RawBytesContainer::<Packet>::open_read_copied_with(
+        &path,
+        raw_bytes_container::TrailingBytes::Ignore,
+    )
+    .unwrap();
+    assert_eq!(reread[1].a, 42);
+
+    let mut empty = RawBytesContainer::<u32>::from_vec(Vec::new());
+    empty.seal().unwrap();
+    assert!(empty.is_empty());
+    empty.seal().unwrap();
+}
+
+#[test]
+fn test_open_mmap_read_lossy() {
+    let packets: Vec<Packet> = (0..4).map(|i| Packet { a: i, b: 0, c: 0 }).collect();
+    let file = NamedTempFile::new().unwrap();
+    let bytes: &[u8] = bytemuck::cast_slice(&packets);
+    std::fs::write(file.path(), &bytes[..bytes.len() - 3]).unwrap();
+
+    assert!(RawBytesContainer::<Packet>::open_mmap_read(file.path()).is_err());
+    let (container, recovery) =
+        RawBytesContainer::<Packet>::open_mmap_read_lossy(file.path()).unwrap();
+    assert_eq!(container.as_slice(), &packets[..3]);
+    assert_eq!(recovery.recovered, 3);
+    assert_eq!(recovery.discarded_bytes, 5);
+    assert_eq!(recovery.lost(), 0);
+    assert!(!recovery.is_complete());
+
+    std::fs::write(file.path(), bytes).unwrap();
+    let (_, recovery) = RawBytesContainer::<Packet>::open_mmap_read_lossy(file.path()).unwrap();
+    assert!(recovery.is_complete());
+}
+
+#[test]
+fn test_reader_writer_adapters() {
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    let source = RawBytesContainer::from_vec(vec![Packet { a: 1, b: 2, c: 3 }; 3]);
+    let mut copy = RawBytesContainer::<Packet>::from_vec(Vec::new());
+    std::io::copy(&mut source.reader(), &mut copy.writer().unwrap()).unwrap();
+    assert_eq!(copy.as_slice(), source.as_slice());
+
+    let mut reader = source.reader();
+    reader.seek(SeekFrom::Start(8)).unwrap();
+    let mut a = [0u8; 4];
+    reader.read_exact(&mut a).unwrap();
+    assert_eq!(u32::from_ne_bytes(a), 1);
+
+    //  Growing  past  the  end  zero-fills  the  last  element
+    let mut writer = copy.writer().unwrap();
+    writer.seek(SeekFrom::End(0)).unwrap();
+    writer.write_all(&7u32.to_ne_bytes()).unwrap();
+    assert_eq!(writer.position(), 28);
+    assert_eq!(copy.len(), 4);
+    assert_eq!(copy[3], Packet { a: 7, b: 0, c: 0 });
+
+    //  Mappings  keep  their  size
+    let file = NamedTempFile::new().unwrap();
+    copy.write_to_file(file.path()).unwrap();
+    let mut mapped = RawBytesContainer::<Packet>::open_mmap_rw(file.path()).unwrap();
+    let mut writer = mapped.writer().unwrap();
+    writer.seek(SeekFrom::Start(28)).unwrap();
+    writer.write_all(&[9; 4]).unwrap();
+    let err = writer.write_all(&[9]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+    assert!(writer.seek(SeekFrom::Current(-40)).is_err());
+    assert_eq!(mapped[3].b, 0x0909);
+
+    let mut ro = RawBytesContainer::<Packet>::open_mmap_read(file.path()).unwrap();
+    assert_eq!(ro.reader().bytes().count(), 32);
+    assert!(ro.writer().is_err());
+}
+
+#[test]
+fn test_dirty_tracking() {
+    use std::io::{Seek, SeekFrom, Write};
+
+    let file = NamedTempFile::new().unwrap();
+    let mut data = RawBytesContainer::<u32>::create_mmap_rw(file.path(), 4096).unwrap();
+    assert_eq!(data.dirty_ranges(), None);
+    assert!(data.flush_dirty().is_err());
+    data.enable_dirty_tracking().unwrap();
+
+    data.view_mut(100..110).unwrap().as_slice_mut().unwrap()[0] = 1;
+    *data.get_mut(110).unwrap() = 2;
+    *data.get_mut(3000).unwrap() = 3;
+    let mut writer = data.writer().unwrap();
+    writer.seek(SeekFrom::Start(4 * 50 + 2)).unwrap();
+    writer.write_all(&[9; 4]).unwrap();
+    assert_eq!(
+        data.dirty_ranges().unwrap(),
+        vec![50..52, 100..111, 3000..3001]
+    );
+
+    data.flush_dirty().unwrap();
+    assert!(data.dirty_ranges().unwrap().is_empty());
+    assert!(!data.stats().dirty);
+    let on_disk = std::fs::read(file.path()).unwrap();
+    assert_eq!(on_disk[3000 * 4], 3);
+
+    //  Whole-slice  access  marks  everything
+    data.as_slice_mut().unwrap();
+    assert_eq!(data.dirty_ranges().unwrap(), vec![0..4096]);
+    data.flush().unwrap();
+    assert!(data.dirty_ranges().unwrap().is_empty());
+
+    let mut memory = RawBytesContainer::from_vec(vec![0u32; 4]);
+    assert!(memory.enable_dirty_tracking().is_err());
+}
+
+#[test]
+fn test_change_generation() {
+    let file = NamedTempFile::new().unwrap();
+    let mut data = RawBytesContainer::<u32>::create_mmap_rw(file.path(), 16).unwrap();
+    let mut other = RawBytesContainer::from_vec(vec![0u32; 4]);
+    assert_eq!(data.change_generation(), 0);
+
+    *data.get_mut(3).unwrap() = 1;
+    let first = data.change_generation();
+    assert_ne!(first, 0);
+
+    //  A  flush  clears  the  dirty  flag  but  not  the  generation
+    data.flush().unwrap();
+    assert_eq!(data.change_generation(), first);
+
+    other.append(&[5]).unwrap();
+    data.as_slice_mut().unwrap();
+    assert_ne!(data.change_generation(), first);
+    assert_ne!(data.change_generation(), other.change_generation());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_sparse_files() {
+    let file = NamedTempFile::new().unwrap();
+    let len = 1 << 20;
+    let mut data = RawBytesContainer::<u64>::create_mmap_rw(file.path(), len).unwrap();
+    assert!(data.allocated_bytes().unwrap() < (len * 8) as u64);
+
+    data.as_slice_mut().unwrap().fill(u64::MAX);
+    data.flush().unwrap();
+    let full = data.allocated_bytes().unwrap();
+
+    data.punch_hole(1000..len - 1000).unwrap();
+    assert!(data.allocated_bytes().unwrap() < full);
+    assert_eq!(data[999], u64::MAX);
+    assert_eq!(data[1000], 0);
+    assert_eq!(data[len - 1001], 0);
+    assert_eq!(data[len - 1000], u64::MAX);
+    assert_eq!(
+        std::fs::metadata(file.path()).unwrap().len(),
+        (len * 8) as u64
+    );
+
+    assert!(data.punch_hole(0..len + 1).is_err());
+    let mut memory = RawBytesContainer::from_vec(vec![1u64; 4]);
+    assert!(memory.punch_hole(..).is_err());
+    assert!(memory.allocated_bytes().is_err());
+}
+
+#[test]
+fn test_protect_read_only() {
+    let file = NamedTempFile::new().unwrap();
+    let mut data = RawBytesContainer::<u32>::create_mmap_rw(file.path(), 1024).unwrap();
+    data.as_slice_mut().unwrap()[7] = 7;
+    let addr = data.as_slice().as_ptr();
+
+    data.protect_read_only().unwrap();
+    data.protect_read_only().unwrap();
+    assert!(data.is_protected());
+    assert!(!data.is_mutable());
+    assert!(data.flush().is_err());
+    assert_eq!(data.as_slice().as_ptr(), addr);
+    assert_eq!(data[7], 7);
+
+    data.unprotect().unwrap();
+    assert!(!data.is_protected());
+    data.as_slice_mut().unwrap()[8] = 8;
+    data.flush().unwrap();
+    assert_eq!(data.as_slice().as_ptr(), addr);
+    data.unprotect().unwrap();
+
+    //  Sealing  makes  the  protection  permanent
+    data.protect_read_only().unwrap();
+    data.seal().unwrap();
+    assert!(!data.is_protected());
+    assert!(data.unprotect().is_err());
+
+    let mut memory = RawBytesContainer::from_vec(vec![0u32; 4]);
+    assert!(memory.protect_read_only().is_err());
+    assert!(memory.unprotect().is_err());
+}
+
+#[test]
+fn test_builder() {
+    use raw_bytes_container::{Advice, FileLock};
+
+    let memory = RawBytesContainer::<u32>::builder()
+        .len(3)
+        .capacity(64)
+        .build()
+        .unwrap();
+    assert_eq!(memory.as_slice(), &[0, 0, 0]);
+    assert!(memory.capacity().unwrap() >= 64);
+    assert!(memory.is_mutable());
+
+    let sealed = RawBytesContainer::<u32>::builder()
+        .len(2)
+        .read_only(true)
+        .build()
+        .unwrap();
+    assert!(!sealed.is_mutable());
+
+    let mut anon = RawBytesContainer::<u64>::builder()
+        .anon()
+        .len(512)
+        .build()
+        .unwrap();
+    anon.as_slice_mut().unwrap()[511] = 9;
+    assert_eq!(anon[511], 9);
+    assert!(matches!(anon.into_storage(), Storage::MmapRW(_)));
+
+    //  Locks  need  a  file
+    let locked = RawBytesContainer::<u32>::builder()
+        .lock(FileLock::Shared)
+        .build();
+    assert!(locked.is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("built.bin");
+    assert!(
+        RawBytesContainer::<u32>::builder()
+            .mmap(&path)
+            .build()
+            .is_err()
+    );
+
+    let mut created = RawBytesContainer::<u32>::builder()
+        .mmap(&path)
+        .create_if_missing(true)
+        .len(256)
+        .lock(FileLock::Exclusive)
+        .advice(Advice::Random)
+        .build()
+        .unwrap();
+    assert_eq!(created.len(), 256);
+    created.as_slice_mut().unwrap()[0] = 42;
+    created.flush().unwrap();
+    drop(created);
+
+    //  An  existing  file  is  opened  as  is,  not  truncated
+    let reopened = RawBytesContainer::<u32>::builder()
+        .mmap(&path)
+        .create_if_missing(true)
+        .len(1)
+        .read_only(true)
+        .build()
+        .unwrap();
+    assert_eq!(reopened.len(), 256);
+    assert_eq!(reopened[0], 42);
+    assert!(!reopened.is_mutable());
+}
+
+#[test]
+fn test_headered_container() {
+    use raw_bytes_container::HeaderedContainer;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    struct Meta {
+        magic: [u8; 4],
+        count: u32,
+    }
+
+    type Table = HeaderedContainer<Meta, u64>;
+    let meta = Meta {
+        magic: *b"TABL",
+        count: 0,
+    };
+
+    let mut table = Table::from_parts(meta, &[1, 2]).unwrap();
+    table.append(&[3]).unwrap();
+    table.header_mut().unwrap().count = table.len() as u32;
+    table.payload_mut().unwrap()[0] = 10;
+    assert_eq!(table.header().count, 3);
+    assert_eq!(table.payload(), &[10, 2, 3]);
+
+    //  The  payload  starts  at  the  next  8-byte  boundary  after  the  header
+    assert_eq!(Table::PAYLOAD_OFFSET, 8);
+    assert_eq!(HeaderedContainer::<[u8; 5], u32>::PAYLOAD_OFFSET, 8);
+
+    let file = NamedTempFile::new().unwrap();
+    table.write_to_file(file.path()).unwrap();
+    let mut mapped = Table::open_mmap_rw(file.path()).unwrap();
+    assert_eq!(mapped.header(), table.header());
+    assert_eq!(mapped.payload(), table.payload());
+    assert!(mapped.append(&[4]).is_err());
+    mapped.payload_mut().unwrap()[2] = 30;
+    mapped.flush().unwrap();
+    drop(mapped);
+
+    let mut read = Table::open_mmap_read(file.path()).unwrap();
+    assert_eq!(read.payload(), &[10, 2, 30]);
+    assert!(read.header_mut().is_err());
+
+    let created = NamedTempFile::new().unwrap();
+    let fresh = Table::create_mmap_rw(created.path(), meta, 16).unwrap();
+    assert_eq!(fresh.len(), 16);
+    assert_eq!(fresh.header().magic, *b"TABL");
+    assert_eq!(std::fs::metadata(created.path()).unwrap().len(), 8 + 16 * 8);
+
+    //  Too  short  for  the  header,  or  a  partial  trailing  element
+    let short = RawBytesContainer::from_vec(vec![0u8; 4]);
+    assert!(Table::from_container(short).is_err());
+    let ragged = RawBytesContainer::from_vec(vec![0u8; 8 + 3]);
+    assert!(Table::from_container(ragged).is_err());
+}
+
+#[test]
+fn test_headered_container_over_aligned() {
+    use raw_bytes_container::HeaderedContainer;
+
+    //  Wider  than  any  alignment  malloc  guarantees
+    #[repr(C, align(128))]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    struct CacheLine {
+        tag: [u8; 128],
+    }
+
+    type Lines = HeaderedContainer<CacheLine, u32>;
+    let mut lines = Lines::from_parts(CacheLine { tag: [7; 128] }, &[1]).unwrap();
+    for i in 0..1000 {
+        lines.append(&[i]).unwrap();
+    }
+    assert_eq!(lines.as_bytes().as_ptr() as usize % 128, 0);
+    assert_eq!(lines.header().tag[127], 7);
+    assert_eq!(lines.len(), 1001);
+    assert_eq!(lines.payload()[1000], 999);
+
+    //  Byte  containers  are  realigned  on  the  way  in
+    let bytes = lines.into_container();
+    let lines = Lines::from_container(bytes).unwrap();
+    assert_eq!(lines.payload()[0], 1);
+}
+
+#[test]
+fn test_segmented_container() {
+    use raw_bytes_container::SegmentedContainer;
+
+    let mut data = SegmentedContainer::<u32>::with_segment_len(4);
+    assert_eq!(data.as_slice().unwrap(), &[] as &[u32]);
+    data.extend_from_slice(&[0, 1, 2]);
+    assert_eq!(data.as_slice().unwrap(), &[0, 1, 2]);
+
+    //  Appends  spill  into  new  segments  without  moving  the  first  one
+    let first = data.segments().next().unwrap().as_ptr();
+    data.extend_from_slice(&[3, 4, 5, 6, 7, 8]);
+    data.push(9);
+    assert_eq!(data.len(), 10);
+    assert_eq!(data.segment_count(), 3);
+    assert_eq!(data.segments().next().unwrap().as_ptr(), first);
+    assert!(data.as_slice().is_err());
+
+    data[5] *= 10;
+    assert_eq!(data.get(5), Some(&50));
+    assert_eq!(data.get(10), None);
+    assert_eq!(data.iter().copied().collect::<Vec<_>>(), data.to_vec());
+
+    let file = NamedTempFile::new().unwrap();
+    data.write_to_file(file.path()).unwrap();
+    let mapped = RawBytesContainer::<u32>::open_mmap_read(file.path()).unwrap();
+    assert_eq!(mapped.as_slice(), data.to_vec().as_slice());
+
+    let container = data.into_container();
+    assert_eq!(container.as_slice(), &[0, 1, 2, 3, 4, 50, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_mmap_vec() {
+    use raw_bytes_container::MmapVec;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("points.vec");
+
+    let mut points = MmapVec::<[f32; 2]>::create(&path).unwrap();
+    assert!(points.is_empty());
+    assert_eq!(points.capacity(), 0);
+    points.push([1.0, 2.0]).unwrap();
+    let first_capacity = points.capacity();
+    assert_eq!(first_capacity, 512);
+
+    //  Growing  past  the  capacity  resizes  the  file  and  keeps  the  data
+    let more: Vec<[f32; 2]> = (0..1000).map(|i| [i as f32, 0.0]).collect();
+    points.extend_from_slice(&more).unwrap();
+    assert_eq!(points.len(), 1001);
+    assert!(points.capacity() >= 1001);
+    assert_eq!(points[0], [1.0, 2.0]);
+    assert_eq!(points[1000], [999.0, 0.0]);
+    assert_eq!(points.pop(), Some([999.0, 0.0]));
+    points.as_mut_slice()[1] = [-1.0, -1.0];
+    points.flush().unwrap();
+    drop(points);
+
+    let mut points = MmapVec::<[f32; 2]>::open(&path).unwrap();
+    assert_eq!(points.len(), 1000);
+    assert_eq!(points[1], [-1.0, -1.0]);
+
+    points.truncate(3);
+    points.shrink_to_fit().unwrap();
+    assert_eq!(points.capacity(), 3);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 16 + 3 * 8);
+    points.clear();
+    assert_eq!(points.pop(), None);
+    drop(points);
+
+    //  Other  files  are  rejected
+    let other = NamedTempFile::new().unwrap();
+    std::fs::write(other.path(), [0u8; 32]).unwrap();
+    assert!(MmapVec::<u32>::open(other.path()).is_err());
+}
+
+#[test]
+fn test_prefetch() {
+    let file = NamedTempFile::new().unwrap();
+    let data = RawBytesContainer::<u32>::create_mmap_rw(file.path(), 1 << 16).unwrap();
+    data.prefetch(..).unwrap();
+    data.prefetch(1000..=2000).unwrap();
+    data.prefetch(10..10).unwrap();
+    assert!(data.prefetch(..(1 << 16) + 1).is_err());
+
+    let read = RawBytesContainer::<u32>::open_mmap_read(file.path()).unwrap();
+    read.prefetch(1 << 15..).unwrap();
+
+    //  Nothing  to  do  for  data  that  is  already  in  memory
+    let memory = RawBytesContainer::from_vec(vec![0u32; 16]);
+    memory.prefetch(..).unwrap();
+    assert!(memory.prefetch(..17).is_err());
+}
+
+#[test]
+fn test_sort_and_search() {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    struct Record {
+        key: u32,
+        value: u32,
+    }
+
+    let file = NamedTempFile::new().unwrap();
+    let mut records = RawBytesContainer::<Record>::create_mmap_rw(file.path(), 5).unwrap();
+    let data = records.as_slice_mut().unwrap();
+    for (i, key) in [3, 1, 2, 1, 0].into_iter().enumerate() {
+        data[i] = Record {
+            key,
+            value: i as u32,
+        };
+    }
+
+    //  Stable:  the  two  records  with  key  1  keep  their  order
+    records.sort_by_key(|r| r.key).unwrap();
+    let values: Vec<u32> = records.iter().map(|r| r.value).collect();
+    assert_eq!(values, [4, 1, 3, 2, 0]);
+    assert_eq!(records.binary_search_by(|r| r.key.cmp(&2)), Ok(3));
+    assert_eq!(records.binary_search_by(|r| r.key.cmp(&5)), Err(5));
+
+    records.sort_unstable_by(|a, b| b.key.cmp(&a.key)).unwrap();
+    assert_eq!(records[0].key, 3);
+    assert_eq!(records[4].key, 0);
+    records.flush().unwrap();
+    drop(records);
+
+    let mut read = RawBytesContainer::<Record>::open_mmap_read(file.path()).unwrap();
+    assert_eq!(read[0].key, 3);
+    assert!(read.sort_by_key(|r| r.key).is_err());
+    assert!(read.sort_unstable_by(|a, b| a.key.cmp(&b.key)).is_err());
+}
+
+#[test]
+fn test_equality_across_backends() {
+    use std::hash::{BuildHasher, RandomState};
+
+    let memory = RawBytesContainer::from_vec(vec![1u32, 2, 3]);
+    let file = NamedTempFile::new().unwrap();
+    memory.write_to_file_atomic(file.path()).unwrap();
+    let mapped = RawBytesContainer::<u32>::open_mmap_read(file.path()).unwrap();
+
+    assert_eq!(memory, mapped);
+    assert_eq!(mapped, [1, 2, 3][..]);
+    assert!(memory.bytes_eq(&mapped));
+    assert!(memory < RawBytesContainer::from_vec(vec![1, 2, 4]));
+    assert_ne!(memory, RawBytesContainer::from_vec(vec![1, 2]));
+
+    let state = RandomState::new();
+    assert_eq!(state.hash_one(&memory), state.hash_one(&mapped));
+
+    //  Raw  bytes  ignore  the  element  type  and  compare  floats  by  bit  pattern
+    let words = RawBytesContainer::from_vec(vec![0x0201u16, 0x0403]);
+    let bytes = RawBytesContainer::from_vec(u32::to_ne_bytes(0x04030201).to_vec());
+    assert!(words.bytes_eq(&bytes));
+    let nan = RawBytesContainer::from_vec(vec![f32::NAN]);
+    assert_ne!(nan, RawBytesContainer::from_vec(vec![f32::NAN]));
+    assert!(nan.bytes_eq(&RawBytesContainer::from_vec(vec![f32::NAN])));
+    assert_eq!(
+        RawBytesContainer::from_vec(vec![0.0f32]),
+        RawBytesContainer::from_vec(vec![-0.0f32])
+    );
+}
+
+#[test]
+fn test_snapshot_restore() {
+    let mut doc = RawBytesContainer::from_vec(vec![1u32, 2, 3]);
+    let v0 = doc.snapshot().unwrap();
+
+    //  Shared  until  the  next  write
+    assert_eq!(doc.as_slice().as_ptr(), v0.as_ptr());
+    doc.as_slice_mut().unwrap()[0] = 10;
+    assert_ne!(doc.as_slice().as_ptr(), v0.as_ptr());
+    assert_eq!(v0.as_slice(), &[1, 2, 3]);
+
+    let v1 = doc.snapshot().unwrap();
+    doc.append(&[4]).unwrap();
+    doc.remove(1).unwrap();
+    assert_eq!(doc.as_slice(), &[10, 3, 4]);
+    assert_eq!(v1.as_slice(), &[10, 2, 3]);
+
+    //  Undo  twice,  then  redo
+    let v2 = doc.snapshot().unwrap();
+    doc.restore(&v1).unwrap();
+    assert_eq!(doc.as_slice(), &[10, 2, 3]);
+    doc.restore(&v0).unwrap();
+    assert_eq!(doc.as_slice(), &[1, 2, 3]);
+    assert_eq!(doc.as_slice().as_ptr(), v0.as_ptr());
+    doc.restore(&v2).unwrap();
+    assert_eq!(doc.as_slice(), &[10, 3, 4]);
+
+    //  Restored  data  behaves  like  any  in-memory  container
+    let file = NamedTempFile::new().unwrap();
+    doc.write_to_file(file.path()).unwrap();
+    assert_eq!(std::fs::read(file.path()).unwrap().len(), 12);
+
+    //  Once  the  last  snapshot  is  gone  the  buffer  is  taken  over  without  copying
+    let addr = doc.as_slice().as_ptr();
+    drop(v2);
+    let vec = doc.into_vec();
+    assert_eq!(vec.as_ptr(), addr);
+
+    let mut reserved = RawBytesContainer::<u32>::with_reserved(8);
+    assert!(reserved.snapshot().is_err());
+    let mut mapped = RawBytesContainer::<u32>::open_mmap_rw(file.path()).unwrap();
+    assert!(mapped.snapshot().is_err());
+    assert!(mapped.restore(&v0).is_err());
+}
+
+#[test]
+fn test_backend_conversion() {
+    use raw_bytes_container::StorageKind;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("converted.bin");
+
+    let mut memory = RawBytesContainer::from_vec(vec![1u64, 2, 3]);
+    memory.enable_checksum().unwrap();
+    let mut mapped = memory.to_mmap(&path).unwrap();
+    assert_eq!(mapped.stats().kind, StorageKind::MmapRW);
+    assert!(mapped.is_checksummed());
+    mapped.verify().unwrap();
+    assert_eq!(mapped.as_slice(), memory.as_slice());
+
+    mapped.as_slice_mut().unwrap()[2] = 30;
+    mapped.commit().unwrap();
+
+    //  The  copy  is  independent  of  the  mapping  and  supports  in-memory  operations
+    let mut copy = mapped.to_in_memory();
+    assert_eq!(copy.stats().kind, StorageKind::InMemory);
+    assert!(copy.is_checksummed());
+    copy.append(&[4]).unwrap();
+    mapped.as_slice_mut().unwrap()[0] = 10;
+    assert_eq!(copy.as_slice(), &[1, 2, 30, 4]);
+
+    //  Borrowed  sources  work  too
+    static WORDS: [u32; 2] = [5, 6];
+    let borrowed = RawBytesContainer::from_static(&WORDS);
+    let remapped = borrowed.to_mmap(dir.path().join("words.bin")).unwrap();
+    assert_eq!(remapped.as_slice(), &[5, 6]);
+    assert_eq!(borrowed.to_in_memory().into_vec(), vec![5, 6]);
+}
+
+#[test]
+fn test_from_bytes_validated() {
+    #[repr(C, packed)]
+    #[derive(Clone, Copy, Debug, Pod, Zeroable)]
+    struct Entry {
+        tag: u8,
+        value: [u8; 4],
+    }
+
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), [1, 0, 0, 0, 7, 2, 0, 0, 0, 9]).unwrap();
+    let bytes = std::fs::read(file.path()).unwrap();
+    let addr = bytes.as_ptr();
+
+    let entries = RawBytesContainer::<Entry>::from_bytes_validated(bytes).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].tag, 2);
+    assert_eq!({ entries[1].value }, [0, 0, 0, 9]);
+    assert_eq!(entries.as_slice().as_ptr() as *const u8, addr);
+
+    //  Errors  name  the  sizes  involved
+    let message = |bytes: Vec<u8>| {
+        let err = RawBytesContainer::<Entry>::from_bytes_validated(bytes).unwrap_err();
+        err.to_string()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    assert!(message(vec![0; 7]).contains("7 bytes"));
+    assert!(message(vec![0; 7]).contains("(5 bytes each)"));
+
+    let mut odd_capacity = Vec::with_capacity(7);
+    odd_capacity.extend_from_slice(&[0u8; 5]);
+    if odd_capacity.capacity() == 7 {
+        assert!(message(odd_capacity).contains("capacity 7"));
+    }
+
+    let err = RawBytesContainer::<u32>::from_bytes_validated(vec![0; 8]).unwrap_err();
+    assert!(matches!(
+        err,
+        raw_bytes_container::ContainerError::AlignmentError(_)
+    ));
+}
+
+#[test]
+fn test_append_mapped() {
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), bytemuck::cast_slice::<u32, u8>(&[1, 2])).unwrap();
+
+    let mut log = RawBytesContainer::<u32>::open_mmap_rw(file.path()).unwrap();
+    assert!(log.append(&[3]).is_err());
+    log.append_mapped(&[3, 4]).unwrap();
+    log.append_mapped(&[]).unwrap();
+    assert_eq!(log.as_slice(), &[1, 2, 3, 4]);
+    log.flush().unwrap();
+    drop(log);
+    assert_eq!(std::fs::metadata(file.path()).unwrap().len(), 16);
+
+    //  Small  appends  reuse  the  spare  room  instead  of  remapping  every  time
+    let mut log = RawBytesContainer::<u32>::open_mmap_rw(file.path()).unwrap();
+    log.append_mapped(&[5]).unwrap();
+    let base = log.as_slice().as_ptr();
+    for i in 6..100 {
+        log.append_mapped(&[i]).unwrap();
+    }
+    assert_eq!(log.as_slice().as_ptr(), base);
+    assert_eq!(log.len(), 99);
+    assert_eq!(log[98], 99);
+    assert!(std::fs::metadata(file.path()).unwrap().len() > 99 * 4);
+
+    //  Flushing  cuts  the  file  back  to  the  elements;  appending  afterwards  still  works
+    log.flush().unwrap();
+    assert_eq!(std::fs::metadata(file.path()).unwrap().len(), 99 * 4);
+    log.append_mapped(&[100]).unwrap();
+    assert_eq!(log.as_slice().as_ptr(), base);
+    drop(log);
+    assert_eq!(std::fs::metadata(file.path()).unwrap().len(), 100 * 4);
+    let reopened = RawBytesContainer::<u32>::open_mmap_read(file.path()).unwrap();
+    assert_eq!(reopened.len(), 100);
+    assert_eq!(reopened[99], 100);
+
+    //  Protecting  a  grown  mapping  drops  the  spare  room  first
+    let mut log = RawBytesContainer::<u32>::open_mmap_rw(file.path()).unwrap();
+    log.append_mapped(&[101]).unwrap();
+    log.protect_read_only().unwrap();
+    assert_eq!(log.len(), 101);
+    assert_eq!(std::fs::metadata(file.path()).unwrap().len(), 101 * 4);
+    drop(log);
+
+    let mut read = RawBytesContainer::<u32>::open_mmap_read(file.path()).unwrap();
+    assert!(read.append_mapped(&[5]).is_err());
+    assert!(
+        RawBytesContainer::from_vec(vec![1u32])
+            .append_mapped(&[2])
+            .is_err()
+    );
+
+    //  Trailing  bytes  would  misalign  the  new  elements
+    std::fs::write(file.path(), [0u8; 6]).unwrap();
+    let mut ragged = RawBytesContainer::<u32>::open_mmap_rw_with(
+        file.path(),
+        raw_bytes_container::TrailingBytes::Ignore,
+    )
+    .unwrap();
+    assert!(ragged.append_mapped(&[1]).is_err());
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bytes_interop() {
+    use bytes::{Bytes, BytesMut};
+
+    let buf = Bytes::from(vec![1u8, 0, 0, 0, 2, 0, 0, 0]);
+    let data = RawBytesContainer::<u32>::from_bytes(buf).unwrap();
+    assert_eq!(data.as_slice(), &[1, 2]);
+    assert!(RawBytesContainer::<u32>::from_bytes(Bytes::from_static(&[0; 7])).is_err());
+
+    //  Byte-aligned  element  types  reuse  the  buffer's  allocation
+    let mut buf = BytesMut::with_capacity(64);
+    buf.extend_from_slice(b"abcdef");
+    let addr = buf.as_ptr();
+    let chars = RawBytesContainer::<[u8; 2]>::from_bytes_mut(buf).unwrap();
+    assert_eq!(chars[1], *b"cd");
+    assert_eq!(chars.as_slice().as_ptr() as *const u8, addr);
+
+    let addr = data.as_slice().as_ptr() as *const u8;
+    let bytes = data.into_bytes();
+    assert_eq!(bytes.as_ptr(), addr);
+    assert_eq!(&bytes[..], &[1, 0, 0, 0, 2, 0, 0, 0]);
+
+    let file = NamedTempFile::new().unwrap();
+    let mut memory = RawBytesContainer::from_vec(vec![0u32, 0, 0, 7]);
+    memory.enable_checksum().unwrap();
+    memory.write_to_file(file.path()).unwrap();
+
+    //  Read-only  mappings  are  shared  without  copying;  the  trailer  is  left  out
+    let mapped = RawBytesContainer::<u32>::open_mmap_read_with_options(
+        file.path(),
+        raw_bytes_container::MapOptions::new().checksum(true),
+    )
+    .unwrap();
+    let addr = mapped.as_slice().as_ptr() as *const u8;
+    let bytes = mapped.into_bytes();
+    assert_eq!(bytes.len(), 16);
+    assert_eq!(bytes.as_ptr(), addr);
+    assert_eq!(bytes[12], 7);
+
+    let rw = RawBytesContainer::<u32>::open_mmap_rw(file.path()).unwrap();
+    let mut buf = rw.into_bytes_mut();
+    buf[0] = 9;
+    assert_eq!(buf.len(), 16 + 16);
+    assert_eq!(
+        RawBytesContainer::<u8>::from_vec(vec![5]).into_bytes_mut(),
+        &[5][..]
+    );
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_read_write() {
+    use raw_bytes_container::{ContainerError, MapOptions, TrailingBytes};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("packets.bin");
+
+    let mut container = RawBytesContainer::from_vec(vec![Packet { a: 1, b: 2, c: 3 }; 4]);
+    container.enable_checksum().unwrap();
+    container.write_to_file_async(&path).await.unwrap();
+    let verified = RawBytesContainer::<Packet>::open_mmap_read_with_options(
+        &path,
+        MapOptions::new().checksum(true),
+    )
+    .unwrap();
+    assert_eq!(verified.len(), 4);
+
+    let plain = dir.path().join("plain.bin");
+    let mut unchecked = RawBytesContainer::from_slice(container.as_slice());
+    unchecked.write_to_file_async(&plain).await.unwrap();
+    let loaded = RawBytesContainer::<Packet>::read_from_path_async(&plain)
+        .await
+        .unwrap();
+    assert_eq!(loaded.as_slice(), container.as_slice());
+
+    std::fs::write(&plain, [0u8; 11]).unwrap();
+    let err = RawBytesContainer::<Packet>::read_from_path_async(&plain).await;
+    assert!(matches!(err, Err(ContainerError::AlignmentError(_))));
+    let loaded =
+        RawBytesContainer::<Packet>::read_from_path_async_with(&plain, TrailingBytes::Ignore)
+            .await
+            .unwrap();
+    assert_eq!(loaded.len(), 1);
+
+    let mut mapped = RawBytesContainer::<Packet>::open_mmap_rw(&path).unwrap();
+    assert!(mapped.write_to_file_async(&path).await.is_err());
+}
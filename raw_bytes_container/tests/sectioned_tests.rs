@@ -0,0 +1,98 @@
+use bytemuck_derive::Pod;
+use bytemuck_derive::Zeroable;
+use raw_bytes_container::{ContainerError, SectionedFile, SectionedFileBuilder};
+use tempfile::NamedTempFile;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+struct Packet {
+    a: u32,
+    b: u16,
+    c: u16,
+}
+
+#[test]
+fn test_sections_round_trip() {
+    let packets = [Packet { a: 1, b: 2, c: 3 }, Packet { a: 4, b: 5, c: 6 }];
+    let weights = [0.5f64, 1.5, 2.5];
+
+    let temp_file = NamedTempFile::new().unwrap();
+    SectionedFileBuilder::new()
+        .add_section("packets", &packets)
+        .add_section("weights", &weights)
+        .add_section("flags", &[7u8])
+        .write(temp_file.path())
+        .unwrap();
+
+    let file = SectionedFile::open(temp_file.path()).unwrap();
+    let sections = file.sections();
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0].name, "packets");
+    assert_eq!(sections[0].count, 2);
+
+    assert_eq!(file.open_section::<Packet>("packets").unwrap(), &packets);
+    assert_eq!(file.open_section::<f64>("weights").unwrap(), &weights);
+    assert_eq!(file.open_section::<u8>("flags").unwrap(), &[7u8]);
+}
+
+#[test]
+fn test_section_errors() {
+    let temp_file = NamedTempFile::new().unwrap();
+    SectionedFileBuilder::new()
+        .add_section("weights", &[1.0f32, 2.0])
+        .write(temp_file.path())
+        .unwrap();
+
+    let file = SectionedFile::open(temp_file.path()).unwrap();
+    assert!(matches!(
+        file.open_section::<u32>("weights"),
+        Err(ContainerError::TypeMismatch(_))
+    ));
+    assert!(matches!(
+        file.open_section::<f32>("missing"),
+        Err(ContainerError::NotFound(_))
+    ));
+
+    std::fs::write(temp_file.path(), b"not  a  sectioned  file").unwrap();
+    assert!(matches!(
+        SectionedFile::open(temp_file.path()),
+        Err(ContainerError::InvalidFormat(_))
+    ));
+}
+
+#[test]
+fn test_copy_section() {
+    let source_file = NamedTempFile::new().unwrap();
+    SectionedFileBuilder::new()
+        .add_section("weights", &[1.0f32, 2.0])
+        .add_section("ids", &[7u64])
+        .write(source_file.path())
+        .unwrap();
+    let source = SectionedFile::open(source_file.path()).unwrap();
+
+    //  Rewriting  a  mapped  file  atomically  leaves  the  old  mapping  intact
+    let mut builder = SectionedFileBuilder::new();
+    builder.add_section("weights", &[0u8]);
+    builder.write_atomic(source_file.path()).unwrap();
+    assert_eq!(source.open_section::<f32>("weights").unwrap(), &[1.0, 2.0]);
+    assert_eq!(
+        SectionedFile::open(source_file.path())
+            .unwrap()
+            .open_section::<u8>("weights")
+            .unwrap(),
+        &[0]
+    );
+
+    let temp_file = NamedTempFile::new().unwrap();
+    builder.copy_section(&source, "weights").unwrap();
+    assert!(matches!(
+        builder.copy_section(&source, "missing"),
+        Err(ContainerError::NotFound(_))
+    ));
+    assert_eq!(builder.section_count(), 1);
+    builder.write(temp_file.path()).unwrap();
+
+    let file = SectionedFile::open(temp_file.path()).unwrap();
+    assert_eq!(file.open_section::<f32>("weights").unwrap(), &[1.0, 2.0]);
+    assert!(!file.contains("ids"));
+}
@@ -0,0 +1,283 @@
+// save/src/autosave.rs
+//! Background autosaving of registered containers.
+//!
+//! Each registered source is a shared container plus a snapshot closure that
+//! adds its sections to a [`SaveWriter`]. The autosaver thread wakes every
+//! `interval`, re-snapshots only the containers whose
+//! [change generation](Watch::change_generation) moved and have been quiet
+//! for `debounce`, and rewrites the save file from the cached sections of all
+//! sources.
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use bytemuck::Pod;
+use packed_bits_container::PackedBitsContainer;
+use packed_bits_container::flags::FlagsContainer;
+use packed_struct_container::PackedStructContainer;
+use raw_bytes_container::RawBytesContainer;
+use crate::sections::SaveWriter;
+
+/// A container whose mutations the autosaver can detect
+///
+/// The generation comes from the container's own dirty tracking, so there is
+/// nothing to call after mutating it.
+pub trait Watch {
+    /// Value that changes on every mutation; see
+    /// [`RawBytesContainer::change_generation`]
+    fn change_generation(&self) -> u64;
+}
+
+impl<T: Pod> Watch for RawBytesContainer<T> {
+    fn change_generation(&self) -> u64 {
+        RawBytesContainer::change_generation(self)
+    }
+}
+
+impl<T: Pod + Copy> Watch for PackedStructContainer<T> {
+    fn change_generation(&self) -> u64 {
+        PackedStructContainer::change_generation(self)
+    }
+}
+
+impl<const N: usize> Watch for PackedBitsContainer<N> {
+    fn change_generation(&self) -> u64 {
+        self.storage().change_generation()
+    }
+}
+
+impl<const N: usize> Watch for FlagsContainer<N> {
+    fn change_generation(&self) -> u64 {
+        self.packed_bits().storage().change_generation()
+    }
+}
+
+/// Lock `mutex`, carrying on with the data if another thread panicked while
+/// holding it
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+type Generation = Box<dyn Fn() -> u64 + Send>;
+/// Adds the source's sections and returns the generation they were taken at
+type Snapshot = Box<dyn Fn(&mut SaveWriter) -> std::io::Result<u64> + Send>;
+type Quiesce = Box<dyn Fn() -> bool + Send>;
+
+struct Source {
+    generation: Generation,
+    snapshot: Snapshot,
+    /// Sections from the last snapshot
+    cached: Option<SaveWriter>,
+    saved_generation: Option<u64>,
+    seen_generation: u64,
+    changed_at: Instant,
+}
+
+struct State {
+    path: PathBuf,
+    debounce: Duration,
+    quiesce: Option<Quiesce>,
+    sources: Vec<Source>,
+    saves: u64,
+    last_error: Option<std::io::Error>,
+}
+
+impl State {
+    /// Snapshot ready sources and rewrite the file; returns whether a save happened
+    fn save(&mut self, force: bool) -> std::io::Result<bool> {
+        let now = Instant::now();
+        let mut ready = Vec::new();
+        for (i, source) in self.sources.iter_mut().enumerate() {
+            let generation = (source.generation)();
+            if generation != source.seen_generation {
+                source.seen_generation = generation;
+                source.changed_at = now;
+            }
+            let dirty = source.saved_generation != Some(generation);
+            if dirty && (force || now.duration_since(source.changed_at) >= self.debounce) {
+                ready.push(i);
+            }
+        }
+        if ready.is_empty() {
+            return Ok(false);
+        }
+        if let Some(quiesce) = &self.quiesce
+            && !quiesce()
+        {
+            return Ok(false);
+        }
+
+        let mut snapshotted = Vec::with_capacity(ready.len());
+        for i in ready {
+            let source = &mut self.sources[i];
+            let mut writer = SaveWriter::new();
+            // Taken under the source's lock, so later changes stay unsaved
+            snapshotted.push((i, (source.snapshot)(&mut writer)?));
+            source.cached = Some(writer);
+        }
+
+        let mut writer = SaveWriter::new();
+        for cached in self.sources.iter().filter_map(|s| s.cached.as_ref()) {
+            writer.append(cached)?;
+        }
+        // Write next to the target and rename, so a crash never leaves a torn save
+        let tmp = self.path.with_extension("autosave.tmp");
+        writer.write(&tmp)?;
+        File::open(&tmp)?.sync_all()?;
+        std::fs::rename(&tmp, &self.path)?;
+        // Make the rename itself durable
+        #[cfg(unix)]
+        {
+            let dir = match self.path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            File::open(dir)?.sync_all()?;
+        }
+        // Only now are the snapshots on disk; a failed write leaves them dirty
+        for (i, generation) in snapshotted {
+            self.sources[i].saved_generation = Some(generation);
+        }
+        self.saves += 1;
+        Ok(true)
+    }
+}
+
+/// Configures and starts an [`Autosaver`]
+pub struct AutosaverBuilder {
+    interval: Duration,
+    state: State,
+}
+
+impl AutosaverBuilder {
+    /// How often the background thread checks for changes (default 30s)
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// How long a source must go unchanged before it is saved (default none)
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.state.debounce = debounce;
+        self
+    }
+
+    /// Called before snapshotting; return `false` to postpone the save,
+    /// e.g. while the game is mid-update
+    pub fn quiesce<F>(mut self, quiesce: F) -> Self
+    where
+        F: Fn() -> bool + Send + 'static,
+    {
+        self.state.quiesce = Some(Box::new(quiesce));
+        self
+    }
+
+    /// Register a shared container; `snapshot` adds its sections to the writer
+    ///
+    /// The container is saved whenever its change generation moves, so callers
+    /// only need to mutate it through the mutex.
+    pub fn register<C, F>(mut self, source: Arc<Mutex<C>>, snapshot: F) -> Self
+    where
+        C: Watch + Send + 'static,
+        F: Fn(&C, &mut SaveWriter) -> std::io::Result<()> + Send + 'static,
+    {
+        let watched = Arc::clone(&source);
+        let generation = Box::new(move || lock(&watched).change_generation());
+        let snapshot = Box::new(move |writer: &mut SaveWriter| {
+            let source = lock(&source);
+            snapshot(&source, writer)?;
+            Ok(source.change_generation())
+        });
+        self.state.sources.push(Source {
+            seen_generation: generation(),
+            generation,
+            snapshot,
+            cached: None,
+            saved_generation: None,
+            changed_at: Instant::now(),
+        });
+        self
+    }
+
+    /// Spawn the background thread
+    pub fn start(self) -> Autosaver {
+        let state = Arc::new(Mutex::new(self.state));
+        let (stop, stop_rx) = mpsc::channel::<()>();
+        let interval = self.interval;
+
+        let thread_state = Arc::clone(&state);
+        let handle = std::thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                let mut state = lock(&thread_state);
+                if let Err(e) = state.save(false) {
+                    state.last_error = Some(e);
+                }
+            }
+        });
+
+        Autosaver { state, stop: Some(stop), handle: Some(handle) }
+    }
+}
+
+/// Background thread that writes incremental saves of registered sources
+pub struct Autosaver {
+    state: Arc<Mutex<State>>,
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Autosaver {
+    /// Start configuring an autosaver that writes to `path`
+    pub fn builder<P: AsRef<Path>>(path: P) -> AutosaverBuilder {
+        AutosaverBuilder {
+            interval: Duration::from_secs(30),
+            state: State {
+                path: path.as_ref().to_path_buf(),
+                debounce: Duration::ZERO,
+                quiesce: None,
+                sources: Vec::new(),
+                saves: 0,
+                last_error: None,
+            },
+        }
+    }
+
+    /// Save every dirty source now, ignoring the debounce
+    ///
+    /// Returns `Ok(false)` if nothing was dirty or the quiesce callback
+    /// postponed the save.
+    pub fn save_now(&self) -> std::io::Result<bool> {
+        lock(&self.state).save(true)
+    }
+
+    /// Number of saves written so far
+    pub fn save_count(&self) -> u64 {
+        lock(&self.state).saves
+    }
+
+    /// Take the error from the most recent failed background save, if any
+    pub fn take_error(&self) -> Option<std::io::Error> {
+        lock(&self.state).last_error.take()
+    }
+
+    /// Stop the background thread, then save any remaining changes
+    pub fn finish(mut self) -> std::io::Result<bool> {
+        self.shutdown();
+        self.save_now()
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Autosaver {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
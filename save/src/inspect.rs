@@ -0,0 +1,184 @@
+// save/src/inspect.rs
+//! Type-free inspection of sectioned save files.
+//!
+//! [`SaveFile::inspect`] reads as much of a file as it can without knowing any
+//! struct types and without failing on corruption, so support tooling can
+//! report what a broken save still contains.
+use std::path::Path;
+use sha2::{Digest, Sha256};
+use packed_bits_container::flags::BitsFormat;
+use packed_bits_container::BYTE_ALIGNED_FLAG;
+use crate::merkle::build_merkle_tree;
+use crate::portable::{split_payload, Schema, PORTABLE_MAGIC};
+use crate::sections::{SaveFile, SectionKind, HEADER_SIZE, MAGIC, SECTION_HEADER_SIZE};
+
+/// Which magic the file starts with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFormat {
+    /// Native save written by [`SaveWriter::write`](crate::SaveWriter::write)
+    Native,
+    /// Portable save written by [`SaveFile::export_portable`]
+    Portable,
+}
+
+/// What [`SaveFile::inspect`] found in one section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionSummary {
+    pub name: String,
+    /// `None` if the stored kind is unknown to this build
+    pub kind: Option<SectionKind>,
+    pub raw_kind: u32,
+    /// Byte offset of the section header in the file
+    pub offset: usize,
+    pub payload_len: usize,
+    /// SHA-256 of the section (header, name and payload), as fed to the Merkle tree
+    pub hash: [u8; 32],
+    /// Number of records, where it can be known without the struct type
+    pub record_count: Option<usize>,
+    /// MTF type name of an MTF struct section
+    pub schema_name: Option<String>,
+    /// Bits per element of a PackedBits or Flags section
+    pub bits_per_element: Option<u32>,
+}
+
+/// Summary of a save file, produced without knowing any struct types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveSummary {
+    pub format: SaveFormat,
+    pub version: u32,
+    pub file_len: usize,
+    /// Number of sections the header claims
+    pub declared_sections: usize,
+    pub sections: Vec<SectionSummary>,
+    pub stored_root: [u8; 32],
+    /// Merkle root over the sections read; only meaningful if `problems` is empty
+    pub computed_root: [u8; 32],
+    /// Structural problems found while reading, e.g. truncation
+    pub problems: Vec<String>,
+}
+
+impl SaveSummary {
+    /// Whether the file parsed cleanly and its Merkle root matches
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty() && self.stored_root == self.computed_root
+    }
+}
+
+/// Record count, schema name and bit width of a payload, where knowable
+fn describe(kind: Option<SectionKind>, payload: &[u8]) -> (Option<usize>, Option<String>, Option<u32>) {
+    match kind {
+        Some(SectionKind::MtfStructs) => {
+            let Ok((blob, data)) = split_payload(payload) else {
+                return (None, None, None);
+            };
+            match Schema::parse(blob) {
+                Ok(schema) => {
+                    let count = (schema.size > 0 && data.len() % schema.size == 0)
+                        .then(|| data.len() / schema.size);
+                    (count, Some(schema.name), None)
+                }
+                Err(_) => (None, None, None),
+            }
+        }
+        Some(SectionKind::PackedBits | SectionKind::Flags) => {
+            // PKBT: [magic][N][len]; PKFL: [magic][version][N][len]
+            let word = |i: usize| payload.get(i..i + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()));
+            let (n, len) = match BitsFormat::detect(payload) {
+                Some(BitsFormat::PackedBits) => (word(4).map(|n| n & !BYTE_ALIGNED_FLAG), word(8)),
+                Some(BitsFormat::Flags) => (word(8), word(12)),
+                None => (None, None),
+            };
+            (len.map(|l| l as usize), None, n)
+        }
+        _ => (None, None, None),
+    }
+}
+
+impl SaveFile {
+    /// Summarise a save file without loading or verifying it
+    ///
+    /// Only I/O errors and unrecognised files are errors; truncation, hash
+    /// mismatches and unknown sections are reported in the summary.
+    pub fn inspect<P: AsRef<Path>>(path: P) -> std::io::Result<SaveSummary> {
+        let bytes = std::fs::read(path)?;
+        let format = match bytes.get(..4) {
+            Some(m) if m == MAGIC => SaveFormat::Native,
+            Some(m) if m == PORTABLE_MAGIC => SaveFormat::Portable,
+            _ => return Err(crate::sections::invalid("not a sectioned save file")),
+        };
+
+        let mut summary = SaveSummary {
+            format,
+            version: 0,
+            file_len: bytes.len(),
+            declared_sections: 0,
+            sections: Vec::new(),
+            stored_root: [0; 32],
+            computed_root: [0; 32],
+            problems: Vec::new(),
+        };
+        if bytes.len() < HEADER_SIZE {
+            summary.problems.push("truncated file header".to_string());
+            return Ok(summary);
+        }
+        summary.version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        summary.declared_sections = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        summary.stored_root.copy_from_slice(&bytes[12..HEADER_SIZE]);
+
+        let mut chunks = Vec::new();
+        let mut pos = HEADER_SIZE;
+        for index in 0..summary.declared_sections {
+            let Some(header) = bytes.get(pos..pos + SECTION_HEADER_SIZE) else {
+                summary.problems.push(format!("section {} header truncated at byte {}", index, pos));
+                break;
+            };
+            let raw_kind = u32::from_le_bytes(header[0..4].try_into().unwrap());
+            let name_len = u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize;
+            let payload_len = u64::from_le_bytes(header[8..16].try_into().unwrap()) as usize;
+
+            let name_start = pos + SECTION_HEADER_SIZE;
+            let end = name_start
+                .checked_add(name_len)
+                .and_then(|n| n.checked_add(payload_len))
+                .filter(|&n| n <= bytes.len());
+            let Some(end) = end else {
+                summary.problems.push(format!(
+                    "section {} claims {} payload bytes past end of file",
+                    index, payload_len
+                ));
+                break;
+            };
+
+            let payload_start = name_start + name_len;
+            let name = String::from_utf8_lossy(&bytes[name_start..payload_start]).into_owned();
+            let kind = SectionKind::from_u32(raw_kind);
+            if kind.is_none() {
+                summary.problems.push(format!("section '{}' has unknown kind {}", name, raw_kind));
+            }
+            let (record_count, schema_name, bits_per_element) = describe(kind, &bytes[payload_start..end]);
+
+            summary.sections.push(SectionSummary {
+                name,
+                kind,
+                raw_kind,
+                offset: pos,
+                payload_len,
+                hash: Sha256::digest(&bytes[pos..end]).into(),
+                record_count,
+                schema_name,
+                bits_per_element,
+            });
+            chunks.push(bytes[pos..end].to_vec());
+            pos = end;
+        }
+        if summary.problems.is_empty() && pos != bytes.len() {
+            summary.problems.push(format!("{} trailing bytes after sections", bytes.len() - pos));
+        }
+
+        if chunks.is_empty() {
+            chunks.push(Vec::new());
+        }
+        summary.computed_root.copy_from_slice(&build_merkle_tree(&chunks).hash());
+        Ok(summary)
+    }
+}
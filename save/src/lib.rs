@@ -2,7 +2,14 @@
 //!
 //! Supports integrity-checked serialization of containers.
 //! Built for use with `PackedStructContainer.
+pub mod autosave;
+pub mod inspect;
 pub mod merkle;
+pub mod portable;
 pub mod save;
+pub mod sections;
 
+pub use autosave::{Autosaver, AutosaverBuilder, Watch};
+pub use inspect::{SaveFormat, SaveSummary, SectionSummary};
 pub use save::{save_game, load_game};
+pub use sections::{SaveFile, SaveWriter, SectionKind};
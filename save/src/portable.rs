@@ -0,0 +1,212 @@
+// save/src/portable.rs
+//! Portable save files for moving saves between builds.
+//!
+//! Struct sections added with [`SaveWriter::add_mtf_structs`] carry their MTF
+//! schema next to the data:
+//!
+//! ```text
+//! [SCHEMA_LEN: u32][SCHEMA: MTF blob][DATA]
+//! ```
+//!
+//! [`SaveFile::export_portable`] writes every such section with its fields in
+//! little-endian order, under a separate magic ("SAVP"), and
+//! [`SaveFile::import_portable`] converts them back to native order. Loading
+//! with [`SaveFile::mtf_structs`] matches fields by name, so a build whose
+//! struct has reordered, added or removed fields can still read the data.
+//!
+//! MTF records only a field's total width, so array fields (e.g. `[u16; 4]`)
+//! are byte-swapped as one scalar on big-endian hosts. Keep multi-byte arrays
+//! out of structs that need to cross endianness.
+use std::path::Path;
+use bytemuck::Pod;
+use mtf::MTFType;
+use packed_struct_container::PackedStructContainer;
+use crate::sections::{invalid, SaveFile, SaveWriter, SectionKind};
+
+pub(crate) const PORTABLE_MAGIC: &[u8; 4] = b"SAVP";
+
+/// Byte layout of one MTF type
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Schema {
+    pub(crate) name: String,
+    pub(crate) size: usize,
+    /// (name, byte offset, byte size)
+    pub(crate) fields: Vec<(String, usize, usize)>,
+}
+
+impl Schema {
+    pub(crate) fn parse(blob: &[u8]) -> std::io::Result<Self> {
+        let (types, strings) = mtf::read_mtf(blob).map_err(|e| invalid(e.to_string()))?;
+        let ty = types.first().ok_or_else(|| invalid("MTF schema has no types"))?;
+        if ty.size_bits % 8 != 0 {
+            return Err(invalid("MTF type is not a whole number of bytes"));
+        }
+
+        let mut fields = Vec::with_capacity(ty.fields.len());
+        for f in &ty.fields {
+            if f.offset_bits % 8 != 0 || f.size_bits % 8 != 0 {
+                return Err(invalid("bit-level MTF fields are not supported"));
+            }
+            let name = mtf::read_string(strings, f.name_offset).map_err(|e| invalid(e.to_string()))?;
+            let (offset, size) = (f.offset_bits as usize / 8, f.size_bits as usize / 8);
+            if offset + size > ty.size_bits as usize / 8 {
+                return Err(invalid(format!("field '{}' lies outside its type", name)));
+            }
+            fields.push((name.to_string(), offset, size));
+        }
+        let name = mtf::read_string(strings, ty.name_offset).map_err(|e| invalid(e.to_string()))?;
+        Ok(Self { name: name.to_string(), size: ty.size_bits as usize / 8, fields })
+    }
+
+    /// Swap every field between native and little-endian order
+    ///
+    /// The swap is its own inverse, so this both exports and imports.
+    fn swap_to_little_endian(&self, data: &mut [u8]) {
+        if cfg!(target_endian = "little") || self.size == 0 {
+            return;
+        }
+        for record in data.chunks_exact_mut(self.size) {
+            for &(_, offset, size) in &self.fields {
+                if size > 1 {
+                    record[offset..offset + size].reverse();
+                }
+            }
+        }
+    }
+}
+
+/// Split an MTF struct payload into its schema blob and data
+pub(crate) fn split_payload(payload: &[u8]) -> std::io::Result<(&[u8], &[u8])> {
+    let len = payload
+        .get(..4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+        .ok_or_else(|| invalid("truncated MTF section"))?;
+    if payload.len() < 4 + len {
+        return Err(invalid("truncated MTF section"));
+    }
+    Ok((&payload[4..4 + len], &payload[4 + len..]))
+}
+
+/// Payload with every MTF struct field swapped between native and little-endian order
+fn swap_payload(payload: &[u8]) -> std::io::Result<Vec<u8>> {
+    let (blob, data) = split_payload(payload)?;
+    let schema = Schema::parse(blob)?;
+    if schema.size == 0 || data.len() % schema.size != 0 {
+        return Err(invalid("MTF section data is not a whole number of records"));
+    }
+    let mut out = payload.to_vec();
+    schema.swap_to_little_endian(&mut out[4 + blob.len()..]);
+    Ok(out)
+}
+
+impl SaveWriter {
+    /// Add a section of Pod structs together with their MTF schema
+    ///
+    /// Unlike [`add_structs`](Self::add_structs), these sections can be
+    /// exported with [`SaveFile::export_portable`]. `T` must have no padding,
+    /// so that the MTF field offsets match its in-memory layout.
+    pub fn add_mtf_structs<T: MTFType + Pod>(
+        &mut self,
+        name: &str,
+        container: &PackedStructContainer<T>,
+    ) -> std::io::Result<&mut Self> {
+        let blob = T::mtf_type_blob();
+        let schema = Schema::parse(blob)?;
+        if schema.size != std::mem::size_of::<T>() {
+            return Err(invalid(format!(
+                "MTF schema covers {} bytes but the type is {} bytes (padding?)",
+                schema.size,
+                std::mem::size_of::<T>()
+            )));
+        }
+
+        let data: &[u8] = bytemuck::cast_slice(container.as_slice());
+        let mut payload = Vec::with_capacity(4 + blob.len() + data.len());
+        payload.extend_from_slice(&(blob.len() as u32).to_le_bytes());
+        payload.extend_from_slice(blob);
+        payload.extend_from_slice(data);
+        self.push(SectionKind::MtfStructs, name, payload)
+    }
+}
+
+impl SaveFile {
+    /// Load an MTF struct section, mapping stored fields onto `T` by name
+    ///
+    /// Fields of `T` missing from the stored schema are zeroed; stored fields
+    /// unknown to `T` are dropped. A field whose size changed is an error.
+    pub fn mtf_structs<T: MTFType + Pod>(&self, name: &str) -> std::io::Result<PackedStructContainer<T>> {
+        let (blob, data) = split_payload(self.payload(name, SectionKind::MtfStructs)?)?;
+        let stored = Schema::parse(blob)?;
+        let target = Schema::parse(T::mtf_type_blob())?;
+        if target.size != std::mem::size_of::<T>() {
+            return Err(invalid("MTF schema does not match the type size"));
+        }
+        if stored.size == 0 || data.len() % stored.size != 0 {
+            return Err(invalid("MTF section data is not a whole number of records"));
+        }
+
+        let count = data.len() / stored.size;
+        let mut out = vec![T::zeroed(); count];
+        if stored == target {
+            bytemuck::cast_slice_mut::<T, u8>(&mut out).copy_from_slice(data);
+            return Ok(PackedStructContainer::from_slice(&out));
+        }
+
+        // Resolve each target field to its stored location once
+        let mut mapping = Vec::with_capacity(target.fields.len());
+        for (field, offset, size) in &target.fields {
+            if let Some((_, src, src_size)) = stored.fields.iter().find(|(n, _, _)| n == field) {
+                if src_size != size {
+                    return Err(invalid(format!(
+                        "field '{}' changed size from {} to {} bytes",
+                        field, src_size, size
+                    )));
+                }
+                mapping.push((*src, *offset, *size));
+            }
+        }
+
+        let bytes = bytemuck::cast_slice_mut::<T, u8>(&mut out);
+        for (src, dst) in data.chunks_exact(stored.size).zip(bytes.chunks_exact_mut(target.size)) {
+            for &(from, to, size) in &mapping {
+                dst[to..to + size].copy_from_slice(&src[from..from + size]);
+            }
+        }
+        Ok(PackedStructContainer::from_slice(&out))
+    }
+
+    /// Write a copy of this save with all struct fields in little-endian order
+    ///
+    /// Bit sections are already byte-order independent and are copied as-is.
+    /// Plain struct sections have no schema to normalise with, so they are
+    /// rejected; add them with [`SaveWriter::add_mtf_structs`] instead.
+    pub fn export_portable<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = SaveWriter::new();
+        for section in &self.sections {
+            let payload = match section.kind {
+                SectionKind::Structs => {
+                    return Err(invalid(format!(
+                        "section '{}' has no MTF schema and cannot be exported",
+                        section.name
+                    )));
+                }
+                SectionKind::MtfStructs => swap_payload(&section.payload)?,
+                SectionKind::PackedBits | SectionKind::Flags => section.payload.clone(),
+            };
+            writer.push(section.kind, &section.name, payload)?;
+        }
+        writer.write_with_magic(path, PORTABLE_MAGIC)
+    }
+
+    /// Read a file written by [`export_portable`](Self::export_portable),
+    /// converting struct fields to native byte order
+    pub fn import_portable<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let mut save = Self::open_with_magic(path, PORTABLE_MAGIC)?;
+        for section in &mut save.sections {
+            if section.kind == SectionKind::MtfStructs {
+                section.payload = swap_payload(&section.payload)?;
+            }
+        }
+        Ok(save)
+    }
+}
@@ -3,7 +3,7 @@ use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
 use bytemuck::Pod;
-use crate::merkle::{MerkleNode, build_merkle_tree};
+use crate::merkle::build_merkle_tree;
 use packed_struct_container::PackedStructContainer;
 
 /// Save a container with a Merkle root prefix
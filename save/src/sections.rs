@@ -0,0 +1,285 @@
+// save/src/sections.rs
+//! Multi-section save files.
+//!
+//! A save file holds several named sections, each verified by one Merkle root:
+//!
+//! ```text
+//! [MAGIC: 4 bytes "SAVS"]
+//! [VERSION: u32]
+//! [SECTION_COUNT: u32]
+//! [ROOT HASH: 32 bytes]
+//! for each section:
+//!   [KIND: u32][NAME_LEN: u32][PAYLOAD_LEN: u64][NAME][PAYLOAD]
+//! ```
+//!
+//! Struct sections hold raw Pod bytes. Packed-bits sections hold the full
+//! `PackedBitsContainer` storage, including its PKBT header, and flags sections
+//! the PKFL serialization of a `FlagsContainer`, so `N` and the element count
+//! are checked again on load.
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Write};
+use std::path::Path;
+use bytemuck::Pod;
+use crate::merkle::build_merkle_tree;
+use packed_bits_container::PackedBitsContainer;
+use packed_bits_container::flags::FlagsContainer;
+use packed_struct_container::PackedStructContainer;
+use raw_bytes_container::RawBytesContainer;
+
+pub(crate) const MAGIC: &[u8; 4] = b"SAVS";
+pub(crate) const VERSION: u32 = 1;
+pub(crate) const HEADER_SIZE: usize = 4 + 4 + 4 + 32;
+pub(crate) const SECTION_HEADER_SIZE: usize = 4 + 4 + 8;
+
+/// What a section's payload holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum SectionKind {
+    /// Array of Pod structs
+    Structs = 0,
+    /// `PackedBitsContainer<N>` storage, PKBT header included
+    PackedBits = 1,
+    /// Serialized `FlagsContainer<N>` (PKFL, or PKBT in older saves)
+    Flags = 2,
+    /// Pod structs with their MTF schema, see [`crate::portable`]
+    MtfStructs = 3,
+}
+
+impl SectionKind {
+    pub(crate) fn from_u32(v: u32) -> Option<Self> {
+        match v {
+            0 => Some(SectionKind::Structs),
+            1 => Some(SectionKind::PackedBits),
+            2 => Some(SectionKind::Flags),
+            3 => Some(SectionKind::MtfStructs),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Section {
+    pub(crate) kind: SectionKind,
+    pub(crate) name: String,
+    pub(crate) payload: Vec<u8>,
+}
+
+impl Section {
+    /// Header, name and payload; the unit hashed into the Merkle tree
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(SECTION_HEADER_SIZE + self.name.len() + self.payload.len());
+        out.extend_from_slice(&(self.kind as u32).to_le_bytes());
+        out.extend_from_slice(&(self.name.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(self.payload.len() as u64).to_le_bytes());
+        out.extend_from_slice(self.name.as_bytes());
+        out.extend_from_slice(&self.payload);
+        out
+    }
+}
+
+pub(crate) fn invalid(msg: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.into())
+}
+
+/// Collects named sections and writes them as one verified save file
+#[derive(Debug, Default)]
+pub struct SaveWriter {
+    pub(crate) sections: Vec<Section>,
+}
+
+impl SaveWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, kind: SectionKind, name: &str, payload: Vec<u8>) -> std::io::Result<&mut Self> {
+        if self.sections.iter().any(|s| s.name == name) {
+            return Err(Error::new(ErrorKind::AlreadyExists, format!("duplicate section '{}'", name)));
+        }
+        self.sections.push(Section { kind, name: name.to_string(), payload });
+        Ok(self)
+    }
+
+    /// Copy all sections of `other` into this writer
+    pub(crate) fn append(&mut self, other: &SaveWriter) -> std::io::Result<()> {
+        for section in &other.sections {
+            self.push(section.kind, &section.name, section.payload.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Add a section of Pod structs
+    pub fn add_structs<T: Pod>(
+        &mut self,
+        name: &str,
+        container: &PackedStructContainer<T>,
+    ) -> std::io::Result<&mut Self> {
+        let bytes: &[u8] = bytemuck::cast_slice(container.as_slice());
+        self.push(SectionKind::Structs, name, bytes.to_vec())
+    }
+
+    /// Add a `PackedBitsContainer<N>` section
+    pub fn add_packed_bits<const N: usize>(
+        &mut self,
+        name: &str,
+        bits: &PackedBitsContainer<N>,
+    ) -> std::io::Result<&mut Self> {
+        self.push(SectionKind::PackedBits, name, bits.storage().as_slice().to_vec())
+    }
+
+    /// Add a `FlagsContainer<N>` section
+    pub fn add_flags<const N: usize>(
+        &mut self,
+        name: &str,
+        flags: &FlagsContainer<N>,
+    ) -> std::io::Result<&mut Self> {
+        self.push(SectionKind::Flags, name, flags.to_bytes())
+    }
+
+    /// Write all sections with a Merkle root over them
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.write_with_magic(path, MAGIC)
+    }
+
+    pub(crate) fn write_with_magic<P: AsRef<Path>>(&self, path: P, magic: &[u8; 4]) -> std::io::Result<()> {
+        let encoded: Vec<Vec<u8>> = self.sections.iter().map(Section::encode).collect();
+        // An empty file still gets a root, over a single empty chunk
+        let root = if encoded.is_empty() {
+            build_merkle_tree(&[Vec::new()])
+        } else {
+            build_merkle_tree(&encoded)
+        };
+
+        let mut file = File::create(path)?;
+        file.write_all(magic)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&(self.sections.len() as u32).to_le_bytes())?;
+        file.write_all(&root.hash())?;
+        for chunk in &encoded {
+            file.write_all(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// A verified multi-section save file
+#[derive(Debug)]
+pub struct SaveFile {
+    pub(crate) sections: Vec<Section>,
+}
+
+impl SaveFile {
+    /// Read a save file and verify its Merkle root
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::open_with_magic(path, MAGIC)
+    }
+
+    pub(crate) fn open_with_magic<P: AsRef<Path>>(path: P, magic: &[u8; 4]) -> std::io::Result<Self> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != magic {
+            return Err(invalid("not a sectioned save file"));
+        }
+        let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        if version != VERSION {
+            return Err(invalid(format!("unsupported save version {}", version)));
+        }
+        let count = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let stored_root = &bytes[12..HEADER_SIZE];
+        // Every section needs at least its header, so an untrusted count can
+        // be rejected before allocating for it
+        if count > (bytes.len() - HEADER_SIZE) / SECTION_HEADER_SIZE {
+            return Err(invalid(format!("section count {} exceeds file size", count)));
+        }
+
+        let mut sections = Vec::with_capacity(count);
+        let mut chunks = Vec::with_capacity(count);
+        let mut pos = HEADER_SIZE;
+        for _ in 0..count {
+            let header = bytes
+                .get(pos..pos + SECTION_HEADER_SIZE)
+                .ok_or_else(|| invalid("truncated section header"))?;
+            let kind = u32::from_le_bytes(header[0..4].try_into().unwrap());
+            let name_len = u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize;
+            let payload_len = u64::from_le_bytes(header[8..16].try_into().unwrap()) as usize;
+
+            let end = (pos + SECTION_HEADER_SIZE)
+                .checked_add(name_len)
+                .and_then(|n| n.checked_add(payload_len))
+                .filter(|&n| n <= bytes.len())
+                .ok_or_else(|| invalid("truncated section"))?;
+            chunks.push(bytes[pos..end].to_vec());
+
+            let name_start = pos + SECTION_HEADER_SIZE;
+            let payload_start = name_start + name_len;
+            let kind = SectionKind::from_u32(kind).ok_or_else(|| invalid(format!("unknown section kind {}", kind)))?;
+            let name = std::str::from_utf8(&bytes[name_start..payload_start])
+                .map_err(|_| invalid("section name is not UTF-8"))?
+                .to_string();
+            sections.push(Section { kind, name, payload: bytes[payload_start..end].to_vec() });
+            pos = end;
+        }
+        if pos != bytes.len() {
+            return Err(invalid("trailing data after sections"));
+        }
+
+        let root = if chunks.is_empty() {
+            build_merkle_tree(&[Vec::new()])
+        } else {
+            build_merkle_tree(&chunks)
+        };
+        if stored_root != root.hash().as_slice() {
+            return Err(invalid("Merkle hash mismatch"));
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// Section names, in file order
+    pub fn section_names(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().map(|s| s.name.as_str())
+    }
+
+    /// Kind of the named section, if present
+    pub fn kind(&self, name: &str) -> Option<SectionKind> {
+        self.sections.iter().find(|s| s.name == name).map(|s| s.kind)
+    }
+
+    pub(crate) fn payload(&self, name: &str, kind: SectionKind) -> std::io::Result<&[u8]> {
+        let section = self
+            .sections
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("no section '{}'", name)))?;
+        if section.kind != kind {
+            return Err(invalid(format!("section '{}' is {:?}, not {:?}", name, section.kind, kind)));
+        }
+        Ok(&section.payload)
+    }
+
+    /// Load a struct section
+    pub fn structs<T: Pod>(&self, name: &str) -> std::io::Result<PackedStructContainer<T>> {
+        let payload = self.payload(name, SectionKind::Structs)?;
+        if payload.len() % std::mem::size_of::<T>() != 0 {
+            return Err(invalid("invalid struct alignment"));
+        }
+        // Payload bytes carry no alignment guarantee, so copy into a Vec<T>
+        let mut structs = vec![T::zeroed(); payload.len() / std::mem::size_of::<T>()];
+        bytemuck::cast_slice_mut::<T, u8>(&mut structs).copy_from_slice(payload);
+        Ok(PackedStructContainer::from_slice(&structs))
+    }
+
+    /// Load a `PackedBitsContainer<N>` section
+    pub fn packed_bits<const N: usize>(&self, name: &str) -> std::io::Result<PackedBitsContainer<N>> {
+        let payload = self.payload(name, SectionKind::PackedBits)?;
+        PackedBitsContainer::from_storage(RawBytesContainer::from_slice(payload))
+            .map_err(|e| invalid(e.to_string()))
+    }
+
+    /// Load a `FlagsContainer<N>` section
+    pub fn flags<const N: usize>(&self, name: &str) -> std::io::Result<FlagsContainer<N>> {
+        let payload = self.payload(name, SectionKind::Flags)?;
+        FlagsContainer::from_bytes(payload).map_err(|e| invalid(e.to_string()))
+    }
+}
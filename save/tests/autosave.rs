@@ -0,0 +1,140 @@
+use save::{Autosaver, SaveFile};
+use packed_bits_container::flags::FlagsContainer;
+use packed_struct_container::PackedStructContainer;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn autosave_writes_dirty_sources() {
+    let path = "autosave_dirty.bin";
+    let scores = Arc::new(Mutex::new(PackedStructContainer::from_slice(&[1u32, 2, 3])));
+    let rooms = Arc::new(Mutex::new(FlagsContainer::<4>::new_in_memory()));
+    let snapshots = Arc::new(Mutex::new(Vec::new()));
+
+    let autosaver = {
+        let log = Arc::clone(&snapshots);
+        let log2 = Arc::clone(&snapshots);
+        Autosaver::builder(path)
+            .interval(Duration::from_secs(3600))
+            .register(Arc::clone(&scores), move |scores, w| {
+                log.lock().unwrap().push("scores");
+                w.add_structs("scores", scores).map(|_| ())
+            })
+            .register(Arc::clone(&rooms), move |rooms, w| {
+                log2.lock().unwrap().push("rooms");
+                w.add_flags("rooms", rooms).map(|_| ())
+            })
+            .start()
+    };
+
+    // Everything is unsaved initially
+    assert!(autosaver.save_now().unwrap());
+    assert!(!autosaver.save_now().unwrap());
+
+    // Only the changed source is snapshotted again
+    rooms.lock().unwrap().push(0b101).unwrap();
+    assert!(autosaver.save_now().unwrap());
+    assert_eq!(*snapshots.lock().unwrap(), ["scores", "rooms", "rooms"]);
+
+    // In-place edits are picked up too
+    scores.lock().unwrap()[1] = 20;
+    assert!(autosaver.save_now().unwrap());
+    assert_eq!(*snapshots.lock().unwrap(), ["scores", "rooms", "rooms", "scores"]);
+
+    let save = SaveFile::open(path).unwrap();
+    assert_eq!(save.structs::<u32>("scores").unwrap().as_slice(), &[1, 20, 3]);
+    assert_eq!(save.flags::<4>("rooms").unwrap().get(0), Some(0b101));
+    assert_eq!(autosaver.save_count(), 3);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn autosave_background_quiesce() {
+    let path = "autosave_background.bin";
+    let data = Arc::new(Mutex::new(PackedStructContainer::from_slice(&[7u64])));
+    let busy = Arc::new(AtomicBool::new(true));
+
+    let autosaver = {
+        let busy = Arc::clone(&busy);
+        Autosaver::builder(path)
+            .interval(Duration::from_millis(5))
+            .quiesce(move || !busy.load(Ordering::SeqCst))
+            .register(data, |data, w| w.add_structs("data", data).map(|_| ()))
+            .start()
+    };
+
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(autosaver.save_count(), 0);
+
+    busy.store(false, Ordering::SeqCst);
+    for _ in 0..200 {
+        if autosaver.save_count() > 0 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(autosaver.save_count(), 1);
+    assert!(autosaver.take_error().is_none());
+    assert!(!autosaver.finish().unwrap());
+
+    let save = SaveFile::open(path).unwrap();
+    assert_eq!(save.structs::<u64>("data").unwrap().as_slice(), &[7]);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn autosave_retries_failed_write() {
+    let missing = std::path::Path::new("autosave_retry_dir");
+    let _ = fs::remove_dir_all(missing);
+    let path = missing.join("save.bin");
+    let data = Arc::new(Mutex::new(PackedStructContainer::from_slice(&[9u32])));
+
+    let autosaver = Autosaver::builder(&path)
+        .interval(Duration::from_secs(3600))
+        .register(data, |data, w| w.add_structs("data", data).map(|_| ()))
+        .start();
+
+    // The directory does not exist yet, so the write fails
+    assert!(autosaver.save_now().is_err());
+    assert_eq!(autosaver.save_count(), 0);
+
+    // The source is still dirty and is saved once the write can succeed
+    fs::create_dir(missing).unwrap();
+    assert!(autosaver.save_now().unwrap());
+    let save = SaveFile::open(&path).unwrap();
+    assert_eq!(save.structs::<u32>("data").unwrap().as_slice(), &[9]);
+
+    fs::remove_dir_all(missing).unwrap();
+}
+
+#[test]
+fn autosave_survives_poisoned_source() {
+    let path = "autosave_poisoned.bin";
+    let data = Arc::new(Mutex::new(PackedStructContainer::from_slice(&[1u32])));
+
+    let autosaver = Autosaver::builder(path)
+        .interval(Duration::from_secs(3600))
+        .register(Arc::clone(&data), |data, w| w.add_structs("data", data).map(|_| ()))
+        .start();
+    assert!(autosaver.save_now().unwrap());
+
+    // A panic while holding the lock poisons it after the edit went through
+    let poisoner = Arc::clone(&data);
+    let _ = std::thread::spawn(move || {
+        let mut data = poisoner.lock().unwrap();
+        data[0] = 2;
+        panic!("poison");
+    })
+    .join();
+    assert!(data.is_poisoned());
+
+    assert!(autosaver.save_now().unwrap());
+    let save = SaveFile::open(path).unwrap();
+    assert_eq!(save.structs::<u32>("data").unwrap().as_slice(), &[2]);
+
+    fs::remove_file(path).unwrap();
+}
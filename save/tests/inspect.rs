@@ -0,0 +1,80 @@
+use save::{SaveFile, SaveFormat, SaveWriter, SectionKind};
+use packed_bits_container::flags::FlagsContainer;
+use packed_struct_container::PackedStructContainer;
+use bytemuck_derive::{Pod, Zeroable};
+use mtf_derive::MTF;
+use std::fs;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable, PartialEq, MTF)]
+struct Npc {
+    id: u32,
+    mood: u32,
+}
+
+fn write_sample(path: &str) {
+    let mut rooms = FlagsContainer::<5>::new_in_memory();
+    for f in [1, 2, 3] {
+        rooms.push(f).unwrap();
+    }
+    SaveWriter::new()
+        .add_structs("raw", &PackedStructContainer::from_slice(&[1u16, 2]))
+        .unwrap()
+        .add_mtf_structs("npcs", &PackedStructContainer::from_slice(&[Npc { id: 1, mood: 2 }; 4]))
+        .unwrap()
+        .add_flags("rooms", &rooms)
+        .unwrap()
+        .write(path)
+        .unwrap();
+}
+
+#[test]
+fn inspect_valid_save() {
+    let path = "inspect_valid.bin";
+    write_sample(path);
+
+    let summary = SaveFile::inspect(path).unwrap();
+    assert!(summary.is_valid());
+    assert_eq!(summary.format, SaveFormat::Native);
+    assert_eq!(summary.version, 1);
+    assert_eq!(summary.sections.len(), 3);
+
+    let raw = &summary.sections[0];
+    assert_eq!((raw.kind, raw.record_count, raw.payload_len), (Some(SectionKind::Structs), None, 4));
+
+    let npcs = &summary.sections[1];
+    assert_eq!(npcs.record_count, Some(4));
+    assert_eq!(npcs.schema_name.as_deref(), Some("Npc"));
+
+    let rooms = &summary.sections[2];
+    assert_eq!((rooms.record_count, rooms.bits_per_element), (Some(3), Some(5)));
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn inspect_corrupt_save() {
+    let path = "inspect_corrupt.bin";
+    write_sample(path);
+    let clean = SaveFile::inspect(path).unwrap();
+
+    // Flip a byte in the last section: only its hash and the root change
+    let mut bytes = fs::read(path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    fs::write(path, &bytes).unwrap();
+
+    let summary = SaveFile::inspect(path).unwrap();
+    assert!(!summary.is_valid());
+    assert!(summary.problems.is_empty());
+    assert_eq!(summary.sections[0].hash, clean.sections[0].hash);
+    assert_ne!(summary.sections[2].hash, clean.sections[2].hash);
+
+    // Truncation is reported, earlier sections are still listed
+    fs::write(path, &bytes[..bytes.len() - 3]).unwrap();
+    let summary = SaveFile::inspect(path).unwrap();
+    assert_eq!(summary.sections.len(), 2);
+    assert_eq!(summary.problems.len(), 1);
+
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,80 @@
+use save::{SaveFile, SaveWriter, SectionKind};
+use packed_bits_container::flags::FlagsContainer;
+use packed_struct_container::PackedStructContainer;
+use bytemuck_derive::{Pod, Zeroable};
+use mtf_derive::MTF;
+use std::fs;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable, PartialEq, MTF)]
+struct PlayerV1 {
+    id: u32,
+    hp: u16,
+    level: u16,
+}
+
+/// Later build: fields reordered, `level` dropped, `gold` added
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable, PartialEq, MTF)]
+struct PlayerV2 {
+    gold: u64,
+    hp: u16,
+    pad: u16,
+    id: u32,
+}
+
+#[test]
+fn portable_round_trip_and_migration() {
+    let players = PackedStructContainer::from_slice(&[
+        PlayerV1 { id: 7, hp: 300, level: 12 },
+        PlayerV1 { id: 0x0102_0304, hp: 1, level: 2 },
+    ]);
+    let mut rooms = FlagsContainer::<3>::new_in_memory();
+    rooms.push(0b110).unwrap();
+
+    let native = "portable_native.bin";
+    let portable = "portable_export.bin";
+    SaveWriter::new()
+        .add_mtf_structs("players", &players).unwrap()
+        .add_flags("rooms", &rooms).unwrap()
+        .write(native)
+        .unwrap();
+
+    let save = SaveFile::open(native).unwrap();
+    save.export_portable(portable).unwrap();
+    assert!(SaveFile::open(portable).is_err());
+
+    let imported = SaveFile::import_portable(portable).unwrap();
+    assert_eq!(imported.kind("players"), Some(SectionKind::MtfStructs));
+    let loaded = imported.mtf_structs::<PlayerV1>("players").unwrap();
+    assert_eq!(loaded.as_slice(), players.as_slice());
+    assert_eq!(imported.flags::<3>("rooms").unwrap().get(0), Some(0b110));
+
+    // Fields are little-endian in the exported file
+    let bytes = fs::read(portable).unwrap();
+    assert!(bytes.windows(4).any(|w| w == [0x04, 0x03, 0x02, 0x01]));
+
+    let migrated = imported.mtf_structs::<PlayerV2>("players").unwrap();
+    assert_eq!(
+        migrated.as_slice()[1],
+        PlayerV2 { gold: 0, hp: 1, pad: 0, id: 0x0102_0304 }
+    );
+
+    fs::remove_file(native).unwrap();
+    fs::remove_file(portable).unwrap();
+}
+
+#[test]
+fn export_rejects_schemaless_structs() {
+    let path = "portable_schemaless.bin";
+    SaveWriter::new()
+        .add_structs("raw", &PackedStructContainer::from_slice(&[1u32]))
+        .unwrap()
+        .write(path)
+        .unwrap();
+
+    let save = SaveFile::open(path).unwrap();
+    assert!(save.export_portable("portable_schemaless_out.bin").is_err());
+
+    fs::remove_file(path).unwrap();
+}
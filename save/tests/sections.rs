@@ -0,0 +1,94 @@
+use save::{SaveFile, SaveWriter, SectionKind};
+use packed_bits_container::PackedBitsContainer;
+use packed_bits_container::flags::FlagsContainer;
+use packed_struct_container::PackedStructContainer;
+use bytemuck_derive::{Pod, Zeroable};
+use std::fs;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable, PartialEq)]
+struct Player {
+    id: u32,
+    hp: u32,
+}
+
+const VISITED: u32 = 1 << 0;
+const LOOTED: u32 = 1 << 2;
+
+#[test]
+fn round_trip_sections() {
+    let players = PackedStructContainer::from_slice(&[Player { id: 1, hp: 90 }, Player { id: 2, hp: 45 }]);
+
+    let mut levels = PackedBitsContainer::<5>::new_in_memory();
+    for v in [3, 17, 31] {
+        levels.push(v).unwrap();
+    }
+
+    let mut rooms = FlagsContainer::<3>::new_in_memory();
+    rooms.push(VISITED).unwrap();
+    rooms.push(VISITED | LOOTED).unwrap();
+    rooms.push(0).unwrap();
+
+    let path = "sections_round_trip.bin";
+    SaveWriter::new()
+        .add_structs("players", &players).unwrap()
+        .add_packed_bits("levels", &levels).unwrap()
+        .add_flags("rooms", &rooms).unwrap()
+        .write(path)
+        .unwrap();
+
+    let save = SaveFile::open(path).unwrap();
+    assert_eq!(save.section_names().collect::<Vec<_>>(), ["players", "levels", "rooms"]);
+    assert_eq!(save.kind("rooms"), Some(SectionKind::Flags));
+
+    let loaded = save.structs::<Player>("players").unwrap();
+    assert_eq!(loaded.as_slice(), players.as_slice());
+
+    let loaded = save.packed_bits::<5>("levels").unwrap();
+    assert_eq!(loaded.iter().collect::<Vec<_>>(), vec![3, 17, 31]);
+
+    let loaded = save.flags::<3>("rooms").unwrap();
+    assert!(loaded.contains(1, LOOTED));
+    assert!(!loaded.contains(0, LOOTED));
+    assert_eq!(loaded.len(), 3);
+
+    // Wrong N is caught by the PKBT header, wrong kind by the section table
+    assert!(save.packed_bits::<4>("levels").is_err());
+    assert!(save.packed_bits::<3>("rooms").is_err());
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn detect_corrupt_section() {
+    let mut rooms = FlagsContainer::<8>::new_in_memory();
+    rooms.push(0xAA).unwrap();
+
+    let path = "sections_corrupt.bin";
+    SaveWriter::new().add_flags("rooms", &rooms).unwrap().write(path).unwrap();
+
+    let mut bytes = fs::read(path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    fs::write(path, &bytes).unwrap();
+
+    let err = SaveFile::open(path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn reject_oversized_section_count() {
+    let path = "sections_count.bin";
+    SaveWriter::new().write(path).unwrap();
+
+    let mut bytes = fs::read(path).unwrap();
+    bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(path, &bytes).unwrap();
+
+    let err = SaveFile::open(path).unwrap_err();
+    assert!(err.to_string().contains("exceeds file size"), "{err}");
+
+    fs::remove_file(path).unwrap();
+}